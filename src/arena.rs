@@ -0,0 +1,1293 @@
+use crate::{
+    visit::{
+        walk_bdo_content_run, walk_bdo_content_run_mut, walk_body, walk_body_mut, walk_cell, walk_cell_mut,
+        walk_content_block_content, walk_custom_xml_block, walk_custom_xml_block_mut, walk_custom_xml_cell,
+        walk_custom_xml_cell_mut, walk_custom_xml_row, walk_custom_xml_row_mut, walk_custom_xml_run,
+        walk_custom_xml_run_mut, walk_dir_content_run, walk_dir_content_run_mut, walk_footnote, walk_footnote_mut,
+        walk_hyperlink, walk_hyperlink_mut, walk_p_content, walk_paragraph, walk_paragraph_mut, walk_row, walk_row_mut,
+        walk_ruby_content, walk_ruby_content_mut, walk_run, walk_run_inner_content, walk_run_inner_content_mut,
+        walk_run_level_element, walk_run_level_element_mut, walk_run_mut, walk_sdt_block, walk_sdt_block_mut,
+        walk_sdt_cell, walk_sdt_cell_mut, walk_sdt_row, walk_sdt_row_mut, walk_sdt_run, walk_sdt_run_mut,
+        walk_simple_field, walk_simple_field_mut, walk_smart_tag_run, walk_smart_tag_run_mut, walk_table,
+        walk_table_mut, walk_txbx_content, walk_txbx_content_mut, Visit, VisitMut,
+    },
+    wml::{
+        document::{
+            BdoContentRun, BlockLevelElts, Body, ContentBlockContent, CustomXmlBlock, CustomXmlRun, DirContentRun,
+            Document, Hyperlink, PContent, RubyContent, RunInnerContent, RunLevelElts, SdtBlock, SdtContentBlock,
+            SdtContentRun, SdtRun, SectPr, SimpleField, SmartTagRun, P, R,
+        },
+        drawing::TxbxContent,
+        footnotes::FtnEdn,
+        table::{CustomXmlCell, CustomXmlRow, Row, SdtCell, SdtRow, Tbl, Tc},
+    },
+};
+use std::{any::TypeId, collections::HashMap};
+
+// Calls the given macro with every node type of the arena: the Visit and VisitMut hooks, their walk functions, the
+// NodeRef variant and the node type. The arena, the visitors building it and the editor all use this list, so they
+// agree on which elements of the tree are nodes.
+macro_rules! with_node_types {
+    ($callback:ident) => {
+        $callback! {
+            visit_body, visit_body_mut, walk_body, walk_body_mut, Body, Body;
+            visit_custom_xml_block, visit_custom_xml_block_mut, walk_custom_xml_block, walk_custom_xml_block_mut,
+                CustomXmlBlock, CustomXmlBlock;
+            visit_sdt_block, visit_sdt_block_mut, walk_sdt_block, walk_sdt_block_mut, SdtBlock, SdtBlock;
+            visit_paragraph, visit_paragraph_mut, walk_paragraph, walk_paragraph_mut, Paragraph, P;
+            visit_table, visit_table_mut, walk_table, walk_table_mut, Table, Tbl;
+            visit_custom_xml_row, visit_custom_xml_row_mut, walk_custom_xml_row, walk_custom_xml_row_mut,
+                CustomXmlRow, CustomXmlRow;
+            visit_sdt_row, visit_sdt_row_mut, walk_sdt_row, walk_sdt_row_mut, SdtRow, SdtRow;
+            visit_row, visit_row_mut, walk_row, walk_row_mut, Row, Row;
+            visit_custom_xml_cell, visit_custom_xml_cell_mut, walk_custom_xml_cell, walk_custom_xml_cell_mut,
+                CustomXmlCell, CustomXmlCell;
+            visit_sdt_cell, visit_sdt_cell_mut, walk_sdt_cell, walk_sdt_cell_mut, SdtCell, SdtCell;
+            visit_cell, visit_cell_mut, walk_cell, walk_cell_mut, Cell, Tc;
+            visit_simple_field, visit_simple_field_mut, walk_simple_field, walk_simple_field_mut, SimpleField,
+                SimpleField;
+            visit_hyperlink, visit_hyperlink_mut, walk_hyperlink, walk_hyperlink_mut, Hyperlink, Hyperlink;
+            visit_custom_xml_run, visit_custom_xml_run_mut, walk_custom_xml_run, walk_custom_xml_run_mut,
+                CustomXmlRun, CustomXmlRun;
+            visit_smart_tag_run, visit_smart_tag_run_mut, walk_smart_tag_run, walk_smart_tag_run_mut, SmartTagRun,
+                SmartTagRun;
+            visit_sdt_run, visit_sdt_run_mut, walk_sdt_run, walk_sdt_run_mut, SdtRun, SdtRun;
+            visit_dir_content_run, visit_dir_content_run_mut, walk_dir_content_run, walk_dir_content_run_mut,
+                DirContentRun, DirContentRun;
+            visit_bdo_content_run, visit_bdo_content_run_mut, walk_bdo_content_run, walk_bdo_content_run_mut,
+                BdoContentRun, BdoContentRun;
+            visit_run, visit_run_mut, walk_run, walk_run_mut, Run, R;
+            visit_run_level_element, visit_run_level_element_mut, walk_run_level_element, walk_run_level_element_mut,
+                RunLevelElement, RunLevelElts;
+            visit_run_inner_content, visit_run_inner_content_mut, walk_run_inner_content, walk_run_inner_content_mut,
+                RunInnerContent, RunInnerContent;
+            visit_ruby_content, visit_ruby_content_mut, walk_ruby_content, walk_ruby_content_mut, RubyContent,
+                RubyContent;
+            visit_txbx_content, visit_txbx_content_mut, walk_txbx_content, walk_txbx_content_mut, TxbxContent,
+                TxbxContent;
+        }
+    };
+}
+
+// Ids stay valid while other nodes are inserted or removed. The generation tells a removed node apart from a node
+// which reuses its slot later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+    index: usize,
+    generation: u32,
+}
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NodeRef<'a> {
+    Document(&'a Document),
+    Note(&'a FtnEdn),
+    TxbxContent(&'a TxbxContent),
+    Body(&'a Body),
+    CustomXmlBlock(&'a CustomXmlBlock),
+    SdtBlock(&'a SdtBlock),
+    Paragraph(&'a P),
+    Table(&'a Tbl),
+    CustomXmlRow(&'a CustomXmlRow),
+    SdtRow(&'a SdtRow),
+    Row(&'a Row),
+    CustomXmlCell(&'a CustomXmlCell),
+    SdtCell(&'a SdtCell),
+    Cell(&'a Tc),
+    SimpleField(&'a SimpleField),
+    Hyperlink(&'a Hyperlink),
+    CustomXmlRun(&'a CustomXmlRun),
+    SmartTagRun(&'a SmartTagRun),
+    SdtRun(&'a SdtRun),
+    DirContentRun(&'a DirContentRun),
+    BdoContentRun(&'a BdoContentRun),
+    Run(&'a R),
+    RunLevelElement(&'a RunLevelElts),
+    RunInnerContent(&'a RunInnerContent),
+    RubyContent(&'a RubyContent),
+}
+
+impl<'a> NodeRef<'a> {
+    pub fn is_same_node(&self, other: &NodeRef) -> bool {
+        self.key() == other.key()
+    }
+
+    pub fn is_sdt(&self) -> bool {
+        matches!(
+            self,
+            NodeRef::SdtBlock(_) | NodeRef::SdtRow(_) | NodeRef::SdtCell(_) | NodeRef::SdtRun(_)
+        )
+    }
+
+    pub fn as_paragraph(&self) -> Option<&'a P> {
+        match self {
+            NodeRef::Paragraph(paragraph) => Some(paragraph),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&'a Tbl> {
+        match self {
+            NodeRef::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+
+    pub fn as_cell(&self) -> Option<&'a Tc> {
+        match self {
+            NodeRef::Cell(cell) => Some(cell),
+            _ => None,
+        }
+    }
+
+    pub fn as_run(&self) -> Option<&'a R> {
+        match self {
+            NodeRef::Run(run) => Some(run),
+            _ => None,
+        }
+    }
+
+    // The type and the address of the node, which tell nodes apart while the tree isn't changed
+    fn key(&self) -> (TypeId, usize) {
+        match self {
+            NodeRef::Document(node) => node_key(*node),
+            NodeRef::Note(node) => node_key(*node),
+            NodeRef::TxbxContent(node) => node_key(*node),
+            NodeRef::Body(node) => node_key(*node),
+            NodeRef::CustomXmlBlock(node) => node_key(*node),
+            NodeRef::SdtBlock(node) => node_key(*node),
+            NodeRef::Paragraph(node) => node_key(*node),
+            NodeRef::Table(node) => node_key(*node),
+            NodeRef::CustomXmlRow(node) => node_key(*node),
+            NodeRef::SdtRow(node) => node_key(*node),
+            NodeRef::Row(node) => node_key(*node),
+            NodeRef::CustomXmlCell(node) => node_key(*node),
+            NodeRef::SdtCell(node) => node_key(*node),
+            NodeRef::Cell(node) => node_key(*node),
+            NodeRef::SimpleField(node) => node_key(*node),
+            NodeRef::Hyperlink(node) => node_key(*node),
+            NodeRef::CustomXmlRun(node) => node_key(*node),
+            NodeRef::SmartTagRun(node) => node_key(*node),
+            NodeRef::SdtRun(node) => node_key(*node),
+            NodeRef::DirContentRun(node) => node_key(*node),
+            NodeRef::BdoContentRun(node) => node_key(*node),
+            NodeRef::Run(node) => node_key(*node),
+            NodeRef::RunLevelElement(node) => node_key(*node),
+            NodeRef::RunInnerContent(node) => node_key(*node),
+            NodeRef::RubyContent(node) => node_key(*node),
+        }
+    }
+}
+
+fn node_key<T: 'static>(node: &T) -> (TypeId, usize) {
+    (TypeId::of::<T>(), node as *const T as usize)
+}
+
+// The tree an arena is built from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaRoot {
+    Document(Box<Document>),
+    Note(FtnEdn),
+    TxbxContent(TxbxContent),
+}
+
+impl ArenaRoot {
+    fn node_ref(&self) -> NodeRef<'_> {
+        match self {
+            ArenaRoot::Document(document) => NodeRef::Document(document),
+            ArenaRoot::Note(note) => NodeRef::Note(note),
+            ArenaRoot::TxbxContent(txbx_content) => NodeRef::TxbxContent(txbx_content),
+        }
+    }
+}
+
+// Content which can be inserted into the arena. Block content goes into bodies, cells, notes, text boxes and block
+// level SDTs and custom XML elements, paragraph content into paragraphs and the run level containers of paragraphs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeContent {
+    Block(ContentBlockContent),
+    Paragraph(PContent),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Section<'a> {
+    pub index: usize,
+    pub properties: Option<&'a SectPr>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u32,
+    node: Option<Node>,
+}
+
+// Lookups into the tree, built once for the tree and again after every edit
+#[derive(Debug, Default)]
+struct NodeIndex {
+    // The position of every node in document order and among the children of its parent
+    positions: HashMap<NodeId, (usize, usize)>,
+    // Nodes by their key. The root isn't in here as its address changes when the arena is moved.
+    keys: HashMap<(TypeId, usize), NodeId>,
+    // The paragraphs ending a section in document order. Paragraphs in tables can't end a section.
+    section_breaks: Vec<NodeId>,
+}
+
+// An indexed view of a tree which owns the tree. Nodes are only borrowed while navigating, edits go through the
+// arena so the tree and the ids stay in sync.
+#[derive(Debug)]
+pub struct DocumentArena {
+    tree: ArenaRoot,
+    slots: Vec<Slot>,
+    free_slots: Vec<usize>,
+    root_id: NodeId,
+    index: NodeIndex,
+}
+
+// The index refers to the nodes by their address, so a clone builds its own
+impl Clone for DocumentArena {
+    fn clone(&self) -> Self {
+        let mut arena = Self {
+            tree: self.tree.clone(),
+            slots: self.slots.clone(),
+            free_slots: self.free_slots.clone(),
+            root_id: self.root_id,
+            index: NodeIndex::default(),
+        };
+
+        arena.build_index();
+        arena
+    }
+}
+
+impl DocumentArena {
+    pub fn new(document: Document) -> Self {
+        Self::from_root(ArenaRoot::Document(Box::new(document)))
+    }
+
+    pub fn from_note(note: FtnEdn) -> Self {
+        Self::from_root(ArenaRoot::Note(note))
+    }
+
+    pub fn from_txbx_content(txbx_content: TxbxContent) -> Self {
+        Self::from_root(ArenaRoot::TxbxContent(txbx_content))
+    }
+
+    pub fn from_root(tree: ArenaRoot) -> Self {
+        let mut arena = Self {
+            tree,
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+            root_id: NodeId {
+                index: 0,
+                generation: 0,
+            },
+            index: NodeIndex::default(),
+        };
+
+        let parents = NodeCollector::collect_tree(arena.tree.node_ref()).parents;
+        arena.root_id = arena.add_nodes(None, 0, &parents);
+        arena.build_index();
+        arena
+    }
+
+    pub fn tree(&self) -> &ArenaRoot {
+        &self.tree
+    }
+
+    pub fn document(&self) -> Option<&Document> {
+        match &self.tree {
+            ArenaRoot::Document(document) => Some(document),
+            _ => None,
+        }
+    }
+
+    pub fn into_tree(self) -> ArenaRoot {
+        self.tree
+    }
+
+    pub fn root(&self) -> NodeId {
+        self.root_id
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free_slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    // The ids of all nodes in document order
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> {
+        self.subtree(self.root_id).into_iter()
+    }
+
+    // All nodes in document order. This is cheaper than calling get for every id.
+    pub fn nodes(&self) -> Vec<(NodeId, NodeRef<'_>)> {
+        self.subtree(self.root_id)
+            .into_iter()
+            .zip(NodeCollector::collect_tree(self.tree.node_ref()).nodes)
+            .collect()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<NodeRef<'_>> {
+        self.node(id)?;
+        self.path(id)
+            .into_iter()
+            .try_fold(self.tree.node_ref(), |node, child_index| {
+                nth_child_node_ref(node, child_index)
+            })
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id)?.parent
+    }
+
+    pub fn first_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id)?.children.first().cloned()
+    }
+
+    pub fn last_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id)?.children.last().cloned()
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = &self.node(self.parent(id)?)?.children;
+        siblings.get(self.child_index(id)? + 1).cloned()
+    }
+
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = &self.node(self.parent(id)?)?.children;
+        siblings.get(self.child_index(id)?.checked_sub(1)?).cloned()
+    }
+
+    pub fn children(&self, id: NodeId) -> Vec<NodeId> {
+        self.node(id).map(|node| node.children.clone()).unwrap_or_default()
+    }
+
+    pub fn ancestors(&self, id: NodeId) -> Vec<NodeId> {
+        std::iter::successors(self.parent(id), |parent_id| self.parent(*parent_id)).collect()
+    }
+
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        self.subtree(id).into_iter().skip(1).collect()
+    }
+
+    pub fn is_ancestor_of(&self, ancestor_id: NodeId, id: NodeId) -> bool {
+        std::iter::successors(self.parent(id), |parent_id| self.parent(*parent_id))
+            .any(|parent_id| parent_id == ancestor_id)
+    }
+
+    pub fn find_node_id(&self, node: NodeRef) -> Option<NodeId> {
+        if node.is_same_node(&self.tree.node_ref()) {
+            return Some(self.root_id);
+        }
+
+        self.index.keys.get(&node.key()).cloned()
+    }
+
+    pub fn find_ancestor<F>(&self, id: NodeId, predicate: F) -> Option<NodeId>
+    where
+        F: Fn(NodeRef) -> bool,
+    {
+        std::iter::successors(self.parent(id), |parent_id| self.parent(*parent_id))
+            .find(|parent_id| self.get(*parent_id).map(&predicate).unwrap_or(false))
+    }
+
+    pub fn containing_paragraph(&self, id: NodeId) -> Option<NodeId> {
+        self.find_ancestor(id, |node| node.as_paragraph().is_some())
+    }
+
+    pub fn containing_cell(&self, id: NodeId) -> Option<NodeId> {
+        self.find_ancestor(id, |node| node.as_cell().is_some())
+    }
+
+    pub fn containing_table(&self, id: NodeId) -> Option<NodeId> {
+        self.find_ancestor(id, |node| node.as_table().is_some())
+    }
+
+    pub fn containing_sdt(&self, id: NodeId) -> Option<NodeId> {
+        self.find_ancestor(id, |node| node.is_sdt())
+    }
+
+    // Sections are found from the current tree, so they follow the edits made through the arena
+    pub fn containing_section(&self, id: NodeId) -> Option<Section<'_>> {
+        let body_id = self.first_child(self.root_id).filter(|_| self.document().is_some())?;
+        if id == self.root_id || id == body_id || !self.contains(id) {
+            return None;
+        }
+
+        // Nodes in a paragraph ending a section belong to the section of the paragraph
+        let section_breaks = &self.index.section_breaks;
+        let position = self
+            .ancestors(id)
+            .into_iter()
+            .rev()
+            .filter_map(|ancestor_id| self.position(ancestor_id))
+            .find(|position| self.section_break_index(*position).is_ok())
+            .or_else(|| self.position(id))?;
+        let index = match self.section_break_index(position) {
+            Ok(index) | Err(index) => index,
+        };
+
+        Some(Section {
+            index,
+            properties: match section_breaks.get(index) {
+                Some(break_id) => self.section_properties(*break_id),
+                None => self.document()?.body.as_ref()?.section_properties.as_ref(),
+            },
+        })
+    }
+
+    pub fn sections(&self) -> Vec<Section<'_>> {
+        let body = match self.document().and_then(|document| document.body.as_ref()) {
+            Some(body) => body,
+            None => return Vec::new(),
+        };
+
+        self.index
+            .section_breaks
+            .iter()
+            .map(|break_id| self.section_properties(*break_id))
+            .chain(std::iter::once(body.section_properties.as_ref()))
+            .enumerate()
+            .map(|(index, properties)| Section { index, properties })
+            .collect()
+    }
+
+    fn section_break_index(&self, position: usize) -> Result<usize, usize> {
+        self.index
+            .section_breaks
+            .binary_search_by_key(&position, |break_id| self.position(*break_id).unwrap_or_default())
+    }
+
+    fn section_properties(&self, id: NodeId) -> Option<&SectPr> {
+        self.get(id)?
+            .as_paragraph()?
+            .properties
+            .as_ref()?
+            .section_properties
+            .as_ref()
+    }
+
+    pub fn path(&self, id: NodeId) -> Vec<usize> {
+        let mut path: Vec<usize> = std::iter::successors(Some(id), |node_id| self.parent(*node_id))
+            .filter_map(|node_id| self.child_index(node_id))
+            .collect();
+
+        path.reverse();
+        path
+    }
+
+    pub fn node_at_path(&self, path: &[usize]) -> Option<NodeId> {
+        path.iter().try_fold(self.root_id, |node_id, child_index| {
+            self.node(node_id)?.children.get(*child_index).cloned()
+        })
+    }
+
+    pub fn insert_before(&mut self, sibling_id: NodeId, content: NodeContent) -> Option<NodeId> {
+        let child_index = self.child_index(sibling_id)?;
+        self.insert(self.parent(sibling_id)?, child_index, content)
+    }
+
+    pub fn insert_after(&mut self, sibling_id: NodeId, content: NodeContent) -> Option<NodeId> {
+        let child_index = self.child_index(sibling_id)?;
+        self.insert(self.parent(sibling_id)?, child_index + 1, content)
+    }
+
+    pub fn append_child(&mut self, parent_id: NodeId, content: NodeContent) -> Option<NodeId> {
+        let child_index = self.node(parent_id)?.children.len();
+        self.insert(parent_id, child_index, content)
+    }
+
+    // Removes the node and its descendants from the tree. Only nodes which can be inserted can be removed.
+    pub fn remove(&mut self, id: NodeId) -> Option<NodeContent> {
+        let parent_id = self.parent(id)?;
+        let child_index = self.child_index(id)?;
+        let content = match self.edit(parent_id, ContainerEdit::Remove(child_index))? {
+            EditResult::Removed(content) => content,
+            EditResult::Inserted => return None,
+        };
+
+        for removed_id in self.subtree(id) {
+            let slot = &mut self.slots[removed_id.index];
+            slot.node = None;
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free_slots.push(removed_id.index);
+        }
+
+        if let Some(parent) = self.node_mut(parent_id) {
+            parent.children.remove(child_index);
+        }
+
+        self.build_index();
+        Some(content)
+    }
+
+    fn insert(&mut self, parent_id: NodeId, child_index: usize, content: NodeContent) -> Option<NodeId> {
+        let parents = match &content {
+            NodeContent::Block(block_content) => {
+                let mut collector = NodeCollector::default();
+                walk_content_block_content(&mut collector, block_content);
+                collector.parents
+            }
+            NodeContent::Paragraph(p_content) => {
+                let mut collector = NodeCollector::default();
+                walk_p_content(&mut collector, p_content);
+                collector.parents
+            }
+        };
+
+        // Content without a node, like a subdocument, couldn't be referred to by an id
+        if parents.is_empty() {
+            return None;
+        }
+
+        match self.edit(parent_id, ContainerEdit::Insert(child_index, content))? {
+            EditResult::Inserted => {
+                let id = self.add_nodes(Some(parent_id), child_index, &parents);
+                self.build_index();
+                Some(id)
+            }
+            EditResult::Removed(_) => None,
+        }
+    }
+
+    fn edit(&mut self, parent_id: NodeId, edit: ContainerEdit) -> Option<EditResult> {
+        let target = self.position(parent_id)?;
+        let mut editor = NodeEditor {
+            target,
+            position: 1,
+            edit: Some(edit),
+            result: None,
+        };
+
+        match &mut self.tree {
+            ArenaRoot::Document(_) if target == 0 => (),
+            ArenaRoot::Document(document) => {
+                if let Some(body) = &mut document.body {
+                    editor.visit_body_mut(body);
+                }
+            }
+            ArenaRoot::Note(note) if target == 0 => editor.apply(note.children_mut()),
+            ArenaRoot::Note(note) => walk_footnote_mut(&mut editor, note),
+            ArenaRoot::TxbxContent(txbx_content) if target == 0 => editor.apply(txbx_content.children_mut()),
+            ArenaRoot::TxbxContent(txbx_content) => walk_txbx_content_mut(&mut editor, txbx_content),
+        }
+
+        editor.result
+    }
+
+    // Adds the nodes of a subtree given by the parent index of every node in document order
+    fn add_nodes(&mut self, parent_id: Option<NodeId>, child_index: usize, parents: &[Option<usize>]) -> NodeId {
+        let ids: Vec<NodeId> = parents.iter().map(|_| self.allocate()).collect();
+        for (id, parent) in ids.iter().zip(parents) {
+            let node_parent = parent.map(|parent| ids[parent]).or(parent_id);
+            if let Some(node) = self.node_mut(*id) {
+                node.parent = node_parent;
+            }
+
+            if let Some(parent) = parent {
+                if let Some(parent_node) = self.node_mut(ids[*parent]) {
+                    parent_node.children.push(*id);
+                }
+            }
+        }
+
+        if let Some(parent_node) = parent_id.and_then(|parent_id| self.node_mut(parent_id)) {
+            parent_node.children.insert(child_index, ids[0]);
+        }
+
+        ids[0]
+    }
+
+    fn allocate(&mut self) -> NodeId {
+        match self.free_slots.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.node = Some(Default::default());
+                NodeId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: Some(Default::default()),
+                });
+                NodeId {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        self.slots
+            .get(id.index)
+            .filter(|slot| slot.generation == id.generation)?
+            .node
+            .as_ref()
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.slots
+            .get_mut(id.index)
+            .filter(|slot| slot.generation == id.generation)?
+            .node
+            .as_mut()
+    }
+
+    fn child_index(&self, id: NodeId) -> Option<usize> {
+        self.parent(id)?;
+        self.index.positions.get(&id).map(|(_, child_index)| *child_index)
+    }
+
+    // The position in document order
+    fn position(&self, id: NodeId) -> Option<usize> {
+        self.node(id)?;
+        self.index.positions.get(&id).map(|(position, _)| *position)
+    }
+
+    fn build_index(&mut self) {
+        let ids = self.subtree(self.root_id);
+        let nodes = NodeCollector::collect_tree(self.tree.node_ref()).nodes;
+        let mut index = NodeIndex::default();
+        let mut child_indices = HashMap::new();
+        for (position, (id, node)) in ids.iter().zip(&nodes).enumerate() {
+            if let Some(arena_node) = self.node(*id) {
+                child_indices.extend(
+                    arena_node
+                        .children
+                        .iter()
+                        .enumerate()
+                        .map(|(child_index, child_id)| (*child_id, child_index)),
+                );
+            }
+
+            index
+                .positions
+                .insert(*id, (position, child_indices.get(id).cloned().unwrap_or_default()));
+            if *id != self.root_id {
+                index.keys.insert(node.key(), *id);
+            }
+
+            let is_section_break = node
+                .as_paragraph()
+                .and_then(|paragraph| paragraph.properties.as_ref()?.section_properties.as_ref())
+                .is_some();
+            let is_in_table = || {
+                self.ancestors(*id).into_iter().any(|ancestor_id| {
+                    index
+                        .positions
+                        .get(&ancestor_id)
+                        .is_some_and(|(ancestor_position, _)| nodes[*ancestor_position].as_table().is_some())
+                })
+            };
+            if is_section_break && !is_in_table() {
+                index.section_breaks.push(*id);
+            }
+        }
+
+        self.index = index;
+    }
+
+    // The node and its descendants in document order
+    fn subtree(&self, id: NodeId) -> Vec<NodeId> {
+        let mut ids = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.node(id) {
+                ids.push(id);
+                stack.extend(node.children.iter().rev());
+            }
+        }
+
+        ids
+    }
+}
+
+fn nth_child_node_ref(node: NodeRef, child_index: usize) -> Option<NodeRef> {
+    let mut collector = ChildFinder {
+        child_index,
+        child: None,
+    };
+    walk_node_ref(&mut collector, node);
+    collector.child
+}
+
+fn walk_node_ref<'a, V: Visit<'a>>(visitor: &mut V, node: NodeRef<'a>) {
+    macro_rules! walk_node_ref {
+        ($($visit:ident, $visit_mut:ident, $walk:ident, $walk_mut:ident, $variant:ident, $node_type:ty;)*) => {
+            match node {
+                NodeRef::Document(document) => {
+                    if let Some(body) = &document.body {
+                        visitor.visit_body(body);
+                    }
+                }
+                NodeRef::Note(note) => walk_footnote(visitor, note),
+                $(NodeRef::$variant(node) => $walk(visitor, node),)*
+            }
+        };
+    }
+
+    with_node_types!(walk_node_ref);
+}
+
+// Collects the nodes of a subtree in document order with the index of their parent
+#[derive(Default)]
+struct NodeCollector<'a> {
+    nodes: Vec<NodeRef<'a>>,
+    parents: Vec<Option<usize>>,
+    open_nodes: Vec<usize>,
+}
+
+impl<'a> NodeCollector<'a> {
+    fn collect_tree(root: NodeRef<'a>) -> Self {
+        let mut collector = Self::default();
+        collector.add(root);
+        collector
+    }
+
+    fn add(&mut self, node: NodeRef<'a>) {
+        self.parents.push(self.open_nodes.last().cloned());
+        self.nodes.push(node);
+        self.open_nodes.push(self.nodes.len() - 1);
+        walk_node_ref(self, node);
+        self.open_nodes.pop();
+    }
+}
+
+macro_rules! node_collector_hooks {
+    ($($visit:ident, $visit_mut:ident, $walk:ident, $walk_mut:ident, $variant:ident, $node_type:ty;)*) => {
+        $(
+            fn $visit(&mut self, node: &'a $node_type) {
+                self.add(NodeRef::$variant(node));
+            }
+        )*
+    };
+}
+
+impl<'a> Visit<'a> for NodeCollector<'a> {
+    with_node_types!(node_collector_hooks);
+}
+
+// Finds the child with the given index without collecting the other children
+struct ChildFinder<'a> {
+    child_index: usize,
+    child: Option<NodeRef<'a>>,
+}
+
+macro_rules! child_finder_hooks {
+    ($($visit:ident, $visit_mut:ident, $walk:ident, $walk_mut:ident, $variant:ident, $node_type:ty;)*) => {
+        $(
+            fn $visit(&mut self, node: &'a $node_type) {
+                match self.child_index.checked_sub(1) {
+                    Some(child_index) => self.child_index = child_index,
+                    None if self.child.is_none() => self.child = Some(NodeRef::$variant(node)),
+                    None => (),
+                }
+            }
+        )*
+    };
+}
+
+impl<'a> Visit<'a> for ChildFinder<'a> {
+    with_node_types!(child_finder_hooks);
+}
+
+enum ContainerEdit {
+    Insert(usize, NodeContent),
+    Remove(usize),
+}
+
+enum EditResult {
+    Inserted,
+    Removed(NodeContent),
+}
+
+// The list of a node which holds its children, if children can be inserted into it
+enum Children<'a> {
+    BlockLevelElements(&'a mut Vec<BlockLevelElts>),
+    BlockContents(&'a mut Vec<ContentBlockContent>),
+    ParagraphContents(&'a mut Vec<PContent>),
+    // The content of an SDT is only added when something is inserted into it
+    SdtBlockContent(&'a mut Option<SdtContentBlock>),
+    SdtRunContent(&'a mut Option<SdtContentRun>),
+}
+
+impl<'a> Children<'a> {
+    fn apply(self, edit: ContainerEdit) -> Option<EditResult> {
+        let children = match (self, &edit) {
+            (Children::SdtBlockContent(content), ContainerEdit::Insert(_, NodeContent::Block(_))) => {
+                Children::BlockContents(&mut content.get_or_insert_with(Default::default).block_contents)
+            }
+            (Children::SdtBlockContent(content), _) => Children::BlockContents(&mut content.as_mut()?.block_contents),
+            (Children::SdtRunContent(content), ContainerEdit::Insert(_, NodeContent::Paragraph(_))) => {
+                Children::ParagraphContents(&mut content.get_or_insert_with(Default::default).p_contents)
+            }
+            (Children::SdtRunContent(content), _) => Children::ParagraphContents(&mut content.as_mut()?.p_contents),
+            (children, _) => children,
+        };
+
+        match (children, edit) {
+            (
+                Children::BlockLevelElements(elements),
+                ContainerEdit::Insert(child_index, NodeContent::Block(content)),
+            ) => {
+                let index = container_index(elements, child_index, |element| {
+                    matches!(element, BlockLevelElts::Chunk(_))
+                })?;
+                elements.insert(index, BlockLevelElts::Chunk(content));
+                Some(EditResult::Inserted)
+            }
+            (Children::BlockContents(contents), ContainerEdit::Insert(child_index, NodeContent::Block(content))) => {
+                let index = container_index(contents, child_index, |_| true)?;
+                contents.insert(index, content);
+                Some(EditResult::Inserted)
+            }
+            (
+                Children::ParagraphContents(contents),
+                ContainerEdit::Insert(child_index, NodeContent::Paragraph(content)),
+            ) => {
+                let index = container_index(contents, child_index, |content| {
+                    !matches!(content, PContent::SubDocument(_))
+                })?;
+                contents.insert(index, content);
+                Some(EditResult::Inserted)
+            }
+            (Children::BlockLevelElements(elements), ContainerEdit::Remove(child_index)) => {
+                let index = container_index(elements, child_index, |element| {
+                    matches!(element, BlockLevelElts::Chunk(_))
+                })?;
+                match elements.get(index) {
+                    Some(BlockLevelElts::Chunk(_)) => match elements.remove(index) {
+                        BlockLevelElts::Chunk(content) => Some(EditResult::Removed(NodeContent::Block(content))),
+                        BlockLevelElts::AltChunk(_) => None,
+                    },
+                    _ => None,
+                }
+            }
+            (Children::BlockContents(contents), ContainerEdit::Remove(child_index)) => {
+                let index = container_index(contents, child_index, |_| true).filter(|index| *index < contents.len())?;
+                Some(EditResult::Removed(NodeContent::Block(contents.remove(index))))
+            }
+            (Children::ParagraphContents(contents), ContainerEdit::Remove(child_index)) => {
+                let index = container_index(contents, child_index, |content| {
+                    !matches!(content, PContent::SubDocument(_))
+                })
+                .filter(|index| *index < contents.len())?;
+                Some(EditResult::Removed(NodeContent::Paragraph(contents.remove(index))))
+            }
+            _ => None,
+        }
+    }
+}
+
+// The index in the list of the child with the given index, or the end of the list for the position after the last
+// child. Only some elements of the list are nodes.
+fn container_index<T, F: Fn(&T) -> bool>(elements: &[T], child_index: usize, is_node: F) -> Option<usize> {
+    let mut node_indices = elements
+        .iter()
+        .enumerate()
+        .filter(|(_, element)| is_node(element))
+        .map(|(index, _)| index);
+
+    match node_indices.nth(child_index) {
+        Some(index) => Some(index),
+        None if elements.iter().filter(|element| is_node(element)).count() == child_index => Some(elements.len()),
+        None => None,
+    }
+}
+
+trait HasChildren {
+    fn children_mut(&mut self) -> Option<Children<'_>> {
+        None
+    }
+}
+
+macro_rules! impl_has_children {
+    ($($node_type:ty => |$node:ident| $children:expr;)*) => {
+        $(
+            impl HasChildren for $node_type {
+                fn children_mut(&mut self) -> Option<Children<'_>> {
+                    let $node = self;
+                    Some($children)
+                }
+            }
+        )*
+    };
+}
+
+impl_has_children! {
+    Body => |body| Children::BlockLevelElements(&mut body.block_level_elements);
+    FtnEdn => |note| Children::BlockLevelElements(&mut note.block_level_elements);
+    TxbxContent => |txbx_content| Children::BlockLevelElements(&mut txbx_content.block_level_elements);
+    Tc => |cell| Children::BlockLevelElements(&mut cell.block_level_elements);
+    CustomXmlBlock => |custom_xml| Children::BlockContents(&mut custom_xml.block_contents);
+    SdtBlock => |sdt| Children::SdtBlockContent(&mut sdt.sdt_content);
+    P => |paragraph| Children::ParagraphContents(&mut paragraph.contents);
+    SimpleField => |simple_field| Children::ParagraphContents(&mut simple_field.paragraph_contents);
+    Hyperlink => |hyperlink| Children::ParagraphContents(&mut hyperlink.paragraph_contents);
+    CustomXmlRun => |custom_xml| Children::ParagraphContents(&mut custom_xml.paragraph_contents);
+    SmartTagRun => |smart_tag| Children::ParagraphContents(&mut smart_tag.paragraph_contents);
+    SdtRun => |sdt| Children::SdtRunContent(&mut sdt.sdt_content);
+    DirContentRun => |dir| Children::ParagraphContents(&mut dir.p_contents);
+    BdoContentRun => |bdo| Children::ParagraphContents(&mut bdo.p_contents);
+}
+
+impl HasChildren for Tbl {}
+impl HasChildren for CustomXmlRow {}
+impl HasChildren for SdtRow {}
+impl HasChildren for Row {}
+impl HasChildren for CustomXmlCell {}
+impl HasChildren for SdtCell {}
+impl HasChildren for R {}
+impl HasChildren for RunLevelElts {}
+impl HasChildren for RunInnerContent {}
+impl HasChildren for RubyContent {}
+
+// Finds a node by its position in document order, which is the order the arena nodes are built in, and edits its
+// children
+struct NodeEditor {
+    target: usize,
+    position: usize,
+    edit: Option<ContainerEdit>,
+    result: Option<EditResult>,
+}
+
+impl NodeEditor {
+    fn enter(&mut self) -> Option<bool> {
+        self.edit.as_ref()?;
+        let is_target = self.position == self.target;
+        self.position += 1;
+        Some(is_target)
+    }
+
+    fn apply(&mut self, children: Option<Children>) {
+        if let (Some(children), Some(edit)) = (children, self.edit.take()) {
+            self.result = children.apply(edit);
+        }
+
+        self.edit = None;
+    }
+}
+
+macro_rules! node_editor_hooks {
+    ($($visit:ident, $visit_mut:ident, $walk:ident, $walk_mut:ident, $variant:ident, $node_type:ty;)*) => {
+        $(
+            fn $visit_mut(&mut self, node: &mut $node_type) {
+                match self.enter() {
+                    Some(true) => self.apply(node.children_mut()),
+                    Some(false) => $walk_mut(self, node),
+                    None => (),
+                }
+            }
+        )*
+    };
+}
+
+impl VisitMut for NodeEditor {
+    with_node_types!(node_editor_hooks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DocumentArena, NodeContent, NodeId, NodeRef};
+    use crate::{
+        test_util::{paragraph, paragraph_block, text_run},
+        wml::{
+            document::{
+                BlockLevelElts, Body, ContentBlockContent, ContentRunContent, Document, PContent, PPr, RunInnerContent,
+                SdtBlock, SectPr, P, R,
+            },
+            table::{ContentCellContent, ContentRowContent, Row, Tbl, TblGrid, TblPr, TblPrBase, Tc},
+        },
+    };
+
+    // The last paragraph of a section other than the last one
+    fn section_paragraph(text: &str) -> P {
+        P {
+            properties: Some(PPr {
+                section_properties: Some(SectPr::default()),
+                ..Default::default()
+            }),
+            ..paragraph(vec![text_run(text)])
+        }
+    }
+
+    fn run_text(run: &R) -> String {
+        run.run_inner_contents
+            .iter()
+            .filter_map(|content| match content {
+                RunInnerContent::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn document_for_test() -> Document {
+        let table = Tbl {
+            range_markup_elements: Vec::new(),
+            properties: TblPr {
+                base: TblPrBase::default(),
+                change: None,
+            },
+            grid: TblGrid::default(),
+            row_contents: vec![ContentRowContent::Table(Box::new(Row {
+                property_exceptions: None,
+                properties: None,
+                contents: vec![ContentCellContent::Cell(Box::new(Tc {
+                    properties: None,
+                    block_level_elements: vec![paragraph_block(paragraph(vec![text_run("in cell")]))],
+                    id: None,
+                }))],
+                run_properties_revision_id: None,
+                run_revision_id: None,
+                deletion_revision_id: None,
+                row_revision_id: None,
+            }))],
+        };
+
+        Document {
+            body: Some(Body {
+                block_level_elements: vec![
+                    paragraph_block(section_paragraph("first section")),
+                    BlockLevelElts::Chunk(ContentBlockContent::Table(Box::new(table))),
+                    paragraph_block(paragraph(vec![text_run("last")])),
+                ],
+                section_properties: Some(SectPr::default()),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_arena_navigation() {
+        let arena = DocumentArena::new(document_for_test());
+
+        let body_id = arena.first_child(arena.root()).unwrap();
+        let top_level_ids = arena.children(body_id);
+        assert_eq!(top_level_ids.len(), 3);
+        assert!(arena.get(top_level_ids[1]).unwrap().as_table().is_some());
+        assert_eq!(arena.next_sibling(top_level_ids[0]), Some(top_level_ids[1]));
+        assert_eq!(arena.previous_sibling(top_level_ids[2]), Some(top_level_ids[1]));
+        assert_eq!(arena.parent(top_level_ids[2]), Some(body_id));
+
+        let cell_run_id = arena
+            .node_ids()
+            .find(|id| match arena.get(*id) {
+                Some(NodeRef::Run(run)) => run_text(run) == "in cell",
+                _ => false,
+            })
+            .unwrap();
+
+        assert_eq!(arena.containing_table(cell_run_id), Some(top_level_ids[1]));
+        assert!(arena
+            .get(arena.containing_cell(cell_run_id).unwrap())
+            .unwrap()
+            .as_cell()
+            .is_some());
+        assert!(arena.is_ancestor_of(top_level_ids[1], cell_run_id));
+        assert_eq!(arena.containing_sdt(cell_run_id), None);
+
+        let path = arena.path(cell_run_id);
+        assert_eq!(arena.node_at_path(&path), Some(cell_run_id));
+    }
+
+    #[test]
+    pub fn test_arena_sections_and_lookup() {
+        let arena = DocumentArena::new(document_for_test());
+        assert_eq!(arena.sections().len(), 2);
+
+        let first_paragraph = match &arena.document().unwrap().body.as_ref().unwrap().block_level_elements[0] {
+            BlockLevelElts::Chunk(ContentBlockContent::Paragraph(paragraph)) => paragraph.as_ref(),
+            _ => panic!("expected paragraph"),
+        };
+        let first_paragraph_id = arena.find_node_id(NodeRef::Paragraph(first_paragraph)).unwrap();
+        assert_eq!(arena.containing_section(first_paragraph_id).unwrap().index, 0);
+
+        let first_run_id = arena.descendants(first_paragraph_id)[0];
+        assert_eq!(arena.containing_paragraph(first_run_id), Some(first_paragraph_id));
+        assert_eq!(arena.containing_section(first_run_id).unwrap().index, 0);
+
+        let last_id = arena.last_child(arena.first_child(arena.root()).unwrap()).unwrap();
+        let last_section = arena.containing_section(last_id).unwrap();
+        assert_eq!(last_section.index, 1);
+        assert!(last_section.properties.is_some());
+    }
+
+    #[test]
+    pub fn test_arena_edits_keep_ids() {
+        let mut arena = DocumentArena::new(document_for_test());
+        let body_id = arena.first_child(arena.root()).unwrap();
+        let top_level_ids = arena.children(body_id);
+        let (first_id, table_id, last_id) = (top_level_ids[0], top_level_ids[1], top_level_ids[2]);
+        let cell_run_id = arena
+            .descendants(table_id)
+            .into_iter()
+            .find(|id| arena.get(*id).unwrap().as_run().is_some())
+            .unwrap();
+
+        let inserted_id = arena
+            .insert_before(
+                first_id,
+                NodeContent::Block(ContentBlockContent::Paragraph(Box::new(paragraph(vec![text_run(
+                    "inserted",
+                )])))),
+            )
+            .unwrap();
+        assert_eq!(arena.children(body_id), vec![inserted_id, first_id, table_id, last_id]);
+        assert_eq!(arena.path(last_id), vec![0, 3]);
+        assert_eq!(arena.containing_section(inserted_id).unwrap().index, 0);
+        assert!(arena.get(last_id).unwrap().as_paragraph().is_some());
+        assert!(arena.get(cell_run_id).unwrap().as_run().is_some());
+
+        let node_count = arena.len();
+        let removed = arena.remove(table_id);
+        assert!(matches!(
+            removed,
+            Some(NodeContent::Block(ContentBlockContent::Table(_)))
+        ));
+        assert!(arena.get(table_id).is_none());
+        assert!(arena.get(cell_run_id).is_none());
+        assert!(arena.len() < node_count);
+
+        let last_run = match arena.get(arena.first_child(last_id).unwrap()) {
+            Some(NodeRef::Run(run)) => run_text(run),
+            _ => panic!("expected run"),
+        };
+        assert_eq!(last_run, "last");
+        assert_eq!(arena.next_sibling(first_id), Some(last_id));
+
+        // The slots of the removed nodes are reused with a new generation
+        let appended_id = arena
+            .append_child(
+                body_id,
+                NodeContent::Block(ContentBlockContent::Paragraph(Box::new(paragraph(vec![text_run(
+                    "appended",
+                )])))),
+            )
+            .unwrap();
+        assert!(arena.get(table_id).is_none());
+        assert!(arena.get(cell_run_id).is_none());
+        assert!(arena.get(appended_id).unwrap().as_paragraph().is_some());
+        assert_eq!(arena.containing_section(appended_id).unwrap().index, 1);
+
+        let texts: Vec<String> = arena
+            .document()
+            .unwrap()
+            .body
+            .as_ref()
+            .unwrap()
+            .block_level_elements
+            .iter()
+            .filter_map(|element| match element {
+                BlockLevelElts::Chunk(ContentBlockContent::Paragraph(paragraph)) => match &paragraph.contents[0] {
+                    PContent::ContentRunContent(content) => match content.as_ref() {
+                        ContentRunContent::Run(run) => Some(run_text(run)),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec!["inserted", "first section", "last", "appended"]);
+    }
+
+    #[test]
+    pub fn test_arena_lookup_after_edits() {
+        let mut arena = DocumentArena::new(document_for_test());
+        let body_id = arena.first_child(arena.root()).unwrap();
+        let first_id = arena.first_child(body_id).unwrap();
+        arena
+            .insert_before(
+                first_id,
+                NodeContent::Block(ContentBlockContent::Paragraph(Box::new(section_paragraph(
+                    "new section",
+                )))),
+            )
+            .unwrap();
+
+        // The tree moved the paragraphs, the lookups find them at their new address
+        let ids: Vec<NodeId> = arena.node_ids().collect();
+        for id in &ids {
+            assert_eq!(arena.find_node_id(arena.get(*id).unwrap()), Some(*id));
+        }
+
+        let clone = arena.clone();
+        assert_eq!(clone.find_node_id(clone.get(first_id).unwrap()), Some(first_id));
+        assert_eq!(arena.sections().len(), 3);
+        assert_eq!(arena.containing_section(first_id).unwrap().index, 1);
+        assert_eq!(
+            arena
+                .containing_section(arena.first_child(first_id).unwrap())
+                .unwrap()
+                .index,
+            1
+        );
+        assert_eq!(
+            arena
+                .containing_section(arena.last_child(body_id).unwrap())
+                .unwrap()
+                .index,
+            2
+        );
+    }
+
+    #[test]
+    pub fn test_arena_sdt_content_only_added_on_insert() {
+        let mut arena = DocumentArena::new(Document {
+            body: Some(Body {
+                block_level_elements: vec![BlockLevelElts::Chunk(ContentBlockContent::Sdt(
+                    Box::<SdtBlock>::default(),
+                ))],
+                section_properties: None,
+            }),
+            ..Default::default()
+        });
+        let sdt_id = arena.first_child(arena.first_child(arena.root()).unwrap()).unwrap();
+        let sdt_content = |arena: &DocumentArena| match arena.get(sdt_id) {
+            Some(NodeRef::SdtBlock(sdt)) => sdt.sdt_content.clone(),
+            _ => panic!("expected SDT"),
+        };
+
+        let run = PContent::ContentRunContent(Box::new(ContentRunContent::Run(R::default())));
+        assert_eq!(arena.append_child(sdt_id, NodeContent::Paragraph(run)), None);
+        assert!(sdt_content(&arena).is_none());
+
+        let paragraph_id = arena
+            .append_child(
+                sdt_id,
+                NodeContent::Block(ContentBlockContent::Paragraph(Box::new(paragraph(vec![text_run(
+                    "in SDT",
+                )])))),
+            )
+            .unwrap();
+        assert_eq!(sdt_content(&arena).unwrap().block_contents.len(), 1);
+        assert!(arena.remove(paragraph_id).is_some());
+        assert_eq!(sdt_content(&arena).unwrap().block_contents.len(), 0);
+    }
+}
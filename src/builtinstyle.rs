@@ -0,0 +1,711 @@
+use crate::{
+    package::Package,
+    wml::{
+        simpletypes::DecimalNumber,
+        styles::{LatentStyles, LsdException, Style, Styles},
+    },
+};
+use msoffice_shared::{sharedtypes::OnOff, xml::XmlNode};
+use std::sync::OnceLock;
+
+// The uiPriority of styles without a latent style default, as specified by ECMA-376
+const DEFAULT_UI_PRIORITY: DecimalNumber = 99;
+
+// The latent style attributes of a built-in style after applying the defaults of the latent styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatentStyleAttributes {
+    pub locked: OnOff,
+    pub ui_priority: DecimalNumber,
+    pub semi_hidden: OnOff,
+    pub unhide_when_used: OnOff,
+    pub primary_style: OnOff,
+}
+
+impl Default for LatentStyleAttributes {
+    fn default() -> Self {
+        Self {
+            locked: false,
+            ui_priority: DEFAULT_UI_PRIORITY,
+            semi_hidden: false,
+            unhide_when_used: false,
+            primary_style: false,
+        }
+    }
+}
+
+impl LatentStyleAttributes {
+    // Style level attributes take precedence over the latent ones
+    pub fn apply_to_style(&self, style: &mut Style) {
+        style.locked = style.locked.or(Some(self.locked));
+        style.ui_priority = style.ui_priority.or(Some(self.ui_priority));
+        style.semi_hidden = style.semi_hidden.or(Some(self.semi_hidden));
+        style.unhide_when_used = style.unhide_when_used.or(Some(self.unhide_when_used));
+        style.primary_style = style.primary_style.or(Some(self.primary_style));
+    }
+}
+
+impl LatentStyles {
+    // Latent style exceptions are matched by the name of the style, case insensitively
+    pub fn exception(&self, style_name: &str) -> Option<&LsdException> {
+        self.lsd_exceptions
+            .iter()
+            .find(|exception| exception.name.eq_ignore_ascii_case(style_name))
+    }
+
+    pub fn attributes(&self, style_name: &str) -> LatentStyleAttributes {
+        let exception = self.exception(style_name);
+        let defaults: LatentStyleAttributes = Default::default();
+
+        LatentStyleAttributes {
+            locked: exception
+                .and_then(|exception| exception.locked)
+                .or(self.default_locked_state)
+                .unwrap_or(defaults.locked),
+            ui_priority: exception
+                .and_then(|exception| exception.ui_priority)
+                .or(self.default_ui_priority)
+                .unwrap_or(defaults.ui_priority),
+            semi_hidden: exception
+                .and_then(|exception| exception.semi_hidden)
+                .or(self.default_semi_hidden)
+                .unwrap_or(defaults.semi_hidden),
+            unhide_when_used: exception
+                .and_then(|exception| exception.unhide_when_used)
+                .or(self.default_unhide_when_used)
+                .unwrap_or(defaults.unhide_when_used),
+            primary_style: exception
+                .and_then(|exception| exception.primary_style)
+                .or(self.default_primary_style)
+                .unwrap_or(defaults.primary_style),
+        }
+    }
+}
+
+// A built-in style of Word as defined by the default Normal template
+pub struct BuiltinStyleDefinition {
+    pub style_id: String,
+    pub name: String,
+    xml: String,
+}
+
+impl BuiltinStyleDefinition {
+    fn new(style_id: &str, name: &str, xml: String) -> Self {
+        Self {
+            style_id: String::from(style_id),
+            name: String::from(name),
+            xml,
+        }
+    }
+
+    // The definitions are known to be valid, which is checked by the tests
+    pub fn to_style(&self) -> Style {
+        let xml_node = XmlNode::from_str(&self.xml).expect("invalid built-in style xml");
+        Style::from_xml_element(&xml_node).expect("invalid built-in style definition")
+    }
+}
+
+fn paragraph_style(style_id: &str, name: &str, relations: &str, properties: &str) -> BuiltinStyleDefinition {
+    let xml = format!(
+        r#"<w:style w:type="paragraph" w:styleId="{}"><w:name w:val="{}"/>{}{}</w:style>"#,
+        style_id, name, relations, properties
+    );
+    BuiltinStyleDefinition::new(style_id, name, xml)
+}
+
+fn character_style(style_id: &str, name: &str, relations: &str, properties: &str) -> BuiltinStyleDefinition {
+    let xml = format!(
+        r#"<w:style w:type="character" w:styleId="{}"><w:name w:val="{}"/>{}{}</w:style>"#,
+        style_id, name, relations, properties
+    );
+    BuiltinStyleDefinition::new(style_id, name, xml)
+}
+
+// The character style linked to a paragraph style has the run properties of the paragraph style
+fn linked_character_style(
+    style_id: &str,
+    name: &str,
+    paragraph_style_id: &str,
+    attributes: &str,
+    run_properties: &str,
+) -> BuiltinStyleDefinition {
+    let relations = format!(
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:link w:val="{}"/>{}"#,
+        paragraph_style_id, attributes
+    );
+    character_style(style_id, name, &relations, run_properties)
+}
+
+fn heading_run_properties(level: i32) -> String {
+    // Headings 8 and 9 lighten the text color, the others darken the accent color
+    let (color, theme_color, theme_shading) = match level {
+        1 | 2 | 4 | 5 => ("2F5496", "accent1", r#"w:themeShade="BF""#),
+        3 | 6 | 7 => ("1F3763", "accent1", r#"w:themeShade="7F""#),
+        _ => ("272727", "text1", r#"w:themeTint="D8""#),
+    };
+    let italic = if level == 4 || level == 7 || level == 9 {
+        "<w:i/><w:iCs/>"
+    } else {
+        ""
+    };
+    let size = match level {
+        1 => r#"<w:sz w:val="32"/><w:szCs w:val="32"/>"#,
+        2 => r#"<w:sz w:val="26"/><w:szCs w:val="26"/>"#,
+        3 => r#"<w:sz w:val="24"/><w:szCs w:val="24"/>"#,
+        8 | 9 => r#"<w:sz w:val="21"/><w:szCs w:val="21"/>"#,
+        _ => "",
+    };
+
+    format!(
+        r#"<w:rPr><w:rFonts w:asciiTheme="majorHAnsi" w:eastAsiaTheme="majorEastAsia" w:hAnsiTheme="majorHAnsi" w:cstheme="majorBidi"/>{}<w:color w:val="{}" w:themeColor="{}" {}/>{}</w:rPr>"#,
+        italic, color, theme_color, theme_shading, size
+    )
+}
+
+fn heading_attributes(level: i32) -> &'static str {
+    if level == 1 {
+        r#"<w:uiPriority w:val="9"/><w:qFormat/>"#
+    } else {
+        r#"<w:uiPriority w:val="9"/><w:semiHidden/><w:unhideWhenUsed/><w:qFormat/>"#
+    }
+}
+
+fn heading_styles(level: i32) -> Vec<BuiltinStyleDefinition> {
+    let style_id = format!("Heading{}", level);
+    let char_style_id = format!("Heading{}Char", level);
+    let spacing_before = if level == 1 { 240 } else { 40 };
+    let relations = format!(
+        r#"<w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:link w:val="{}"/>{}"#,
+        char_style_id,
+        heading_attributes(level)
+    );
+    let properties = format!(
+        r#"<w:pPr><w:keepNext/><w:keepLines/><w:spacing w:before="{}" w:after="0"/><w:outlineLvl w:val="{}"/></w:pPr>{}"#,
+        spacing_before,
+        level - 1,
+        heading_run_properties(level)
+    );
+
+    vec![
+        paragraph_style(&style_id, &format!("heading {}", level), &relations, &properties),
+        linked_character_style(
+            &char_style_id,
+            &format!("Heading {} Char", level),
+            &style_id,
+            heading_attributes(level),
+            &heading_run_properties(level),
+        ),
+    ]
+}
+
+fn toc_style(level: i32) -> BuiltinStyleDefinition {
+    let indent = if level > 1 {
+        format!(r#"<w:ind w:left="{}"/>"#, (level - 1) * 220)
+    } else {
+        String::new()
+    };
+
+    paragraph_style(
+        &format!("TOC{}", level),
+        &format!("toc {}", level),
+        r#"<w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:autoRedefine/><w:uiPriority w:val="39"/><w:unhideWhenUsed/>"#,
+        &format!(r#"<w:pPr><w:spacing w:after="100"/>{}</w:pPr>"#, indent),
+    )
+}
+
+// The text styles which are stored with a linked character style: (style id, name, character style name, attributes,
+// paragraph properties, run properties)
+const LINKED_PARAGRAPH_STYLES: &[(&str, &str, &str, &str, &str, &str)] = &[
+    (
+        "Title",
+        "Title",
+        "Title Char",
+        r#"<w:uiPriority w:val="10"/><w:qFormat/>"#,
+        r#"<w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/><w:contextualSpacing/></w:pPr>"#,
+        r#"<w:rPr><w:rFonts w:asciiTheme="majorHAnsi" w:eastAsiaTheme="majorEastAsia" w:hAnsiTheme="majorHAnsi" w:cstheme="majorBidi"/><w:spacing w:val="-10"/><w:kern w:val="28"/><w:sz w:val="56"/><w:szCs w:val="56"/></w:rPr>"#,
+    ),
+    (
+        "Subtitle",
+        "Subtitle",
+        "Subtitle Char",
+        r#"<w:uiPriority w:val="11"/><w:qFormat/>"#,
+        r#"<w:pPr><w:spacing w:after="160"/></w:pPr>"#,
+        r#"<w:rPr><w:rFonts w:eastAsiaTheme="minorEastAsia"/><w:color w:val="5A5A5A" w:themeColor="text1" w:themeTint="A5"/><w:spacing w:val="15"/><w:sz w:val="22"/><w:szCs w:val="22"/></w:rPr>"#,
+    ),
+    (
+        "Quote",
+        "Quote",
+        "Quote Char",
+        r#"<w:uiPriority w:val="29"/><w:qFormat/>"#,
+        r#"<w:pPr><w:spacing w:before="200" w:after="160"/><w:ind w:left="864" w:right="864"/><w:jc w:val="center"/></w:pPr>"#,
+        r#"<w:rPr><w:i/><w:iCs/><w:color w:val="404040" w:themeColor="text1" w:themeTint="BF"/></w:rPr>"#,
+    ),
+    (
+        "IntenseQuote",
+        "Intense Quote",
+        "Intense Quote Char",
+        r#"<w:uiPriority w:val="30"/><w:qFormat/>"#,
+        r#"<w:pPr><w:pBdr><w:top w:val="single" w:sz="4" w:space="10" w:color="4472C4" w:themeColor="accent1"/><w:bottom w:val="single" w:sz="4" w:space="10" w:color="4472C4" w:themeColor="accent1"/></w:pBdr><w:spacing w:before="360" w:after="360"/><w:ind w:left="864" w:right="864"/><w:jc w:val="center"/></w:pPr>"#,
+        r#"<w:rPr><w:i/><w:iCs/><w:color w:val="4472C4" w:themeColor="accent1"/></w:rPr>"#,
+    ),
+    (
+        "Header",
+        "header",
+        "Header Char",
+        r#"<w:uiPriority w:val="99"/><w:unhideWhenUsed/>"#,
+        r#"<w:pPr><w:tabs><w:tab w:val="center" w:pos="4680"/><w:tab w:val="right" w:pos="9360"/></w:tabs><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr>"#,
+        "",
+    ),
+    (
+        "Footer",
+        "footer",
+        "Footer Char",
+        r#"<w:uiPriority w:val="99"/><w:unhideWhenUsed/>"#,
+        r#"<w:pPr><w:tabs><w:tab w:val="center" w:pos="4680"/><w:tab w:val="right" w:pos="9360"/></w:tabs><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr>"#,
+        "",
+    ),
+    (
+        "FootnoteText",
+        "footnote text",
+        "Footnote Text Char",
+        r#"<w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr>"#,
+        r#"<w:rPr><w:sz w:val="20"/><w:szCs w:val="20"/></w:rPr>"#,
+    ),
+    (
+        "EndnoteText",
+        "endnote text",
+        "Endnote Text Char",
+        r#"<w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr>"#,
+        r#"<w:rPr><w:sz w:val="20"/><w:szCs w:val="20"/></w:rPr>"#,
+    ),
+    (
+        "CommentText",
+        "annotation text",
+        "Comment Text Char",
+        r#"<w:uiPriority w:val="99"/><w:unhideWhenUsed/>"#,
+        r#"<w:pPr><w:spacing w:line="240" w:lineRule="auto"/></w:pPr>"#,
+        r#"<w:rPr><w:sz w:val="20"/><w:szCs w:val="20"/></w:rPr>"#,
+    ),
+    (
+        "BalloonText",
+        "Balloon Text",
+        "Balloon Text Char",
+        r#"<w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr>"#,
+        r#"<w:rPr><w:rFonts w:ascii="Segoe UI" w:hAnsi="Segoe UI" w:cs="Segoe UI"/><w:sz w:val="18"/><w:szCs w:val="18"/></w:rPr>"#,
+    ),
+];
+
+// Paragraph styles without a linked character style: (style id, name, relations and attributes, properties)
+const PARAGRAPH_STYLES: &[(&str, &str, &str, &str)] = &[
+    (
+        "NoSpacing",
+        "No Spacing",
+        r#"<w:uiPriority w:val="1"/><w:qFormat/>"#,
+        r#"<w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr>"#,
+    ),
+    (
+        "ListParagraph",
+        "List Paragraph",
+        r#"<w:basedOn w:val="Normal"/><w:uiPriority w:val="34"/><w:qFormat/>"#,
+        r#"<w:pPr><w:ind w:left="720"/><w:contextualSpacing/></w:pPr>"#,
+    ),
+    (
+        "Caption",
+        "caption",
+        r#"<w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:uiPriority w:val="35"/><w:semiHidden/><w:unhideWhenUsed/><w:qFormat/>"#,
+        r#"<w:pPr><w:spacing w:after="200" w:line="240" w:lineRule="auto"/></w:pPr><w:rPr><w:i/><w:iCs/><w:color w:val="44546A" w:themeColor="text2"/><w:sz w:val="18"/><w:szCs w:val="18"/></w:rPr>"#,
+    ),
+    (
+        "TOCHeading",
+        "TOC Heading",
+        r#"<w:basedOn w:val="Heading1"/><w:next w:val="Normal"/><w:uiPriority w:val="39"/><w:semiHidden/><w:unhideWhenUsed/><w:qFormat/>"#,
+        r#"<w:pPr><w:outlineLvl w:val="9"/></w:pPr>"#,
+    ),
+];
+
+// Character styles: (style id, name, relations and attributes, run properties)
+const CHARACTER_STYLES: &[(&str, &str, &str, &str)] = &[
+    (
+        "Strong",
+        "Strong",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="22"/><w:qFormat/>"#,
+        r#"<w:rPr><w:b/><w:bCs/></w:rPr>"#,
+    ),
+    (
+        "Emphasis",
+        "Emphasis",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="20"/><w:qFormat/>"#,
+        r#"<w:rPr><w:i/><w:iCs/></w:rPr>"#,
+    ),
+    (
+        "SubtleEmphasis",
+        "Subtle Emphasis",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="19"/><w:qFormat/>"#,
+        r#"<w:rPr><w:i/><w:iCs/><w:color w:val="404040" w:themeColor="text1" w:themeTint="BF"/></w:rPr>"#,
+    ),
+    (
+        "IntenseEmphasis",
+        "Intense Emphasis",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="21"/><w:qFormat/>"#,
+        r#"<w:rPr><w:i/><w:iCs/><w:color w:val="4472C4" w:themeColor="accent1"/></w:rPr>"#,
+    ),
+    (
+        "SubtleReference",
+        "Subtle Reference",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="31"/><w:qFormat/>"#,
+        r#"<w:rPr><w:smallCaps/><w:color w:val="5A5A5A" w:themeColor="text1" w:themeTint="A5"/></w:rPr>"#,
+    ),
+    (
+        "IntenseReference",
+        "Intense Reference",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="32"/><w:qFormat/>"#,
+        r#"<w:rPr><w:b/><w:bCs/><w:smallCaps/><w:color w:val="4472C4" w:themeColor="accent1"/><w:spacing w:val="5"/></w:rPr>"#,
+    ),
+    (
+        "BookTitle",
+        "Book Title",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="33"/><w:qFormat/>"#,
+        r#"<w:rPr><w:b/><w:bCs/><w:i/><w:iCs/><w:spacing w:val="5"/></w:rPr>"#,
+    ),
+    (
+        "Hyperlink",
+        "Hyperlink",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="99"/><w:unhideWhenUsed/>"#,
+        r#"<w:rPr><w:color w:val="0563C1" w:themeColor="hyperlink"/><w:u w:val="single"/></w:rPr>"#,
+    ),
+    (
+        "FollowedHyperlink",
+        "FollowedHyperlink",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:rPr><w:color w:val="954F72" w:themeColor="followedHyperlink"/><w:u w:val="single"/></w:rPr>"#,
+    ),
+    (
+        "FootnoteReference",
+        "footnote reference",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:rPr><w:vertAlign w:val="superscript"/></w:rPr>"#,
+    ),
+    (
+        "EndnoteReference",
+        "endnote reference",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:rPr><w:vertAlign w:val="superscript"/></w:rPr>"#,
+    ),
+    (
+        "CommentReference",
+        "annotation reference",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/>"#,
+        r#"<w:rPr><w:sz w:val="16"/><w:szCs w:val="16"/></w:rPr>"#,
+    ),
+    (
+        "PlaceholderText",
+        "Placeholder Text",
+        r#"<w:basedOn w:val="DefaultParagraphFont"/><w:uiPriority w:val="99"/><w:semiHidden/>"#,
+        r#"<w:rPr><w:color w:val="808080"/></w:rPr>"#,
+    ),
+];
+
+const NORMAL_XML: &str =
+    r#"<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style>"#;
+
+const DEFAULT_PARAGRAPH_FONT_XML: &str = r#"<w:style w:type="character" w:default="1" w:styleId="DefaultParagraphFont"><w:name w:val="Default Paragraph Font"/><w:uiPriority w:val="1"/><w:semiHidden/><w:unhideWhenUsed/></w:style>"#;
+
+const TABLE_NORMAL_XML: &str = r#"<w:style w:type="table" w:default="1" w:styleId="TableNormal"><w:name w:val="Normal Table"/><w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/><w:tblPr><w:tblInd w:w="0" w:type="dxa"/><w:tblCellMar><w:top w:w="0" w:type="dxa"/><w:left w:w="108" w:type="dxa"/><w:bottom w:w="0" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style>"#;
+
+const TABLE_GRID_XML: &str = r#"<w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:basedOn w:val="TableNormal"/><w:uiPriority w:val="39"/><w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/></w:pPr><w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:left w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:right w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideH w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="auto"/></w:tblBorders></w:tblPr></w:style>"#;
+
+const NO_LIST_XML: &str = r#"<w:style w:type="numbering" w:default="1" w:styleId="NoList"><w:name w:val="No List"/><w:uiPriority w:val="99"/><w:semiHidden/><w:unhideWhenUsed/></w:style>"#;
+
+// The catalogue of Word's built-in styles, built on first use
+pub fn builtin_style_definitions() -> &'static [BuiltinStyleDefinition] {
+    static DEFINITIONS: OnceLock<Vec<BuiltinStyleDefinition>> = OnceLock::new();
+    DEFINITIONS.get_or_init(build_builtin_style_definitions)
+}
+
+fn build_builtin_style_definitions() -> Vec<BuiltinStyleDefinition> {
+    let mut definitions = vec![BuiltinStyleDefinition::new(
+        "Normal",
+        "Normal",
+        String::from(NORMAL_XML),
+    )];
+
+    for (style_id, name, relations, properties) in PARAGRAPH_STYLES {
+        definitions.push(paragraph_style(style_id, name, relations, properties));
+    }
+
+    for level in 1..=9 {
+        definitions.extend(heading_styles(level));
+    }
+
+    for (style_id, name, char_name, attributes, paragraph_properties, run_properties) in LINKED_PARAGRAPH_STYLES {
+        let char_style_id = format!("{}Char", style_id);
+        let relations = format!(
+            r#"<w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:link w:val="{}"/>{}"#,
+            char_style_id, attributes
+        );
+        let properties = format!("{}{}", paragraph_properties, run_properties);
+        definitions.push(paragraph_style(style_id, name, &relations, &properties));
+        definitions.push(linked_character_style(
+            &char_style_id,
+            char_name,
+            style_id,
+            attributes,
+            run_properties,
+        ));
+    }
+
+    definitions.extend((1..=9).map(toc_style));
+
+    definitions.push(BuiltinStyleDefinition::new(
+        "DefaultParagraphFont",
+        "Default Paragraph Font",
+        String::from(DEFAULT_PARAGRAPH_FONT_XML),
+    ));
+    for (style_id, name, relations, properties) in CHARACTER_STYLES {
+        definitions.push(character_style(style_id, name, relations, properties));
+    }
+
+    definitions.push(BuiltinStyleDefinition::new(
+        "TableNormal",
+        "Normal Table",
+        String::from(TABLE_NORMAL_XML),
+    ));
+    definitions.push(BuiltinStyleDefinition::new(
+        "TableGrid",
+        "Table Grid",
+        String::from(TABLE_GRID_XML),
+    ));
+    definitions.push(BuiltinStyleDefinition::new(
+        "NoList",
+        "No List",
+        String::from(NO_LIST_XML),
+    ));
+
+    definitions
+}
+
+pub fn builtin_style_definition(style_id: &str) -> Option<&'static BuiltinStyleDefinition> {
+    builtin_style_definitions()
+        .iter()
+        .find(|definition| definition.style_id == style_id)
+}
+
+pub fn builtin_style_definition_by_name(name: &str) -> Option<&'static BuiltinStyleDefinition> {
+    builtin_style_definitions()
+        .iter()
+        .find(|definition| definition.name.eq_ignore_ascii_case(name))
+}
+
+// The standard definition of a built-in style, parsed once and shared by every document. The latent style attributes
+// are left out since they take no part in formatting.
+pub fn standard_builtin_style(style_id: &str) -> Option<&'static Style> {
+    static STYLES: OnceLock<Vec<Style>> = OnceLock::new();
+    STYLES
+        .get_or_init(|| {
+            builtin_style_definitions()
+                .iter()
+                .map(BuiltinStyleDefinition::to_style)
+                .collect()
+        })
+        .iter()
+        .find(|style| style.style_id.as_deref() == Some(style_id))
+}
+
+// The standard definition of a built-in style with the latent style attributes of the document applied
+pub fn builtin_style(style_id: &str, latent_styles: Option<&LatentStyles>) -> Option<Style> {
+    let definition = builtin_style_definition(style_id)?;
+    let mut style = definition.to_style();
+    if let Some(latent_styles) = latent_styles {
+        latent_styles.attributes(&definition.name).apply_to_style(&mut style);
+    }
+
+    Some(style)
+}
+
+impl Package {
+    // The latent style attributes of a style by its name. Returns the defaults of ECMA-376 when there are no latent
+    // styles in the document.
+    pub fn latent_style_attributes(&self, style_name: &str) -> LatentStyleAttributes {
+        self.styles
+            .as_ref()
+            .and_then(|styles| styles.latent_styles.as_ref())
+            .map(|latent_styles| latent_styles.attributes(style_name))
+            .unwrap_or_default()
+    }
+
+    pub fn builtin_style(&self, style_id: &str) -> Option<Style> {
+        let latent_styles = self.styles.as_ref().and_then(|styles| styles.latent_styles.as_ref());
+        builtin_style(style_id, latent_styles)
+    }
+
+    // Adds the standard definition of a built-in style to the styles of the document unless it's already defined.
+    // The styles it's based on and its linked style are materialised too. Returns the ids of the added styles.
+    pub fn materialize_builtin_style(&mut self, style_id: &str) -> Vec<String> {
+        let mut materialized_style_ids = Vec::new();
+        let mut pending_style_ids = vec![String::from(style_id)];
+
+        while let Some(style_id) = pending_style_ids.pop() {
+            if self.find_style_by_id(&style_id).is_some() {
+                continue;
+            }
+
+            let style = match self.builtin_style(&style_id) {
+                Some(style) => style,
+                None => continue,
+            };
+
+            pending_style_ids.extend(style.based_on.iter().chain(style.link.iter()).cloned());
+            self.styles
+                .get_or_insert_with(|| Box::new(Styles::default()))
+                .styles
+                .push(style);
+            materialized_style_ids.push(style_id);
+        }
+
+        materialized_style_ids
+    }
+
+    fn find_style_by_id(&self, style_id: &str) -> Option<&Style> {
+        self.styles
+            .as_ref()?
+            .styles
+            .iter()
+            .find(|style| style.style_id.as_deref() == Some(style_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{builtin_style, builtin_style_definition_by_name, builtin_style_definitions, LatentStyleAttributes};
+    use crate::{
+        package::Package,
+        wml::{
+            document::RPrBase,
+            styles::{LatentStyles, LsdException, StyleType, Styles},
+        },
+    };
+
+    fn latent_styles_for_test() -> LatentStyles {
+        LatentStyles {
+            lsd_exceptions: vec![LsdException {
+                name: String::from("heading 3"),
+                locked: None,
+                ui_priority: Some(5),
+                semi_hidden: Some(false),
+                unhide_when_used: None,
+                primary_style: Some(true),
+            }],
+            default_locked_state: Some(true),
+            default_ui_priority: Some(50),
+            default_semi_hidden: Some(true),
+            default_unhide_when_used: Some(true),
+            default_primary_style: Some(false),
+            count: None,
+        }
+    }
+
+    #[test]
+    pub fn test_builtin_style_definitions() {
+        for definition in builtin_style_definitions() {
+            let style = definition.to_style();
+            assert_eq!(style.style_id.as_deref(), Some(definition.style_id.as_str()));
+            assert_eq!(style.name.as_deref(), Some(definition.name.as_str()));
+        }
+
+        let heading = builtin_style_definition_by_name("Heading 3").unwrap().to_style();
+        assert_eq!(heading.style_id.as_deref(), Some("Heading3"));
+        assert_eq!(heading.based_on.as_deref(), Some("Normal"));
+        assert_eq!(heading.link.as_deref(), Some("Heading3Char"));
+        assert_eq!(heading.paragraph_properties.unwrap().base.outline_level, Some(2));
+
+        let heading = builtin_style("Heading8", None).unwrap();
+        let color = heading
+            .run_properties
+            .unwrap()
+            .r_pr_bases
+            .into_iter()
+            .find_map(|r_pr_base| match r_pr_base {
+                RPrBase::Color(color) => Some(color),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(color.theme_tint, Some(0xD8));
+        assert_eq!(color.theme_shade, None);
+
+        let heading_char = builtin_style("Heading3Char", None).unwrap();
+        assert_eq!(heading_char.style_type, Some(StyleType::Character));
+        assert_eq!(heading_char.link.as_deref(), Some("Heading3"));
+        assert_eq!(builtin_style("Missing", None), None);
+
+        // Normal and Default Paragraph Font are the default styles of their types
+        assert_eq!(builtin_style("Normal", None).unwrap().is_default, Some(true));
+        assert_eq!(
+            builtin_style("DefaultParagraphFont", None).unwrap().is_default,
+            Some(true)
+        );
+        assert_eq!(builtin_style("Heading1", None).unwrap().is_default, None);
+    }
+
+    #[test]
+    pub fn test_latent_style_attributes() {
+        let latent_styles = latent_styles_for_test();
+        assert_eq!(
+            latent_styles.attributes("Heading 3"),
+            LatentStyleAttributes {
+                locked: true,
+                ui_priority: 5,
+                semi_hidden: false,
+                unhide_when_used: true,
+                primary_style: true,
+            }
+        );
+        assert_eq!(latent_styles.attributes("Quote").ui_priority, 50);
+
+        // Attributes of the definition take precedence over the latent ones
+        let heading = builtin_style("Heading3", Some(&latent_styles)).unwrap();
+        assert_eq!(heading.ui_priority, Some(9));
+        assert_eq!(heading.semi_hidden, Some(true));
+        assert_eq!(heading.locked, Some(true));
+        assert_eq!(Package::default().latent_style_attributes("Normal"), Default::default());
+    }
+
+    #[test]
+    pub fn test_materialize_builtin_style() {
+        let mut package = Package {
+            styles: Some(Box::new(Styles {
+                document_defaults: None,
+                latent_styles: Some(latent_styles_for_test()),
+                styles: Vec::new(),
+            })),
+            ..Default::default()
+        };
+        // Styles missing from the document resolve to the standard definitions
+        let builtin_heading = package.style_resolver().resolve_style_with_id("Heading3").unwrap();
+        assert_eq!(builtin_heading.paragraph_properties.outline_level, Some(2));
+        assert_eq!(package.style_resolver().resolve_style_with_id("Missing"), None);
+
+        let mut materialized_style_ids = package.materialize_builtin_style("Heading3");
+        materialized_style_ids.sort();
+        assert_eq!(
+            materialized_style_ids,
+            vec!["DefaultParagraphFont", "Heading3", "Heading3Char", "Normal"]
+        );
+        assert!(package.materialize_builtin_style("Heading3").is_empty());
+
+        let heading = package.style_resolver().resolve_style_with_id("Heading3").unwrap();
+        assert_eq!(heading.paragraph_properties.keep_with_next, Some(true));
+        assert_eq!(heading, builtin_heading);
+
+        // The styles of the document take precedence over the standard definitions
+        let styles = &mut package.styles.as_mut().unwrap().styles;
+        let heading_style = styles
+            .iter_mut()
+            .find(|style| style.style_id.as_deref() == Some("Heading3"))
+            .unwrap();
+        heading_style.paragraph_properties = None;
+        let heading = package.style_resolver().resolve_style_with_id("Heading3").unwrap();
+        assert_eq!(heading.paragraph_properties.keep_with_next, None);
+    }
+}
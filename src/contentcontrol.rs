@@ -0,0 +1,975 @@
+use crate::{
+    fieldformat::FieldDateTime,
+    package::Package,
+    search::run_inner_content_text,
+    visit::{
+        walk_paragraph, walk_sdt_block, walk_sdt_block_mut, walk_sdt_cell, walk_sdt_cell_mut, walk_sdt_row,
+        walk_sdt_row_mut, walk_sdt_run, walk_sdt_run_mut, Visit, VisitMut,
+    },
+    wml::{
+        document::{
+            Br, ContentBlockContent, ContentRunContent, Lock, PContent, PPr, RPr, RunInnerContent, SdtBlock,
+            SdtListItem, SdtPr, SdtPrChoice, SdtRun, Text, P, R,
+        },
+        table::{SdtCell, SdtRow},
+    },
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+const DEFAULT_DATE_FORMAT: &str = "M/d/yyyy";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentControlStory {
+    MainDocument,
+    Header(String),
+    Footer(String),
+    Footnote(i32),
+    Endnote(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentControlLevel {
+    Block,
+    Run,
+    Row,
+    Cell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentControlType {
+    RichText,
+    PlainText,
+    ComboBox,
+    DropDownList,
+    Date,
+    Picture,
+    DocumentPartObject,
+    DocumentPartList,
+    Equation,
+    Citation,
+    Group,
+    Bibliography,
+}
+
+impl ContentControlType {
+    // Content controls without a type are rich text controls
+    pub fn from_choice(choice: Option<&SdtPrChoice>) -> Self {
+        match choice {
+            None | Some(SdtPrChoice::RichText) => ContentControlType::RichText,
+            Some(SdtPrChoice::Text(_)) => ContentControlType::PlainText,
+            Some(SdtPrChoice::ComboBox(_)) => ContentControlType::ComboBox,
+            Some(SdtPrChoice::DropDownList(_)) => ContentControlType::DropDownList,
+            Some(SdtPrChoice::Date(_)) => ContentControlType::Date,
+            Some(SdtPrChoice::Picture) => ContentControlType::Picture,
+            Some(SdtPrChoice::DocumentPartObject(_)) => ContentControlType::DocumentPartObject,
+            Some(SdtPrChoice::DocumentPartList(_)) => ContentControlType::DocumentPartList,
+            Some(SdtPrChoice::Equation) => ContentControlType::Equation,
+            Some(SdtPrChoice::Citation) => ContentControlType::Citation,
+            Some(SdtPrChoice::Group) => ContentControlType::Group,
+            Some(SdtPrChoice::Bibliography) => ContentControlType::Bibliography,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentControl {
+    // Position in document order, see Package::content_controls
+    pub index: usize,
+    pub story: ContentControlStory,
+    pub level: ContentControlLevel,
+    pub control_type: ContentControlType,
+    pub id: Option<i32>,
+    pub tag: Option<String>,
+    pub alias: Option<String>,
+    pub lock: Option<Lock>,
+    pub showing_placeholder: bool,
+    // The displayed text, including placeholder text
+    pub text: String,
+    // Empty while the placeholder is shown, the selected item value of lists and the full date of dates
+    pub value: String,
+    pub list_items: Vec<SdtListItem>,
+    pub date_format: Option<String>,
+    pub is_multi_line: bool,
+    // The controls nested in this one, they follow it in document order
+    pub nested_control_count: usize,
+}
+
+impl ContentControl {
+    fn new(index: usize, story: ContentControlStory, level: ContentControlLevel, sdt_pr: Option<&SdtPr>) -> Self {
+        let choice = sdt_pr.and_then(|sdt_pr| sdt_pr.control_choice.as_ref());
+        let (list_items, date_format, is_multi_line) = match choice {
+            Some(SdtPrChoice::ComboBox(combo_box)) => (combo_box.list_items.clone(), None, false),
+            Some(SdtPrChoice::DropDownList(drop_down_list)) => (drop_down_list.list_items.clone(), None, false),
+            Some(SdtPrChoice::Date(date)) => (Vec::new(), date.date_format.clone(), false),
+            Some(SdtPrChoice::Text(text)) => (Vec::new(), None, text.is_multi_line),
+            _ => (Vec::new(), None, false),
+        };
+
+        Self {
+            index,
+            story,
+            level,
+            control_type: ContentControlType::from_choice(choice),
+            id: sdt_pr.and_then(|sdt_pr| sdt_pr.id),
+            tag: sdt_pr.and_then(|sdt_pr| sdt_pr.tag.clone()),
+            alias: sdt_pr.and_then(|sdt_pr| sdt_pr.alias.clone()),
+            lock: sdt_pr.and_then(|sdt_pr| sdt_pr.lock),
+            showing_placeholder: sdt_pr
+                .and_then(|sdt_pr| sdt_pr.showing_placeholder_header)
+                .unwrap_or(false),
+            text: String::new(),
+            value: String::new(),
+            list_items,
+            date_format,
+            is_multi_line,
+            nested_control_count: 0,
+        }
+    }
+
+    fn with_text(mut self, text: String, choice: Option<&SdtPrChoice>) -> Self {
+        if !self.showing_placeholder {
+            let list_value = |list_items: &[SdtListItem], last_value: &Option<String>| {
+                list_items
+                    .iter()
+                    .find(|item| item.display_text == text)
+                    .map(|item| item.value.clone())
+                    .or_else(|| last_value.clone())
+            };
+
+            self.value = match choice {
+                Some(SdtPrChoice::ComboBox(combo_box)) => list_value(&combo_box.list_items, &combo_box.last_value),
+                Some(SdtPrChoice::DropDownList(drop_down_list)) => {
+                    list_value(&drop_down_list.list_items, &drop_down_list.last_value)
+                }
+                Some(SdtPrChoice::Date(date)) => date.full_date.clone(),
+                _ => None,
+            }
+            .unwrap_or_else(|| text.clone());
+        }
+
+        self.text = text;
+        self
+    }
+
+    // The control itself can't be deleted, but its contents can still be edited when only sdtLocked is set
+    pub fn is_content_locked(&self) -> bool {
+        matches!(self.lock, Some(Lock::ContentLocked) | Some(Lock::SdtContentLocked))
+    }
+
+    pub fn is_control_locked(&self) -> bool {
+        matches!(self.lock, Some(Lock::SdtLocked) | Some(Lock::SdtContentLocked))
+    }
+
+    fn validate_update(&self, update: &ContentControlUpdate, nested_controls: &[ContentControl]) -> Result<()> {
+        if self.is_content_locked() {
+            return Err(ContentControlError::ContentLocked(self.index));
+        }
+
+        // Replacing the contents deletes the nested controls
+        if let Some(nested_control) = nested_controls.iter().find(|control| control.is_control_locked()) {
+            return Err(ContentControlError::NestedControlLocked {
+                index: self.index,
+                nested_index: nested_control.index,
+            });
+        }
+
+        if self.level == ContentControlLevel::Row || self.level == ContentControlLevel::Cell {
+            return Err(ContentControlError::UnsupportedLevel {
+                index: self.index,
+                level: self.level,
+            });
+        }
+
+        let is_valid_type = match update {
+            ContentControlUpdate::Text(_) => matches!(
+                self.control_type,
+                ContentControlType::RichText | ContentControlType::PlainText | ContentControlType::ComboBox
+            ),
+            ContentControlUpdate::ListItem(_) => matches!(
+                self.control_type,
+                ContentControlType::ComboBox | ContentControlType::DropDownList
+            ),
+            ContentControlUpdate::Date(_) => self.control_type == ContentControlType::Date,
+            ContentControlUpdate::RichContent(_) => self.control_type == ContentControlType::RichText,
+        };
+        if !is_valid_type {
+            return Err(ContentControlError::TypeMismatch {
+                index: self.index,
+                control_type: self.control_type,
+            });
+        }
+
+        // Combo boxes accept any text, drop-down lists only their own items
+        match update {
+            ContentControlUpdate::ListItem(value)
+                if self.control_type == ContentControlType::DropDownList
+                    && find_list_item(&self.list_items, value).is_none() =>
+            {
+                Err(ContentControlError::ListItemNotFound {
+                    index: self.index,
+                    value: value.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentControlSelector {
+    Index(usize),
+    Id(i32),
+    Tag(String),
+    Alias(String),
+}
+
+impl ContentControlSelector {
+    pub fn matches(&self, control: &ContentControl) -> bool {
+        self.matches_properties(
+            control.index,
+            control.id,
+            control.tag.as_deref(),
+            control.alias.as_deref(),
+        )
+    }
+
+    fn matches_properties(&self, index: usize, id: Option<i32>, tag: Option<&str>, alias: Option<&str>) -> bool {
+        match self {
+            ContentControlSelector::Index(selected_index) => *selected_index == index,
+            ContentControlSelector::Id(selected_id) => Some(*selected_id) == id,
+            ContentControlSelector::Tag(selected_tag) => Some(selected_tag.as_str()) == tag,
+            ContentControlSelector::Alias(selected_alias) => Some(selected_alias.as_str()) == alias,
+        }
+    }
+
+    fn matches_sdt_properties(&self, index: usize, sdt_pr: Option<&SdtPr>) -> bool {
+        self.matches_properties(
+            index,
+            sdt_pr.and_then(|sdt_pr| sdt_pr.id),
+            sdt_pr.and_then(|sdt_pr| sdt_pr.tag.as_deref()),
+            sdt_pr.and_then(|sdt_pr| sdt_pr.alias.as_deref()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentControlUpdate {
+    Text(String),
+    // Matched against the values first and the display texts second
+    ListItem(String),
+    Date(FieldDateTime),
+    RichContent(Vec<PContent>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentControlError {
+    NotFound(ContentControlSelector),
+    ContentLocked(usize),
+    NestedControlLocked {
+        index: usize,
+        nested_index: usize,
+    },
+    TypeMismatch {
+        index: usize,
+        control_type: ContentControlType,
+    },
+    UnsupportedLevel {
+        index: usize,
+        level: ContentControlLevel,
+    },
+    ListItemNotFound {
+        index: usize,
+        value: String,
+    },
+}
+
+impl Display for ContentControlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContentControlError::NotFound(selector) => write!(f, "No content control matches {:?}", selector),
+            ContentControlError::ContentLocked(index) => write!(f, "Contents of content control {} are locked", index),
+            ContentControlError::NestedControlLocked { index, nested_index } => write!(
+                f,
+                "Content control {} contains content control {} which can't be deleted",
+                index, nested_index
+            ),
+            ContentControlError::TypeMismatch { index, control_type } => write!(
+                f,
+                "Content control {} is a {:?} control and can't take this value",
+                index, control_type
+            ),
+            ContentControlError::UnsupportedLevel { index, level } => write!(
+                f,
+                "Contents of {:?} level content control {} can't be replaced",
+                level, index
+            ),
+            ContentControlError::ListItemNotFound { index, value } => {
+                write!(f, "Content control {} has no list item '{}'", index, value)
+            }
+        }
+    }
+}
+
+impl Error for ContentControlError {}
+
+type Result<T> = std::result::Result<T, ContentControlError>;
+
+fn find_list_item<'a>(list_items: &'a [SdtListItem], value: &str) -> Option<&'a SdtListItem> {
+    list_items
+        .iter()
+        .find(|item| item.value == value)
+        .or_else(|| list_items.iter().find(|item| item.display_text == value))
+}
+
+// Headers and footers are stored by part name, Word numbers them in the order they are referenced
+fn sorted_part_names<T>(parts: &HashMap<String, T>) -> Vec<String> {
+    let mut names: Vec<String> = parts.keys().cloned().collect();
+    names.sort_by(|lhs, rhs| lhs.len().cmp(&rhs.len()).then_with(|| lhs.cmp(rhs)));
+    names
+}
+
+#[derive(Debug, Default)]
+struct TextCollector {
+    text: String,
+    paragraph_count: usize,
+}
+
+impl<'a> Visit<'a> for TextCollector {
+    fn visit_paragraph(&mut self, paragraph: &'a P) {
+        if self.paragraph_count > 0 {
+            self.text.push('\n');
+        }
+        self.paragraph_count += 1;
+        walk_paragraph(self, paragraph);
+    }
+
+    fn visit_run(&mut self, run: &'a R) {
+        for content in &run.run_inner_contents {
+            if let Some(text) = run_inner_content_text(content) {
+                self.text.push_str(text);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct FirstRunProperties<'a> {
+    properties: Option<&'a RPr>,
+    is_found: bool,
+}
+
+impl<'a> Visit<'a> for FirstRunProperties<'a> {
+    fn visit_run(&mut self, run: &'a R) {
+        if !self.is_found {
+            self.is_found = true;
+            self.properties = run.run_properties.as_ref();
+        }
+    }
+}
+
+struct ContentControlCollector {
+    story: ContentControlStory,
+    controls: Vec<ContentControl>,
+}
+
+impl ContentControlCollector {
+    // Returns the index of the new control
+    fn push<F>(&mut self, level: ContentControlLevel, sdt_pr: Option<&SdtPr>, collect_text: F) -> usize
+    where
+        F: FnOnce(&mut TextCollector),
+    {
+        let mut text_collector: TextCollector = Default::default();
+        collect_text(&mut text_collector);
+
+        let index = self.controls.len();
+        let control = ContentControl::new(index, self.story.clone(), level, sdt_pr);
+        let choice = sdt_pr.and_then(|sdt_pr| sdt_pr.control_choice.as_ref());
+        self.controls.push(control.with_text(text_collector.text, choice));
+        index
+    }
+
+    // Called once the contents of the control have been visited
+    fn count_nested_controls(&mut self, index: usize) {
+        self.controls[index].nested_control_count = self.controls.len() - index - 1;
+    }
+}
+
+impl<'a> Visit<'a> for ContentControlCollector {
+    fn visit_sdt_block(&mut self, sdt: &'a SdtBlock) {
+        let index = self.push(ContentControlLevel::Block, sdt.sdt_properties.as_ref(), |collector| {
+            walk_sdt_block(collector, sdt)
+        });
+        walk_sdt_block(self, sdt);
+        self.count_nested_controls(index);
+    }
+
+    fn visit_sdt_run(&mut self, sdt: &'a SdtRun) {
+        let index = self.push(ContentControlLevel::Run, sdt.sdt_properties.as_ref(), |collector| {
+            walk_sdt_run(collector, sdt)
+        });
+        walk_sdt_run(self, sdt);
+        self.count_nested_controls(index);
+    }
+
+    fn visit_sdt_row(&mut self, sdt: &'a SdtRow) {
+        let index = self.push(ContentControlLevel::Row, sdt.properties.as_ref(), |collector| {
+            walk_sdt_row(collector, sdt)
+        });
+        walk_sdt_row(self, sdt);
+        self.count_nested_controls(index);
+    }
+
+    fn visit_sdt_cell(&mut self, sdt: &'a SdtCell) {
+        let index = self.push(ContentControlLevel::Cell, sdt.properties.as_ref(), |collector| {
+            walk_sdt_cell(collector, sdt)
+        });
+        walk_sdt_cell(self, sdt);
+        self.count_nested_controls(index);
+    }
+}
+
+fn text_run(text: &str, run_properties: Option<RPr>) -> Option<PContent> {
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut run_inner_contents = Vec::new();
+    for (line_index, line) in text.split('\n').enumerate() {
+        if line_index > 0 {
+            run_inner_contents.push(RunInnerContent::Break(Br::default()));
+        }
+        if !line.is_empty() {
+            run_inner_contents.push(RunInnerContent::Text(Text {
+                text: String::from(line),
+                xml_space: Some(String::from("preserve")),
+            }));
+        }
+    }
+
+    Some(PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+        run_properties,
+        run_inner_contents,
+        ..Default::default()
+    }))))
+}
+
+// Updates the properties and returns the new contents, one entry per paragraph
+fn updated_paragraphs(
+    sdt_pr: &mut SdtPr,
+    update: &ContentControlUpdate,
+    run_properties: Option<RPr>,
+    is_block_level: bool,
+) -> Vec<Vec<PContent>> {
+    sdt_pr.showing_placeholder_header = None;
+
+    let text = match (update, &mut sdt_pr.control_choice) {
+        (ContentControlUpdate::RichContent(contents), _) => return vec![contents.clone()],
+        (ContentControlUpdate::Text(text), _) => text.clone(),
+        (ContentControlUpdate::ListItem(value), Some(SdtPrChoice::ComboBox(combo_box))) => {
+            let item = find_list_item(&combo_box.list_items, value);
+            combo_box.last_value = Some(item.map(|item| item.value.clone()).unwrap_or_else(|| value.clone()));
+            item.map(|item| item.display_text.clone())
+                .unwrap_or_else(|| value.clone())
+        }
+        (ContentControlUpdate::ListItem(value), Some(SdtPrChoice::DropDownList(drop_down_list))) => {
+            let item = find_list_item(&drop_down_list.list_items, value);
+            drop_down_list.last_value = item.map(|item| item.value.clone());
+            item.map(|item| item.display_text.clone())
+                .unwrap_or_else(|| value.clone())
+        }
+        (ContentControlUpdate::ListItem(value), _) => value.clone(),
+        (ContentControlUpdate::Date(date_time), Some(SdtPrChoice::Date(date))) => {
+            date.full_date = Some(date_time.to_w3cdtf());
+            date_time.format(date.date_format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT))
+        }
+        (ContentControlUpdate::Date(date_time), _) => date_time.format(DEFAULT_DATE_FORMAT),
+    };
+
+    match &sdt_pr.control_choice {
+        Some(SdtPrChoice::Text(sdt_text)) if !sdt_text.is_multi_line => {
+            vec![text_run(&text.replace('\n', " "), run_properties).into_iter().collect()]
+        }
+        Some(SdtPrChoice::Text(_)) => vec![text_run(&text, run_properties).into_iter().collect()],
+        _ if is_block_level => text
+            .split('\n')
+            .map(|line| text_run(line, run_properties.clone()).into_iter().collect())
+            .collect(),
+        _ => vec![text_run(&text, run_properties).into_iter().collect()],
+    }
+}
+
+struct ContentControlUpdater<'a> {
+    selector: &'a ContentControlSelector,
+    update: &'a ContentControlUpdate,
+    next_index: usize,
+    updated_count: usize,
+}
+
+impl<'a> ContentControlUpdater<'a> {
+    fn new(selector: &'a ContentControlSelector, update: &'a ContentControlUpdate) -> Self {
+        Self {
+            selector,
+            update,
+            next_index: 0,
+            updated_count: 0,
+        }
+    }
+
+    fn is_selected(&mut self, sdt_pr: Option<&SdtPr>) -> bool {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.selector.matches_sdt_properties(index, sdt_pr)
+    }
+
+    fn run_properties(sdt_pr: &SdtPr, first_run_properties: FirstRunProperties) -> Option<RPr> {
+        // The placeholder text has its own style, new text gets the formatting of the control
+        if sdt_pr.showing_placeholder_header == Some(true) {
+            sdt_pr.run_properties.clone()
+        } else {
+            first_run_properties.properties.cloned()
+        }
+    }
+
+    fn update_sdt_block(&mut self, sdt: &mut SdtBlock) {
+        let block_contents = sdt.sdt_content.get_or_insert_with(Default::default);
+        let sdt_pr = sdt.sdt_properties.get_or_insert_with(Default::default);
+
+        let mut first_run_properties: FirstRunProperties = Default::default();
+        for content in &block_contents.block_contents {
+            first_run_properties.visit_content_block_content(content);
+        }
+        let run_properties = Self::run_properties(sdt_pr, first_run_properties);
+        let paragraph_properties: Option<PPr> = block_contents
+            .block_contents
+            .iter()
+            .find_map(|content| match content {
+                ContentBlockContent::Paragraph(paragraph) => Some(paragraph.properties.clone()),
+                _ => None,
+            })
+            .flatten();
+
+        block_contents.block_contents = updated_paragraphs(sdt_pr, self.update, run_properties, true)
+            .into_iter()
+            .map(|contents| {
+                ContentBlockContent::Paragraph(Box::new(P {
+                    properties: paragraph_properties.clone(),
+                    contents,
+                    ..Default::default()
+                }))
+            })
+            .collect();
+        self.updated_count += 1;
+    }
+
+    fn update_sdt_run(&mut self, sdt: &mut SdtRun) {
+        let run_contents = sdt.sdt_content.get_or_insert_with(Default::default);
+        let sdt_pr = sdt.sdt_properties.get_or_insert_with(Default::default);
+
+        let mut first_run_properties: FirstRunProperties = Default::default();
+        for content in &run_contents.p_contents {
+            first_run_properties.visit_p_content(content);
+        }
+        let run_properties = Self::run_properties(sdt_pr, first_run_properties);
+
+        run_contents.p_contents = updated_paragraphs(sdt_pr, self.update, run_properties, false)
+            .into_iter()
+            .flatten()
+            .collect();
+        self.updated_count += 1;
+    }
+}
+
+// Nested content controls are visited before their parent is updated, so indices stay in document order
+impl<'a> VisitMut for ContentControlUpdater<'a> {
+    fn visit_sdt_block_mut(&mut self, sdt: &mut SdtBlock) {
+        let is_selected = self.is_selected(sdt.sdt_properties.as_ref());
+        walk_sdt_block_mut(self, sdt);
+        if is_selected {
+            self.update_sdt_block(sdt);
+        }
+    }
+
+    fn visit_sdt_run_mut(&mut self, sdt: &mut SdtRun) {
+        let is_selected = self.is_selected(sdt.sdt_properties.as_ref());
+        walk_sdt_run_mut(self, sdt);
+        if is_selected {
+            self.update_sdt_run(sdt);
+        }
+    }
+
+    fn visit_sdt_row_mut(&mut self, sdt: &mut SdtRow) {
+        self.is_selected(sdt.properties.as_ref());
+        walk_sdt_row_mut(self, sdt);
+    }
+
+    fn visit_sdt_cell_mut(&mut self, sdt: &mut SdtCell) {
+        self.is_selected(sdt.properties.as_ref());
+        walk_sdt_cell_mut(self, sdt);
+    }
+}
+
+impl Package {
+    // Content controls of the main document, the headers, the footers, the footnotes and the endnotes in this order.
+    // Nested controls follow their parent.
+    pub fn content_controls(&self) -> Vec<ContentControl> {
+        let mut collector = ContentControlCollector {
+            story: ContentControlStory::MainDocument,
+            controls: Vec::new(),
+        };
+
+        if let Some(document) = &self.main_document {
+            collector.visit_document(document);
+        }
+
+        for name in sorted_part_names(&self.headers) {
+            collector.story = ContentControlStory::Header(name.clone());
+            for element in &self.headers[&name].block_level_elements {
+                collector.visit_block_level_element(element);
+            }
+        }
+
+        for name in sorted_part_names(&self.footers) {
+            collector.story = ContentControlStory::Footer(name.clone());
+            for element in &self.footers[&name].block_level_elements {
+                collector.visit_block_level_element(element);
+            }
+        }
+
+        for footnote in self.footnotes.iter().flat_map(|footnotes| footnotes.0.iter()) {
+            collector.story = ContentControlStory::Footnote(footnote.id);
+            collector.visit_footnote(footnote);
+        }
+
+        for endnote in self.endnotes.iter().flat_map(|endnotes| endnotes.0.iter()) {
+            collector.story = ContentControlStory::Endnote(endnote.id);
+            collector.visit_endnote(endnote);
+        }
+
+        collector.controls
+    }
+
+    pub fn set_content_control_text(&mut self, selector: &ContentControlSelector, text: &str) -> Result<usize> {
+        self.update_content_controls(selector, &ContentControlUpdate::Text(String::from(text)))
+    }
+
+    pub fn select_content_control_item(&mut self, selector: &ContentControlSelector, value: &str) -> Result<usize> {
+        self.update_content_controls(selector, &ContentControlUpdate::ListItem(String::from(value)))
+    }
+
+    pub fn set_content_control_date(
+        &mut self,
+        selector: &ContentControlSelector,
+        date: FieldDateTime,
+    ) -> Result<usize> {
+        self.update_content_controls(selector, &ContentControlUpdate::Date(date))
+    }
+
+    pub fn set_content_control_rich_content(
+        &mut self,
+        selector: &ContentControlSelector,
+        contents: Vec<PContent>,
+    ) -> Result<usize> {
+        self.update_content_controls(selector, &ContentControlUpdate::RichContent(contents))
+    }
+
+    // Every selected control is validated before the document is changed, returns the number of updated controls
+    pub fn update_content_controls(
+        &mut self,
+        selector: &ContentControlSelector,
+        update: &ContentControlUpdate,
+    ) -> Result<usize> {
+        let controls = self.content_controls();
+        let selected_controls: Vec<&ContentControl> =
+            controls.iter().filter(|control| selector.matches(control)).collect();
+        if selected_controls.is_empty() {
+            return Err(ContentControlError::NotFound(selector.clone()));
+        }
+        for control in selected_controls {
+            let nested_controls = &controls[control.index + 1..=control.index + control.nested_control_count];
+            control.validate_update(update, nested_controls)?;
+        }
+
+        let mut updater = ContentControlUpdater::new(selector, update);
+        if let Some(document) = &mut self.main_document {
+            updater.visit_document_mut(document);
+        }
+
+        for name in sorted_part_names(&self.headers) {
+            if let Some(header) = self.headers.get_mut(&name) {
+                for element in &mut header.block_level_elements {
+                    updater.visit_block_level_element_mut(element);
+                }
+            }
+        }
+
+        for name in sorted_part_names(&self.footers) {
+            if let Some(footer) = self.footers.get_mut(&name) {
+                for element in &mut footer.block_level_elements {
+                    updater.visit_block_level_element_mut(element);
+                }
+            }
+        }
+
+        for footnote in self.footnotes.iter_mut().flat_map(|footnotes| footnotes.0.iter_mut()) {
+            updater.visit_footnote_mut(footnote);
+        }
+
+        for endnote in self.endnotes.iter_mut().flat_map(|endnotes| endnotes.0.iter_mut()) {
+            updater.visit_endnote_mut(endnote);
+        }
+
+        Ok(updater.updated_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ContentControlError, ContentControlLevel, ContentControlSelector, ContentControlStory, ContentControlType,
+    };
+    use crate::{
+        fieldformat::FieldDateTime,
+        package::Package,
+        search::ParagraphText,
+        test_util::{package_with_blocks, paragraph, paragraph_block, text_run},
+        wml::{
+            document::{
+                BlockLevelElts, ContentBlockContent, ContentRunContent, Lock, PContent, SdtBlock, SdtContentBlock,
+                SdtContentRun, SdtDate, SdtDropDownList, SdtListItem, SdtPr, SdtPrChoice, SdtRun, SdtText,
+            },
+            headerfooter::HeaderFooter,
+        },
+    };
+
+    fn sdt_run(tag: &str, control_choice: Option<SdtPrChoice>, text: &str) -> PContent {
+        PContent::ContentRunContent(Box::new(ContentRunContent::Sdt(Box::new(SdtRun {
+            sdt_properties: Some(SdtPr {
+                tag: Some(String::from(tag)),
+                control_choice,
+                ..Default::default()
+            }),
+            sdt_end_properties: None,
+            sdt_content: Some(SdtContentRun {
+                p_contents: vec![text_run(text)],
+            }),
+        }))))
+    }
+
+    fn sdt_package() -> Package {
+        let drop_down_list = SdtDropDownList {
+            list_items: vec![
+                SdtListItem {
+                    display_text: String::from("Red"),
+                    value: String::from("r"),
+                },
+                SdtListItem {
+                    display_text: String::from("Green"),
+                    value: String::from("g"),
+                },
+            ],
+            last_value: None,
+        };
+        let date = SdtDate {
+            date_format: Some(String::from("d MMMM yyyy")),
+            full_date: Some(String::from("2019-03-05T00:00:00Z")),
+            ..Default::default()
+        };
+
+        let rich_text = SdtBlock {
+            sdt_properties: Some(SdtPr {
+                alias: Some(String::from("Notes")),
+                tag: Some(String::from("notes")),
+                showing_placeholder_header: Some(true),
+                ..Default::default()
+            }),
+            sdt_end_properties: None,
+            sdt_content: Some(SdtContentBlock {
+                block_contents: vec![ContentBlockContent::Paragraph(Box::new(paragraph(vec![
+                    text_run("Click here to enter text."),
+                    sdt_run(
+                        "name",
+                        Some(SdtPrChoice::Text(SdtText { is_multi_line: false })),
+                        "Jane",
+                    ),
+                ])))],
+            }),
+        };
+
+        let mut package = package_with_blocks(vec![
+            BlockLevelElts::Chunk(ContentBlockContent::Sdt(Box::new(rich_text))),
+            paragraph_block(paragraph(vec![
+                sdt_run("color", Some(SdtPrChoice::DropDownList(drop_down_list)), "Red"),
+                sdt_run("date", Some(SdtPrChoice::Date(date)), "5 March 2019"),
+            ])),
+        ]);
+
+        package.headers.insert(
+            String::from("header1.xml"),
+            HeaderFooter {
+                block_level_elements: vec![paragraph_block(paragraph(vec![sdt_run("title", None, "Report")]))],
+            },
+        );
+        package
+    }
+
+    fn body_texts(package: &Package) -> Vec<String> {
+        let body = package.main_document.as_ref().unwrap().body.as_ref().unwrap();
+        let mut texts = Vec::new();
+        for element in &body.block_level_elements {
+            match element {
+                BlockLevelElts::Chunk(ContentBlockContent::Paragraph(paragraph)) => {
+                    texts.push(ParagraphText::from_paragraph(paragraph).text)
+                }
+                BlockLevelElts::Chunk(ContentBlockContent::Sdt(sdt)) => {
+                    for content in &sdt.sdt_content.as_ref().unwrap().block_contents {
+                        if let ContentBlockContent::Paragraph(paragraph) = content {
+                            texts.push(ParagraphText::from_paragraph(paragraph).text);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        texts
+    }
+
+    #[test]
+    pub fn test_content_controls() {
+        let controls = sdt_package().content_controls();
+        assert_eq!(controls.len(), 5);
+
+        assert_eq!(controls[0].level, ContentControlLevel::Block);
+        assert_eq!(controls[0].control_type, ContentControlType::RichText);
+        assert_eq!(controls[0].alias, Some(String::from("Notes")));
+        assert_eq!(controls[0].text, "Click here to enter text.Jane");
+        assert_eq!(controls[0].value, "");
+
+        assert_eq!(controls[1].tag, Some(String::from("name")));
+        assert_eq!(controls[1].control_type, ContentControlType::PlainText);
+        assert_eq!(controls[1].value, "Jane");
+
+        assert_eq!(controls[2].control_type, ContentControlType::DropDownList);
+        assert_eq!(controls[2].value, "r");
+        assert_eq!(controls[3].control_type, ContentControlType::Date);
+        assert_eq!(controls[3].value, "2019-03-05T00:00:00Z");
+
+        assert_eq!(controls[4].index, 4);
+        assert_eq!(
+            controls[4].story,
+            ContentControlStory::Header(String::from("header1.xml"))
+        );
+        assert_eq!(controls[4].value, "Report");
+    }
+
+    #[test]
+    pub fn test_update_content_controls() {
+        let mut package = sdt_package();
+        let tag = |tag: &str| ContentControlSelector::Tag(String::from(tag));
+
+        assert_eq!(package.select_content_control_item(&tag("color"), "Green"), Ok(1));
+        assert_eq!(
+            package.set_content_control_date(&tag("date"), FieldDateTime::new(2020, 12, 24, 0, 0, 0)),
+            Ok(1)
+        );
+        assert_eq!(package.set_content_control_text(&tag("name"), "John\nDoe"), Ok(1));
+        assert_eq!(
+            body_texts(&package),
+            vec!["Click here to enter text.John Doe", "Green24 December 2020"]
+        );
+
+        assert_eq!(package.set_content_control_text(&tag("notes"), "First\nSecond"), Ok(1));
+        assert_eq!(body_texts(&package), vec!["First", "Second", "Green24 December 2020"]);
+
+        let controls = package.content_controls();
+        assert!(!controls[0].showing_placeholder);
+        assert_eq!(controls[1].value, "g");
+        assert_eq!(controls[2].value, "2020-12-24T00:00:00Z");
+        assert_eq!(
+            package.set_content_control_text(&ContentControlSelector::Index(3), "Header"),
+            Ok(1)
+        );
+        assert_eq!(package.content_controls()[3].text, "Header");
+    }
+
+    #[test]
+    pub fn test_update_locked_content_controls() {
+        let mut package = sdt_package();
+        let selector = ContentControlSelector::Tag(String::from("title"));
+        if let Some(BlockLevelElts::Chunk(ContentBlockContent::Paragraph(paragraph))) = package
+            .headers
+            .get_mut("header1.xml")
+            .unwrap()
+            .block_level_elements
+            .first_mut()
+        {
+            if let PContent::ContentRunContent(run_content) = &mut paragraph.contents[0] {
+                if let ContentRunContent::Sdt(sdt) = run_content.as_mut() {
+                    sdt.sdt_properties.as_mut().unwrap().lock = Some(Lock::ContentLocked);
+                }
+            }
+        }
+
+        assert_eq!(
+            package.set_content_control_text(&selector, "Changed"),
+            Err(ContentControlError::ContentLocked(4))
+        );
+        assert_eq!(
+            package.select_content_control_item(&ContentControlSelector::Tag(String::from("color")), "Blue"),
+            Err(ContentControlError::ListItemNotFound {
+                index: 2,
+                value: String::from("Blue"),
+            })
+        );
+        assert_eq!(
+            package.set_content_control_date(&ContentControlSelector::Tag(String::from("name")), Default::default()),
+            Err(ContentControlError::TypeMismatch {
+                index: 1,
+                control_type: ContentControlType::PlainText,
+            })
+        );
+        assert_eq!(
+            package.set_content_control_text(&ContentControlSelector::Id(7), "Missing"),
+            Err(ContentControlError::NotFound(ContentControlSelector::Id(7)))
+        );
+    }
+
+    #[test]
+    pub fn test_update_content_control_with_locked_nested_control() {
+        let mut package = sdt_package();
+        if let Some(BlockLevelElts::Chunk(ContentBlockContent::Sdt(sdt))) = package
+            .main_document
+            .as_mut()
+            .and_then(|document| document.body.as_mut())
+            .and_then(|body| body.block_level_elements.first_mut())
+        {
+            if let Some(ContentBlockContent::Paragraph(paragraph)) =
+                sdt.sdt_content.as_mut().unwrap().block_contents.first_mut()
+            {
+                if let PContent::ContentRunContent(run_content) = &mut paragraph.contents[1] {
+                    if let ContentRunContent::Sdt(nested_sdt) = run_content.as_mut() {
+                        nested_sdt.sdt_properties.as_mut().unwrap().lock = Some(Lock::SdtLocked);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(package.content_controls()[0].nested_control_count, 1);
+        assert_eq!(
+            package.set_content_control_text(&ContentControlSelector::Tag(String::from("notes")), "Replaced"),
+            Err(ContentControlError::NestedControlLocked {
+                index: 0,
+                nested_index: 1,
+            })
+        );
+
+        // The locked control itself can still be edited
+        assert_eq!(
+            package.set_content_control_text(&ContentControlSelector::Tag(String::from("name")), "John"),
+            Ok(1)
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    package::Package,
+    wml::{
+        document::{BlockLevelElts, ContentBlockContent, Drawing, DrawingChoice, Hyperlink},
+        drawing::GraphicDataContent,
+        table::{ContentCellContent, ContentRowContent, Row, Tbl, Tc},
+    },
+};
+use msoffice_shared::drawingml::coordsys::PositiveSize2D;
+
+// Helpers shared by the plain text, Markdown and HTML exporters
+
+// A cell of a table row and the first grid column it spans
+#[derive(Debug, Clone, Copy)]
+pub struct GridCell<'a> {
+    pub grid_column: usize,
+    pub cell: &'a Tc,
+}
+
+pub fn table_rows(table: &Tbl) -> Vec<&Row> {
+    let mut rows = Vec::new();
+    collect_rows(&table.row_contents, &mut rows);
+    rows
+}
+
+pub fn row_cells(row: &Row) -> Vec<&Tc> {
+    let mut cells = Vec::new();
+    collect_cells(&row.contents, &mut cells);
+    cells
+}
+
+pub fn cell_grid_span(cell: &Tc) -> usize {
+    cell.properties
+        .as_ref()
+        .and_then(|tc_pr| tc_pr.base.base.grid_span)
+        .unwrap_or(1)
+        .max(1) as usize
+}
+
+pub fn grid_cells(row: &Row) -> Vec<GridCell<'_>> {
+    let mut grid_column = 0;
+    row_cells(row)
+        .into_iter()
+        .map(|cell| {
+            let grid_cell = GridCell { grid_column, cell };
+            grid_column += cell_grid_span(cell);
+            grid_cell
+        })
+        .collect()
+}
+
+pub fn contains_table(elements: &[BlockLevelElts]) -> bool {
+    fn block_contains_table(content: &ContentBlockContent) -> bool {
+        match content {
+            ContentBlockContent::Table(_) => true,
+            ContentBlockContent::CustomXml(custom_xml) => custom_xml.block_contents.iter().any(block_contains_table),
+            ContentBlockContent::Sdt(sdt) => sdt
+                .sdt_content
+                .as_ref()
+                .is_some_and(|sdt_content| sdt_content.block_contents.iter().any(block_contains_table)),
+            _ => false,
+        }
+    }
+
+    elements.iter().any(|element| match element {
+        BlockLevelElts::Chunk(content) => block_contains_table(content),
+        BlockLevelElts::AltChunk(_) => false,
+    })
+}
+
+// The target of the hyperlink relationship followed by the anchor, or only the anchor for links within the document
+pub fn hyperlink_url(package: &Package, hyperlink: &Hyperlink) -> Option<String> {
+    let target = hyperlink
+        .rel_id
+        .as_ref()
+        .and_then(|rel_id| package.main_document_relationship_target(rel_id));
+
+    match (target, &hyperlink.anchor) {
+        (Some(target), Some(anchor)) => Some(format!("{}#{}", target, anchor)),
+        (Some(target), None) => Some(String::from(target)),
+        (None, Some(anchor)) => Some(format!("#{}", anchor)),
+        (None, None) => None,
+    }
+}
+
+// A picture embedded in the main document
+#[derive(Debug, Clone)]
+pub struct DrawingImage<'a> {
+    pub alt_text: &'a str,
+    pub extent: &'a PositiveSize2D,
+    pub package_path: String,
+}
+
+// The pictures of a drawing, looked up by the relationship id of their blip. Shapes and linked pictures are skipped.
+pub fn drawing_images<'a>(package: &'a Package, drawing: &'a Drawing) -> Vec<DrawingImage<'a>> {
+    drawing
+        .0
+        .iter()
+        .filter_map(|choice| {
+            let (doc_properties, extent, graphic_data_content) = match choice {
+                DrawingChoice::Inline(inline) => (&inline.doc_properties, &inline.extent, &inline.graphic_data_content),
+                DrawingChoice::Anchor(anchor) => (
+                    &anchor.document_properties,
+                    &anchor.extent,
+                    &anchor.graphic_data_content,
+                ),
+            };
+
+            let relationship_id = match graphic_data_content {
+                Some(GraphicDataContent::Picture(blip)) => blip.embed_relationship_id.as_ref()?,
+                _ => return None,
+            };
+
+            Some(DrawingImage {
+                alt_text: doc_properties.description.as_deref().unwrap_or(&doc_properties.name),
+                extent,
+                package_path: package.main_document_relationship_part_path(relationship_id)?,
+            })
+        })
+        .collect()
+}
+
+fn collect_rows<'a>(row_contents: &'a [ContentRowContent], rows: &mut Vec<&'a Row>) {
+    for row_content in row_contents {
+        match row_content {
+            ContentRowContent::Table(row) => rows.push(row),
+            ContentRowContent::CustomXml(custom_xml) => collect_rows(&custom_xml.contents, rows),
+            ContentRowContent::Sdt(sdt) => {
+                if let Some(content) = &sdt.content {
+                    collect_rows(&content.contents, rows);
+                }
+            }
+            ContentRowContent::RunLevelElements(_) => (),
+        }
+    }
+}
+
+fn collect_cells<'a>(cell_contents: &'a [ContentCellContent], cells: &mut Vec<&'a Tc>) {
+    for cell_content in cell_contents {
+        match cell_content {
+            ContentCellContent::Cell(cell) => cells.push(cell),
+            ContentCellContent::CustomXml(custom_xml) => collect_cells(&custom_xml.contents, cells),
+            ContentCellContent::Sdt(sdt) => {
+                if let Some(content) = &sdt.content {
+                    collect_cells(&content.contents, cells);
+                }
+            }
+            ContentCellContent::RunLevelElement(_) => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{grid_cells, hyperlink_url};
+    use crate::{
+        package::Package,
+        wml::{
+            document::Hyperlink,
+            table::{ContentCellContent, Row, Tc, TcPr, TcPrBase, TcPrInner},
+        },
+    };
+    use msoffice_shared::relationship::Relationship;
+
+    fn cell(grid_span: Option<i32>) -> ContentCellContent {
+        ContentCellContent::Cell(Box::new(Tc {
+            properties: grid_span.map(|grid_span| TcPr {
+                base: TcPrInner {
+                    base: TcPrBase {
+                        grid_span: Some(grid_span),
+                        ..Default::default()
+                    },
+                    markup_element: None,
+                },
+                change: None,
+            }),
+            block_level_elements: Vec::new(),
+            id: None,
+        }))
+    }
+
+    #[test]
+    pub fn test_grid_cells() {
+        let row = Row {
+            property_exceptions: None,
+            properties: None,
+            contents: vec![cell(Some(2)), cell(None), cell(Some(3)), cell(None)],
+            run_properties_revision_id: None,
+            run_revision_id: None,
+            deletion_revision_id: None,
+            row_revision_id: None,
+        };
+
+        let grid_columns: Vec<usize> = grid_cells(&row).iter().map(|grid_cell| grid_cell.grid_column).collect();
+        assert_eq!(grid_columns, vec![0, 2, 3, 6]);
+    }
+
+    #[test]
+    pub fn test_hyperlink_url() {
+        let mut package: Package = Default::default();
+        package.main_document_relationships.push(Relationship {
+            id: String::from("rId1"),
+            rel_type: String::from("http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink"),
+            target: String::from("https://example.com/page"),
+        });
+
+        let hyperlink = Hyperlink {
+            rel_id: Some(String::from("rId1")),
+            anchor: Some(String::from("section")),
+            ..Default::default()
+        };
+        assert_eq!(
+            hyperlink_url(&package, &hyperlink).as_deref(),
+            Some("https://example.com/page#section")
+        );
+
+        let hyperlink = Hyperlink {
+            anchor: Some(String::from("_Toc1")),
+            ..Default::default()
+        };
+        assert_eq!(hyperlink_url(&package, &hyperlink).as_deref(), Some("#_Toc1"));
+        assert_eq!(hyperlink_url(&package, &Default::default()), None);
+    }
+}
@@ -0,0 +1,454 @@
+use crate::{
+    fieldcode::{FieldCode, ParseFieldCodeError},
+    search::{paragraph_runs, run_inner_content_text},
+    visit::Visit,
+    wml::{
+        document::{
+            Body, ContentRunContent, Document, FFData, FldChar, FldCharType, PContent, RPr, RunInnerContent, Text, P, R,
+        },
+        footnotes::FtnEdn,
+    },
+};
+
+// Position of a run inner content. Paragraphs are counted in visiting order within the collected scope and runs are
+// counted in the order returned by paragraph_runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ContentPosition {
+    pub paragraph_index: usize,
+    pub run_index: usize,
+    pub content_index: usize,
+}
+
+impl ContentPosition {
+    pub fn new(paragraph_index: usize, run_index: usize, content_index: usize) -> Self {
+        Self {
+            paragraph_index,
+            run_index,
+            content_index,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    // Nested fields in the instruction part are included as "{ instruction }"
+    pub instruction: String,
+    pub begin: ContentPosition,
+    pub separate: Option<ContentPosition>,
+    pub end: ContentPosition,
+    pub children: Vec<Field>,
+    pub result_text: String,
+    pub dirty: bool,
+    pub locked: bool,
+    pub form_field_data: Vec<FFData>,
+}
+
+impl Field {
+    fn new(begin: ContentPosition) -> Self {
+        Self {
+            instruction: String::new(),
+            begin,
+            separate: None,
+            end: begin,
+            children: Vec::new(),
+            result_text: String::new(),
+            dirty: false,
+            locked: false,
+            form_field_data: Vec::new(),
+        }
+    }
+
+    pub fn field_code(&self) -> Result<FieldCode, ParseFieldCodeError> {
+        self.instruction.parse()
+    }
+
+    pub fn has_result(&self) -> bool {
+        self.separate.is_some()
+    }
+
+    // The result lies strictly between the separate and the end field characters
+    pub fn result_range(&self) -> Option<(ContentPosition, ContentPosition)> {
+        self.separate.map(|separate| (separate, self.end))
+    }
+
+    pub fn contains(&self, position: ContentPosition) -> bool {
+        position > self.begin && position < self.end
+    }
+
+    pub fn is_in_result(&self, position: ContentPosition) -> bool {
+        self.separate
+            .map(|separate| position > separate && position < self.end)
+            .unwrap_or(false)
+    }
+
+    pub fn descendants(&self) -> Vec<&Field> {
+        self.children
+            .iter()
+            .flat_map(|child| std::iter::once(child).chain(child.descendants()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FieldCollector {
+    paragraph_index: usize,
+    open_fields: Vec<Field>,
+    fields: Vec<Field>,
+}
+
+impl FieldCollector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn collect_paragraph(&mut self, paragraph: &P) {
+        for (run_index, run) in paragraph_runs(paragraph).into_iter().enumerate() {
+            for (content_index, content) in run.run_inner_contents.iter().enumerate() {
+                let position = ContentPosition::new(self.paragraph_index, run_index, content_index);
+                self.collect_run_inner_content(content, position);
+            }
+        }
+
+        if let Some(field) = self.open_fields.last_mut() {
+            if field.separate.is_some() {
+                field.result_text.push('\n');
+            }
+        }
+
+        self.paragraph_index += 1;
+    }
+
+    // Fields that are still open at this point are incomplete and get dropped
+    pub fn finish(self) -> Vec<Field> {
+        self.fields
+    }
+
+    fn collect_run_inner_content(&mut self, content: &RunInnerContent, position: ContentPosition) {
+        match content {
+            RunInnerContent::FieldCharacter(field_char) => match field_char.field_char_type {
+                FldCharType::Begin => {
+                    let mut field = Field::new(position);
+                    field.dirty = field_char.dirty.unwrap_or(false);
+                    field.locked = field_char.field_lock.unwrap_or(false);
+                    field.form_field_data = field_char.form_field_properties.clone();
+                    self.open_fields.push(field);
+                }
+                FldCharType::Separate => {
+                    if let Some(field) = self.open_fields.last_mut() {
+                        field.separate = Some(position);
+                    }
+                }
+                FldCharType::End => {
+                    if let Some(mut field) = self.open_fields.pop() {
+                        field.end = position;
+                        self.close_field(field);
+                    }
+                }
+            },
+            RunInnerContent::InstructionText(text) => {
+                if let Some(field) = self.open_fields.last_mut() {
+                    if field.separate.is_none() {
+                        field.instruction.push_str(&text.text);
+                    }
+                }
+            }
+            _ => {
+                if let (Some(field), Some(text)) = (self.open_fields.last_mut(), run_inner_content_text(content)) {
+                    if field.separate.is_some() {
+                        field.result_text.push_str(text);
+                    }
+                }
+            }
+        }
+    }
+
+    fn close_field(&mut self, field: Field) {
+        match self.open_fields.last_mut() {
+            Some(parent) => {
+                if parent.separate.is_none() {
+                    parent
+                        .instruction
+                        .push_str(&format!("{{ {} }}", field.instruction.trim()));
+                } else {
+                    parent.result_text.push_str(&field.result_text);
+                }
+
+                parent.children.push(field);
+            }
+            None => self.fields.push(field),
+        }
+    }
+}
+
+impl<'a> Visit<'a> for FieldCollector {
+    fn visit_paragraph(&mut self, paragraph: &'a P) {
+        self.collect_paragraph(paragraph);
+    }
+}
+
+pub fn paragraph_fields(paragraph: &P) -> Vec<Field> {
+    let mut collector = FieldCollector::new();
+    collector.collect_paragraph(paragraph);
+    collector.finish()
+}
+
+pub fn body_fields(body: &Body) -> Vec<Field> {
+    let mut collector = FieldCollector::new();
+    collector.visit_body(body);
+    collector.finish()
+}
+
+pub fn document_fields(document: &Document) -> Vec<Field> {
+    let mut collector = FieldCollector::new();
+    collector.visit_document(document);
+    collector.finish()
+}
+
+pub fn note_fields(note: &FtnEdn) -> Vec<Field> {
+    let mut collector = FieldCollector::new();
+    collector.visit_footnote(note);
+    collector.finish()
+}
+
+pub(crate) fn as_run(content: &PContent) -> Option<&R> {
+    match content {
+        PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+            ContentRunContent::Run(run) => Some(run),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn run_properties(content: &PContent) -> Option<RPr> {
+    as_run(content)?.run_properties.clone()
+}
+
+pub(crate) fn as_run_mut(content: &mut PContent) -> Option<&mut R> {
+    match content {
+        PContent::ContentRunContent(run_content) => match run_content.as_mut() {
+            ContentRunContent::Run(run) => Some(run),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Only matches runs holding nothing but the field character, see split_field_character_runs
+pub(crate) fn field_character(content: &PContent) -> Option<&FldChar> {
+    match as_run(content)?.run_inner_contents.as_slice() {
+        [RunInnerContent::FieldCharacter(field_character)] => Some(field_character),
+        _ => None,
+    }
+}
+
+pub(crate) fn field_character_type(content: &PContent) -> Option<FldCharType> {
+    field_character(content).map(|field_character| field_character.field_char_type)
+}
+
+pub(crate) fn field_character_run(field_char_type: FldCharType, run_properties: Option<RPr>) -> PContent {
+    PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+        run_properties,
+        run_inner_contents: vec![RunInnerContent::FieldCharacter(FldChar {
+            form_field_properties: Vec::new(),
+            field_char_type,
+            field_lock: None,
+            dirty: None,
+        })],
+        ..Default::default()
+    })))
+}
+
+pub(crate) fn run_text(content: &PContent, is_in_result: bool) -> Option<String> {
+    let run = as_run(content)?;
+    Some(
+        run.run_inner_contents
+            .iter()
+            .filter_map(|inner_content| match (inner_content, is_in_result) {
+                (RunInnerContent::InstructionText(text), false) | (RunInnerContent::Text(text), true) => {
+                    Some(text.text.as_str())
+                }
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+// Runs holding field characters along other contents are split so every field character has its own run
+pub(crate) fn split_field_character_runs(contents: Vec<PContent>) -> Vec<PContent> {
+    let mut split_contents = Vec::new();
+    for content in contents {
+        let run = match &content {
+            PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                ContentRunContent::Run(run)
+                    if run.run_inner_contents.len() > 1
+                        && run
+                            .run_inner_contents
+                            .iter()
+                            .any(|inner_content| matches!(inner_content, RunInnerContent::FieldCharacter(_))) =>
+                {
+                    run.clone()
+                }
+                _ => {
+                    split_contents.push(content);
+                    continue;
+                }
+            },
+            _ => {
+                split_contents.push(content);
+                continue;
+            }
+        };
+
+        let mut pending_contents = Vec::new();
+        let new_run = |run_inner_contents| {
+            PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+                run_inner_contents,
+                ..run.clone()
+            })))
+        };
+
+        for inner_content in run.run_inner_contents.clone() {
+            if let RunInnerContent::FieldCharacter(_) = inner_content {
+                if !pending_contents.is_empty() {
+                    split_contents.push(new_run(std::mem::take(&mut pending_contents)));
+                }
+                split_contents.push(new_run(vec![inner_content]));
+            } else {
+                pending_contents.push(inner_content);
+            }
+        }
+
+        if !pending_contents.is_empty() {
+            split_contents.push(new_run(pending_contents));
+        }
+    }
+
+    split_contents
+}
+
+pub(crate) fn result_run(result: &str, run_properties: Option<RPr>, is_instruction: bool) -> Option<PContent> {
+    if result.is_empty() {
+        return None;
+    }
+
+    let text = Text {
+        text: String::from(result),
+        xml_space: Some(String::from("preserve")),
+    };
+    let inner_content = if is_instruction {
+        RunInnerContent::InstructionText(text)
+    } else {
+        RunInnerContent::Text(text)
+    };
+
+    Some(PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+        run_properties,
+        run_inner_contents: vec![inner_content],
+        ..Default::default()
+    }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{body_fields, paragraph_fields, ContentPosition};
+    use crate::{
+        fieldcode::FieldType,
+        test_util::{field_character, instruction, paragraph, paragraph_block, run, text},
+        wml::document::{Body, FFData, FldChar, FldCharType, RunInnerContent},
+    };
+
+    #[test]
+    pub fn test_nested_field_in_instruction() {
+        let paragraph = paragraph(vec![
+            run(vec![field_character(FldCharType::Begin)]),
+            run(vec![instruction(" IF ")]),
+            run(vec![field_character(FldCharType::Begin)]),
+            run(vec![instruction(" MERGEFIELD Country ")]),
+            run(vec![field_character(FldCharType::Separate)]),
+            run(vec![text("«Country»")]),
+            run(vec![field_character(FldCharType::End)]),
+            run(vec![instruction(" = \"Hungary\" \"Szia\" \"Hello\" ")]),
+            run(vec![field_character(FldCharType::Separate)]),
+            run(vec![text("Hello")]),
+            run(vec![field_character(FldCharType::End)]),
+        ]);
+
+        let fields = paragraph_fields(&paragraph);
+        assert_eq!(fields.len(), 1);
+
+        let field = &fields[0];
+        assert_eq!(
+            field.instruction,
+            " IF { MERGEFIELD Country } = \"Hungary\" \"Szia\" \"Hello\" "
+        );
+        assert_eq!(field.result_text, "Hello");
+        assert_eq!(field.begin, ContentPosition::new(0, 0, 0));
+        assert_eq!(field.separate, Some(ContentPosition::new(0, 8, 0)));
+        assert_eq!(field.end, ContentPosition::new(0, 10, 0));
+        assert!(field.is_in_result(ContentPosition::new(0, 9, 0)));
+        assert!(!field.is_in_result(ContentPosition::new(0, 5, 0)));
+
+        assert_eq!(field.children.len(), 1);
+        assert_eq!(field.children[0].instruction, " MERGEFIELD Country ");
+        assert_eq!(field.children[0].result_text, "«Country»");
+        match field.field_code().unwrap().field_type {
+            FieldType::If(_) => (),
+            other => panic!("unexpected field type {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_field_across_paragraphs() {
+        let body = Body {
+            block_level_elements: vec![
+                paragraph_block(paragraph(vec![
+                    run(vec![text("Contents")]),
+                    run(vec![
+                        field_character(FldCharType::Begin),
+                        instruction(" TOC \\o \"1-3\" "),
+                        field_character(FldCharType::Separate),
+                    ]),
+                    run(vec![text("Introduction")]),
+                ])),
+                paragraph_block(paragraph(vec![
+                    run(vec![text("Summary")]),
+                    run(vec![field_character(FldCharType::End)]),
+                ])),
+            ],
+            section_properties: None,
+        };
+
+        let fields = body_fields(&body);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].begin, ContentPosition::new(0, 1, 0));
+        assert_eq!(
+            fields[0].result_range(),
+            Some((ContentPosition::new(0, 1, 2), ContentPosition::new(1, 1, 0)))
+        );
+        assert_eq!(fields[0].result_text, "Introduction\nSummary");
+        assert!(fields[0].contains(ContentPosition::new(1, 0, 0)));
+    }
+
+    #[test]
+    pub fn test_field_flags() {
+        let paragraph = paragraph(vec![
+            run(vec![RunInnerContent::FieldCharacter(FldChar {
+                form_field_properties: vec![FFData::Name(String::from("Check1"))],
+                field_char_type: FldCharType::Begin,
+                field_lock: Some(true),
+                dirty: Some(true),
+            })]),
+            run(vec![instruction(" FORMCHECKBOX ")]),
+            run(vec![field_character(FldCharType::End)]),
+            run(vec![field_character(FldCharType::Begin)]),
+        ]);
+
+        let fields = paragraph_fields(&paragraph);
+        assert_eq!(fields.len(), 1);
+        assert!(fields[0].dirty);
+        assert!(fields[0].locked);
+        assert!(!fields[0].has_result());
+        assert_eq!(fields[0].form_field_data, vec![FFData::Name(String::from("Check1"))]);
+    }
+}
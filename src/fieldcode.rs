@@ -0,0 +1,1007 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseFieldCodeError {
+    EmptyInstruction,
+    UnterminatedQuote,
+    UnbalancedBraces,
+    MissingArgument { field: String, argument: &'static str },
+    InvalidSwitchArgument { switch: char, argument: String },
+}
+
+impl Display for ParseFieldCodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseFieldCodeError::EmptyInstruction => write!(f, "Field instruction is empty"),
+            ParseFieldCodeError::UnterminatedQuote => write!(f, "Field instruction has an unterminated quote"),
+            ParseFieldCodeError::UnbalancedBraces => write!(f, "Field instruction has unbalanced nested field braces"),
+            ParseFieldCodeError::MissingArgument { field, argument } => {
+                write!(f, "{} field is missing its {} argument", field, argument)
+            }
+            ParseFieldCodeError::InvalidSwitchArgument { switch, argument } => {
+                write!(f, "Invalid argument '{}' for switch \\{}", argument, switch)
+            }
+        }
+    }
+}
+
+impl Error for ParseFieldCodeError {}
+
+type Result<T> = std::result::Result<T, ParseFieldCodeError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentPart {
+    Text(String),
+    Field(Box<FieldCode>),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Argument {
+    pub parts: Vec<ArgumentPart>,
+    pub is_quoted: bool,
+}
+
+impl Argument {
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            parts: vec![ArgumentPart::Text(String::from(text))],
+            is_quoted: false,
+        }
+    }
+
+    pub fn has_nested_fields(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part, ArgumentPart::Field(_)))
+    }
+
+    // Nested fields are replaced with the text returned by field_text
+    pub fn text_with<F: FnMut(&FieldCode) -> String>(&self, mut field_text: F) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                ArgumentPart::Text(text) => text.clone(),
+                ArgumentPart::Field(field_code) => field_text(field_code),
+            })
+            .collect()
+    }
+
+    // Nested fields are written back in their { instruction } form
+    pub fn text(&self) -> String {
+        self.text_with(|field_code| format!("{{ {} }}", field_code.instruction))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSwitch {
+    pub name: char,
+    pub argument: Option<Argument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeneralSwitches {
+    pub formats: Vec<String>,
+    pub numeric_picture: Option<String>,
+    pub date_time_picture: Option<String>,
+}
+
+impl GeneralSwitches {
+    pub fn has_merge_format(&self) -> bool {
+        self.formats
+            .iter()
+            .any(|format| format.eq_ignore_ascii_case("MERGEFORMAT"))
+    }
+
+    // Applies the case conversion formats of \*, other formats are left to the field evaluation
+    pub fn format_text(&self, text: &str) -> String {
+        self.formats.iter().fold(String::from(text), |text, format| {
+            match format.to_lowercase().as_str() {
+                "upper" => text.to_uppercase(),
+                "lower" => text.to_lowercase(),
+                "firstcap" => capitalize_words(&text, true),
+                "caps" => capitalize_words(&text, false),
+                _ => text,
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl FromStr for ComparisonOperator {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "=" => Ok(ComparisonOperator::Equal),
+            "<>" => Ok(ComparisonOperator::NotEqual),
+            "<" => Ok(ComparisonOperator::Less),
+            "<=" => Ok(ComparisonOperator::LessOrEqual),
+            ">" => Ok(ComparisonOperator::Greater),
+            ">=" => Ok(ComparisonOperator::GreaterOrEqual),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ComparisonOperator {
+    // Numbers are compared by value, texts compared for (in)equality may contain * and ? wildcards
+    pub fn compare(self, left: &str, right: &str) -> bool {
+        let ordering = match (left.trim().parse::<f64>(), right.trim().parse::<f64>()) {
+            (Ok(left_number), Ok(right_number)) => left_number.partial_cmp(&right_number),
+            _ => match self {
+                ComparisonOperator::Equal => return matches_wildcard(left, right),
+                ComparisonOperator::NotEqual => return !matches_wildcard(left, right),
+                _ => Some(left.cmp(right)),
+            },
+        };
+
+        let ordering = match ordering {
+            Some(ordering) => ordering,
+            None => return false,
+        };
+
+        match self {
+            ComparisonOperator::Equal => ordering == std::cmp::Ordering::Equal,
+            ComparisonOperator::NotEqual => ordering != std::cmp::Ordering::Equal,
+            ComparisonOperator::Less => ordering == std::cmp::Ordering::Less,
+            ComparisonOperator::LessOrEqual => ordering != std::cmp::Ordering::Greater,
+            ComparisonOperator::Greater => ordering == std::cmp::Ordering::Greater,
+            ComparisonOperator::GreaterOrEqual => ordering != std::cmp::Ordering::Less,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub left: Argument,
+    pub operator: ComparisonOperator,
+    pub right: Argument,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelRange {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl LevelRange {
+    pub fn contains(self, level: u8) -> bool {
+        self.start <= level && level <= self.end
+    }
+}
+
+impl FromStr for LevelRange {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut bounds = s.trim().splitn(2, '-');
+        let start = bounds.next().ok_or(())?.trim().parse().map_err(|_| ())?;
+        let end = match bounds.next() {
+            Some(end) => end.trim().parse().map_err(|_| ())?,
+            None => start,
+        };
+
+        Ok(Self { start, end })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RefField {
+    pub bookmark: String,
+    pub footnote_format: bool,
+    pub hyperlink: bool,
+    pub paragraph_number: bool,
+    pub relative_position: bool,
+    pub relative_number: bool,
+    pub suppress_non_delimiters: bool,
+    pub full_context: bool,
+    pub separator: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PageRefField {
+    pub bookmark: String,
+    pub hyperlink: bool,
+    pub relative_position: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HyperlinkField {
+    pub target: Option<Argument>,
+    pub anchor: Option<String>,
+    pub tooltip: Option<String>,
+    pub target_frame: Option<String>,
+    pub image_map: bool,
+    pub new_window: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TocField {
+    pub outline_levels: Option<LevelRange>,
+    pub use_outline_levels: bool,
+    pub styles: Vec<(String, u8)>,
+    pub hyperlinks: bool,
+    pub omit_page_numbers: Option<LevelRange>,
+    pub bookmark: Option<String>,
+    pub caption: Option<String>,
+    pub entry_identifier: Option<String>,
+    pub entry_levels: Option<LevelRange>,
+    pub separator: Option<String>,
+    pub preserve_tabs: bool,
+    pub preserve_line_breaks: bool,
+    pub hide_in_web_layout: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SeqField {
+    pub identifier: String,
+    pub bookmark: Option<String>,
+    pub repeat: bool,
+    pub hide: bool,
+    pub next: bool,
+    pub reset_to: Option<i32>,
+    pub reset_heading_level: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MergeField {
+    pub name: String,
+    pub text_before: Option<String>,
+    pub text_after: Option<String>,
+    pub mapped: bool,
+    pub vertical: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IfField {
+    pub condition: Condition,
+    pub true_text: Option<Argument>,
+    pub false_text: Option<Argument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StyleRefField {
+    pub style: String,
+    pub search_from_bottom: bool,
+    pub paragraph_number: bool,
+    pub relative_position: bool,
+    pub relative_number: bool,
+    pub suppress_non_delimiters: bool,
+    pub full_context: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IncludePictureField {
+    pub path: Argument,
+    pub converter: Option<String>,
+    pub do_not_store: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct XeField {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub entry_type: Option<String>,
+    pub page_range_bookmark: Option<String>,
+    pub cross_reference: Option<String>,
+    pub yomi: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Page,
+    NumPages,
+    Ref(RefField),
+    NoteRef(RefField),
+    PageRef(PageRefField),
+    Hyperlink(HyperlinkField),
+    Toc(TocField),
+    Seq(SeqField),
+    MergeField(MergeField),
+    MergeRec,
+    Next,
+    NextIf(Condition),
+    SkipIf(Condition),
+    Date,
+    Time,
+    CreateDate,
+    SaveDate,
+    PrintDate,
+    If(IfField),
+    DocProperty(String),
+    DocVariable(String),
+    StyleRef(StyleRefField),
+    IncludePicture(IncludePictureField),
+    Xe(XeField),
+    Other {
+        name: String,
+        arguments: Vec<Argument>,
+        switches: Vec<FieldSwitch>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldCode {
+    pub instruction: String,
+    pub field_type: FieldType,
+    pub general_switches: GeneralSwitches,
+}
+
+impl FromStr for FieldCode {
+    type Err = ParseFieldCodeError;
+
+    // Nested fields are written in braces, the way Word shows field codes: IF { MERGEFIELD x } = "1" "a" "b"
+    fn from_str(s: &str) -> Result<Self> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut position = 0;
+        let field_code = parse_field_code(&chars, &mut position)?;
+        if position < chars.len() {
+            return Err(ParseFieldCodeError::UnbalancedBraces);
+        }
+
+        Ok(field_code)
+    }
+}
+
+impl FieldCode {
+    pub fn name(&self) -> &str {
+        match &self.field_type {
+            FieldType::Page => "PAGE",
+            FieldType::NumPages => "NUMPAGES",
+            FieldType::Ref(_) => "REF",
+            FieldType::NoteRef(_) => "NOTEREF",
+            FieldType::PageRef(_) => "PAGEREF",
+            FieldType::Hyperlink(_) => "HYPERLINK",
+            FieldType::Toc(_) => "TOC",
+            FieldType::Seq(_) => "SEQ",
+            FieldType::MergeField(_) => "MERGEFIELD",
+            FieldType::MergeRec => "MERGEREC",
+            FieldType::Next => "NEXT",
+            FieldType::NextIf(_) => "NEXTIF",
+            FieldType::SkipIf(_) => "SKIPIF",
+            FieldType::Date => "DATE",
+            FieldType::Time => "TIME",
+            FieldType::CreateDate => "CREATEDATE",
+            FieldType::SaveDate => "SAVEDATE",
+            FieldType::PrintDate => "PRINTDATE",
+            FieldType::If(_) => "IF",
+            FieldType::DocProperty(_) => "DOCPROPERTY",
+            FieldType::DocVariable(_) => "DOCVARIABLE",
+            FieldType::StyleRef(_) => "STYLEREF",
+            FieldType::IncludePicture(_) => "INCLUDEPICTURE",
+            FieldType::Xe(_) => "XE",
+            FieldType::Other { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Switch(char),
+    Argument(Argument),
+}
+
+fn parse_field_code(chars: &[char], position: &mut usize) -> Result<FieldCode> {
+    let start = *position;
+    let tokens = tokenize(chars, position)?;
+    let instruction = chars[start..*position].iter().collect::<String>().trim().to_string();
+
+    let mut tokens = tokens.into_iter();
+    let name = match tokens.next() {
+        Some(Token::Argument(argument)) if !argument.is_quoted && !argument.has_nested_fields() => {
+            argument.text().to_uppercase()
+        }
+        _ => return Err(ParseFieldCodeError::EmptyInstruction),
+    };
+
+    let argument_switches = switches_with_argument(&name);
+    let mut general_switches = GeneralSwitches::default();
+    let mut arguments = Vec::new();
+    let mut switches = Vec::new();
+    let mut tokens = tokens.peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Argument(argument) => arguments.push(argument),
+            Token::Switch(switch) => {
+                let takes_argument = matches!(switch, '*' | '#' | '@') || argument_switches.contains(&switch);
+                let argument = match tokens.peek() {
+                    Some(Token::Argument(_)) if takes_argument => match tokens.next() {
+                        Some(Token::Argument(argument)) => Some(argument),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                match (switch, argument) {
+                    ('*', Some(argument)) => general_switches.formats.push(argument.text()),
+                    ('#', Some(argument)) => general_switches.numeric_picture = Some(argument.text()),
+                    ('@', Some(argument)) => general_switches.date_time_picture = Some(argument.text()),
+                    (switch, argument) => switches.push(FieldSwitch { name: switch, argument }),
+                }
+            }
+        }
+    }
+
+    let field_type = typed_field(name, arguments, switches)?;
+    Ok(FieldCode {
+        instruction,
+        field_type,
+        general_switches,
+    })
+}
+
+fn tokenize(chars: &[char], position: &mut usize) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    while let Some(&character) = chars.get(*position) {
+        match character {
+            '}' => break,
+            character if character.is_whitespace() => *position += 1,
+            '\\' => {
+                let switch = chars
+                    .get(*position + 1)
+                    .copied()
+                    .ok_or(ParseFieldCodeError::EmptyInstruction)?;
+                let switch = if switch.is_alphabetic() {
+                    switch.to_ascii_lowercase()
+                } else {
+                    switch
+                };
+                tokens.push(Token::Switch(switch));
+                *position += 2;
+            }
+            '"' | '\u{201C}' => {
+                *position += 1;
+                tokens.push(Token::Argument(parse_quoted_argument(chars, position)?));
+            }
+            _ => tokens.push(Token::Argument(parse_argument(chars, position)?)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_nested_field(chars: &[char], position: &mut usize) -> Result<FieldCode> {
+    *position += 1;
+    let field_code = parse_field_code(chars, position)?;
+    if chars.get(*position) != Some(&'}') {
+        return Err(ParseFieldCodeError::UnbalancedBraces);
+    }
+
+    *position += 1;
+    Ok(field_code)
+}
+
+fn parse_quoted_argument(chars: &[char], position: &mut usize) -> Result<Argument> {
+    let mut argument = Argument {
+        parts: Vec::new(),
+        is_quoted: true,
+    };
+    let mut text = String::new();
+    loop {
+        match chars.get(*position) {
+            None => return Err(ParseFieldCodeError::UnterminatedQuote),
+            Some('"') | Some('\u{201D}') => {
+                *position += 1;
+                break;
+            }
+            Some('\\') if matches!(chars.get(*position + 1), Some('"') | Some('\\')) => {
+                text.push(chars[*position + 1]);
+                *position += 2;
+            }
+            Some('{') => {
+                if !text.is_empty() {
+                    argument.parts.push(ArgumentPart::Text(std::mem::take(&mut text)));
+                }
+                argument
+                    .parts
+                    .push(ArgumentPart::Field(Box::new(parse_nested_field(chars, position)?)));
+            }
+            Some(&character) => {
+                text.push(character);
+                *position += 1;
+            }
+        }
+    }
+
+    if !text.is_empty() || argument.parts.is_empty() {
+        argument.parts.push(ArgumentPart::Text(text));
+    }
+
+    Ok(argument)
+}
+
+fn parse_argument(chars: &[char], position: &mut usize) -> Result<Argument> {
+    let mut argument = Argument::default();
+    let mut text = String::new();
+    while let Some(&character) = chars.get(*position) {
+        match character {
+            '{' => {
+                if !text.is_empty() {
+                    argument.parts.push(ArgumentPart::Text(std::mem::take(&mut text)));
+                }
+                argument
+                    .parts
+                    .push(ArgumentPart::Field(Box::new(parse_nested_field(chars, position)?)));
+            }
+            '}' | '"' | '\u{201C}' => break,
+            character if character.is_whitespace() => break,
+            character => {
+                text.push(character);
+                *position += 1;
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        argument.parts.push(ArgumentPart::Text(text));
+    }
+
+    Ok(argument)
+}
+
+fn switches_with_argument(field_name: &str) -> &'static [char] {
+    match field_name {
+        "REF" | "NOTEREF" => &['d'],
+        "HYPERLINK" => &['l', 'o', 't'],
+        "TOC" => &['a', 'b', 'c', 'd', 'f', 'l', 'n', 'o', 'p', 's', 't'],
+        "SEQ" => &['r', 's'],
+        "MERGEFIELD" => &['b', 'f'],
+        "INCLUDEPICTURE" => &['c'],
+        "XE" => &['f', 'r', 't', 'y'],
+        _ => &[],
+    }
+}
+
+fn typed_field(name: String, arguments: Vec<Argument>, switches: Vec<FieldSwitch>) -> Result<FieldType> {
+    let has_switch = |switch_name: char| switches.iter().any(|switch| switch.name == switch_name);
+    let switch_text = |switch_name: char| {
+        switches
+            .iter()
+            .find(|switch| switch.name == switch_name)
+            .and_then(|switch| switch.argument.as_ref())
+            .map(Argument::text)
+    };
+    let required_text = |argument_name: &'static str| {
+        arguments
+            .first()
+            .map(Argument::text)
+            .ok_or_else(|| ParseFieldCodeError::MissingArgument {
+                field: name.clone(),
+                argument: argument_name,
+            })
+    };
+    let level_range = |switch_name: char| -> Result<Option<LevelRange>> {
+        match switch_text(switch_name) {
+            Some(text) => text
+                .parse()
+                .map(Some)
+                .map_err(|_| ParseFieldCodeError::InvalidSwitchArgument {
+                    switch: switch_name,
+                    argument: text,
+                }),
+            None => Ok(None),
+        }
+    };
+
+    let field_type = match name.as_str() {
+        "PAGE" => FieldType::Page,
+        "NUMPAGES" => FieldType::NumPages,
+        "REF" | "NOTEREF" => {
+            let ref_field = RefField {
+                bookmark: required_text("bookmark")?,
+                footnote_format: has_switch('f'),
+                hyperlink: has_switch('h'),
+                paragraph_number: has_switch('n'),
+                relative_position: has_switch('p'),
+                relative_number: has_switch('r'),
+                suppress_non_delimiters: has_switch('t'),
+                full_context: has_switch('w'),
+                separator: switch_text('d'),
+            };
+
+            if name == "REF" {
+                FieldType::Ref(ref_field)
+            } else {
+                FieldType::NoteRef(ref_field)
+            }
+        }
+        "PAGEREF" => FieldType::PageRef(PageRefField {
+            bookmark: required_text("bookmark")?,
+            hyperlink: has_switch('h'),
+            relative_position: has_switch('p'),
+        }),
+        "HYPERLINK" => FieldType::Hyperlink(HyperlinkField {
+            target: arguments.first().cloned(),
+            anchor: switch_text('l'),
+            tooltip: switch_text('o'),
+            target_frame: switch_text('t'),
+            image_map: has_switch('m'),
+            new_window: has_switch('n'),
+        }),
+        "TOC" => {
+            let styles = match switch_text('t') {
+                Some(text) => parse_toc_styles(&text).ok_or(ParseFieldCodeError::InvalidSwitchArgument {
+                    switch: 't',
+                    argument: text,
+                })?,
+                None => Vec::new(),
+            };
+
+            // \n without levels omits the page numbers of every level
+            let omit_page_numbers = match level_range('n')? {
+                Some(levels) => Some(levels),
+                None if has_switch('n') => Some(LevelRange { start: 1, end: 9 }),
+                None => None,
+            };
+
+            // \o without levels uses every outline level
+            let outline_levels = match level_range('o')? {
+                Some(levels) => Some(levels),
+                None if has_switch('o') => Some(LevelRange { start: 1, end: 9 }),
+                None => None,
+            };
+
+            FieldType::Toc(TocField {
+                outline_levels,
+                use_outline_levels: has_switch('u'),
+                styles,
+                hyperlinks: has_switch('h'),
+                omit_page_numbers,
+                bookmark: switch_text('b'),
+                caption: switch_text('c'),
+                entry_identifier: switch_text('f'),
+                entry_levels: level_range('l')?,
+                separator: switch_text('p'),
+                preserve_tabs: has_switch('w'),
+                preserve_line_breaks: has_switch('x'),
+                hide_in_web_layout: has_switch('z'),
+            })
+        }
+        "SEQ" => {
+            let reset_to = match switch_text('r') {
+                Some(text) => Some(
+                    text.trim()
+                        .parse()
+                        .map_err(|_| ParseFieldCodeError::InvalidSwitchArgument {
+                            switch: 'r',
+                            argument: text,
+                        })?,
+                ),
+                None => None,
+            };
+            let reset_heading_level = match switch_text('s') {
+                Some(text) => Some(
+                    text.trim()
+                        .parse()
+                        .map_err(|_| ParseFieldCodeError::InvalidSwitchArgument {
+                            switch: 's',
+                            argument: text,
+                        })?,
+                ),
+                None => None,
+            };
+
+            FieldType::Seq(SeqField {
+                identifier: required_text("identifier")?,
+                bookmark: arguments.get(1).map(Argument::text),
+                repeat: has_switch('c'),
+                hide: has_switch('h'),
+                next: has_switch('n'),
+                reset_to,
+                reset_heading_level,
+            })
+        }
+        "MERGEFIELD" => FieldType::MergeField(MergeField {
+            name: required_text("field name")?,
+            text_before: switch_text('b'),
+            text_after: switch_text('f'),
+            mapped: has_switch('m'),
+            vertical: has_switch('v'),
+        }),
+        "MERGEREC" => FieldType::MergeRec,
+        "NEXT" => FieldType::Next,
+        "NEXTIF" => FieldType::NextIf(parse_condition(&name, &arguments)?.0),
+        "SKIPIF" => FieldType::SkipIf(parse_condition(&name, &arguments)?.0),
+        "DATE" => FieldType::Date,
+        "TIME" => FieldType::Time,
+        "CREATEDATE" => FieldType::CreateDate,
+        "SAVEDATE" => FieldType::SaveDate,
+        "PRINTDATE" => FieldType::PrintDate,
+        "IF" => {
+            let (condition, texts) = parse_condition(&name, &arguments)?;
+            let mut texts = texts.into_iter();
+            FieldType::If(IfField {
+                condition,
+                true_text: texts.next(),
+                false_text: texts.next(),
+            })
+        }
+        "DOCPROPERTY" => FieldType::DocProperty(required_text("property name")?),
+        "DOCVARIABLE" => FieldType::DocVariable(required_text("variable name")?),
+        "STYLEREF" => FieldType::StyleRef(StyleRefField {
+            style: required_text("style name")?,
+            search_from_bottom: has_switch('l'),
+            paragraph_number: has_switch('n'),
+            relative_position: has_switch('p'),
+            relative_number: has_switch('r'),
+            suppress_non_delimiters: has_switch('t'),
+            full_context: has_switch('w'),
+        }),
+        "INCLUDEPICTURE" => FieldType::IncludePicture(IncludePictureField {
+            path: arguments
+                .first()
+                .cloned()
+                .ok_or_else(|| ParseFieldCodeError::MissingArgument {
+                    field: name.clone(),
+                    argument: "file name",
+                })?,
+            converter: switch_text('c'),
+            do_not_store: has_switch('d'),
+        }),
+        "XE" => FieldType::Xe(XeField {
+            text: required_text("entry text")?,
+            bold: has_switch('b'),
+            italic: has_switch('i'),
+            entry_type: switch_text('f'),
+            page_range_bookmark: switch_text('r'),
+            cross_reference: switch_text('t'),
+            yomi: switch_text('y'),
+        }),
+        _ => FieldType::Other {
+            name,
+            arguments,
+            switches,
+        },
+    };
+
+    Ok(field_type)
+}
+
+// Operators may be written without spaces around them, like in IF { MERGEFIELD Country }="Hungary"
+fn parse_condition(field_name: &str, arguments: &[Argument]) -> Result<(Condition, Vec<Argument>)> {
+    let mut split_arguments = Vec::new();
+    for argument in arguments {
+        if argument.is_quoted {
+            split_arguments.push(argument.clone());
+            continue;
+        }
+
+        let operator_position = argument
+            .parts
+            .iter()
+            .enumerate()
+            .find_map(|(part_index, part)| match part {
+                ArgumentPart::Text(text) => ["<>", "<=", ">=", "=", "<", ">"]
+                    .iter()
+                    .filter_map(|operator| Some((text.find(operator)?, *operator)))
+                    .min_by_key(|(position, operator)| (*position, std::cmp::Reverse(operator.len())))
+                    .map(|(position, operator)| (part_index, text, position, operator)),
+                ArgumentPart::Field(_) => None,
+            });
+
+        match operator_position {
+            Some((part_index, text, position, operator)) if argument.parts.len() > 1 || text.as_str() != operator => {
+                let mut left = Argument {
+                    parts: argument.parts[..part_index].to_vec(),
+                    is_quoted: false,
+                };
+                let mut right = Argument {
+                    parts: argument.parts[part_index + 1..].to_vec(),
+                    is_quoted: false,
+                };
+
+                let (left_text, rest) = text.split_at(position);
+                let right_text = &rest[operator.len()..];
+                if !left_text.is_empty() {
+                    left.parts.push(ArgumentPart::Text(String::from(left_text)));
+                }
+                if !right_text.is_empty() {
+                    right.parts.insert(0, ArgumentPart::Text(String::from(right_text)));
+                }
+
+                split_arguments.extend(
+                    vec![left, Argument::from_text(operator), right]
+                        .into_iter()
+                        .filter(|argument| !argument.parts.is_empty()),
+                );
+            }
+            _ => split_arguments.push(argument.clone()),
+        }
+    }
+
+    let mut split_arguments = split_arguments.into_iter();
+    let missing_argument = |argument| ParseFieldCodeError::MissingArgument {
+        field: String::from(field_name),
+        argument,
+    };
+
+    let left = split_arguments.next().ok_or_else(|| missing_argument("expression"))?;
+    let operator = split_arguments
+        .next()
+        .filter(|argument| !argument.is_quoted)
+        .and_then(|argument| argument.text().parse().ok())
+        .ok_or_else(|| missing_argument("operator"))?;
+    let right = split_arguments.next().ok_or_else(|| missing_argument("expression"))?;
+
+    Ok((Condition { left, operator, right }, split_arguments.collect()))
+}
+
+fn parse_toc_styles(text: &str) -> Option<Vec<(String, u8)>> {
+    let separator = if text.contains(';') { ';' } else { ',' };
+    let parts: Vec<&str> = text.split(separator).map(str::trim).collect();
+    parts
+        .chunks(2)
+        .map(|chunk| match chunk {
+            [style, level] => Some((String::from(*style), level.parse().ok()?)),
+            [style] if !style.is_empty() => Some((String::from(*style), 1)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn matches_wildcard(text: &str, pattern: &str) -> bool {
+    fn matches(text: &[char], pattern: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => (0..=text.len()).any(|skip| matches(&text[skip..], rest)),
+            Some(('?', rest)) => !text.is_empty() && matches(&text[1..], rest),
+            Some((character, rest)) => text.first() == Some(character) && matches(&text[1..], rest),
+        }
+    }
+
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    matches(&text, &pattern)
+}
+
+fn capitalize_words(text: &str, only_first_word: bool) -> String {
+    let mut is_word_start = true;
+    let mut is_first_word = true;
+    text.chars()
+        .map(|character| {
+            let mapped = if is_word_start && (is_first_word || !only_first_word) {
+                character.to_uppercase().collect::<String>()
+            } else {
+                character.to_string()
+            };
+
+            if character.is_whitespace() {
+                is_word_start = true;
+            } else {
+                if is_word_start {
+                    is_first_word = false;
+                }
+                is_word_start = false;
+            }
+
+            mapped
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_toc() {
+        let field_code: FieldCode = r#" TOC \o "1-3" \h \z \t "Title,1,Subtitle,2" \n 2-3 \u "#.parse().unwrap();
+        assert_eq!(
+            field_code.instruction,
+            r#"TOC \o "1-3" \h \z \t "Title,1,Subtitle,2" \n 2-3 \u"#
+        );
+        assert_eq!(
+            field_code.field_type,
+            FieldType::Toc(TocField {
+                outline_levels: Some(LevelRange { start: 1, end: 3 }),
+                use_outline_levels: true,
+                styles: vec![(String::from("Title"), 1), (String::from("Subtitle"), 2)],
+                hyperlinks: true,
+                omit_page_numbers: Some(LevelRange { start: 2, end: 3 }),
+                hide_in_web_layout: true,
+                ..Default::default()
+            })
+        );
+
+        let field_code: FieldCode = r#"TOC \o \h"#.parse().unwrap();
+        assert_eq!(
+            field_code.field_type,
+            FieldType::Toc(TocField {
+                outline_levels: Some(LevelRange { start: 1, end: 9 }),
+                hyperlinks: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_parse_general_switches() {
+        let field_code: FieldCode = r#"DATE \@ "dd MMMM yyyy" \* MERGEFORMAT"#.parse().unwrap();
+        assert_eq!(field_code.field_type, FieldType::Date);
+        assert_eq!(
+            field_code.general_switches,
+            GeneralSwitches {
+                formats: vec![String::from("MERGEFORMAT")],
+                numeric_picture: None,
+                date_time_picture: Some(String::from("dd MMMM yyyy")),
+            }
+        );
+        assert!(field_code.general_switches.has_merge_format());
+
+        let field_code: FieldCode = r#"MERGEFIELD "First Name" \b "Dear " \* FirstCap \# "0.00""#.parse().unwrap();
+        assert_eq!(
+            field_code.field_type,
+            FieldType::MergeField(MergeField {
+                name: String::from("First Name"),
+                text_before: Some(String::from("Dear ")),
+                ..Default::default()
+            })
+        );
+        assert_eq!(field_code.general_switches.numeric_picture, Some(String::from("0.00")));
+        assert_eq!(field_code.general_switches.format_text("john smith"), "John smith");
+    }
+
+    #[test]
+    pub fn test_parse_nested_fields() {
+        let field_code: FieldCode = r#"IF { MERGEFIELD Country }="Hungary" "Szia { MERGEFIELD Name }!" "Hi""#
+            .parse()
+            .unwrap();
+        let if_field = match field_code.field_type {
+            FieldType::If(if_field) => if_field,
+            field_type => panic!("expected IF field, got {:?}", field_type),
+        };
+
+        assert_eq!(if_field.condition.operator, ComparisonOperator::Equal);
+        assert_eq!(if_field.condition.right.text(), "Hungary");
+        match if_field.condition.left.parts.as_slice() {
+            [ArgumentPart::Field(nested)] => assert_eq!(nested.name(), "MERGEFIELD"),
+            parts => panic!("expected nested field, got {:?}", parts),
+        }
+
+        let true_text = if_field.true_text.unwrap();
+        assert_eq!(true_text.text(), "Szia { MERGEFIELD Name }!");
+        assert_eq!(true_text.text_with(|_| String::from("Anna")), "Szia Anna!");
+        assert_eq!(if_field.false_text.unwrap().text(), "Hi");
+        assert!(!ComparisonOperator::NotEqual.compare("Hungary", "Hun*y"));
+        assert!(ComparisonOperator::Less.compare("9", "10"));
+    }
+
+    #[test]
+    pub fn test_parse_errors() {
+        assert_eq!("  ".parse::<FieldCode>(), Err(ParseFieldCodeError::EmptyInstruction));
+        assert_eq!(
+            r#"REF "unterminated"#.parse::<FieldCode>(),
+            Err(ParseFieldCodeError::UnterminatedQuote)
+        );
+        assert_eq!(
+            "IF { MERGEFIELD x = 1".parse::<FieldCode>(),
+            Err(ParseFieldCodeError::UnbalancedBraces)
+        );
+        assert_eq!(
+            "PAGEREF \\h".parse::<FieldCode>(),
+            Err(ParseFieldCodeError::MissingArgument {
+                field: String::from("PAGEREF"),
+                argument: "bookmark",
+            })
+        );
+        assert_eq!(
+            "SEQ Figure \\r x".parse::<FieldCode>(),
+            Err(ParseFieldCodeError::InvalidSwitchArgument {
+                switch: 'r',
+                argument: String::from("x"),
+            })
+        );
+    }
+}
@@ -0,0 +1,545 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFieldDateTimeError {
+    pub text: String,
+}
+
+impl Display for ParseFieldDateTimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid W3CDTF date time: {}", self.text)
+    }
+}
+
+impl Error for ParseFieldDateTimeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct FieldDateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl FieldDateTime {
+    pub fn new(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    // The current time in the time zone with the given offset from UTC in minutes, as time zones aren't known to the
+    // library
+    pub fn now(utc_offset_minutes: i32) -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0);
+        Self::from_unix_timestamp(seconds + i64::from(utc_offset_minutes) * 60)
+    }
+
+    pub fn from_unix_timestamp(seconds: i64) -> Self {
+        let days = seconds.div_euclid(86400);
+        let seconds_of_day = seconds.rem_euclid(86400) as u32;
+
+        // Converts the days since the epoch to a civil date of the proleptic Gregorian calendar
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = (year_of_era + era * 400) as i32 + if month <= 2 { 1 } else { 0 };
+
+        Self::new(
+            year,
+            month,
+            day,
+            seconds_of_day / 3600,
+            seconds_of_day % 3600 / 60,
+            seconds_of_day % 60,
+        )
+    }
+
+    pub fn days_since_epoch(&self) -> i64 {
+        let year = i64::from(self.year) - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let shifted_month = i64::from((self.month + 9) % 12);
+        let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    // 0 is Sunday
+    pub fn weekday(&self) -> u32 {
+        (self.days_since_epoch() + 4).rem_euclid(7) as u32
+    }
+
+    pub fn to_w3cdtf(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    // Formats with a date-time picture of the \@ switch, like "dddd, MMMM d, yyyy" or "h:mm am/pm"
+    pub fn format(&self, picture: &str) -> String {
+        let chars: Vec<char> = picture.chars().collect();
+        let mut formatted = String::new();
+        let mut position = 0;
+
+        while position < chars.len() {
+            let character = chars[position];
+            if character == '\'' {
+                let literal_end = chars[position + 1..]
+                    .iter()
+                    .position(|c| *c == '\'')
+                    .map(|offset| position + 1 + offset)
+                    .unwrap_or_else(|| chars.len());
+                formatted.extend(&chars[position + 1..literal_end]);
+                position = literal_end + 1;
+                continue;
+            }
+
+            let remaining: String = chars[position..].iter().take(5).collect();
+            if remaining.eq_ignore_ascii_case("am/pm") {
+                let is_am = self.hour < 12;
+                formatted.push_str(match (is_am, character.is_lowercase()) {
+                    (true, true) => "am",
+                    (true, false) => "AM",
+                    (false, true) => "pm",
+                    (false, false) => "PM",
+                });
+                position += 5;
+                continue;
+            }
+
+            let is_same_token = |c: char| match character {
+                'd' | 'D' => c == 'd' || c == 'D',
+                'y' | 'Y' => c == 'y' || c == 'Y',
+                's' | 'S' => c == 's' || c == 'S',
+                _ => c == character,
+            };
+            let count = chars[position..].iter().take_while(|c| is_same_token(**c)).count();
+
+            let hour12 = match self.hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            match character {
+                'M' => formatted.push_str(&match count {
+                    1 => self.month.to_string(),
+                    2 => format!("{:02}", self.month),
+                    3 => month_name(self.month).chars().take(3).collect(),
+                    _ => String::from(month_name(self.month)),
+                }),
+                'd' | 'D' => formatted.push_str(&match count {
+                    1 => self.day.to_string(),
+                    2 => format!("{:02}", self.day),
+                    3 => WEEKDAY_NAMES[self.weekday() as usize].chars().take(3).collect(),
+                    _ => String::from(WEEKDAY_NAMES[self.weekday() as usize]),
+                }),
+                'y' | 'Y' => formatted.push_str(&match count {
+                    1 | 2 => format!("{:02}", self.year.rem_euclid(100)),
+                    _ => format!("{:04}", self.year),
+                }),
+                'h' => formatted.push_str(&pad_number(hour12, count)),
+                'H' => formatted.push_str(&pad_number(self.hour, count)),
+                'm' => formatted.push_str(&pad_number(self.minute, count)),
+                's' | 'S' => formatted.push_str(&pad_number(self.second, count)),
+                _ => {
+                    formatted.push(character);
+                    position += 1;
+                    continue;
+                }
+            }
+
+            position += count;
+        }
+
+        formatted
+    }
+}
+
+impl FromStr for FieldDateTime {
+    type Err = ParseFieldDateTimeError;
+
+    // Parses the W3CDTF dates of the core properties, the time zone designator is ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseFieldDateTimeError { text: String::from(s) };
+        let s = s.trim();
+        let (date, time) = match s.find('T') {
+            Some(index) => (&s[..index], Some(&s[index + 1..])),
+            None => (s, None),
+        };
+
+        let mut date_parts = date.split('-');
+        let year = date_parts.next().and_then(|year| year.parse().ok()).ok_or_else(error)?;
+        let mut next_date_part = || -> Result<u32, ParseFieldDateTimeError> {
+            match date_parts.next() {
+                Some(part) => part.parse().map_err(|_| error()),
+                None => Ok(1),
+            }
+        };
+        let month = next_date_part()?;
+        let day = next_date_part()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(error());
+        }
+
+        let mut instance = Self::new(year, month, day, 0, 0, 0);
+        if let Some(time) = time {
+            let time_end = time.find(['Z', '+', '-'].as_ref()).unwrap_or(time.len());
+            let mut time_parts = time[..time_end].split(':');
+            let mut next_time_part = || -> Result<u32, ParseFieldDateTimeError> {
+                match time_parts.next() {
+                    Some(part) => part
+                        .split('.')
+                        .next()
+                        .and_then(|whole| whole.parse().ok())
+                        .ok_or_else(error),
+                    None => Ok(0),
+                }
+            };
+            instance.hour = next_time_part()?;
+            instance.minute = next_time_part()?;
+            instance.second = next_time_part()?;
+        }
+
+        Ok(instance)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PictureToken {
+    Zero,
+    Hash,
+    DropDigit,
+    DecimalPoint,
+    GroupSeparator,
+    Minus,
+    Plus,
+    Literal(String),
+}
+
+impl PictureToken {
+    fn is_digit(&self) -> bool {
+        matches!(self, PictureToken::Zero | PictureToken::Hash | PictureToken::DropDigit)
+    }
+}
+
+// Formats with a numeric picture of the \# switch, like "#,##0.00" or "$###;($###);'zero'"
+pub fn format_number(value: f64, picture: &str) -> String {
+    let sections = split_picture_sections(picture);
+    let (section, value, has_negative_section) = match sections.as_slice() {
+        [_, negative, ..] if value < 0.0 => (negative.as_str(), -value, true),
+        [_, _, zero, ..] if value == 0.0 => (zero.as_str(), value, false),
+        [section, ..] => (section.as_str(), value, false),
+        [] => return value.to_string(),
+    };
+
+    let tokens = tokenize_picture(section);
+    let decimal_index = tokens
+        .iter()
+        .position(|token| *token == PictureToken::DecimalPoint)
+        .unwrap_or(tokens.len());
+    let (integer_tokens, fraction_tokens) = tokens.split_at(decimal_index);
+    let count_tokens =
+        |tokens: &[PictureToken], expected: &PictureToken| tokens.iter().filter(|t| *t == expected).count();
+
+    // x drops the integer digits left of it and rounds the fraction at its position
+    let fraction_digit_tokens: Vec<&PictureToken> = fraction_tokens.iter().filter(|token| token.is_digit()).collect();
+    let fraction_digits = fraction_digit_tokens
+        .iter()
+        .position(|token| **token == PictureToken::DropDigit)
+        .map(|index| index + 1)
+        .unwrap_or_else(|| fraction_digit_tokens.len());
+    let kept_integer_digits = integer_tokens
+        .iter()
+        .position(|token| *token == PictureToken::DropDigit)
+        .map(|index| integer_tokens[index..].iter().filter(|token| token.is_digit()).count());
+    let zero_digits = count_tokens(integer_tokens, &PictureToken::Zero);
+    let placeholder_digits = zero_digits
+        + count_tokens(integer_tokens, &PictureToken::Hash)
+        + count_tokens(integer_tokens, &PictureToken::DropDigit);
+
+    let rounded = format!("{:.*}", fraction_digits, value.abs());
+    let mut rounded_parts = rounded.split('.');
+    let mut integer_digits = String::from(rounded_parts.next().unwrap_or("0"));
+    let fraction_text = rounded_parts.next().unwrap_or("");
+    if let Some(kept_integer_digits) = kept_integer_digits {
+        let dropped_digits = integer_digits.len().saturating_sub(kept_integer_digits);
+        integer_digits = match integer_digits[dropped_digits..].trim_start_matches('0') {
+            "" => String::from("0"),
+            digits => String::from(digits),
+        };
+    }
+    if integer_digits == "0" && zero_digits == 0 {
+        integer_digits.clear();
+    }
+    while integer_digits.len() < zero_digits {
+        integer_digits.insert(0, '0');
+    }
+
+    let padding = " ".repeat(placeholder_digits.saturating_sub(integer_digits.len()));
+    if integer_tokens.contains(&PictureToken::GroupSeparator) {
+        integer_digits = group_digits(&integer_digits);
+    }
+
+    let is_negative = value < 0.0 && !has_negative_section;
+    let has_sign_token = tokens
+        .iter()
+        .any(|token| matches!(token, PictureToken::Minus | PictureToken::Plus));
+    let mut number = format!("{}{}", padding, integer_digits);
+    if is_negative && !has_sign_token {
+        number.insert(0, '-');
+    }
+    if fraction_digits > 0 {
+        number.push('.');
+        number.push_str(fraction_text);
+    }
+
+    let mut formatted = String::new();
+    let mut is_number_written = false;
+    for token in &tokens {
+        match token {
+            PictureToken::Literal(text) => formatted.push_str(text),
+            PictureToken::Minus => formatted.push(if is_negative || has_negative_section { '-' } else { ' ' }),
+            PictureToken::Plus => formatted.push(if is_negative || has_negative_section { '-' } else { '+' }),
+            _ if !is_number_written => {
+                formatted.push_str(&number);
+                is_number_written = true;
+            }
+            _ => (),
+        }
+    }
+
+    formatted
+}
+
+// Applies the numeric formats of the \* switch, returns None for formats that aren't numeric
+pub fn format_number_text(value: i64, format: &str) -> Option<String> {
+    let is_lower_case = format.chars().next().map(char::is_lowercase).unwrap_or(false);
+    match format.to_lowercase().as_str() {
+        "arabic" => Some(value.to_string()),
+        "arabicdash" => Some(format!("- {} -", value)),
+        "roman" => to_roman(value).map(|roman| if is_lower_case { roman.to_lowercase() } else { roman }),
+        "alphabetic" => to_alphabetic(value).map(|alphabetic| {
+            if is_lower_case {
+                alphabetic
+            } else {
+                alphabetic.to_uppercase()
+            }
+        }),
+        "ordinal" => Some(to_ordinal(value)),
+        "hex" => Some(format!("{:X}", value)),
+        _ => None,
+    }
+}
+
+pub fn to_roman(value: i64) -> Option<String> {
+    const NUMERALS: [(i64, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    if value <= 0 || value >= 4000 {
+        return None;
+    }
+
+    let mut remaining = value;
+    let mut roman = String::new();
+    for (numeral_value, numeral) in NUMERALS.iter() {
+        while remaining >= *numeral_value {
+            roman.push_str(numeral);
+            remaining -= numeral_value;
+        }
+    }
+
+    Some(roman)
+}
+
+// Word repeats the letter after z: 27 is aa, 28 is bb
+pub fn to_alphabetic(value: i64) -> Option<String> {
+    if value <= 0 {
+        return None;
+    }
+
+    let letter = (b'a' + ((value - 1) % 26) as u8) as char;
+    let repeat = ((value - 1) / 26 + 1) as usize;
+    Some(letter.to_string().repeat(repeat))
+}
+
+pub fn to_ordinal(value: i64) -> String {
+    let suffix = match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", value, suffix)
+}
+
+fn month_name(month: u32) -> &'static str {
+    MONTH_NAMES[(month.clamp(1, 12) - 1) as usize]
+}
+
+fn pad_number(value: u32, count: usize) -> String {
+    if count >= 2 {
+        format!("{:02}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn split_picture_sections(picture: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    let mut is_quoted = false;
+    for character in picture.chars() {
+        match character {
+            '\'' => {
+                is_quoted = !is_quoted;
+                sections.last_mut().unwrap().push(character);
+            }
+            ';' if !is_quoted => sections.push(String::new()),
+            _ => sections.last_mut().unwrap().push(character),
+        }
+    }
+
+    sections
+}
+
+fn tokenize_picture(picture: &str) -> Vec<PictureToken> {
+    let mut tokens = Vec::new();
+    let mut chars = picture.chars();
+    while let Some(character) = chars.next() {
+        let token = match character {
+            '0' => PictureToken::Zero,
+            '#' => PictureToken::Hash,
+            'x' => PictureToken::DropDigit,
+            '.' => PictureToken::DecimalPoint,
+            ',' => PictureToken::GroupSeparator,
+            '-' => PictureToken::Minus,
+            '+' => PictureToken::Plus,
+            '\'' => PictureToken::Literal(chars.by_ref().take_while(|c| *c != '\'').collect()),
+            _ => PictureToken::Literal(character.to_string()),
+        };
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+fn group_digits(digits: &str) -> String {
+    let reversed_digits: Vec<char> = digits.chars().rev().collect();
+    let groups: Vec<String> = reversed_digits
+        .chunks(3)
+        .rev()
+        .map(|group| group.iter().rev().collect())
+        .collect();
+    groups.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_number, format_number_text, FieldDateTime};
+
+    #[test]
+    pub fn test_format_date_time() {
+        let date_time: FieldDateTime = "2019-03-05T14:07:09Z".parse().unwrap();
+        assert_eq!(date_time, FieldDateTime::new(2019, 3, 5, 14, 7, 9));
+        assert_eq!(date_time.format("dddd, MMMM d, yyyy"), "Tuesday, March 5, 2019");
+        assert_eq!(date_time.format("dd/MM/yy h:mm am/pm"), "05/03/19 2:07 pm");
+        assert_eq!(date_time.format("ddd MMM 'at' HH:mm:ss"), "Tue Mar at 14:07:09");
+        assert_eq!(FieldDateTime::from_unix_timestamp(1_551_794_829), date_time);
+    }
+
+    #[test]
+    pub fn test_now_with_utc_offset() {
+        let seconds = |date_time: FieldDateTime| {
+            date_time.days_since_epoch() * 86400
+                + i64::from(date_time.hour * 3600 + date_time.minute * 60 + date_time.second)
+        };
+        let utc = FieldDateTime::now(0);
+        let offset = seconds(FieldDateTime::now(-330)) - seconds(utc);
+
+        // The clock may tick between the two calls
+        assert!((-330 * 60..=-330 * 60 + 1).contains(&offset));
+    }
+
+    #[test]
+    pub fn test_format_number() {
+        assert_eq!(format_number(1234.567, "#,##0.00"), "1,234.57");
+        assert_eq!(format_number(15.0, "$###"), "$ 15");
+        assert_eq!(format_number(-3.0, "0.0"), "-3.0");
+        assert_eq!(format_number(-3.0, "#;(#)"), "(3)");
+        assert_eq!(format_number(0.0, "#;(#);'none'"), "none");
+        assert_eq!(format_number(7.0, "+00"), "+07");
+        assert_eq!(format_number(222_492.0, "x##"), "492");
+        assert_eq!(format_number(1_005.0, "x00"), " 05");
+        assert_eq!(format_number(0.125, "0.00x"), "0.125");
+        assert_eq!(format_number(0.75, ".x"), ".8");
+        assert_eq!(format_number(1.2345, "0.x#"), "1.2");
+        assert_eq!(format_number_text(14, "roman").as_deref(), Some("xiv"));
+        assert_eq!(format_number_text(28, "ALPHABETIC").as_deref(), Some("BB"));
+        assert_eq!(format_number_text(22, "Ordinal").as_deref(), Some("22nd"));
+        assert_eq!(format_number_text(3, "Upper"), None);
+    }
+}
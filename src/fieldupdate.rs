@@ -0,0 +1,808 @@
+use crate::{
+    field::{
+        as_run_mut, field_character, field_character_run, field_character_type, result_run, run_properties, run_text,
+        split_field_character_runs,
+    },
+    fieldcode::{Argument, FieldCode, FieldType, GeneralSwitches, SeqField, StyleRefField},
+    fieldformat::{format_number, format_number_text, to_roman, FieldDateTime},
+    package::Package,
+    search::{run_inner_content_text, ParagraphText},
+    styleresolver::StyleResolver,
+    visit::{walk_paragraph, Visit, VisitMut},
+    wml::{
+        document::{
+            BlockLevelElts, ContentRunContent, Document, Drawing, FldCharType, PContent, RPr, RangeMarkupElements,
+            RunInnerContent, SimpleField, P, R,
+        },
+        footnotes::FtnEdn,
+    },
+};
+use msoffice_shared::docprops::{AppInfo, Core};
+use std::collections::HashMap;
+
+pub const REFERENCE_NOT_FOUND: &str = "Error! Reference source not found.";
+pub const STYLE_NOT_FOUND: &str = "Error! No text of specified style in document.";
+pub const DOCUMENT_VARIABLE_NOT_FOUND: &str = "Error! No document variable supplied.";
+pub const UNKNOWN_DOCUMENT_PROPERTY: &str = "Error! Unknown document property name.";
+
+const DEFAULT_DATE_PICTURE: &str = "M/d/yyyy";
+const DEFAULT_TIME_PICTURE: &str = "h:mm am/pm";
+const DEFAULT_DATE_TIME_PICTURE: &str = "M/d/yyyy h:mm:ss am/pm";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BookmarkContent {
+    pub text: String,
+    // Number of the first footnote or endnote reference inside the bookmark, as NOTEREF shows it
+    pub note_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct ParagraphInfo {
+    style_id: Option<String>,
+    heading_level: Option<usize>,
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct SequenceState {
+    value: i64,
+    heading_counts: [usize; 9],
+}
+
+#[derive(Debug)]
+struct OpenField {
+    contents: Vec<PContent>,
+    instruction: String,
+    result_text: String,
+    separate_index: Option<usize>,
+    begin_properties: Option<RPr>,
+    result_properties: Option<RPr>,
+    is_locked: bool,
+}
+
+struct DocumentScanner<'a> {
+    style_resolver: StyleResolver<'a>,
+    paragraphs: Vec<ParagraphInfo>,
+    bookmarks: HashMap<String, BookmarkContent>,
+    open_bookmarks: Vec<(i32, String, BookmarkContent)>,
+    footnote_count: i64,
+    endnote_count: i64,
+}
+
+impl<'a, 'd> Visit<'d> for DocumentScanner<'a> {
+    fn visit_paragraph(&mut self, paragraph: &'d P) {
+        let heading_level = self
+            .style_resolver
+            .resolve_paragraph_properties(paragraph)
+            .outline_level
+            .filter(|outline_level| (0..9).contains(outline_level))
+            .map(|outline_level| outline_level as usize + 1);
+
+        self.paragraphs.push(ParagraphInfo {
+            style_id: paragraph.properties.as_ref().and_then(|p_pr| p_pr.base.style.clone()),
+            heading_level,
+            text: ParagraphText::from_paragraph(paragraph).text,
+        });
+
+        walk_paragraph(self, paragraph);
+        for (_, _, bookmark) in &mut self.open_bookmarks {
+            bookmark.text.push('\n');
+        }
+    }
+
+    // Paragraphs of text boxes aren't counted, the update pass doesn't enter drawings either
+    fn visit_drawing(&mut self, _drawing: &'d Drawing) {}
+
+    fn visit_run(&mut self, run: &'d R) {
+        for inner_content in &run.run_inner_contents {
+            let note_number = match inner_content {
+                RunInnerContent::FootnoteReference(reference) if reference.custom_mark_follows != Some(true) => {
+                    self.footnote_count += 1;
+                    Some(self.footnote_count.to_string())
+                }
+                // Endnotes are numbered with lower case roman numerals by default
+                RunInnerContent::EndnoteReference(reference) if reference.custom_mark_follows != Some(true) => {
+                    self.endnote_count += 1;
+                    to_roman(self.endnote_count).map(|roman| roman.to_lowercase())
+                }
+                _ => None,
+            };
+
+            let text = run_inner_content_text(inner_content);
+            for (_, _, bookmark) in &mut self.open_bookmarks {
+                if let Some(text) = text {
+                    bookmark.text.push_str(text);
+                }
+                if bookmark.note_number.is_none() {
+                    bookmark.note_number = note_number.clone();
+                }
+            }
+        }
+    }
+
+    fn visit_range_markup_element(&mut self, range_markup: &'d RangeMarkupElements) {
+        match range_markup {
+            RangeMarkupElements::BookmarkStart(bookmark) => {
+                self.open_bookmarks
+                    .push((bookmark.base.base.base.id, bookmark.name.clone(), Default::default()));
+            }
+            RangeMarkupElements::BookmarkEnd(bookmark_end) => {
+                let index = self
+                    .open_bookmarks
+                    .iter()
+                    .position(|(id, _, _)| *id == bookmark_end.base.id);
+                if let Some(index) = index {
+                    let (_, name, mut bookmark) = self.open_bookmarks.remove(index);
+                    let text_length = bookmark.text.trim_end_matches('\n').len();
+                    bookmark.text.truncate(text_length);
+                    self.bookmarks.insert(name, bookmark);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+// Computes field results the way Word does when the document is opened with Settings::update_fields set. Fields
+// that can't be evaluated without laying out pages, like PAGE or TOC, keep their stale results.
+pub struct FieldUpdater<'a> {
+    package: &'a Package,
+    document_variables: HashMap<String, String>,
+    style_names: HashMap<String, String>,
+    bookmarks: HashMap<String, BookmarkContent>,
+    paragraphs: Vec<ParagraphInfo>,
+    now: FieldDateTime,
+    sequences: HashMap<String, SequenceState>,
+    heading_counts: [usize; 9],
+    is_in_main_document: bool,
+    current_paragraph: Option<usize>,
+    next_paragraph: usize,
+    updated_count: usize,
+}
+
+impl<'a> FieldUpdater<'a> {
+    // The document is scanned for bookmarks, headings and styled paragraphs, it can be taken out of the package
+    // beforehand so it can be updated while the package is borrowed
+    pub fn new(package: &'a Package, document: &Document) -> Self {
+        let document_variables = package
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.document_variables.as_ref())
+            .map(|document_variables| {
+                document_variables
+                    .0
+                    .iter()
+                    .map(|variable| (variable.name.clone(), variable.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let style_names = package
+            .styles
+            .as_ref()
+            .map(|styles| {
+                styles
+                    .styles
+                    .iter()
+                    .filter_map(|style| Some((style.style_id.clone()?, style.name.clone()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut instance = Self {
+            package,
+            document_variables,
+            style_names,
+            bookmarks: HashMap::new(),
+            paragraphs: Vec::new(),
+            // UTC unless the local time is given with with_date_time
+            now: FieldDateTime::now(0),
+            sequences: HashMap::new(),
+            heading_counts: Default::default(),
+            is_in_main_document: false,
+            current_paragraph: None,
+            next_paragraph: 0,
+            updated_count: 0,
+        };
+        instance.scan_document(document);
+        instance
+    }
+
+    pub fn with_date_time(mut self, now: FieldDateTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    pub fn updated_count(&self) -> usize {
+        self.updated_count
+    }
+
+    pub fn bookmark(&self, name: &str) -> Option<&BookmarkContent> {
+        self.bookmarks.get(name)
+    }
+
+    // Must be called with the same document the updater was created with
+    pub fn update_document(&mut self, document: &mut Document) {
+        // References must show the updated results of the fields inside their bookmarks, so the document is scanned
+        // again after the first pass and updated once more with the sequences starting over
+        let updated_count = self.updated_count;
+        self.update_main_story(document);
+        self.scan_document(document);
+        self.sequences.clear();
+        self.updated_count = updated_count;
+        self.update_main_story(document);
+    }
+
+    // Headers, footers and notes aren't part of the main story, STYLEREF finds the first matching paragraph there
+    pub fn update_block_level_elements(&mut self, elements: &mut [BlockLevelElts]) {
+        for element in elements {
+            self.visit_block_level_element_mut(element);
+        }
+    }
+
+    pub fn update_note(&mut self, note: &mut FtnEdn) {
+        self.update_block_level_elements(&mut note.block_level_elements);
+    }
+
+    fn scan_document(&mut self, document: &Document) {
+        let mut scanner = DocumentScanner {
+            style_resolver: self.package.style_resolver(),
+            paragraphs: Vec::new(),
+            bookmarks: HashMap::new(),
+            open_bookmarks: Vec::new(),
+            footnote_count: 0,
+            endnote_count: 0,
+        };
+        scanner.visit_document(document);
+
+        self.paragraphs = scanner.paragraphs;
+        self.bookmarks = scanner.bookmarks;
+    }
+
+    fn update_main_story(&mut self, document: &mut Document) {
+        self.is_in_main_document = true;
+        self.next_paragraph = 0;
+        self.heading_counts = Default::default();
+        self.visit_document_mut(document);
+        self.is_in_main_document = false;
+        self.current_paragraph = None;
+    }
+
+    // Returns None for fields that can't be evaluated, those keep their current result
+    pub fn evaluate(&mut self, field_code: &FieldCode) -> Option<String> {
+        let switches = &field_code.general_switches;
+        let result = match &field_code.field_type {
+            FieldType::Seq(seq_field) => return Some(self.evaluate_sequence(seq_field, switches)),
+            FieldType::Ref(ref_field) => match self.bookmarks.get(&ref_field.bookmark) {
+                Some(bookmark) => bookmark.text.clone(),
+                None => return Some(String::from(REFERENCE_NOT_FOUND)),
+            },
+            FieldType::NoteRef(ref_field) => match self
+                .bookmarks
+                .get(&ref_field.bookmark)
+                .and_then(|bookmark| bookmark.note_number.clone())
+            {
+                Some(note_number) => note_number,
+                None => return Some(String::from(REFERENCE_NOT_FOUND)),
+            },
+            FieldType::Date => return Some(self.format_date_time(self.now, switches, DEFAULT_DATE_PICTURE)),
+            FieldType::Time => return Some(self.format_date_time(self.now, switches, DEFAULT_TIME_PICTURE)),
+            FieldType::CreateDate => return self.format_core_date(|core| core.created.as_ref(), switches),
+            FieldType::SaveDate => return self.format_core_date(|core| core.modified.as_ref(), switches),
+            FieldType::PrintDate => return self.format_core_date(|core| core.last_printed.as_ref(), switches),
+            FieldType::NumPages => self.package.app_info.as_ref()?.pages?.to_string(),
+            FieldType::DocProperty(name) => match self.document_property(name, switches) {
+                Some(value) => value,
+                None => return Some(String::from(UNKNOWN_DOCUMENT_PROPERTY)),
+            },
+            FieldType::DocVariable(name) => match self.document_variables.get(name) {
+                Some(value) => value.clone(),
+                None => return Some(String::from(DOCUMENT_VARIABLE_NOT_FOUND)),
+            },
+            FieldType::StyleRef(style_ref_field) => match self.find_style_reference(style_ref_field) {
+                Some(text) => text,
+                None => return Some(String::from(STYLE_NOT_FOUND)),
+            },
+            FieldType::If(if_field) => {
+                let condition = &if_field.condition;
+                let text = if condition
+                    .operator
+                    .compare(&condition.left.text(), &condition.right.text())
+                {
+                    &if_field.true_text
+                } else {
+                    &if_field.false_text
+                };
+                text.as_ref().map(Argument::text).unwrap_or_default()
+            }
+            _ => return None,
+        };
+
+        Some(format_result(&result, switches))
+    }
+
+    fn evaluate_sequence(&mut self, seq_field: &SeqField, switches: &GeneralSwitches) -> String {
+        let heading_counts = self.heading_counts;
+        let state = self
+            .sequences
+            .entry(seq_field.identifier.clone())
+            .or_insert(SequenceState {
+                value: 0,
+                heading_counts,
+            });
+
+        // \s restarts the numbering after each heading of the given level or above
+        if let Some(level) = seq_field.reset_heading_level {
+            let level = std::cmp::min(level as usize, 9);
+            if state.heading_counts[..level] != heading_counts[..level] {
+                state.value = 0;
+            }
+        }
+        state.heading_counts = heading_counts;
+
+        match seq_field.reset_to {
+            Some(reset_to) => state.value = i64::from(reset_to),
+            None if seq_field.repeat => (),
+            None => state.value += 1,
+        }
+
+        if seq_field.hide && switches.formats.is_empty() {
+            String::new()
+        } else {
+            format_result(&state.value.to_string(), switches)
+        }
+    }
+
+    fn find_style_reference(&self, style_ref_field: &StyleRefField) -> Option<String> {
+        let is_matching_style = |paragraph: &&ParagraphInfo| match &paragraph.style_id {
+            Some(style_id) => {
+                style_id.eq_ignore_ascii_case(&style_ref_field.style)
+                    || self
+                        .style_names
+                        .get(style_id)
+                        .map(|style_name| style_name.eq_ignore_ascii_case(&style_ref_field.style))
+                        .unwrap_or(false)
+            }
+            None => false,
+        };
+
+        // Word looks backwards from the field first, then forwards
+        let (preceding, following) = match self.current_paragraph {
+            Some(index) => self
+                .paragraphs
+                .split_at(std::cmp::min(index + 1, self.paragraphs.len())),
+            None => (&self.paragraphs[..0], self.paragraphs.as_slice()),
+        };
+
+        preceding
+            .iter()
+            .rev()
+            .find(is_matching_style)
+            .or_else(|| following.iter().find(is_matching_style))
+            .map(|paragraph| String::from(paragraph.text.trim()))
+    }
+
+    fn format_date_time(&self, date_time: FieldDateTime, switches: &GeneralSwitches, default_picture: &str) -> String {
+        let picture = switches.date_time_picture.as_deref().unwrap_or(default_picture);
+        switches.format_text(&date_time.format(picture))
+    }
+
+    fn format_core_date<F>(&self, date: F, switches: &GeneralSwitches) -> Option<String>
+    where
+        F: Fn(&Core) -> Option<&String>,
+    {
+        let date_time = date(self.package.core.as_ref()?)?.parse().ok()?;
+        Some(self.format_date_time(date_time, switches, DEFAULT_DATE_PICTURE))
+    }
+
+    fn document_property(&self, name: &str, switches: &GeneralSwitches) -> Option<String> {
+        let core = self.package.core.as_ref();
+        let app_info = self.package.app_info.as_ref();
+        let core_text = |text: fn(&Core) -> Option<&String>| core.and_then(text).cloned().unwrap_or_default();
+        let app_text = |text: fn(&AppInfo) -> Option<&String>| app_info.and_then(text).cloned().unwrap_or_default();
+        let app_number = |number: fn(&AppInfo) -> Option<i32>| {
+            app_info
+                .and_then(number)
+                .map(|number| number.to_string())
+                .unwrap_or_default()
+        };
+        let core_date = |date: fn(&Core) -> Option<&String>| {
+            core.and_then(date)
+                .and_then(|date| date.parse().ok())
+                .map(|date_time| self.format_date_time(date_time, switches, DEFAULT_DATE_TIME_PICTURE))
+                .unwrap_or_default()
+        };
+
+        let value = match name.to_lowercase().as_str() {
+            "title" => core_text(|core| core.title.as_ref()),
+            "subject" => core_text(|core| core.subject.as_ref()),
+            "author" => core_text(|core| core.creator.as_ref()),
+            "keywords" => core_text(|core| core.keywords.as_ref()),
+            "comments" => core_text(|core| core.description.as_ref()),
+            "category" => core_text(|core| core.category.as_ref()),
+            "contentstatus" => core_text(|core| core.content_status.as_ref()),
+            "lastsavedby" => core_text(|core| core.last_modified_by.as_ref()),
+            "revisionnumber" => core
+                .and_then(|core| core.revision)
+                .map(|revision| revision.to_string())
+                .unwrap_or_default(),
+            "createtime" => core_date(|core| core.created.as_ref()),
+            "lastsavetime" => core_date(|core| core.modified.as_ref()),
+            "lastprinted" => core_date(|core| core.last_printed.as_ref()),
+            "company" => app_text(|app_info| app_info.company.as_ref()),
+            "manager" => app_text(|app_info| app_info.manager.as_ref()),
+            "template" => app_text(|app_info| app_info.template.as_ref()),
+            "nameofapplication" => app_text(|app_info| app_info.application.as_ref()),
+            "hyperlinkbase" => app_text(|app_info| app_info.hyperlink_base.as_ref()),
+            "pages" => app_number(|app_info| app_info.pages),
+            "words" => app_number(|app_info| app_info.words),
+            "characters" => app_number(|app_info| app_info.characters),
+            "characterswithspaces" => app_number(|app_info| app_info.characters_with_spaces),
+            "lines" => app_number(|app_info| app_info.lines),
+            "paragraphs" => app_number(|app_info| app_info.paragraphs),
+            "totaleditingtime" => app_number(|app_info| app_info.total_time),
+            "security" => app_number(|app_info| app_info.doc_security),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    fn update_p_contents(&mut self, contents: &mut Vec<PContent>) {
+        let mut updated_contents = Vec::new();
+        let mut open_fields: Vec<OpenField> = Vec::new();
+
+        for mut content in split_field_character_runs(std::mem::take(contents)) {
+            match field_character_type(&content) {
+                Some(FldCharType::Begin) => {
+                    open_fields.push(OpenField {
+                        instruction: String::new(),
+                        result_text: String::new(),
+                        separate_index: None,
+                        begin_properties: run_properties(&content),
+                        result_properties: None,
+                        is_locked: field_character(&content)
+                            .and_then(|field_character| field_character.field_lock)
+                            .unwrap_or(false),
+                        contents: vec![content],
+                    });
+                    continue;
+                }
+                Some(FldCharType::Separate) => {
+                    if let Some(open_field) = open_fields.last_mut() {
+                        open_field.separate_index = Some(open_field.contents.len());
+                        open_field.contents.push(content);
+                        continue;
+                    }
+                }
+                Some(FldCharType::End) => {
+                    if let Some(field) = open_fields.pop() {
+                        let (field_text, field_contents) = self.update_field(field, content);
+                        match open_fields.last_mut() {
+                            Some(parent) => {
+                                if parent.separate_index.is_some() {
+                                    parent.result_text.push_str(&field_text);
+                                } else {
+                                    parent.instruction.push_str(&field_text);
+                                }
+                                parent.contents.extend(field_contents);
+                            }
+                            None => updated_contents.extend(field_contents),
+                        }
+                        continue;
+                    }
+                }
+                None => (),
+            }
+
+            self.update_nested_p_contents(&mut content);
+            if let PContent::SimpleField(simple_field) = &mut content {
+                self.update_simple_field(simple_field);
+            }
+
+            match open_fields.last_mut() {
+                Some(open_field) => {
+                    let is_in_result = open_field.separate_index.is_some();
+                    if let Some(text) = run_text(&content, is_in_result) {
+                        if is_in_result {
+                            open_field.result_text.push_str(&text);
+                            if open_field.result_properties.is_none() {
+                                open_field.result_properties = run_properties(&content);
+                            }
+                        } else {
+                            open_field.instruction.push_str(&text);
+                        }
+                    }
+                    open_field.contents.push(content);
+                }
+                None => updated_contents.push(content),
+            }
+        }
+
+        // Fields continuing in another paragraph are left untouched
+        for open_field in open_fields {
+            updated_contents.extend(open_field.contents);
+        }
+
+        *contents = updated_contents;
+    }
+
+    // Nested fields were already updated, their results are part of the instruction
+    fn update_field(&mut self, field: OpenField, end: PContent) -> (String, Vec<PContent>) {
+        let OpenField {
+            mut contents,
+            instruction,
+            result_text,
+            separate_index,
+            begin_properties,
+            result_properties,
+            is_locked,
+        } = field;
+
+        let result = match instruction.parse::<FieldCode>() {
+            Ok(field_code) if !is_locked => self.evaluate(&field_code),
+            _ => None,
+        };
+        let result = match result {
+            Some(result) => result,
+            None => {
+                contents.push(end);
+                return (result_text, contents);
+            }
+        };
+
+        match separate_index {
+            Some(separate_index) => contents.truncate(separate_index + 1),
+            None => contents.push(field_character_run(FldCharType::Separate, run_properties(&end))),
+        }
+        contents.extend(result_run(&result, result_properties.or(begin_properties), false));
+        contents.push(end);
+
+        if let Some(RunInnerContent::FieldCharacter(begin)) = contents
+            .first_mut()
+            .and_then(as_run_mut)
+            .and_then(|run| run.run_inner_contents.first_mut())
+        {
+            begin.dirty = None;
+        }
+
+        self.updated_count += 1;
+        (result, contents)
+    }
+
+    fn update_simple_field(&mut self, simple_field: &mut SimpleField) {
+        if simple_field.field_lock == Some(true) {
+            return;
+        }
+
+        let result = match simple_field.field_codes.parse::<FieldCode>() {
+            Ok(field_code) => self.evaluate(&field_code),
+            Err(_) => None,
+        };
+        if let Some(result) = result {
+            let properties = simple_field.paragraph_contents.iter().find_map(run_properties);
+            simple_field.paragraph_contents = result_run(&result, properties, false).into_iter().collect();
+            simple_field.dirty = None;
+            self.updated_count += 1;
+        }
+    }
+
+    fn update_nested_p_contents(&mut self, content: &mut PContent) {
+        match content {
+            PContent::Hyperlink(hyperlink) => self.update_p_contents(&mut hyperlink.paragraph_contents),
+            PContent::ContentRunContent(run_content) => match run_content.as_mut() {
+                ContentRunContent::CustomXml(custom_xml) => self.update_p_contents(&mut custom_xml.paragraph_contents),
+                ContentRunContent::SmartTag(smart_tag) => self.update_p_contents(&mut smart_tag.paragraph_contents),
+                ContentRunContent::Sdt(sdt) => {
+                    if let Some(sdt_content) = &mut sdt.sdt_content {
+                        self.update_p_contents(&mut sdt_content.p_contents);
+                    }
+                }
+                ContentRunContent::Bidirectional(dir) => self.update_p_contents(&mut dir.p_contents),
+                ContentRunContent::BidirectionalOverride(bdo) => self.update_p_contents(&mut bdo.p_contents),
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+}
+
+impl<'a> VisitMut for FieldUpdater<'a> {
+    fn visit_paragraph_mut(&mut self, paragraph: &mut P) {
+        if self.is_in_main_document {
+            let index = self.next_paragraph;
+            self.next_paragraph += 1;
+            self.current_paragraph = Some(index);
+
+            if let Some(heading_level) = self.paragraphs.get(index).and_then(|paragraph| paragraph.heading_level) {
+                self.heading_counts[heading_level - 1] += 1;
+            }
+        }
+
+        self.update_p_contents(&mut paragraph.contents);
+    }
+}
+
+impl Package {
+    // Word does this on open when Settings::update_fields is set, returns the number of updated fields
+    pub fn update_fields(&mut self) -> usize {
+        let mut document = match self.main_document.take() {
+            Some(document) => document,
+            None => return 0,
+        };
+        let mut headers = std::mem::take(&mut self.headers);
+        let mut footers = std::mem::take(&mut self.footers);
+        let mut footnotes = self.footnotes.take();
+        let mut endnotes = self.endnotes.take();
+
+        let updated_count = {
+            let mut updater = FieldUpdater::new(self, &document);
+            updater.update_document(&mut document);
+
+            for header_footer in headers.values_mut().chain(footers.values_mut()) {
+                updater.update_block_level_elements(&mut header_footer.block_level_elements);
+            }
+
+            let footnotes = footnotes.iter_mut().flat_map(|footnotes| footnotes.0.iter_mut());
+            let endnotes = endnotes.iter_mut().flat_map(|endnotes| endnotes.0.iter_mut());
+            for note in footnotes.chain(endnotes) {
+                updater.update_note(note);
+            }
+
+            updater.updated_count()
+        };
+
+        self.main_document = Some(document);
+        self.headers = headers;
+        self.footers = footers;
+        self.footnotes = footnotes;
+        self.endnotes = endnotes;
+        updated_count
+    }
+}
+
+// Applies the \# numeric picture and the \* formats of the general switches
+fn format_result(result: &str, switches: &GeneralSwitches) -> String {
+    let mut formatted = match (&switches.numeric_picture, result.trim().parse::<f64>()) {
+        (Some(picture), Ok(value)) => format_number(value, picture),
+        _ => String::from(result),
+    };
+
+    if let Ok(value) = formatted.trim().parse::<i64>() {
+        if let Some(number_text) = switches
+            .formats
+            .iter()
+            .find_map(|format| format_number_text(value, format))
+        {
+            formatted = number_text;
+        }
+    }
+
+    switches.format_text(&formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldUpdater, REFERENCE_NOT_FOUND};
+    use crate::{
+        fieldformat::FieldDateTime,
+        package::Package,
+        search::ParagraphText,
+        test_util::{
+            body_paragraphs, bookmark_end, bookmark_start, complex_field, package_with_paragraphs, paragraph,
+            styled_paragraph, text_run,
+        },
+        wml::{
+            document::{PContent, SimpleField},
+            settings::{DocVar, DocVars, Settings},
+        },
+    };
+    use msoffice_shared::docprops::Core;
+
+    fn simple_field(instruction: &str) -> PContent {
+        PContent::SimpleField(SimpleField {
+            paragraph_contents: vec![text_run("stale")],
+            field_codes: String::from(instruction),
+            field_lock: None,
+            dirty: None,
+        })
+    }
+
+    fn paragraph_texts(package: &Package) -> Vec<String> {
+        body_paragraphs(package)
+            .into_iter()
+            .map(|paragraph| ParagraphText::from_paragraph(paragraph).text)
+            .collect()
+    }
+
+    #[test]
+    pub fn test_update_sequences_and_references() {
+        let caption = |stale: &str| {
+            let mut contents = vec![text_run("Figure ")];
+            contents.extend(complex_field(" SEQ Figure \\* ARABIC ", stale));
+            contents
+        };
+
+        let mut second_caption = vec![bookmark_start(0, "SecondFigure")];
+        second_caption.extend(caption("9"));
+        second_caption.push(bookmark_end(0));
+
+        let mut reference = vec![text_run("See ")];
+        reference.extend(complex_field(" REF SecondFigure \\h ", "Figure 9"));
+        reference.push(text_run(", "));
+        reference.extend(complex_field(" SEQ Figure \\c \\* roman ", "ix"));
+        reference.push(simple_field(" REF Missing "));
+
+        let mut package = package_with_paragraphs(vec![
+            paragraph(caption("7")),
+            paragraph(second_caption),
+            paragraph(caption("8")),
+            paragraph(reference),
+        ]);
+
+        assert_eq!(package.update_fields(), 6);
+        let texts = paragraph_texts(&package);
+        assert_eq!(texts[0], "Figure 1");
+        assert_eq!(texts[1], "Figure 2");
+        assert_eq!(texts[2], "Figure 3");
+        assert_eq!(texts[3], format!("See Figure 2, iii{}", REFERENCE_NOT_FOUND));
+    }
+
+    #[test]
+    pub fn test_update_properties_dates_and_variables() {
+        let mut contents = complex_field(" DOCPROPERTY Title \\* Upper ", "old title");
+        contents.extend(complex_field(" CREATEDATE \\@ \"dddd, MMMM d, yyyy\" ", "old date"));
+        contents.push(text_run(" / "));
+        contents.extend(complex_field(" DATE \\@ \"yyyy-MM-dd\" ", "old date"));
+        contents.push(text_run(" / "));
+        contents.extend(complex_field(" DOCVARIABLE Amount \\# \"#,##0.00\" ", "0"));
+        contents.extend(complex_field(" PAGE ", "3"));
+
+        let mut package = package_with_paragraphs(vec![paragraph(contents)]);
+        package.core = Some(Core {
+            title: Some(String::from("Annual report")),
+            created: Some(String::from("2019-03-05T14:07:09Z")),
+            ..Default::default()
+        });
+        package.settings = Some(Box::new(Settings {
+            document_variables: Some(DocVars(vec![DocVar {
+                name: String::from("Amount"),
+                value: String::from("1234.5"),
+            }])),
+            ..Default::default()
+        }));
+
+        let mut document = package.main_document.take().unwrap();
+        let mut updater =
+            FieldUpdater::new(&package, &document).with_date_time(FieldDateTime::new(2020, 1, 2, 0, 0, 0));
+        updater.update_document(&mut document);
+        assert_eq!(updater.updated_count(), 4);
+        package.main_document = Some(document);
+
+        assert_eq!(
+            paragraph_texts(&package)[0],
+            "ANNUAL REPORTTuesday, March 5, 2019 / 2020-01-02 / 1,234.503"
+        );
+    }
+
+    #[test]
+    pub fn test_update_style_references_and_heading_resets() {
+        let numbered_table = || complex_field(" SEQ Table \\s 1 ", "0");
+        let mut style_reference = complex_field(" STYLEREF Heading1 ", "stale");
+        style_reference.extend(numbered_table());
+
+        let mut package = package_with_paragraphs(vec![
+            styled_paragraph(vec![text_run("Introduction")], Some("Heading1"), Some(0)),
+            paragraph(numbered_table()),
+            paragraph(numbered_table()),
+            styled_paragraph(vec![text_run("Results")], Some("Heading1"), Some(0)),
+            paragraph(style_reference),
+        ]);
+
+        package.update_fields();
+        let texts = paragraph_texts(&package);
+        assert_eq!(texts[1], "1");
+        assert_eq!(texts[2], "2");
+        assert_eq!(texts[4], "Results1");
+    }
+}
@@ -0,0 +1,596 @@
+use crate::{
+    field::{
+        as_run_mut, document_fields, field_character, field_character_run, field_character_type, result_run,
+        run_properties, split_field_character_runs, Field,
+    },
+    package::Package,
+    visit::VisitMut,
+    wml::document::{ContentRunContent, FFData, FFTextType, FldCharType, PContent, RPr, RunInnerContent, P},
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+// Word shows five en spaces in text form fields without a value
+pub const EMPTY_TEXT_FORM_FIELD_RESULT: &str = "\u{2002}\u{2002}\u{2002}\u{2002}\u{2002}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormFieldType {
+    Text,
+    CheckBox,
+    DropDown,
+}
+
+impl FormFieldType {
+    pub fn from_field_name(name: &str) -> Option<Self> {
+        match name {
+            "FORMTEXT" => Some(FormFieldType::Text),
+            "FORMCHECKBOX" => Some(FormFieldType::CheckBox),
+            "FORMDROPDOWN" => Some(FormFieldType::DropDown),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormFieldValue {
+    Text(String),
+    CheckBox(bool),
+    // Index into FormField::list_entries
+    DropDown(usize),
+}
+
+impl FormFieldValue {
+    pub fn field_type(&self) -> FormFieldType {
+        match self {
+            FormFieldValue::Text(_) => FormFieldType::Text,
+            FormFieldValue::CheckBox(_) => FormFieldType::CheckBox,
+            FormFieldValue::DropDown(_) => FormFieldType::DropDown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    pub name: String,
+    pub field_type: FormFieldType,
+    pub enabled: bool,
+    pub default_value: FormFieldValue,
+    pub value: FormFieldValue,
+    pub max_length: Option<usize>,
+    pub entry_macro: Option<String>,
+    pub exit_macro: Option<String>,
+    pub help_text: Option<String>,
+    pub status_text: Option<String>,
+    pub list_entries: Vec<String>,
+    pub text_type: Option<FFTextType>,
+    pub text_format: Option<String>,
+}
+
+impl FormField {
+    pub fn from_field(field: &Field) -> Option<Self> {
+        let field_code = field.field_code().ok()?;
+        let field_type = FormFieldType::from_field_name(field_code.name())?;
+
+        let mut form_field = Self {
+            name: String::new(),
+            field_type,
+            enabled: true,
+            default_value: Self::empty_value(field_type),
+            value: Self::empty_value(field_type),
+            max_length: None,
+            entry_macro: None,
+            exit_macro: None,
+            help_text: None,
+            status_text: None,
+            list_entries: Vec::new(),
+            text_type: None,
+            text_format: None,
+        };
+
+        for data in &field.form_field_data {
+            match data {
+                FFData::Name(name) => form_field.name = name.clone(),
+                FFData::Enabled(enabled) => form_field.enabled = *enabled,
+                FFData::EntryMacro(entry_macro) => form_field.entry_macro = Some(entry_macro.clone()),
+                FFData::ExitMacro(exit_macro) => form_field.exit_macro = Some(exit_macro.clone()),
+                FFData::HelpText(help_text) => form_field.help_text = help_text.value.clone(),
+                FFData::StatusText(status_text) => form_field.status_text = status_text.value.clone(),
+                FFData::CheckBox(check_box) => {
+                    let is_default = check_box.is_default.unwrap_or(false);
+                    form_field.default_value = FormFieldValue::CheckBox(is_default);
+                    form_field.value = FormFieldValue::CheckBox(check_box.is_checked.unwrap_or(is_default));
+                }
+                FFData::DropDownList(drop_down_list) => {
+                    let default = drop_down_list.default.unwrap_or(0).max(0) as usize;
+                    let result = drop_down_list.result.map(|result| result.max(0) as usize);
+                    form_field.default_value = FormFieldValue::DropDown(default);
+                    form_field.value = FormFieldValue::DropDown(result.unwrap_or(default));
+                    form_field.list_entries = drop_down_list.list_entries.clone();
+                }
+                FFData::TextInput(text_input) => {
+                    form_field.default_value = FormFieldValue::Text(text_input.default.clone().unwrap_or_default());
+                    // A maximum length of 0 means the length isn't limited
+                    form_field.max_length = text_input
+                        .max_length
+                        .filter(|max_length| *max_length > 0)
+                        .map(|max_length| max_length as usize);
+                    form_field.text_type = text_input.text_type;
+                    form_field.text_format = text_input.format.clone();
+                }
+                _ => (),
+            }
+        }
+
+        if field_type == FormFieldType::Text {
+            let text = match field.result_text.as_str() {
+                EMPTY_TEXT_FORM_FIELD_RESULT => "",
+                text => text,
+            };
+            form_field.value = FormFieldValue::Text(String::from(text));
+        }
+
+        Some(form_field)
+    }
+
+    pub fn selected_entry(&self) -> Option<&str> {
+        match self.value {
+            FormFieldValue::DropDown(index) => self.list_entries.get(index).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    pub fn validate_value(&self, value: &FormFieldValue) -> Result<()> {
+        if value.field_type() != self.field_type {
+            return Err(FormFieldError::TypeMismatch {
+                name: self.name.clone(),
+                field_type: self.field_type,
+            });
+        }
+
+        match value {
+            FormFieldValue::Text(text) => match self.max_length {
+                Some(max_length) if text.chars().count() > max_length => Err(FormFieldError::TextTooLong {
+                    name: self.name.clone(),
+                    max_length,
+                }),
+                _ => Ok(()),
+            },
+            FormFieldValue::DropDown(index) if *index >= self.list_entries.len() => {
+                Err(FormFieldError::DropDownIndexOutOfRange {
+                    name: self.name.clone(),
+                    index: *index,
+                    entry_count: self.list_entries.len(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn empty_value(field_type: FormFieldType) -> FormFieldValue {
+        match field_type {
+            FormFieldType::Text => FormFieldValue::Text(String::new()),
+            FormFieldType::CheckBox => FormFieldValue::CheckBox(false),
+            FormFieldType::DropDown => FormFieldValue::DropDown(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormFieldError {
+    NotFound(String),
+    TypeMismatch {
+        name: String,
+        field_type: FormFieldType,
+    },
+    DropDownIndexOutOfRange {
+        name: String,
+        index: usize,
+        entry_count: usize,
+    },
+    TextTooLong {
+        name: String,
+        max_length: usize,
+    },
+}
+
+impl Display for FormFieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormFieldError::NotFound(name) => write!(f, "Form field '{}' not found", name),
+            FormFieldError::TypeMismatch { name, field_type } => {
+                write!(f, "Form field '{}' is a {:?} field", name, field_type)
+            }
+            FormFieldError::DropDownIndexOutOfRange {
+                name,
+                index,
+                entry_count,
+            } => write!(
+                f,
+                "Index {} is out of range for drop-down form field '{}' with {} entries",
+                index, name, entry_count
+            ),
+            FormFieldError::TextTooLong { name, max_length } => write!(
+                f,
+                "Text form field '{}' accepts at most {} characters",
+                name, max_length
+            ),
+        }
+    }
+}
+
+impl Error for FormFieldError {}
+
+type Result<T> = std::result::Result<T, FormFieldError>;
+
+pub fn document_form_fields(fields: &[Field]) -> Vec<FormField> {
+    fields
+        .iter()
+        .flat_map(|field| std::iter::once(field).chain(field.descendants()))
+        .filter_map(FormField::from_field)
+        .collect()
+}
+
+struct PendingField {
+    contents: Vec<PContent>,
+    value: FormFieldValue,
+    result_text: Option<String>,
+    nesting_depth: usize,
+    separate_index: Option<usize>,
+    begin_properties: Option<RPr>,
+    result_properties: Option<RPr>,
+}
+
+// Values have to be validated beforehand, see FormField::validate_value
+pub struct FormFieldFiller<'a> {
+    values: &'a HashMap<String, FormFieldValue>,
+    filled_count: usize,
+}
+
+impl<'a> FormFieldFiller<'a> {
+    pub fn new(values: &'a HashMap<String, FormFieldValue>) -> Self {
+        Self {
+            values,
+            filled_count: 0,
+        }
+    }
+
+    pub fn filled_count(&self) -> usize {
+        self.filled_count
+    }
+
+    pub fn fill_p_contents(&mut self, contents: &mut Vec<PContent>) {
+        let mut filled_contents = Vec::new();
+        let mut pending_field: Option<PendingField> = None;
+
+        for mut content in split_field_character_runs(std::mem::take(contents)) {
+            let field = match &mut pending_field {
+                Some(field) => field,
+                None => {
+                    match self.begin_form_field(&mut content) {
+                        Some((value, result_text)) => {
+                            pending_field = Some(PendingField {
+                                begin_properties: run_properties(&content),
+                                contents: vec![content],
+                                value,
+                                result_text,
+                                nesting_depth: 0,
+                                separate_index: None,
+                                result_properties: None,
+                            })
+                        }
+                        None => {
+                            self.fill_nested_p_contents(&mut content);
+                            filled_contents.push(content);
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            match (field_character_type(&content), field.nesting_depth) {
+                (Some(FldCharType::Begin), _) => field.nesting_depth += 1,
+                (Some(FldCharType::End), 0) => {
+                    if let Some(field) = pending_field.take() {
+                        filled_contents.extend(self.fill_field(field, content));
+                    }
+                    continue;
+                }
+                (Some(FldCharType::End), _) => field.nesting_depth -= 1,
+                (Some(FldCharType::Separate), 0) => field.separate_index = Some(field.contents.len()),
+                (None, 0) if field.separate_index.is_some() && field.result_properties.is_none() => {
+                    field.result_properties = run_properties(&content)
+                }
+                _ => (),
+            }
+            field.contents.push(content);
+        }
+
+        // Form fields continuing in another paragraph are left untouched
+        if let Some(field) = pending_field {
+            filled_contents.extend(field.contents);
+        }
+
+        *contents = filled_contents;
+    }
+
+    // Updates the form field data of a begin field character and returns the value and the text of the new result
+    fn begin_form_field(&self, content: &mut PContent) -> Option<(FormFieldValue, Option<String>)> {
+        let name = field_character(content)
+            .filter(|field_character| field_character.field_char_type == FldCharType::Begin)?
+            .form_field_properties
+            .iter()
+            .find_map(|data| match data {
+                FFData::Name(name) => Some(name),
+                _ => None,
+            })?;
+        let value = self.values.get(name)?.clone();
+
+        let field_character = match as_run_mut(content)?.run_inner_contents.first_mut() {
+            Some(RunInnerContent::FieldCharacter(field_character)) => field_character,
+            _ => return None,
+        };
+
+        let mut result_text = None;
+        for data in &mut field_character.form_field_properties {
+            match (data, &value) {
+                (FFData::CheckBox(check_box), FormFieldValue::CheckBox(is_checked)) => {
+                    check_box.is_checked = Some(*is_checked)
+                }
+                (FFData::DropDownList(drop_down_list), FormFieldValue::DropDown(index)) => {
+                    drop_down_list.result = Some(*index as i32);
+                    result_text = drop_down_list.list_entries.get(*index).cloned();
+                }
+                (FFData::TextInput(_), FormFieldValue::Text(text)) => {
+                    result_text = match text.as_str() {
+                        "" => Some(String::from(EMPTY_TEXT_FORM_FIELD_RESULT)),
+                        text => Some(String::from(text)),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Some((value, result_text))
+    }
+
+    fn fill_field(&mut self, field: PendingField, end: PContent) -> Vec<PContent> {
+        let PendingField {
+            mut contents,
+            value,
+            result_text,
+            separate_index,
+            begin_properties,
+            result_properties,
+            ..
+        } = field;
+
+        // Check boxes have no result text, their state is kept in the form field data only
+        if let (Some(result_text), false) = (result_text, value.field_type() == FormFieldType::CheckBox) {
+            match separate_index {
+                Some(separate_index) => contents.truncate(separate_index + 1),
+                None => contents.push(field_character_run(FldCharType::Separate, run_properties(&end))),
+            }
+            contents.extend(result_run(&result_text, result_properties.or(begin_properties), false));
+        }
+        contents.push(end);
+
+        self.filled_count += 1;
+        contents
+    }
+
+    fn fill_nested_p_contents(&mut self, content: &mut PContent) {
+        match content {
+            PContent::Hyperlink(hyperlink) => self.fill_p_contents(&mut hyperlink.paragraph_contents),
+            PContent::ContentRunContent(run_content) => match run_content.as_mut() {
+                ContentRunContent::CustomXml(custom_xml) => self.fill_p_contents(&mut custom_xml.paragraph_contents),
+                ContentRunContent::SmartTag(smart_tag) => self.fill_p_contents(&mut smart_tag.paragraph_contents),
+                ContentRunContent::Sdt(sdt) => {
+                    if let Some(sdt_content) = &mut sdt.sdt_content {
+                        self.fill_p_contents(&mut sdt_content.p_contents);
+                    }
+                }
+                ContentRunContent::Bidirectional(dir) => self.fill_p_contents(&mut dir.p_contents),
+                ContentRunContent::BidirectionalOverride(bdo) => self.fill_p_contents(&mut bdo.p_contents),
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+}
+
+impl<'a> VisitMut for FormFieldFiller<'a> {
+    fn visit_paragraph_mut(&mut self, paragraph: &mut P) {
+        self.fill_p_contents(&mut paragraph.contents);
+    }
+}
+
+impl Package {
+    pub fn form_fields(&self) -> Vec<FormField> {
+        match &self.main_document {
+            Some(document) => document_form_fields(&document_fields(document)),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn form_field(&self, name: &str) -> Option<FormField> {
+        self.form_fields()
+            .into_iter()
+            .find(|form_field| form_field.name == name)
+    }
+
+    pub fn set_form_field_value(&mut self, name: &str, value: FormFieldValue) -> Result<()> {
+        let mut values = HashMap::new();
+        values.insert(String::from(name), value);
+        self.set_form_field_values(&values).map(|_| ())
+    }
+
+    // All values are validated before the document is changed, returns the number of filled form fields
+    pub fn set_form_field_values(&mut self, values: &HashMap<String, FormFieldValue>) -> Result<usize> {
+        let form_fields = self.form_fields();
+        for (name, value) in values {
+            form_fields
+                .iter()
+                .find(|form_field| &form_field.name == name)
+                .ok_or_else(|| FormFieldError::NotFound(name.clone()))?
+                .validate_value(value)?;
+        }
+
+        let mut filler = FormFieldFiller::new(values);
+        if let Some(document) = &mut self.main_document {
+            filler.visit_document_mut(document);
+        }
+
+        Ok(filler.filled_count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormFieldError, FormFieldType, FormFieldValue, EMPTY_TEXT_FORM_FIELD_RESULT};
+    use crate::{
+        package::Package,
+        search::ParagraphText,
+        test_util::{body_paragraphs, complex_field, package_with_paragraphs, paragraph, run},
+        wml::document::{
+            FFCheckBox, FFCheckBoxSizeChoice, FFDDList, FFData, FFTextInput, FldChar, FldCharType, PContent,
+            RunInnerContent,
+        },
+    };
+    use std::collections::HashMap;
+
+    fn form_field(instruction: &str, form_field_properties: Vec<FFData>, result: &str) -> Vec<PContent> {
+        let mut contents = complex_field(instruction, result);
+        contents[0] = run(vec![RunInnerContent::FieldCharacter(FldChar {
+            form_field_properties,
+            field_char_type: FldCharType::Begin,
+            field_lock: None,
+            dirty: None,
+        })]);
+        contents
+    }
+
+    fn form_package() -> Package {
+        let mut contents = form_field(
+            " FORMTEXT ",
+            vec![
+                FFData::Name(String::from("Customer")),
+                FFData::ExitMacro(String::from("Validate")),
+                FFData::TextInput(FFTextInput {
+                    text_type: None,
+                    default: Some(String::from("Name")),
+                    max_length: Some(10),
+                    format: None,
+                }),
+            ],
+            EMPTY_TEXT_FORM_FIELD_RESULT,
+        );
+        contents.extend(form_field(
+            " FORMCHECKBOX ",
+            vec![
+                FFData::Name(String::from("Approved")),
+                FFData::CheckBox(FFCheckBox {
+                    size: FFCheckBoxSizeChoice::Auto(true),
+                    is_default: Some(true),
+                    is_checked: None,
+                }),
+            ],
+            "",
+        ));
+        contents.extend(form_field(
+            " FORMDROPDOWN ",
+            vec![
+                FFData::Name(String::from("Country")),
+                FFData::DropDownList(FFDDList {
+                    result: Some(1),
+                    default: None,
+                    list_entries: vec![String::from("Austria"), String::from("Hungary")],
+                }),
+            ],
+            "Hungary",
+        ));
+
+        package_with_paragraphs(vec![paragraph(contents)])
+    }
+
+    fn document_text(package: &Package) -> String {
+        ParagraphText::from_paragraph(body_paragraphs(package)[0]).text
+    }
+
+    #[test]
+    pub fn test_form_fields() {
+        let form_fields = form_package().form_fields();
+        assert_eq!(form_fields.len(), 3);
+
+        assert_eq!(form_fields[0].name, "Customer");
+        assert_eq!(form_fields[0].field_type, FormFieldType::Text);
+        assert_eq!(form_fields[0].default_value, FormFieldValue::Text(String::from("Name")));
+        assert_eq!(form_fields[0].value, FormFieldValue::Text(String::new()));
+        assert_eq!(form_fields[0].max_length, Some(10));
+        assert_eq!(form_fields[0].exit_macro, Some(String::from("Validate")));
+
+        assert_eq!(form_fields[1].field_type, FormFieldType::CheckBox);
+        assert_eq!(form_fields[1].value, FormFieldValue::CheckBox(true));
+
+        assert_eq!(form_fields[2].field_type, FormFieldType::DropDown);
+        assert_eq!(form_fields[2].default_value, FormFieldValue::DropDown(0));
+        assert_eq!(form_fields[2].selected_entry(), Some("Hungary"));
+    }
+
+    #[test]
+    pub fn test_set_form_field_values() {
+        let mut package = form_package();
+        let mut values = HashMap::new();
+        values.insert(String::from("Customer"), FormFieldValue::Text(String::from("ACME")));
+        values.insert(String::from("Approved"), FormFieldValue::CheckBox(false));
+        values.insert(String::from("Country"), FormFieldValue::DropDown(0));
+        assert_eq!(package.set_form_field_values(&values), Ok(3));
+
+        let form_fields = package.form_fields();
+        assert_eq!(form_fields[0].value, FormFieldValue::Text(String::from("ACME")));
+        assert_eq!(form_fields[1].value, FormFieldValue::CheckBox(false));
+        assert_eq!(form_fields[2].selected_entry(), Some("Austria"));
+        assert_eq!(document_text(&package), "ACMEAustria");
+
+        package
+            .set_form_field_value("Customer", FormFieldValue::Text(String::new()))
+            .unwrap();
+        assert_eq!(
+            document_text(&package),
+            format!("{}Austria", EMPTY_TEXT_FORM_FIELD_RESULT)
+        );
+    }
+
+    #[test]
+    pub fn test_set_invalid_form_field_values() {
+        let mut package = form_package();
+        assert_eq!(
+            package.set_form_field_value("Missing", FormFieldValue::CheckBox(true)),
+            Err(FormFieldError::NotFound(String::from("Missing")))
+        );
+        assert_eq!(
+            package.set_form_field_value("Approved", FormFieldValue::Text(String::from("yes"))),
+            Err(FormFieldError::TypeMismatch {
+                name: String::from("Approved"),
+                field_type: FormFieldType::CheckBox,
+            })
+        );
+        assert_eq!(
+            package.set_form_field_value("Customer", FormFieldValue::Text(String::from("Much too long"))),
+            Err(FormFieldError::TextTooLong {
+                name: String::from("Customer"),
+                max_length: 10,
+            })
+        );
+        assert_eq!(
+            package.set_form_field_value("Country", FormFieldValue::DropDown(2)),
+            Err(FormFieldError::DropDownIndexOutOfRange {
+                name: String::from("Country"),
+                index: 2,
+                entry_count: 2,
+            })
+        );
+    }
+}
@@ -0,0 +1,1429 @@
+use crate::{
+    export::{drawing_images, grid_cells, hyperlink_url, table_rows, GridCell},
+    package::{MediaReference, Package},
+    plaintext::symbol_to_char,
+    resolvedstyle::{ParagraphProperties, RunProperties},
+    styleresolver::StyleResolver,
+    wml::{
+        document::{
+            BlockLevelElts, Border, BorderType, BrType, ContentBlockContent, ContentRunContent, FldCharType, FtnEdnRef,
+            HexColor, HpsMeasure, Hyperlink, Jc, LineSpacingRule, NumberFormat, PContent, RangeMarkupElements, Ruby,
+            RubyContent, RubyContentChoice, RunInnerContent, RunLevelElts, RunTrackChange, RunTrackChangeChoice, Shd,
+            SignedTwipsMeasure, UnderlineType, P, R,
+        },
+        footnotes::FtnEdn,
+        styles::StyleType,
+        table::{Merge, Tbl},
+    },
+};
+use msoffice_shared::sharedtypes::{TwipsMeasure, UniversalMeasure, UniversalMeasureUnit, VerticalAlignRun};
+use std::{collections::HashMap, path::Path};
+
+const EMUS_PER_PIXEL: u64 = 9525;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageSource {
+    ExternalFiles,
+    DataUri,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlOptions {
+    pub image_source: ImageSource,
+    pub media_directory: String,
+    pub standalone: bool,
+    pub title: Option<String>,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            image_source: ImageSource::ExternalFiles,
+            media_directory: String::from("media"),
+            standalone: true,
+            title: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldPart {
+    Instruction,
+    Result,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NoteKind {
+    Footnote,
+    Endnote,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OpenList {
+    tag: &'static str,
+    list_type: Option<&'static str>,
+}
+
+pub struct HtmlExporter<'a> {
+    package: &'a Package,
+    style_resolver: StyleResolver<'a>,
+    options: HtmlOptions,
+    media_data: HashMap<String, Vec<u8>>,
+    field_stack: Vec<FieldPart>,
+    notes: Vec<String>,
+    open_lists: Vec<OpenList>,
+    media_references: Vec<MediaReference>,
+}
+
+impl<'a> HtmlExporter<'a> {
+    pub fn new(package: &'a Package, options: HtmlOptions) -> Self {
+        Self {
+            package,
+            style_resolver: package.style_resolver(),
+            options,
+            media_data: HashMap::new(),
+            field_stack: Vec::new(),
+            notes: Vec::new(),
+            open_lists: Vec::new(),
+            media_references: Vec::new(),
+        }
+    }
+
+    // Media files missing from the package, like the ones of a package built in memory
+    pub fn with_media_data(mut self, media_data: HashMap<String, Vec<u8>>) -> Self {
+        self.media_data = media_data;
+        self
+    }
+
+    pub fn media_references(&self) -> &[MediaReference] {
+        &self.media_references
+    }
+
+    pub fn export_main_document(&mut self) -> String {
+        let package = self.package;
+        let mut body = match package
+            .main_document
+            .as_ref()
+            .and_then(|document| document.body.as_ref())
+        {
+            Some(body) => self.export_block_level_elements(&body.block_level_elements),
+            None => String::new(),
+        };
+
+        if !self.notes.is_empty() {
+            body.push_str("<section class=\"footnotes\">\n<hr>\n<ol>\n");
+            for (index, note) in self.notes.iter().enumerate() {
+                let note_number = index + 1;
+                body.push_str(&format!(
+                    "<li id=\"footnote-{0}\">{1} <a href=\"#footnote-ref-{0}\">&#8617;</a></li>\n",
+                    note_number, note
+                ));
+            }
+            body.push_str("</ol>\n</section>\n");
+        }
+
+        if !self.options.standalone {
+            return body;
+        }
+
+        let title = self
+            .options
+            .title
+            .clone()
+            .or_else(|| package.core.as_ref().and_then(|core| core.title.clone()))
+            .unwrap_or_default();
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(&title),
+            self.export_stylesheet(),
+            body
+        )
+    }
+
+    pub fn export_stylesheet(&self) -> String {
+        let package = self.package;
+        let mut stylesheet =
+            String::from("p { margin: 0; white-space: pre-wrap; }\ntable { border-collapse: collapse; }\n");
+
+        if let Some(default_style) = package.resolve_document_default_style() {
+            let mut declarations = paragraph_properties_to_css(&default_style.paragraph_properties);
+            declarations.extend(run_properties_to_css(&default_style.run_properties));
+            stylesheet.push_str(&css_rule("body", &declarations));
+        }
+
+        let styles = match &package.styles {
+            Some(styles) => &styles.styles,
+            None => return stylesheet,
+        };
+
+        let mut style_resolver = package.style_resolver();
+        for style in styles {
+            let (style_id, style_type) = match (&style.style_id, style.style_type) {
+                (Some(style_id), Some(style_type)) => (style_id, style_type),
+                _ => continue,
+            };
+
+            let resolved_style = match style_resolver.resolve_style_with_id(style_id) {
+                Some(resolved_style) => resolved_style,
+                None => continue,
+            };
+
+            let mut declarations = match style_type {
+                StyleType::Paragraph | StyleType::Table => {
+                    paragraph_properties_to_css(&resolved_style.paragraph_properties)
+                }
+                StyleType::Character | StyleType::Numbering => Vec::new(),
+            };
+            if style_type != StyleType::Numbering {
+                declarations.extend(run_properties_to_css(&resolved_style.run_properties));
+            }
+
+            stylesheet.push_str(&css_rule(&format!(".{}", style_class_name(style_id)), &declarations));
+        }
+
+        stylesheet
+    }
+
+    pub fn export_block_level_elements(&mut self, elements: &[BlockLevelElts]) -> String {
+        let mut html = String::new();
+        for element in elements {
+            if let BlockLevelElts::Chunk(content) = element {
+                self.write_block_content(content, &mut html);
+            }
+        }
+
+        self.close_lists(0, &mut html);
+        html
+    }
+
+    pub fn export_paragraph(&mut self, paragraph: &P) -> String {
+        let mut html = String::new();
+        self.write_paragraph(paragraph, &mut html);
+        html
+    }
+
+    pub fn export_table(&mut self, table: &Tbl) -> String {
+        let mut html = String::new();
+        self.write_table(table, &mut html);
+        html
+    }
+
+    fn write_block_content(&mut self, content: &ContentBlockContent, out: &mut String) {
+        match content {
+            ContentBlockContent::CustomXml(custom_xml) => {
+                for block_content in &custom_xml.block_contents {
+                    self.write_block_content(block_content, out);
+                }
+            }
+            ContentBlockContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.sdt_content {
+                    for block_content in &sdt_content.block_contents {
+                        self.write_block_content(block_content, out);
+                    }
+                }
+            }
+            ContentBlockContent::Paragraph(paragraph) => self.write_list_item_or_paragraph(paragraph, out),
+            ContentBlockContent::Table(table) => {
+                self.close_lists(0, out);
+                self.write_table(table, out);
+            }
+            ContentBlockContent::RunLevelElement(_) => (),
+        }
+    }
+
+    fn write_list_item_or_paragraph(&mut self, paragraph: &P, out: &mut String) {
+        let list = match self.list_of_paragraph(paragraph) {
+            Some(list) => list,
+            None => {
+                self.close_lists(0, out);
+                self.write_paragraph(paragraph, out);
+                return;
+            }
+        };
+
+        let (open_list, indent_level) = list;
+        if self.open_lists.len() > indent_level + 1 {
+            self.close_lists(indent_level + 1, out);
+        }
+
+        if self.open_lists.len() == indent_level + 1 {
+            if self.open_lists.last() == Some(&open_list) {
+                out.push_str("</li>\n");
+            } else {
+                self.close_lists(indent_level, out);
+            }
+        }
+
+        while self.open_lists.len() < indent_level + 1 {
+            match open_list.list_type {
+                Some(list_type) => out.push_str(&format!("<{} type=\"{}\">\n", open_list.tag, list_type)),
+                None => out.push_str(&format!("<{}>\n", open_list.tag)),
+            }
+            self.open_lists.push(open_list.clone());
+        }
+
+        out.push_str("<li>");
+        self.write_paragraph(paragraph, out);
+    }
+
+    fn list_of_paragraph(&mut self, paragraph: &P) -> Option<(OpenList, usize)> {
+        let paragraph_numbering = self.style_resolver.resolve_numbering(paragraph)?;
+        let indent_level = std::cmp::max(paragraph_numbering.level, 0);
+
+        let numbering_format = self
+            .package
+            .find_numbering_level(paragraph_numbering.numbering_id, indent_level)
+            .and_then(|level| level.numbering_format.as_ref())
+            .map(|numbering_format| numbering_format.value);
+
+        let (tag, list_type) = match numbering_format {
+            Some(NumberFormat::None) => return None,
+            Some(NumberFormat::Bullet) | None => ("ul", None),
+            Some(NumberFormat::LowerRoman) => ("ol", Some("i")),
+            Some(NumberFormat::UpperRoman) => ("ol", Some("I")),
+            Some(NumberFormat::LowerLetter) => ("ol", Some("a")),
+            Some(NumberFormat::UpperLetter) => ("ol", Some("A")),
+            Some(_) => ("ol", None),
+        };
+
+        Some((OpenList { tag, list_type }, indent_level as usize))
+    }
+
+    fn close_lists(&mut self, remaining_depth: usize, out: &mut String) {
+        while self.open_lists.len() > remaining_depth {
+            if let Some(open_list) = self.open_lists.pop() {
+                out.push_str(&format!("</li>\n</{}>\n", open_list.tag));
+            }
+        }
+    }
+
+    fn write_paragraph(&mut self, paragraph: &P, out: &mut String) {
+        let style_id = paragraph
+            .properties
+            .as_ref()
+            .and_then(|p_pr| p_pr.base.style.clone())
+            .or_else(|| default_style_id(self.package, StyleType::Paragraph));
+
+        let direct_declarations = paragraph
+            .properties
+            .as_ref()
+            .map(|p_pr| paragraph_properties_to_css(&p_pr.base))
+            .unwrap_or_default();
+
+        out.push_str("<p");
+        write_class_and_style_attributes(style_id.as_ref(), &direct_declarations, out);
+        out.push('>');
+
+        let mut contents = String::new();
+        for content in &paragraph.contents {
+            self.write_p_content(content, &mut contents);
+        }
+
+        if contents.is_empty() {
+            // Empty paragraphs still take up a line in the document
+            out.push_str("<br>");
+        } else {
+            out.push_str(&contents);
+        }
+
+        out.push_str("</p>\n");
+    }
+
+    fn write_p_content(&mut self, content: &PContent, out: &mut String) {
+        match content {
+            PContent::ContentRunContent(run_content) => self.write_content_run_content(run_content, out),
+            PContent::SimpleField(simple_field) => self.write_p_contents(&simple_field.paragraph_contents, out),
+            PContent::Hyperlink(hyperlink) => self.write_hyperlink(hyperlink, out),
+            PContent::SubDocument(_) => (),
+        }
+    }
+
+    fn write_p_contents(&mut self, contents: &[PContent], out: &mut String) {
+        for content in contents {
+            self.write_p_content(content, out);
+        }
+    }
+
+    fn write_hyperlink(&mut self, hyperlink: &Hyperlink, out: &mut String) {
+        // Links with other schemes, like javascript:, are written as plain text
+        let url = hyperlink_url(self.package, hyperlink).filter(|url| is_allowed_link(url));
+
+        match url {
+            Some(url) => {
+                out.push_str(&format!("<a href=\"{}\"", escape_html(&url)));
+                if let Some(tooltip) = &hyperlink.tooltip {
+                    out.push_str(&format!(" title=\"{}\"", escape_html(tooltip)));
+                }
+                if let Some(target_frame) = &hyperlink.target_frame {
+                    out.push_str(&format!(" target=\"{}\"", escape_html(target_frame)));
+                }
+                out.push('>');
+                self.write_p_contents(&hyperlink.paragraph_contents, out);
+                out.push_str("</a>");
+            }
+            None => self.write_p_contents(&hyperlink.paragraph_contents, out),
+        }
+    }
+
+    fn write_content_run_content(&mut self, content: &ContentRunContent, out: &mut String) {
+        match content {
+            ContentRunContent::CustomXml(custom_xml) => self.write_p_contents(&custom_xml.paragraph_contents, out),
+            ContentRunContent::SmartTag(smart_tag) => self.write_p_contents(&smart_tag.paragraph_contents, out),
+            ContentRunContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.sdt_content {
+                    self.write_p_contents(&sdt_content.p_contents, out);
+                }
+            }
+            ContentRunContent::Bidirectional(dir) => self.write_p_contents(&dir.p_contents, out),
+            ContentRunContent::BidirectionalOverride(bdo) => self.write_p_contents(&bdo.p_contents, out),
+            ContentRunContent::Run(run) => self.write_run(run, out),
+            ContentRunContent::RunLevelElements(run_level_element) => {
+                self.write_run_level_element(run_level_element, out)
+            }
+        }
+    }
+
+    fn write_run_level_element(&mut self, element: &RunLevelElts, out: &mut String) {
+        match element {
+            RunLevelElts::Insert(track_change) | RunLevelElts::MoveTo(track_change) => {
+                self.write_run_track_change(track_change, out)
+            }
+            RunLevelElts::RangeMarkupElements(RangeMarkupElements::BookmarkStart(bookmark)) => {
+                out.push_str(&format!("<a id=\"{}\"></a>", escape_html(&bookmark.name)))
+            }
+            _ => (),
+        }
+    }
+
+    fn write_run_track_change(&mut self, track_change: &RunTrackChange, out: &mut String) {
+        for choice in &track_change.choices {
+            match choice {
+                RunTrackChangeChoice::ContentRunContent(content) => self.write_content_run_content(content, out),
+            }
+        }
+    }
+
+    fn write_run(&mut self, run: &R, out: &mut String) {
+        let direct_properties = run
+            .run_properties
+            .as_ref()
+            .map(|r_pr| RunProperties::from_vec(&r_pr.r_pr_bases))
+            .unwrap_or_default();
+
+        let mut run_html = String::new();
+        for inner_content in &run.run_inner_contents {
+            if let RunInnerContent::FieldCharacter(field_char) = inner_content {
+                self.handle_field_character(field_char.field_char_type);
+                continue;
+            }
+
+            if self.field_stack.contains(&FieldPart::Instruction) {
+                continue;
+            }
+
+            match inner_content {
+                RunInnerContent::Text(text) => run_html.push_str(&escape_html(&text.text)),
+                RunInnerContent::Break(br) => match br.break_type {
+                    Some(BrType::Page) => run_html.push_str("<br class=\"page-break\">"),
+                    _ => run_html.push_str("<br>"),
+                },
+                RunInnerContent::CarriageReturn => run_html.push_str("<br>"),
+                RunInnerContent::Tab | RunInnerContent::PositionTab(_) => run_html.push('\t'),
+                RunInnerContent::NonBreakingHyphen => run_html.push('\u{2011}'),
+                RunInnerContent::OptionalHypen => run_html.push_str("&shy;"),
+                RunInnerContent::Symbol(symbol) => {
+                    if let Some(character) = symbol_to_char(symbol) {
+                        run_html.push_str(&escape_html(&character.to_string()));
+                    }
+                }
+                RunInnerContent::Ruby(ruby) => self.write_ruby(ruby, &mut run_html),
+                RunInnerContent::FootnoteReference(reference) => {
+                    self.write_note_reference(NoteKind::Footnote, reference, &mut run_html)
+                }
+                RunInnerContent::EndnoteReference(reference) => {
+                    self.write_note_reference(NoteKind::Endnote, reference, &mut run_html)
+                }
+                RunInnerContent::Drawing(drawing) => {
+                    for image in drawing_images(self.package, drawing) {
+                        self.write_image(
+                            image.alt_text,
+                            image.package_path,
+                            image.extent.width / EMUS_PER_PIXEL,
+                            image.extent.height / EMUS_PER_PIXEL,
+                            &mut run_html,
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if run_html.is_empty() {
+            return;
+        }
+
+        let style_id = direct_properties.style.clone();
+        let direct_declarations = run_properties_to_css(&RunProperties {
+            style: None,
+            ..direct_properties
+        });
+
+        if style_id.is_none() && direct_declarations.is_empty() {
+            out.push_str(&run_html);
+        } else {
+            out.push_str("<span");
+            write_class_and_style_attributes(style_id.as_ref(), &direct_declarations, out);
+            out.push('>');
+            out.push_str(&run_html);
+            out.push_str("</span>");
+        }
+    }
+
+    fn write_ruby(&mut self, ruby: &Ruby, out: &mut String) {
+        out.push_str("<ruby>");
+        self.write_ruby_content(&ruby.ruby_base, out);
+        out.push_str("<rp>(</rp><rt>");
+        self.write_ruby_content(&ruby.ruby_content, out);
+        out.push_str("</rt><rp>)</rp></ruby>");
+    }
+
+    fn write_ruby_content(&mut self, ruby_content: &RubyContent, out: &mut String) {
+        for choice in &ruby_content.ruby_contents {
+            match choice {
+                RubyContentChoice::Run(run) => self.write_run(run, out),
+                RubyContentChoice::RunLevelElement(element) => self.write_run_level_element(element, out),
+            }
+        }
+    }
+
+    fn handle_field_character(&mut self, field_char_type: FldCharType) {
+        match field_char_type {
+            FldCharType::Begin => self.field_stack.push(FieldPart::Instruction),
+            FldCharType::Separate => {
+                if let Some(field_part) = self.field_stack.last_mut() {
+                    *field_part = FieldPart::Result;
+                }
+            }
+            FldCharType::End => {
+                self.field_stack.pop();
+            }
+        }
+    }
+
+    fn write_note_reference(&mut self, note_kind: NoteKind, reference: &FtnEdnRef, out: &mut String) {
+        let package = self.package;
+        let note = match note_kind {
+            NoteKind::Footnote => package.find_footnote_with_id(reference.id),
+            NoteKind::Endnote => package.find_endnote_with_id(reference.id),
+        };
+
+        if let Some(note) = note {
+            self.notes.push(String::new());
+            let note_number = self.notes.len();
+            self.notes[note_number - 1] = self.export_note(note);
+            out.push_str(&format!(
+                "<sup><a href=\"#footnote-{0}\" id=\"footnote-ref-{0}\">{0}</a></sup>",
+                note_number
+            ));
+        }
+    }
+
+    fn export_note(&mut self, note: &FtnEdn) -> String {
+        // Notes have their own field and list context, they can't continue the ones of the referencing paragraph
+        let saved_field_stack = std::mem::take(&mut self.field_stack);
+        let saved_open_lists = std::mem::take(&mut self.open_lists);
+        let note_html = self.export_block_level_elements(&note.block_level_elements);
+        self.field_stack = saved_field_stack;
+        self.open_lists = saved_open_lists;
+
+        String::from(note_html.trim_end())
+    }
+
+    fn write_image(&mut self, alt_text: &str, package_path: String, width: u64, height: u64, out: &mut String) {
+        let file_name = match Path::new(&package_path)
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+        {
+            Some(file_name) => file_name,
+            None => return,
+        };
+
+        let exported_path = if self.options.media_directory.is_empty() {
+            String::from(file_name)
+        } else {
+            format!("{}/{}", self.options.media_directory.trim_end_matches('/'), file_name)
+        };
+
+        let data = self
+            .media_data
+            .get(&package_path)
+            .or_else(|| self.package.media_files.get(&package_path));
+        let source = match (self.options.image_source, data) {
+            (ImageSource::DataUri, Some(data)) => {
+                format!("data:{};base64,{}", media_type(file_name), encode_base64(data))
+            }
+            _ => exported_path.clone(),
+        };
+
+        self.media_references.push(MediaReference {
+            package_path,
+            exported_path,
+        });
+
+        out.push_str(&format!(
+            "<img src=\"{}\" alt=\"{}\"",
+            escape_html(&source),
+            escape_html(alt_text)
+        ));
+        if width > 0 && height > 0 {
+            out.push_str(&format!(" width=\"{}\" height=\"{}\"", width, height));
+        }
+        out.push('>');
+    }
+
+    fn write_table(&mut self, table: &Tbl, out: &mut String) {
+        let grid_rows: Vec<Vec<GridCell>> = table_rows(table).into_iter().map(grid_cells).collect();
+
+        out.push_str("<table");
+        let style_id = table
+            .properties
+            .base
+            .style
+            .clone()
+            .or_else(|| default_style_id(self.package, StyleType::Table));
+        write_class_and_style_attributes(style_id.as_ref(), &[], out);
+        out.push_str(">\n");
+
+        for (row_index, grid_row) in grid_rows.iter().enumerate() {
+            out.push_str("<tr>");
+            for grid_cell in grid_row {
+                let tc_pr_base = grid_cell.cell.properties.as_ref().map(|tc_pr| &tc_pr.base.base);
+                let vertical_merge = tc_pr_base.and_then(|tc_pr_base| tc_pr_base.vertical_merge.as_ref());
+                if vertical_merge == Some(&Merge::Continue) {
+                    continue;
+                }
+
+                out.push_str("<td");
+                let column_span = tc_pr_base.and_then(|tc_pr_base| tc_pr_base.grid_span).unwrap_or(1);
+                if column_span > 1 {
+                    out.push_str(&format!(" colspan=\"{}\"", column_span));
+                }
+
+                if vertical_merge == Some(&Merge::Restart) {
+                    let row_span = 1 + grid_rows[row_index + 1..]
+                        .iter()
+                        .take_while(|next_row| {
+                            next_row.iter().any(|next_cell| {
+                                next_cell.grid_column == grid_cell.grid_column
+                                    && next_cell
+                                        .cell
+                                        .properties
+                                        .as_ref()
+                                        .and_then(|tc_pr| tc_pr.base.base.vertical_merge.as_ref())
+                                        == Some(&Merge::Continue)
+                            })
+                        })
+                        .count();
+
+                    if row_span > 1 {
+                        out.push_str(&format!(" rowspan=\"{}\"", row_span));
+                    }
+                }
+
+                let declarations = tc_pr_base
+                    .and_then(|tc_pr_base| tc_pr_base.shading.as_ref())
+                    .and_then(shading_to_css)
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                write_class_and_style_attributes(None, &declarations, out);
+                out.push('>');
+
+                let saved_open_lists = std::mem::take(&mut self.open_lists);
+                let cell_html = self.export_block_level_elements(&grid_cell.cell.block_level_elements);
+                self.open_lists = saved_open_lists;
+                out.push_str(cell_html.trim_end());
+                out.push_str("</td>");
+            }
+            out.push_str("</tr>\n");
+        }
+
+        out.push_str("</table>\n");
+    }
+}
+
+fn default_style_id(package: &Package, style_type: StyleType) -> Option<String> {
+    package
+        .styles
+        .as_ref()?
+        .styles
+        .iter()
+        .find(|style| style.style_type == Some(style_type) && style.is_default == Some(true))
+        .and_then(|style| style.style_id.clone())
+}
+
+fn is_allowed_link(url: &str) -> bool {
+    if url.starts_with('#') {
+        return true;
+    }
+
+    match url.find(':') {
+        Some(scheme_end) => ["http", "https", "mailto"]
+            .iter()
+            .any(|scheme| url[..scheme_end].eq_ignore_ascii_case(scheme)),
+        None => false,
+    }
+}
+
+fn write_class_and_style_attributes(style_id: Option<&String>, declarations: &[String], out: &mut String) {
+    if let Some(style_id) = style_id {
+        out.push_str(&format!(" class=\"{}\"", style_class_name(style_id)));
+    }
+
+    if !declarations.is_empty() {
+        out.push_str(&format!(" style=\"{}\"", escape_html(&declarations.join("; "))));
+    }
+}
+
+pub fn style_class_name(style_id: &str) -> String {
+    let sanitized_id: String = style_id
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || character == '-' || character == '_' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    format!("style-{}", sanitized_id)
+}
+
+fn css_rule(selector: &str, declarations: &[String]) -> String {
+    if declarations.is_empty() {
+        return String::new();
+    }
+
+    format!("{} {{ {}; }}\n", selector, declarations.join("; "))
+}
+
+pub fn run_properties_to_css(run_properties: &RunProperties) -> Vec<String> {
+    let mut declarations = Vec::new();
+
+    if let Some(font_name) = run_properties.fonts.as_ref().and_then(|fonts| {
+        fonts
+            .ascii
+            .as_ref()
+            .or(fonts.high_ansi.as_ref())
+            .or(fonts.east_asia.as_ref())
+    }) {
+        declarations.push(format!("font-family: {}", css_string(font_name)));
+    }
+
+    if let Some(font_size) = &run_properties.font_size {
+        declarations.push(format!("font-size: {}pt", half_points_to_points(font_size)));
+    }
+
+    if let Some(bold) = run_properties.bold {
+        declarations.push(String::from(if bold {
+            "font-weight: bold"
+        } else {
+            "font-weight: normal"
+        }));
+    }
+
+    if let Some(italic) = run_properties.italic {
+        declarations.push(String::from(if italic {
+            "font-style: italic"
+        } else {
+            "font-style: normal"
+        }));
+    }
+
+    let underline = run_properties
+        .underline
+        .as_ref()
+        .map(|underline| underline.value != Some(UnderlineType::None));
+    let strikethrough = run_properties.strikethrough.or(run_properties.double_strikethrough);
+    if underline.is_some() || strikethrough.is_some() {
+        let mut decorations = Vec::new();
+        if underline == Some(true) {
+            decorations.push("underline");
+        }
+        if strikethrough == Some(true) {
+            decorations.push("line-through");
+        }
+
+        if decorations.is_empty() {
+            declarations.push(String::from("text-decoration: none"));
+        } else {
+            declarations.push(format!("text-decoration: {}", decorations.join(" ")));
+        }
+    }
+
+    if run_properties.all_capitals == Some(true) {
+        declarations.push(String::from("text-transform: uppercase"));
+    } else if run_properties.all_small_capitals == Some(true) {
+        declarations.push(String::from("font-variant: small-caps"));
+    }
+
+    if let Some(HexColor::RGB(rgb)) = run_properties.color.as_ref().map(|color| color.value) {
+        declarations.push(format!("color: {}", rgb_to_css(rgb)));
+    }
+
+    if let Some(rgb) = run_properties.highlight.and_then(|highlight| highlight.to_rgb()) {
+        declarations.push(format!("background-color: {}", rgb_to_css(rgb)));
+    } else if let Some(declaration) = run_properties.shading.as_ref().and_then(shading_to_css) {
+        declarations.push(declaration);
+    }
+
+    match run_properties.vertical_alignment {
+        Some(VerticalAlignRun::Superscript) => declarations.push(String::from("vertical-align: super")),
+        Some(VerticalAlignRun::Subscript) => declarations.push(String::from("vertical-align: sub")),
+        _ => (),
+    }
+
+    if let Some(spacing) = &run_properties.spacing {
+        declarations.push(format!("letter-spacing: {}pt", signed_twips_to_points(spacing)));
+    }
+
+    if let Some(border) = run_properties.border.as_ref().and_then(border_to_css) {
+        declarations.push(format!("border: {}", border));
+    }
+
+    if run_properties.vanish == Some(true) {
+        declarations.push(String::from("display: none"));
+    }
+
+    declarations
+}
+
+pub fn paragraph_properties_to_css(paragraph_properties: &ParagraphProperties) -> Vec<String> {
+    let mut declarations = Vec::new();
+
+    if let Some(spacing) = &paragraph_properties.spacing {
+        if let Some(before) = &spacing.before {
+            declarations.push(format!("margin-top: {}pt", twips_to_points(before)));
+        }
+
+        if let Some(after) = &spacing.after {
+            declarations.push(format!("margin-bottom: {}pt", twips_to_points(after)));
+        }
+
+        if let Some(line) = &spacing.line {
+            match spacing.line_rule {
+                Some(LineSpacingRule::Exact) | Some(LineSpacingRule::AtLeast) => {
+                    declarations.push(format!("line-height: {}pt", signed_twips_to_points(line)))
+                }
+                _ => {
+                    // Auto line spacing is measured in 240ths of a line
+                    if let SignedTwipsMeasure::Decimal(value) = line {
+                        declarations.push(format!("line-height: {}", f64::from(*value) / 240.0));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(indent) = &paragraph_properties.indent {
+        if let Some(start) = indent.start.as_ref().or(indent.left.as_ref()) {
+            declarations.push(format!("margin-left: {}pt", signed_twips_to_points(start)));
+        }
+
+        if let Some(end) = indent.end.as_ref().or(indent.right.as_ref()) {
+            declarations.push(format!("margin-right: {}pt", signed_twips_to_points(end)));
+        }
+
+        if let Some(hanging) = &indent.hanging {
+            declarations.push(format!("text-indent: -{}pt", twips_to_points(hanging)));
+        } else if let Some(first_line) = &indent.first_line {
+            declarations.push(format!("text-indent: {}pt", twips_to_points(first_line)));
+        }
+    }
+
+    if let Some(alignment) = paragraph_properties.alignment {
+        let text_align = match alignment {
+            Jc::Start | Jc::Left => "left",
+            Jc::Center => "center",
+            Jc::End | Jc::Right => "right",
+            Jc::Both | Jc::Distribute | Jc::MediumKashida | Jc::HighKashida | Jc::LowKashida | Jc::ThaiDistribute => {
+                "justify"
+            }
+            _ => "left",
+        };
+        declarations.push(format!("text-align: {}", text_align));
+    }
+
+    if let Some(borders) = &paragraph_properties.borders {
+        let sides = [
+            ("top", &borders.top),
+            ("left", &borders.left),
+            ("bottom", &borders.bottom),
+            ("right", &borders.right),
+        ];
+
+        for (side, border) in sides.iter() {
+            if let Some(border) = border.as_ref().and_then(border_to_css) {
+                declarations.push(format!("border-{}: {}", side, border));
+            }
+        }
+    }
+
+    if let Some(declaration) = paragraph_properties.shading.as_ref().and_then(shading_to_css) {
+        declarations.push(declaration);
+    }
+
+    if paragraph_properties.bidirectional == Some(true) {
+        declarations.push(String::from("direction: rtl"));
+    }
+
+    declarations
+}
+
+// Quotes a string of the document for CSS. Characters which could end the string, the declaration, the rule or the
+// style element are written as CSS escapes.
+fn css_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in value.chars() {
+        if character.is_control() || "\"\\<>{};".contains(character) {
+            quoted.push_str(&format!("\\{:X} ", u32::from(character)));
+        } else {
+            quoted.push(character);
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+fn shading_to_css(shading: &Shd) -> Option<String> {
+    match shading.fill {
+        Some(HexColor::RGB(rgb)) => Some(format!("background-color: {}", rgb_to_css(rgb))),
+        _ => None,
+    }
+}
+
+fn border_to_css(border: &Border) -> Option<String> {
+    let style = match border.value {
+        BorderType::Nil | BorderType::None => return Some(String::from("none")),
+        BorderType::Double => "double",
+        BorderType::Dotted => "dotted",
+        BorderType::Dashed | BorderType::DotDash | BorderType::DotDotDash => "dashed",
+        _ => "solid",
+    };
+
+    // Border widths are measured in eighths of a point
+    let width = border.size.map(|size| size as f64 / 8.0).unwrap_or(0.5);
+    let color = match border.color {
+        Some(HexColor::RGB(rgb)) => rgb_to_css(rgb),
+        _ => String::from("black"),
+    };
+
+    Some(format!("{}pt {} {}", width, style, color))
+}
+
+fn rgb_to_css(rgb: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2])
+}
+
+fn half_points_to_points(measure: &HpsMeasure) -> f64 {
+    match measure {
+        HpsMeasure::Decimal(value) => *value as f64 / 2.0,
+        HpsMeasure::UniversalMeasure(measure) => universal_measure_to_points(measure),
+    }
+}
+
+fn twips_to_points(measure: &TwipsMeasure) -> f64 {
+    match measure {
+        TwipsMeasure::Decimal(value) => *value as f64 / 20.0,
+        TwipsMeasure::UniversalMeasure(measure) => universal_measure_to_points(measure),
+    }
+}
+
+fn signed_twips_to_points(measure: &SignedTwipsMeasure) -> f64 {
+    match measure {
+        SignedTwipsMeasure::Decimal(value) => f64::from(*value) / 20.0,
+        SignedTwipsMeasure::UniversalMeasure(measure) => universal_measure_to_points(measure),
+    }
+}
+
+fn universal_measure_to_points(measure: &UniversalMeasure) -> f64 {
+    match measure.unit {
+        UniversalMeasureUnit::Millimeter => measure.value * 72.0 / 25.4,
+        UniversalMeasureUnit::Centimeter => measure.value * 72.0 / 2.54,
+        UniversalMeasureUnit::Inch => measure.value * 72.0,
+        UniversalMeasureUnit::Point => measure.value,
+        UniversalMeasureUnit::Pica | UniversalMeasureUnit::Pi => measure.value * 12.0,
+    }
+}
+
+fn media_type(file_name: &str) -> &'static str {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "tif" | "tiff" => "image/tiff",
+        "emf" => "image/emf",
+        "wmf" => "image/wmf",
+        _ => "application/octet-stream",
+    }
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2]);
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * index) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped_text = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped_text.push_str("&amp;"),
+            '<' => escaped_text.push_str("&lt;"),
+            '>' => escaped_text.push_str("&gt;"),
+            '"' => escaped_text.push_str("&quot;"),
+            '\'' => escaped_text.push_str("&#39;"),
+            _ => escaped_text.push(character),
+        }
+    }
+
+    escaped_text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_base64, run_properties_to_css, HtmlExporter, HtmlOptions, ImageSource};
+    use crate::{
+        resolvedstyle::RunProperties,
+        test_util::{
+            formatted_run, package_with_blocks, package_with_paragraphs, paragraph, paragraph_block, run_content,
+            styled_paragraph, text, text_run,
+        },
+        wml::{
+            document::{
+                BlockLevelElts, Color, ContentBlockContent, Drawing, DrawingChoice, Fonts, HexColor, HpsMeasure,
+                Hyperlink, NumFmt, NumPr, NumberFormat, PContent, PPr, PPrBase, PPrGeneral, RPr, RPrBase, Ruby,
+                RubyAlign, RubyContent, RubyContentChoice, RubyPr, RunInnerContent, P, R,
+            },
+            drawing::{Anchor, GraphicDataContent, PictureBlip},
+            numbering::{AbstractNum, Lvl, Num, Numbering},
+            styles::{Style, StyleType, Styles},
+            table::{
+                ContentCellContent, ContentRowContent, Merge, Row, Tbl, TblGrid, TblPr, TblPrBase, Tc, TcPr, TcPrBase,
+                TcPrInner,
+            },
+        },
+    };
+    use msoffice_shared::{drawingml::coordsys::PositiveSize2D, relationship::Relationship};
+
+    fn fragment_options() -> HtmlOptions {
+        HtmlOptions {
+            standalone: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_run_properties_to_css() {
+        let run_properties = RunProperties {
+            bold: Some(true),
+            font_size: Some(HpsMeasure::Decimal(24)),
+            color: Some(Color {
+                value: HexColor::RGB([0xFF, 0x00, 0x80]),
+                theme_color: None,
+                theme_tint: None,
+                theme_shade: None,
+            }),
+            strikethrough: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            run_properties_to_css(&run_properties),
+            vec![
+                String::from("font-size: 12pt"),
+                String::from("font-weight: bold"),
+                String::from("text-decoration: line-through"),
+                String::from("color: #FF0080"),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_export_paragraph_with_style_and_direct_formatting() {
+        let mut package = package_with_paragraphs(vec![styled_paragraph(
+            vec![formatted_run("a < b", vec![RPrBase::Italic(true)])],
+            Some("Heading 1"),
+            None,
+        )]);
+        package.styles = Some(Box::new(Styles {
+            document_defaults: None,
+            latent_styles: None,
+            styles: vec![Style {
+                style_id: Some(String::from("Heading 1")),
+                style_type: Some(StyleType::Paragraph),
+                run_properties: Some(RPr {
+                    r_pr_bases: vec![RPrBase::Bold(true)],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        }));
+
+        let mut exporter = HtmlExporter::new(&package, fragment_options());
+        assert_eq!(
+            exporter.export_main_document(),
+            "<p class=\"style-Heading_1\"><span style=\"font-style: italic\">a &lt; b</span></p>\n"
+        );
+        assert_eq!(
+            exporter.export_stylesheet(),
+            "p { margin: 0; white-space: pre-wrap; }\ntable { border-collapse: collapse; }\n.style-Heading_1 { font-weight: bold; }\n"
+        );
+    }
+
+    #[test]
+    pub fn test_escape_font_names_in_css() {
+        let font_name = "Evil\"; } </style><script>alert(1)</script>{\\";
+        let run_fonts = || {
+            RPrBase::RunFonts(Fonts {
+                ascii: Some(String::from(font_name)),
+                ..Default::default()
+            })
+        };
+
+        let mut package = package_with_paragraphs(vec![paragraph(vec![formatted_run("text", vec![run_fonts()])])]);
+        package.styles = Some(Box::new(Styles {
+            document_defaults: None,
+            latent_styles: None,
+            styles: vec![Style {
+                style_id: Some(String::from("Evil")),
+                style_type: Some(StyleType::Character),
+                run_properties: Some(RPr {
+                    r_pr_bases: vec![run_fonts()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        }));
+
+        let escaped_font_name =
+            "\"Evil\\22 \\3B  \\7D  \\3C /style\\3E \\3C script\\3E alert(1)\\3C /script\\3E \\7B \\5C \"";
+        let mut exporter = HtmlExporter::new(&package, HtmlOptions::default());
+        let html = exporter.export_main_document();
+        assert_eq!(html.matches("</style>").count(), 1);
+        assert!(!html.contains("<script>"));
+        assert!(exporter
+            .export_stylesheet()
+            .contains(&format!(".style-Evil {{ font-family: {}; }}", escaped_font_name)));
+        assert!(html.contains(&format!(
+            "<span style=\"font-family: {}\">text</span>",
+            escaped_font_name.replace('"', "&quot;")
+        )));
+    }
+
+    #[test]
+    pub fn test_export_hyperlinks_with_allowed_schemes_only() {
+        let hyperlink = |rel_id: Option<&str>, anchor: Option<&str>, text: &str| {
+            PContent::Hyperlink(Hyperlink {
+                paragraph_contents: vec![text_run(text)],
+                target_frame: None,
+                tooltip: None,
+                document_location: None,
+                history: None,
+                anchor: anchor.map(String::from),
+                rel_id: rel_id.map(String::from),
+            })
+        };
+
+        let mut package = package_with_paragraphs(vec![paragraph(vec![
+            hyperlink(Some("rId1"), None, "web"),
+            hyperlink(Some("rId2"), None, "mail"),
+            hyperlink(None, Some("_Toc1"), "anchor"),
+            hyperlink(Some("rId3"), None, "script"),
+            hyperlink(Some("rId4"), None, "file"),
+        ])]);
+        let targets = [
+            ("rId1", "HTTPS://example.com/"),
+            ("rId2", "mailto:someone@example.com"),
+            ("rId3", "javascript:alert(1)"),
+            ("rId4", "file:///etc/passwd"),
+        ];
+        for (id, target) in &targets {
+            package.main_document_relationships.push(Relationship {
+                id: String::from(*id),
+                rel_type: String::from("http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink"),
+                target: String::from(*target),
+            });
+        }
+
+        let html = HtmlExporter::new(&package, fragment_options()).export_main_document();
+        assert_eq!(
+            html,
+            "<p><a href=\"HTTPS://example.com/\">web</a><a href=\"mailto:someone@example.com\">mail</a><a href=\"#_Toc1\">anchor</a>scriptfile</p>\n"
+        );
+    }
+
+    #[test]
+    pub fn test_export_list_numbering_from_style() {
+        let paragraph_base = |style: Option<&str>, numbering_id: Option<i32>| PPrBase {
+            style: style.map(String::from),
+            numbering_properties: numbering_id.map(|numbering_id| NumPr {
+                indent_level: None,
+                numbering_id: Some(numbering_id),
+                inserted: None,
+            }),
+            ..Default::default()
+        };
+        let list_paragraph = |text: &str, style: Option<&str>, numbering_id: Option<i32>| P {
+            properties: Some(PPr {
+                base: paragraph_base(style, numbering_id),
+                ..Default::default()
+            }),
+            ..paragraph(vec![text_run(text)])
+        };
+        let level = |level: i32, value: NumberFormat, paragraph_style: Option<&str>| Lvl {
+            start: Some(1),
+            numbering_format: Some(NumFmt { value, format: None }),
+            level_restart: None,
+            paragraph_style: paragraph_style.map(String::from),
+            display_as_arabic_numerals: None,
+            suffix: None,
+            level_text: None,
+            level_picture_bullet_id: None,
+            level_alignment: None,
+            paragraph_properties: None,
+            run_properties: None,
+            level,
+            template_code: None,
+            tentative: None,
+        };
+
+        // The second paragraph inherits the numbering of its style, on the level linked to the style
+        let mut package = package_with_paragraphs(vec![
+            list_paragraph("first", None, Some(1)),
+            list_paragraph("nested", Some("ListBullet"), None),
+        ]);
+        package.styles = Some(Box::new(Styles {
+            document_defaults: None,
+            latent_styles: None,
+            styles: vec![Style {
+                style_id: Some(String::from("ListBullet")),
+                style_type: Some(StyleType::Paragraph),
+                paragraph_properties: Some(PPrGeneral {
+                    base: paragraph_base(None, Some(1)),
+                    change: None,
+                }),
+                ..Default::default()
+            }],
+        }));
+        package.numbering = Some(Numbering {
+            picture_numbering_symbols: Vec::new(),
+            abstract_numberings: vec![AbstractNum {
+                levels: vec![
+                    level(0, NumberFormat::Decimal, None),
+                    level(1, NumberFormat::Bullet, Some("ListBullet")),
+                ],
+                ..AbstractNum::new(0)
+            }],
+            numberings: vec![Num {
+                abstract_num_id: 0,
+                level_overrides: Vec::new(),
+                numbering_id: 1,
+            }],
+            numbering_id_mac_at_cleanup: None,
+        });
+
+        let html = HtmlExporter::new(&package, fragment_options()).export_main_document();
+        assert_eq!(
+            html,
+            "<ol>\n<li><p>first</p>\n<ul>\n<li><p class=\"style-ListBullet\">nested</p>\n</li>\n</ul>\n</li>\n</ol>\n"
+        );
+    }
+
+    #[test]
+    pub fn test_export_table_with_merged_cells() {
+        let cell = |text: &str, grid_span: Option<i32>, vertical_merge: Option<Merge>| {
+            ContentCellContent::Cell(Box::new(Tc {
+                properties: Some(TcPr {
+                    base: TcPrInner {
+                        base: TcPrBase {
+                            grid_span,
+                            vertical_merge,
+                            ..Default::default()
+                        },
+                        markup_element: None,
+                    },
+                    change: None,
+                }),
+                block_level_elements: vec![paragraph_block(paragraph(vec![text_run(text)]))],
+                id: None,
+            }))
+        };
+        let row = |contents: Vec<ContentCellContent>| {
+            ContentRowContent::Table(Box::new(Row {
+                property_exceptions: None,
+                properties: None,
+                contents,
+                run_properties_revision_id: None,
+                run_revision_id: None,
+                deletion_revision_id: None,
+                row_revision_id: None,
+            }))
+        };
+
+        let table = Tbl {
+            range_markup_elements: Vec::new(),
+            properties: TblPr {
+                base: TblPrBase::default(),
+                change: None,
+            },
+            grid: TblGrid::default(),
+            row_contents: vec![
+                row(vec![
+                    cell("wide", Some(2), None),
+                    cell("tall", None, Some(Merge::Restart)),
+                ]),
+                row(vec![
+                    cell("a", None, None),
+                    cell("b", None, None),
+                    cell("", None, Some(Merge::Continue)),
+                ]),
+            ],
+        };
+
+        let package = package_with_blocks(vec![BlockLevelElts::Chunk(ContentBlockContent::Table(Box::new(table)))]);
+        let html = HtmlExporter::new(&package, fragment_options()).export_main_document();
+        assert_eq!(
+            html,
+            "<table>\n<tr><td colspan=\"2\"><p>wide</p></td><td rowspan=\"2\"><p>tall</p></td></tr>\n<tr><td><p>a</p></td><td><p>b</p></td></tr>\n</table>\n"
+        );
+    }
+
+    #[test]
+    pub fn test_export_ruby() {
+        let ruby_run = R {
+            run_inner_contents: vec![RunInnerContent::Ruby(Ruby {
+                ruby_properties: RubyPr {
+                    ruby_align: RubyAlign::Center,
+                    hps: HpsMeasure::Decimal(10),
+                    hps_raise: HpsMeasure::Decimal(20),
+                    hps_base_text: HpsMeasure::Decimal(20),
+                    language_id: String::from("ja-JP"),
+                    dirty: None,
+                },
+                ruby_content: RubyContent {
+                    ruby_contents: vec![RubyContentChoice::Run(R {
+                        run_inner_contents: vec![text("kan")],
+                        ..Default::default()
+                    })],
+                },
+                ruby_base: RubyContent {
+                    ruby_contents: vec![RubyContentChoice::Run(R {
+                        run_inner_contents: vec![text("漢")],
+                        ..Default::default()
+                    })],
+                },
+            })],
+            ..Default::default()
+        };
+
+        let package = package_with_paragraphs(vec![paragraph(vec![run_content(ruby_run)])]);
+        let html = HtmlExporter::new(&package, fragment_options()).export_main_document();
+        assert_eq!(html, "<p><ruby>漢<rp>(</rp><rt>kan</rt><rp>)</rp></ruby></p>\n");
+    }
+
+    #[test]
+    pub fn test_encode_base64() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    pub fn test_export_image_by_relationship_id() {
+        let anchor = Anchor {
+            extent: PositiveSize2D::new(952_500, 476_250),
+            graphic_data_content: Some(GraphicDataContent::Picture(PictureBlip {
+                embed_relationship_id: Some(String::from("rId9")),
+                link_relationship_id: None,
+            })),
+            ..Anchor::test_instance()
+        };
+        let image_run = R {
+            run_inner_contents: vec![RunInnerContent::Drawing(Drawing(vec![DrawingChoice::Anchor(anchor)]))],
+            ..Default::default()
+        };
+
+        let mut package = package_with_blocks(Vec::new());
+        for (id, target) in &[("rId8", "media/image1.png"), ("rId9", "/word/media/image2.png")] {
+            package.main_document_relationships.push(Relationship {
+                id: String::from(*id),
+                rel_type: String::from("http://schemas.openxmlformats.org/officeDocument/2006/relationships/image"),
+                target: String::from(*target),
+            });
+        }
+
+        let mut exporter = HtmlExporter::new(&package, fragment_options());
+        assert_eq!(
+            exporter.export_paragraph(&paragraph(vec![run_content(image_run)])),
+            "<p><img src=\"media/image2.png\" alt=\"Some description\" width=\"100\" height=\"50\"></p>\n"
+        );
+        assert_eq!(exporter.media_references()[0].package_path, "word/media/image2.png");
+    }
+
+    #[test]
+    pub fn test_export_image_as_data_uri() {
+        let image_run = R {
+            run_inner_contents: vec![RunInnerContent::Drawing(Drawing(vec![DrawingChoice::Anchor(Anchor {
+                graphic_data_content: Some(GraphicDataContent::Picture(PictureBlip {
+                    embed_relationship_id: Some(String::from("rId1")),
+                    link_relationship_id: None,
+                })),
+                ..Anchor::test_instance()
+            })]))],
+            ..Default::default()
+        };
+
+        let mut package = package_with_paragraphs(vec![paragraph(vec![run_content(image_run)])]);
+        package.main_document_relationships.push(Relationship {
+            id: String::from("rId1"),
+            rel_type: String::from("http://schemas.openxmlformats.org/officeDocument/2006/relationships/image"),
+            target: String::from("media/image1.png"),
+        });
+        package
+            .media_files
+            .insert(String::from("word/media/image1.png"), b"foo".to_vec());
+
+        let html = package.export_html(HtmlOptions {
+            image_source: ImageSource::DataUri,
+            ..fragment_options()
+        });
+        assert!(html.starts_with("<p><img src=\"data:image/png;base64,Zm9v\""));
+    }
+}
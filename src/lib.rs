@@ -4,8 +4,35 @@ extern crate strum;
 #[macro_use]
 extern crate strum_macros;
 
+pub mod arena;
+pub mod builtinstyle;
+pub mod contentcontrol;
+pub mod export;
+pub mod field;
+pub mod fieldcode;
+pub mod fieldformat;
+pub mod fieldupdate;
+pub mod formfield;
+pub mod html;
+pub mod linkedstyle;
+pub mod mailmerge;
+pub mod markdown;
 pub mod package;
+pub mod paragraphmark;
+pub mod plaintext;
+pub mod provenance;
+pub mod replace;
 pub mod resolvedstyle;
+pub mod search;
+pub mod styleresolver;
+pub mod tablestyle;
+pub mod template;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod themecolor;
+pub mod themefont;
+pub mod toc;
+pub mod visit;
 pub mod wml;
 
 pub extern crate msoffice_shared as shared;
@@ -0,0 +1,242 @@
+use crate::{
+    package::Package,
+    styleresolver::StyleResolver,
+    visit::{walk_paragraph, walk_run, Visit},
+    wml::{
+        document::{P, R},
+        styles::StyleType,
+    },
+};
+use std::collections::BTreeMap;
+
+// A paragraph style and its linked character style. Styles without a link only have one side of the pair.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct LinkedStylePair {
+    pub paragraph_style_id: Option<String>,
+    pub character_style_id: Option<String>,
+}
+
+// How many paragraphs and runs use either style of a linked pair
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LinkedStyleUsage {
+    pub styles: LinkedStylePair,
+    pub paragraph_count: usize,
+    pub run_count: usize,
+}
+
+impl<'a> StyleResolver<'a> {
+    // Missing styles are paired by the type they are used as
+    pub fn linked_style_pair<T: AsRef<str>>(&self, style_id: T, used_as: StyleType) -> LinkedStylePair {
+        let style_id = style_id.as_ref();
+        let style_type = self
+            .style(style_id)
+            .and_then(|style| style.style_type)
+            .unwrap_or(used_as);
+        let linked_style_id = self
+            .linked_style(style_id)
+            .and_then(|linked_style| linked_style.style_id.clone());
+
+        match style_type {
+            StyleType::Character => LinkedStylePair {
+                paragraph_style_id: linked_style_id,
+                character_style_id: Some(String::from(style_id)),
+            },
+            _ => LinkedStylePair {
+                paragraph_style_id: Some(String::from(style_id)),
+                character_style_id: linked_style_id,
+            },
+        }
+    }
+}
+
+struct LinkedStyleUsageCollector<'r, 'a> {
+    resolver: &'r StyleResolver<'a>,
+    usages: BTreeMap<LinkedStylePair, LinkedStyleUsage>,
+}
+
+impl<'r, 'a> LinkedStyleUsageCollector<'r, 'a> {
+    fn usage(&mut self, style_id: &str, used_as: StyleType) -> &mut LinkedStyleUsage {
+        let styles = self.resolver.linked_style_pair(style_id, used_as);
+        self.usages.entry(styles.clone()).or_insert_with(|| LinkedStyleUsage {
+            styles,
+            ..Default::default()
+        })
+    }
+
+    fn finish(self) -> Vec<LinkedStyleUsage> {
+        self.usages.into_values().collect()
+    }
+}
+
+impl<'v, 'r, 'a> Visit<'v> for LinkedStyleUsageCollector<'r, 'a> {
+    fn visit_paragraph(&mut self, paragraph: &'v P) {
+        if let Some(style_id) = self.resolver.paragraph_style_id(paragraph) {
+            self.usage(&style_id, StyleType::Paragraph).paragraph_count += 1;
+        }
+
+        walk_paragraph(self, paragraph);
+    }
+
+    fn visit_run(&mut self, run: &'v R) {
+        if let Some(style_id) = self.resolver.run_style_id(run) {
+            self.usage(&style_id, StyleType::Character).run_count += 1;
+        }
+
+        walk_run(self, run);
+    }
+}
+
+impl Package {
+    // The styles referenced by the paragraphs and runs of every story of the package, grouped by linked
+    // pairs. Paragraph styles used on runs are counted as their linked character styles.
+    pub fn linked_style_usage(&self) -> Vec<LinkedStyleUsage> {
+        let resolver = self.style_resolver();
+        let mut collector = LinkedStyleUsageCollector {
+            resolver: &resolver,
+            usages: BTreeMap::new(),
+        };
+        collector.visit_package(self);
+        collector.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkedStylePair, LinkedStyleUsage};
+    use crate::{
+        package::Package,
+        styleresolver::StyleResolver,
+        test_util::{package_with_paragraphs, paragraph, run, style, styled_paragraph},
+        wml::{
+            document::{ContentRunContent, PContent, RPr, RPrBase, P, R},
+            styles::{Style, StyleType, Styles},
+        },
+    };
+
+    fn linked_style(style_id: &str, style_type: StyleType, link: Option<&str>, r_pr_bases: Vec<RPrBase>) -> Style {
+        Style {
+            link: link.map(String::from),
+            ..style(style_id, style_type, None, r_pr_bases)
+        }
+    }
+
+    fn styles_for_test() -> Vec<Style> {
+        vec![
+            linked_style(
+                "Heading1",
+                StyleType::Paragraph,
+                Some("Heading1Char"),
+                vec![RPrBase::Bold(true)],
+            ),
+            linked_style(
+                "Heading1Char",
+                StyleType::Character,
+                Some("Heading1"),
+                vec![RPrBase::Italic(true)],
+            ),
+            linked_style("Strong", StyleType::Character, None, vec![RPrBase::Bold(true)]),
+            linked_style("Broken", StyleType::Paragraph, Some("Heading1"), Vec::new()),
+        ]
+    }
+
+    fn run_properties(style_id: &str) -> RPr {
+        RPr {
+            r_pr_bases: vec![RPrBase::RunStyle(String::from(style_id))],
+            ..Default::default()
+        }
+    }
+
+    fn run_with_style(style_id: &str) -> PContent {
+        PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+            run_properties: Some(run_properties(style_id)),
+            ..Default::default()
+        })))
+    }
+
+    fn package_with_styled_paragraphs(paragraphs: Vec<P>) -> Package {
+        Package {
+            styles: Some(Box::new(Styles {
+                document_defaults: None,
+                latent_styles: None,
+                styles: styles_for_test(),
+            })),
+            ..package_with_paragraphs(paragraphs)
+        }
+    }
+
+    #[test]
+    pub fn test_linked_style() {
+        let package = package_with_styled_paragraphs(Vec::new());
+        let resolver = StyleResolver::new(&package);
+
+        assert_eq!(
+            resolver
+                .linked_style("Heading1")
+                .and_then(|style| style.style_id.as_deref()),
+            Some("Heading1Char")
+        );
+        assert_eq!(
+            resolver
+                .linked_style("Heading1Char")
+                .and_then(|style| style.style_id.as_deref()),
+            Some("Heading1")
+        );
+        // Links between styles of the same type are ignored
+        assert_eq!(resolver.linked_style("Broken"), None);
+        assert_eq!(
+            resolver.style_id_for_type("Heading1", StyleType::Character),
+            "Heading1Char"
+        );
+        assert_eq!(resolver.style_id_for_type("Heading1", StyleType::Paragraph), "Heading1");
+        assert_eq!(resolver.style_id_for_type("Broken", StyleType::Character), "Broken");
+
+        // A paragraph style used on a run resolves to its linked character style
+        let run_style = package.resolve_run_style(&run_properties("Heading1")).unwrap();
+        assert_eq!(run_style.run_properties.italic, Some(true));
+        assert_eq!(run_style.run_properties.bold, None);
+    }
+
+    #[test]
+    pub fn test_linked_style_usage() {
+        let package = package_with_styled_paragraphs(vec![
+            styled_paragraph(
+                vec![run_with_style("Heading1Char"), run(Vec::new())],
+                Some("Heading1"),
+                None,
+            ),
+            styled_paragraph(vec![run_with_style("Heading1")], Some("Heading1Char"), None),
+            paragraph(vec![run_with_style("Strong"), run_with_style("Missing")]),
+        ]);
+
+        let heading = LinkedStylePair {
+            paragraph_style_id: Some(String::from("Heading1")),
+            character_style_id: Some(String::from("Heading1Char")),
+        };
+        assert_eq!(
+            package.linked_style_usage(),
+            vec![
+                LinkedStyleUsage {
+                    styles: LinkedStylePair {
+                        paragraph_style_id: None,
+                        character_style_id: Some(String::from("Missing")),
+                    },
+                    paragraph_count: 0,
+                    run_count: 1,
+                },
+                LinkedStyleUsage {
+                    styles: LinkedStylePair {
+                        paragraph_style_id: None,
+                        character_style_id: Some(String::from("Strong")),
+                    },
+                    paragraph_count: 0,
+                    run_count: 1,
+                },
+                LinkedStyleUsage {
+                    styles: heading,
+                    paragraph_count: 2,
+                    run_count: 2,
+                },
+            ]
+        );
+    }
+}
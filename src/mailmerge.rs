@@ -0,0 +1,684 @@
+use crate::{
+    field::{as_run, field_character_type, result_run, run_properties, run_text, split_field_character_runs},
+    fieldcode::{Argument, Condition, FieldCode, FieldType, MergeField},
+    package::Package,
+    search::ParagraphText,
+    wml::{
+        document::{
+            BlockLevelElts, ContentBlockContent, ContentRunContent, Document, FldCharType, PContent, PPr, RPr,
+            RunInnerContent, P,
+        },
+        settings::{MailMergeOdsoFMDFieldType, OdsoFieldMapData, Settings},
+        table::{ContentCellContent, ContentRowContent},
+    },
+};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseCsvError {
+    pub line: usize,
+}
+
+impl Display for ParseCsvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unterminated quoted field starting on line {}", self.line)
+    }
+}
+
+impl Error for ParseCsvError {}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MergeDataSource {
+    pub columns: Vec<String>,
+    pub records: Vec<Vec<String>>,
+}
+
+impl MergeDataSource {
+    pub fn new(columns: Vec<String>, records: Vec<Vec<String>>) -> Self {
+        Self { columns, records }
+    }
+
+    // The first row of the text holds the column names
+    pub fn from_csv(text: &str, delimiter: char) -> Result<Self, ParseCsvError> {
+        let mut rows = parse_csv(text, delimiter)?.into_iter();
+        let columns = rows.next().unwrap_or_default();
+
+        Ok(Self {
+            columns,
+            records: rows.collect(),
+        })
+    }
+
+    pub fn column_index(&self, column_name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(column_name.trim()))
+    }
+
+    pub fn value(&self, record: usize, column: usize) -> Option<&str> {
+        self.records.get(record)?.get(column).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MergeState {
+    record: usize,
+    is_skipped: bool,
+}
+
+#[derive(Debug, Clone)]
+struct OpenField {
+    contents: Vec<PContent>,
+    instruction: String,
+    result_text: String,
+    is_in_result: bool,
+    begin_properties: Option<RPr>,
+    result_properties: Option<RPr>,
+}
+
+pub struct MailMerger<'a> {
+    data_source: &'a MergeDataSource,
+    field_mappings: Vec<OdsoFieldMapData>,
+    suppress_blank_lines: bool,
+}
+
+impl<'a> MailMerger<'a> {
+    pub fn new(data_source: &'a MergeDataSource) -> Self {
+        Self {
+            data_source,
+            field_mappings: Vec::new(),
+            suppress_blank_lines: true,
+        }
+    }
+
+    pub fn with_settings(data_source: &'a MergeDataSource, settings: &Settings) -> Self {
+        let mail_merge = settings.mail_merge.as_ref();
+        let field_mappings = mail_merge
+            .and_then(|mail_merge| mail_merge.odso.as_ref())
+            .map(|odso| odso.field_map_datas.clone())
+            .unwrap_or_default();
+        let suppress_blank_lines = !mail_merge
+            .and_then(|mail_merge| mail_merge.do_not_suppress_blank_lines)
+            .unwrap_or(false);
+
+        Self {
+            data_source,
+            field_mappings,
+            suppress_blank_lines,
+        }
+    }
+
+    pub fn merge_documents(&self, template: &Document) -> Vec<Document> {
+        let mut documents = Vec::new();
+        let mut record = 0;
+        while record < self.data_source.records.len() {
+            let mut state = MergeState {
+                record,
+                is_skipped: false,
+            };
+
+            let mut document = template.clone();
+            if let Some(body) = &mut document.body {
+                self.merge_block_level_elements(&mut body.block_level_elements, &mut state);
+            }
+
+            if !state.is_skipped {
+                documents.push(document);
+            }
+
+            record = state.record + 1;
+        }
+
+        documents
+    }
+
+    // Records are separated by a copy of the template's final section properties, starting a new section each
+    pub fn merge_single_document(&self, template: &Document) -> Document {
+        let section_properties = template.body.as_ref().and_then(|body| body.section_properties.clone());
+
+        let mut merged_document = template.clone();
+        let mut block_level_elements = Vec::new();
+        for (index, document) in self.merge_documents(template).into_iter().enumerate() {
+            if index > 0 {
+                block_level_elements.push(BlockLevelElts::Chunk(ContentBlockContent::Paragraph(Box::new(P {
+                    properties: Some(PPr {
+                        section_properties: Some(section_properties.clone().unwrap_or_default()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }))));
+            }
+
+            if let Some(body) = document.body {
+                block_level_elements.extend(body.block_level_elements);
+            }
+        }
+
+        if let Some(body) = &mut merged_document.body {
+            body.block_level_elements = block_level_elements;
+        }
+
+        merged_document
+    }
+
+    fn merge_block_level_elements(&self, elements: &mut Vec<BlockLevelElts>, state: &mut MergeState) {
+        let mut merged_elements = Vec::new();
+        for element in std::mem::take(elements) {
+            match element {
+                BlockLevelElts::Chunk(content) => {
+                    if let Some(content) = self.merge_content_block_content(content, state) {
+                        merged_elements.push(BlockLevelElts::Chunk(content));
+                    }
+                }
+                element => merged_elements.push(element),
+            }
+        }
+
+        *elements = merged_elements;
+    }
+
+    fn merge_content_block_contents(&self, contents: &mut Vec<ContentBlockContent>, state: &mut MergeState) {
+        *contents = std::mem::take(contents)
+            .into_iter()
+            .filter_map(|content| self.merge_content_block_content(content, state))
+            .collect();
+    }
+
+    fn merge_content_block_content(
+        &self,
+        content: ContentBlockContent,
+        state: &mut MergeState,
+    ) -> Option<ContentBlockContent> {
+        match content {
+            ContentBlockContent::Paragraph(mut paragraph) => {
+                let has_merge_fields = self.merge_p_contents(&mut paragraph.contents, state);
+                if has_merge_fields && self.suppress_blank_lines && is_blank_paragraph(&paragraph) {
+                    None
+                } else {
+                    Some(ContentBlockContent::Paragraph(paragraph))
+                }
+            }
+            ContentBlockContent::Table(mut table) => {
+                self.merge_row_contents(&mut table.row_contents, state);
+                Some(ContentBlockContent::Table(table))
+            }
+            ContentBlockContent::CustomXml(mut custom_xml) => {
+                self.merge_content_block_contents(&mut custom_xml.block_contents, state);
+                Some(ContentBlockContent::CustomXml(custom_xml))
+            }
+            ContentBlockContent::Sdt(mut sdt) => {
+                if let Some(sdt_content) = &mut sdt.sdt_content {
+                    self.merge_content_block_contents(&mut sdt_content.block_contents, state);
+                }
+                Some(ContentBlockContent::Sdt(sdt))
+            }
+            content => Some(content),
+        }
+    }
+
+    fn merge_row_contents(&self, row_contents: &mut [ContentRowContent], state: &mut MergeState) {
+        for row_content in row_contents {
+            match row_content {
+                ContentRowContent::Table(row) => self.merge_cell_contents(&mut row.contents, state),
+                ContentRowContent::CustomXml(custom_xml) => self.merge_row_contents(&mut custom_xml.contents, state),
+                ContentRowContent::Sdt(sdt) => {
+                    if let Some(sdt_content) = &mut sdt.content {
+                        self.merge_row_contents(&mut sdt_content.contents, state);
+                    }
+                }
+                ContentRowContent::RunLevelElements(_) => (),
+            }
+        }
+    }
+
+    fn merge_cell_contents(&self, cell_contents: &mut [ContentCellContent], state: &mut MergeState) {
+        for cell_content in cell_contents {
+            match cell_content {
+                ContentCellContent::Cell(cell) => {
+                    self.merge_block_level_elements(&mut cell.block_level_elements, state)
+                }
+                ContentCellContent::CustomXml(custom_xml) => self.merge_cell_contents(&mut custom_xml.contents, state),
+                ContentCellContent::Sdt(sdt) => {
+                    if let Some(sdt_content) = &mut sdt.content {
+                        self.merge_cell_contents(&mut sdt_content.contents, state);
+                    }
+                }
+                ContentCellContent::RunLevelElement(_) => (),
+            }
+        }
+    }
+
+    // Returns whether any merge field was replaced in the contents
+    fn merge_p_contents(&self, contents: &mut Vec<PContent>, state: &mut MergeState) -> bool {
+        let mut has_merge_fields = false;
+        let mut merged_contents = Vec::new();
+        let mut open_fields: Vec<OpenField> = Vec::new();
+
+        for mut content in split_field_character_runs(std::mem::take(contents)) {
+            match field_character_type(&content) {
+                Some(FldCharType::Begin) => {
+                    open_fields.push(OpenField {
+                        begin_properties: run_properties(&content),
+                        contents: vec![content],
+                        instruction: String::new(),
+                        result_text: String::new(),
+                        is_in_result: false,
+                        result_properties: None,
+                    });
+                    continue;
+                }
+                Some(FldCharType::Separate) => {
+                    if let Some(open_field) = open_fields.last_mut() {
+                        open_field.is_in_result = true;
+                        open_field.contents.push(content);
+                        continue;
+                    }
+                }
+                Some(FldCharType::End) => {
+                    if let Some(mut field) = open_fields.pop() {
+                        field.contents.push(content);
+
+                        let is_in_instruction = open_fields.last().map(|parent| !parent.is_in_result).unwrap_or(false);
+                        let (field_text, field_contents) = match self.evaluate_field(&field.instruction, state) {
+                            Some(result) => {
+                                has_merge_fields = true;
+                                let properties = field.result_properties.or(field.begin_properties);
+                                let result_contents =
+                                    result_run(&result, properties, is_in_instruction).into_iter().collect();
+                                (result, result_contents)
+                            }
+                            None => (field.result_text, field.contents),
+                        };
+
+                        match open_fields.last_mut() {
+                            Some(parent) => {
+                                if parent.is_in_result {
+                                    parent.result_text.push_str(&field_text);
+                                } else {
+                                    parent.instruction.push_str(&field_text);
+                                }
+                                parent.contents.extend(field_contents);
+                            }
+                            None => merged_contents.extend(field_contents),
+                        }
+                        continue;
+                    }
+                }
+                None => (),
+            }
+
+            has_merge_fields |= self.merge_nested_p_contents(&mut content, state);
+            let content = match content {
+                PContent::SimpleField(simple_field) => match self.evaluate_field(&simple_field.field_codes, state) {
+                    Some(result) => {
+                        has_merge_fields = true;
+                        let properties = simple_field.paragraph_contents.iter().find_map(run_properties);
+                        match result_run(&result, properties, false) {
+                            Some(result_content) => result_content,
+                            None => continue,
+                        }
+                    }
+                    None => PContent::SimpleField(simple_field),
+                },
+                content => content,
+            };
+
+            match open_fields.last_mut() {
+                Some(open_field) => {
+                    if let Some(text) = run_text(&content, open_field.is_in_result) {
+                        if open_field.is_in_result {
+                            open_field.result_text.push_str(&text);
+                            if open_field.result_properties.is_none() {
+                                open_field.result_properties = run_properties(&content);
+                            }
+                        } else {
+                            open_field.instruction.push_str(&text);
+                        }
+                    }
+                    open_field.contents.push(content);
+                }
+                None => merged_contents.push(content),
+            }
+        }
+
+        // Fields not ending in these contents are left untouched
+        for open_field in open_fields {
+            merged_contents.extend(open_field.contents);
+        }
+
+        *contents = merged_contents;
+        has_merge_fields
+    }
+
+    fn merge_nested_p_contents(&self, content: &mut PContent, state: &mut MergeState) -> bool {
+        match content {
+            PContent::Hyperlink(hyperlink) => self.merge_p_contents(&mut hyperlink.paragraph_contents, state),
+            PContent::ContentRunContent(run_content) => match run_content.as_mut() {
+                ContentRunContent::CustomXml(custom_xml) => {
+                    self.merge_p_contents(&mut custom_xml.paragraph_contents, state)
+                }
+                ContentRunContent::SmartTag(smart_tag) => {
+                    self.merge_p_contents(&mut smart_tag.paragraph_contents, state)
+                }
+                ContentRunContent::Sdt(sdt) => match &mut sdt.sdt_content {
+                    Some(sdt_content) => self.merge_p_contents(&mut sdt_content.p_contents, state),
+                    None => false,
+                },
+                ContentRunContent::Bidirectional(dir) => self.merge_p_contents(&mut dir.p_contents, state),
+                ContentRunContent::BidirectionalOverride(bdo) => self.merge_p_contents(&mut bdo.p_contents, state),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    // Returns None for fields that aren't part of the merge, those are kept as they are
+    fn evaluate_field(&self, instruction: &str, state: &mut MergeState) -> Option<String> {
+        let field_code: FieldCode = instruction.parse().ok()?;
+        match &field_code.field_type {
+            FieldType::MergeField(merge_field) => Some(self.evaluate_merge_field(&field_code, merge_field, state)),
+            FieldType::MergeRec => Some((state.record + 1).to_string()),
+            FieldType::Next => {
+                state.record += 1;
+                Some(String::new())
+            }
+            FieldType::NextIf(condition) => {
+                if evaluate_condition(condition) {
+                    state.record += 1;
+                }
+                Some(String::new())
+            }
+            FieldType::SkipIf(condition) => {
+                if evaluate_condition(condition) {
+                    state.is_skipped = true;
+                }
+                Some(String::new())
+            }
+            FieldType::If(if_field) => {
+                let text = if evaluate_condition(&if_field.condition) {
+                    &if_field.true_text
+                } else {
+                    &if_field.false_text
+                };
+                Some(text.as_ref().map(Argument::text).unwrap_or_default())
+            }
+            _ => None,
+        }
+    }
+
+    fn evaluate_merge_field(&self, field_code: &FieldCode, merge_field: &MergeField, state: &MergeState) -> String {
+        let value = self
+            .resolve_column(&merge_field.name)
+            .and_then(|column| self.data_source.value(state.record, column))
+            .unwrap_or("");
+        if value.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            "{}{}{}",
+            merge_field.text_before.as_deref().unwrap_or(""),
+            field_code.general_switches.format_text(value),
+            merge_field.text_after.as_deref().unwrap_or(""),
+        )
+    }
+
+    fn resolve_column(&self, field_name: &str) -> Option<usize> {
+        self.data_source.column_index(field_name).or_else(|| {
+            let field_map_data = self.field_mappings.iter().find(|field_map_data| {
+                field_map_data.field_type == Some(MailMergeOdsoFMDFieldType::DatabaseColumn)
+                    && field_map_data
+                        .mapped_name
+                        .as_ref()
+                        .map(|mapped_name| mapped_name.eq_ignore_ascii_case(field_name))
+                        .unwrap_or(false)
+            })?;
+
+            field_map_data
+                .name
+                .as_ref()
+                .and_then(|column_name| self.data_source.column_index(column_name))
+                .or_else(|| {
+                    field_map_data
+                        .column
+                        .map(|column| column as usize)
+                        .filter(|column| *column < self.data_source.columns.len())
+                })
+        })
+    }
+}
+
+impl Package {
+    pub fn mail_merge(&self, data_source: &MergeDataSource) -> Vec<Document> {
+        match &self.main_document {
+            Some(document) => self.mail_merger(data_source).merge_documents(document),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn mail_merge_single_document(&self, data_source: &MergeDataSource) -> Option<Document> {
+        let document = self.main_document.as_ref()?;
+        Some(self.mail_merger(data_source).merge_single_document(document))
+    }
+
+    fn mail_merger<'a>(&self, data_source: &'a MergeDataSource) -> MailMerger<'a> {
+        match &self.settings {
+            Some(settings) => MailMerger::with_settings(data_source, settings),
+            None => MailMerger::new(data_source),
+        }
+    }
+}
+
+fn parse_csv(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, ParseCsvError> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut quote_line = 0;
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        match character {
+            '"' if is_quoted => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    is_quoted = false;
+                }
+            }
+            '"' if field.is_empty() => {
+                is_quoted = true;
+                quote_line = line;
+            }
+            '\n' if is_quoted => {
+                field.push(character);
+                line += 1;
+            }
+            '\r' if !is_quoted => (),
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+                line += 1;
+            }
+            character if character == delimiter && !is_quoted => row.push(std::mem::take(&mut field)),
+            character => field.push(character),
+        }
+    }
+
+    if is_quoted {
+        return Err(ParseCsvError { line: quote_line });
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows
+        .into_iter()
+        .filter(|row| !(row.len() == 1 && row[0].is_empty()))
+        .collect())
+}
+
+fn evaluate_condition(condition: &Condition) -> bool {
+    condition
+        .operator
+        .compare(&condition.left.text(), &condition.right.text())
+}
+
+fn is_blank_paragraph(paragraph: &P) -> bool {
+    ParagraphText::from_paragraph(paragraph).text.trim().is_empty()
+        && !paragraph.contents.iter().any(|content| match as_run(content) {
+            Some(run) => run
+                .run_inner_contents
+                .iter()
+                .any(|inner_content| matches!(inner_content, RunInnerContent::Drawing(_) | RunInnerContent::Object(_))),
+            None => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MailMerger, MergeDataSource};
+    use crate::{
+        package::Package,
+        plaintext::{PlainTextOptions, TextExtractor},
+        test_util::{field_character, instruction, paragraph, paragraph_block, run, text_run},
+        wml::{
+            document::{BlockLevelElts, Body, ContentBlockContent, Document, FldCharType, PContent, SimpleField},
+            settings::{MailMergeOdsoFMDFieldType, OdsoFieldMapData},
+        },
+    };
+
+    fn simple_field(field_codes: &str) -> PContent {
+        PContent::SimpleField(SimpleField {
+            paragraph_contents: vec![text_run("«field»")],
+            field_codes: String::from(field_codes),
+            field_lock: None,
+            dirty: None,
+        })
+    }
+
+    fn document_text(document: Document) -> String {
+        let package = Package {
+            main_document: Some(Box::new(document)),
+            ..Default::default()
+        };
+
+        TextExtractor::new(&package, PlainTextOptions::default()).extract_main_document()
+    }
+
+    fn template_for_test() -> Document {
+        Document {
+            body: Some(Body {
+                block_level_elements: vec![
+                    paragraph(vec![
+                        text_run("Dear "),
+                        simple_field(" MERGEFIELD  \"First Name\" "),
+                        text_run(","),
+                    ]),
+                    // { IF { MERGEFIELD Country } = "Hungary" "Köszönjük!" "Thank you!" }
+                    paragraph(vec![
+                        run(vec![field_character(FldCharType::Begin)]),
+                        run(vec![instruction(" IF ")]),
+                        run(vec![field_character(FldCharType::Begin)]),
+                        run(vec![instruction(" MERGEFIELD Country ")]),
+                        run(vec![field_character(FldCharType::Separate)]),
+                        text_run("«Country»"),
+                        run(vec![field_character(FldCharType::End)]),
+                        run(vec![instruction(" = \"Hungary\" \"Köszönjük!\" \"Thank you!\" ")]),
+                        run(vec![field_character(FldCharType::Separate)]),
+                        text_run("Thank you!"),
+                        run(vec![field_character(FldCharType::End)]),
+                    ]),
+                    paragraph(vec![simple_field(" MERGEFIELD Company \\b \"at \" ")]),
+                    paragraph(vec![
+                        run(vec![field_character(FldCharType::Begin)]),
+                        run(vec![instruction(" SKIPIF ")]),
+                        run(vec![field_character(FldCharType::Begin)]),
+                        run(vec![instruction(" MERGEFIELD Country ")]),
+                        run(vec![field_character(FldCharType::End)]),
+                        run(vec![instruction("=\"Nowhere\"")]),
+                        run(vec![field_character(FldCharType::End)]),
+                    ]),
+                ]
+                .into_iter()
+                .map(paragraph_block)
+                .collect(),
+                section_properties: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn data_source_for_test() -> MergeDataSource {
+        MergeDataSource::from_csv(
+            "Given,Country,Company\r\nAnna,Hungary,\"Acme, Inc.\"\nBob,Nowhere,\nCarol,England,\n",
+            ',',
+        )
+        .unwrap()
+    }
+
+    #[test]
+    pub fn test_parse_csv() {
+        let data_source = data_source_for_test();
+        assert_eq!(data_source.columns, vec!["Given", "Country", "Company"]);
+        assert_eq!(data_source.records.len(), 3);
+        assert_eq!(data_source.value(0, 2), Some("Acme, Inc."));
+        assert!(MergeDataSource::from_csv("a,b\n\"unterminated,c", ',').is_err());
+    }
+
+    #[test]
+    pub fn test_merge_documents() {
+        let data_source = data_source_for_test();
+        let mut merger = MailMerger::new(&data_source);
+        merger.field_mappings = vec![OdsoFieldMapData {
+            field_type: Some(MailMergeOdsoFMDFieldType::DatabaseColumn),
+            name: Some(String::from("Given")),
+            mapped_name: Some(String::from("First Name")),
+            column: Some(0),
+            ..Default::default()
+        }];
+
+        let documents = merger.merge_documents(&template_for_test());
+        assert_eq!(documents.len(), 2);
+
+        let texts: Vec<String> = documents.into_iter().map(document_text).collect();
+        assert_eq!(texts[0], "Dear Anna,\nKöszönjük!\nat Acme, Inc.\n");
+        assert_eq!(texts[1], "Dear Carol,\nThank you!\n");
+    }
+
+    #[test]
+    pub fn test_merge_single_document_with_next() {
+        let data_source = data_source_for_test();
+        let template = Document {
+            body: Some(Body {
+                block_level_elements: vec![paragraph_block(paragraph(vec![
+                    simple_field("MERGEFIELD Given \\* Upper"),
+                    simple_field("NEXT"),
+                    text_run(" and "),
+                    simple_field("MERGEFIELD Given"),
+                ]))],
+                section_properties: None,
+            }),
+            ..Default::default()
+        };
+
+        let merged_document = MailMerger::new(&data_source).merge_single_document(&template);
+        let block_level_elements = &merged_document.body.as_ref().unwrap().block_level_elements;
+        assert_eq!(block_level_elements.len(), 3);
+        match &block_level_elements[1] {
+            BlockLevelElts::Chunk(ContentBlockContent::Paragraph(paragraph)) => {
+                assert!(paragraph.properties.as_ref().unwrap().section_properties.is_some())
+            }
+            _ => panic!("expected section break paragraph"),
+        }
+
+        assert_eq!(document_text(merged_document), "ANNA and Bob\n\nCAROL and \n");
+    }
+}
@@ -0,0 +1,941 @@
+use crate::{
+    export::{contains_table, drawing_images, hyperlink_url, row_cells, table_rows},
+    package::{MediaReference, Package},
+    plaintext::symbol_to_char,
+    resolvedstyle::RunProperties,
+    styleresolver::StyleResolver,
+    wml::{
+        document::{
+            BlockLevelElts, BrType, ContentBlockContent, ContentRunContent, FldCharType, FtnEdnRef, Hyperlink,
+            NumberFormat, PContent, RubyContentChoice, RunInnerContent, RunLevelElts, RunTrackChange,
+            RunTrackChangeChoice, P, R,
+        },
+        footnotes::FtnEdn,
+        table::{Merge, Tbl},
+    },
+};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownOptions {
+    pub media_directory: String,
+    pub code_fonts: Vec<String>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            media_directory: String::from("media"),
+            code_fonts: vec![
+                String::from("Consolas"),
+                String::from("Courier"),
+                String::from("Courier New"),
+                String::from("Lucida Console"),
+                String::from("Menlo"),
+                String::from("Monaco"),
+                String::from("Source Code Pro"),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldPart {
+    Instruction,
+    Result,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NoteKind {
+    Footnote,
+    Endnote,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct InlineFormat {
+    bold: bool,
+    italic: bool,
+    strikethrough: bool,
+    code: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Inline {
+    Text(String, InlineFormat),
+    Markup(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Paragraph(String),
+    ListItem(String),
+}
+
+pub struct MarkdownExporter<'a> {
+    package: &'a Package,
+    style_resolver: StyleResolver<'a>,
+    options: MarkdownOptions,
+    field_stack: Vec<FieldPart>,
+    notes: Vec<String>,
+    media_references: Vec<MediaReference>,
+    table_depth: usize,
+}
+
+impl<'a> MarkdownExporter<'a> {
+    pub fn new(package: &'a Package, options: MarkdownOptions) -> Self {
+        Self {
+            package,
+            style_resolver: package.style_resolver(),
+            options,
+            field_stack: Vec::new(),
+            notes: Vec::new(),
+            media_references: Vec::new(),
+            table_depth: 0,
+        }
+    }
+
+    pub fn export_main_document(&mut self) -> String {
+        let package = self.package;
+        let mut markdown = match package
+            .main_document
+            .as_ref()
+            .and_then(|document| document.body.as_ref())
+        {
+            Some(body) => self.export_block_level_elements(&body.block_level_elements),
+            None => String::new(),
+        };
+
+        if !self.notes.is_empty() {
+            markdown.push('\n');
+            for (index, note) in self.notes.iter().enumerate() {
+                markdown.push_str(&format!("\n[^{}]: {}", index + 1, note));
+            }
+
+            markdown.push('\n');
+        }
+
+        markdown
+    }
+
+    pub fn export_block_level_elements(&mut self, elements: &[BlockLevelElts]) -> String {
+        let mut blocks = Vec::new();
+        for element in elements {
+            if let BlockLevelElts::Chunk(content) = element {
+                self.collect_blocks(content, &mut blocks);
+            }
+        }
+
+        join_blocks(&blocks)
+    }
+
+    pub fn export_paragraph(&mut self, paragraph: &P) -> String {
+        match self.paragraph_block(paragraph) {
+            Some(Block::Paragraph(text)) | Some(Block::ListItem(text)) => text,
+            None => String::new(),
+        }
+    }
+
+    pub fn export_table(&mut self, table: &Tbl) -> String {
+        let mut blocks = Vec::new();
+        self.collect_table_blocks(table, &mut blocks);
+        join_blocks(&blocks)
+    }
+
+    pub fn media_references(&self) -> &[MediaReference] {
+        &self.media_references
+    }
+
+    fn collect_blocks(&mut self, content: &ContentBlockContent, blocks: &mut Vec<Block>) {
+        match content {
+            ContentBlockContent::CustomXml(custom_xml) => {
+                for block_content in &custom_xml.block_contents {
+                    self.collect_blocks(block_content, blocks);
+                }
+            }
+            ContentBlockContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.sdt_content {
+                    for block_content in &sdt_content.block_contents {
+                        self.collect_blocks(block_content, blocks);
+                    }
+                }
+            }
+            ContentBlockContent::Paragraph(paragraph) => {
+                if let Some(block) = self.paragraph_block(paragraph) {
+                    blocks.push(block);
+                }
+            }
+            ContentBlockContent::Table(table) => self.collect_table_blocks(table, blocks),
+            ContentBlockContent::RunLevelElement(_) => (),
+        }
+    }
+
+    fn paragraph_block(&mut self, paragraph: &P) -> Option<Block> {
+        let paragraph_properties = self.style_resolver.resolve_paragraph_properties(paragraph);
+        let heading_level = paragraph_properties
+            .outline_level
+            .filter(|outline_level| (0..9).contains(outline_level))
+            .map(|outline_level| std::cmp::min(outline_level + 1, 6) as usize);
+
+        let mut inlines = Vec::new();
+        for content in &paragraph.contents {
+            self.collect_p_content_inlines(paragraph, content, &mut inlines);
+        }
+
+        if heading_level.is_some() {
+            // Headings are usually bold by their style, emphasizing them again would only add noise
+            for inline in &mut inlines {
+                if let Inline::Text(_, format) = inline {
+                    format.bold = false;
+                }
+            }
+        }
+
+        let text = render_inlines(&inlines);
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        if let Some(heading_level) = heading_level {
+            return Some(Block::Paragraph(format!("{} {}", "#".repeat(heading_level), text)));
+        }
+
+        match self.list_marker(paragraph) {
+            Some((marker, indent_level)) => Some(Block::ListItem(format!(
+                "{}{} {}",
+                "    ".repeat(indent_level),
+                marker,
+                text
+            ))),
+            None => Some(Block::Paragraph(text.to_string())),
+        }
+    }
+
+    fn list_marker(&mut self, paragraph: &P) -> Option<(&'static str, usize)> {
+        let paragraph_numbering = self.style_resolver.resolve_numbering(paragraph)?;
+        let indent_level = paragraph_numbering.level;
+
+        let numbering_format = self
+            .package
+            .find_numbering_level(paragraph_numbering.numbering_id, indent_level)
+            .and_then(|level| level.numbering_format.as_ref())
+            .map(|numbering_format| numbering_format.value);
+
+        let marker = match numbering_format {
+            Some(NumberFormat::None) => return None,
+            Some(NumberFormat::Bullet) | None => "-",
+            Some(_) => "1.",
+        };
+
+        Some((marker, std::cmp::max(indent_level, 0) as usize))
+    }
+
+    fn collect_p_content_inlines(&mut self, paragraph: &P, content: &PContent, inlines: &mut Vec<Inline>) {
+        match content {
+            PContent::ContentRunContent(run_content) => {
+                self.collect_content_run_content_inlines(paragraph, run_content, inlines)
+            }
+            PContent::SimpleField(simple_field) => {
+                self.collect_p_contents_inlines(paragraph, &simple_field.paragraph_contents, inlines)
+            }
+            PContent::Hyperlink(hyperlink) => self.collect_hyperlink_inlines(paragraph, hyperlink, inlines),
+            PContent::SubDocument(_) => (),
+        }
+    }
+
+    fn collect_p_contents_inlines(&mut self, paragraph: &P, contents: &[PContent], inlines: &mut Vec<Inline>) {
+        for content in contents {
+            self.collect_p_content_inlines(paragraph, content, inlines);
+        }
+    }
+
+    fn collect_hyperlink_inlines(&mut self, paragraph: &P, hyperlink: &Hyperlink, inlines: &mut Vec<Inline>) {
+        let mut link_inlines = Vec::new();
+        self.collect_p_contents_inlines(paragraph, &hyperlink.paragraph_contents, &mut link_inlines);
+
+        let url = hyperlink_url(self.package, hyperlink);
+
+        let link_text = render_inlines(&link_inlines);
+        match url {
+            Some(url) if !link_text.trim().is_empty() => inlines.push(Inline::Markup(format!(
+                "[{}]({})",
+                link_text,
+                encode_link_destination(&url)
+            ))),
+            _ => inlines.extend(link_inlines),
+        }
+    }
+
+    fn collect_content_run_content_inlines(
+        &mut self,
+        paragraph: &P,
+        content: &ContentRunContent,
+        inlines: &mut Vec<Inline>,
+    ) {
+        match content {
+            ContentRunContent::CustomXml(custom_xml) => {
+                self.collect_p_contents_inlines(paragraph, &custom_xml.paragraph_contents, inlines)
+            }
+            ContentRunContent::SmartTag(smart_tag) => {
+                self.collect_p_contents_inlines(paragraph, &smart_tag.paragraph_contents, inlines)
+            }
+            ContentRunContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.sdt_content {
+                    self.collect_p_contents_inlines(paragraph, &sdt_content.p_contents, inlines);
+                }
+            }
+            ContentRunContent::Bidirectional(dir) => {
+                self.collect_p_contents_inlines(paragraph, &dir.p_contents, inlines)
+            }
+            ContentRunContent::BidirectionalOverride(bdo) => {
+                self.collect_p_contents_inlines(paragraph, &bdo.p_contents, inlines)
+            }
+            ContentRunContent::Run(run) => self.collect_run_inlines(paragraph, run, inlines),
+            ContentRunContent::RunLevelElements(run_level_element) => {
+                self.collect_run_level_element_inlines(paragraph, run_level_element, inlines)
+            }
+        }
+    }
+
+    fn collect_run_level_element_inlines(&mut self, paragraph: &P, element: &RunLevelElts, inlines: &mut Vec<Inline>) {
+        match element {
+            RunLevelElts::Insert(track_change) | RunLevelElts::MoveTo(track_change) => {
+                self.collect_run_track_change_inlines(paragraph, track_change, inlines)
+            }
+            _ => (),
+        }
+    }
+
+    fn collect_run_track_change_inlines(
+        &mut self,
+        paragraph: &P,
+        track_change: &RunTrackChange,
+        inlines: &mut Vec<Inline>,
+    ) {
+        for choice in &track_change.choices {
+            match choice {
+                RunTrackChangeChoice::ContentRunContent(content) => {
+                    self.collect_content_run_content_inlines(paragraph, content, inlines)
+                }
+            }
+        }
+    }
+
+    fn collect_run_inlines(&mut self, paragraph: &P, run: &R, inlines: &mut Vec<Inline>) {
+        let run_properties = self
+            .style_resolver
+            .resolve_style_inheritance(paragraph, run)
+            .map(|resolved_style| *resolved_style.run_properties)
+            .or_else(|| {
+                run.run_properties
+                    .as_ref()
+                    .map(|r_pr| RunProperties::from_vec(&r_pr.r_pr_bases))
+            })
+            .unwrap_or_default();
+
+        let is_hidden = run_properties.vanish.unwrap_or(false);
+        let format = self.inline_format(&run_properties);
+
+        for inner_content in &run.run_inner_contents {
+            if let RunInnerContent::FieldCharacter(field_char) = inner_content {
+                self.handle_field_character(field_char.field_char_type);
+                continue;
+            }
+
+            if is_hidden || self.field_stack.contains(&FieldPart::Instruction) {
+                continue;
+            }
+
+            match inner_content {
+                RunInnerContent::Text(text) => {
+                    if run_properties.all_capitals == Some(true) {
+                        inlines.push(Inline::Text(text.text.to_uppercase(), format));
+                    } else {
+                        inlines.push(Inline::Text(text.text.clone(), format));
+                    }
+                }
+                RunInnerContent::Break(br) => match br.break_type {
+                    Some(BrType::Page) | Some(BrType::Column) => (),
+                    _ => inlines.push(self.line_break()),
+                },
+                RunInnerContent::CarriageReturn => inlines.push(self.line_break()),
+                RunInnerContent::Tab | RunInnerContent::PositionTab(_) => {
+                    inlines.push(Inline::Text(String::from(" "), format))
+                }
+                RunInnerContent::NonBreakingHyphen => inlines.push(Inline::Text(String::from("-"), format)),
+                RunInnerContent::Symbol(symbol) => {
+                    if let Some(character) = symbol_to_char(symbol) {
+                        inlines.push(Inline::Text(character.to_string(), format));
+                    }
+                }
+                RunInnerContent::Ruby(ruby) => {
+                    for choice in &ruby.ruby_base.ruby_contents {
+                        match choice {
+                            RubyContentChoice::Run(base_run) => self.collect_run_inlines(paragraph, base_run, inlines),
+                            RubyContentChoice::RunLevelElement(element) => {
+                                self.collect_run_level_element_inlines(paragraph, element, inlines)
+                            }
+                        }
+                    }
+                }
+                RunInnerContent::FootnoteReference(reference) => {
+                    self.collect_note_reference_inlines(NoteKind::Footnote, reference, inlines)
+                }
+                RunInnerContent::EndnoteReference(reference) => {
+                    self.collect_note_reference_inlines(NoteKind::Endnote, reference, inlines)
+                }
+                RunInnerContent::Drawing(drawing) => {
+                    for image in drawing_images(self.package, drawing) {
+                        if let Some(image) = self.image_reference(image.alt_text, image.package_path) {
+                            inlines.push(image);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn inline_format(&self, run_properties: &RunProperties) -> InlineFormat {
+        let is_code = run_properties
+            .fonts
+            .as_ref()
+            .and_then(|fonts| fonts.ascii.as_ref().or(fonts.high_ansi.as_ref()))
+            .map(|font_name| {
+                self.options
+                    .code_fonts
+                    .iter()
+                    .any(|code_font| code_font.eq_ignore_ascii_case(font_name))
+            })
+            .unwrap_or(false);
+
+        InlineFormat {
+            bold: run_properties.bold.unwrap_or(false),
+            italic: run_properties.italic.unwrap_or(false),
+            strikethrough: run_properties.strikethrough.unwrap_or(false)
+                || run_properties.double_strikethrough.unwrap_or(false),
+            code: is_code,
+        }
+    }
+
+    fn line_break(&self) -> Inline {
+        if self.table_depth > 0 {
+            Inline::Markup(String::from("<br>"))
+        } else {
+            Inline::Markup(String::from("\\\n"))
+        }
+    }
+
+    fn handle_field_character(&mut self, field_char_type: FldCharType) {
+        match field_char_type {
+            FldCharType::Begin => self.field_stack.push(FieldPart::Instruction),
+            FldCharType::Separate => {
+                if let Some(field_part) = self.field_stack.last_mut() {
+                    *field_part = FieldPart::Result;
+                }
+            }
+            FldCharType::End => {
+                self.field_stack.pop();
+            }
+        }
+    }
+
+    fn collect_note_reference_inlines(
+        &mut self,
+        note_kind: NoteKind,
+        reference: &FtnEdnRef,
+        inlines: &mut Vec<Inline>,
+    ) {
+        let package = self.package;
+        let note = match note_kind {
+            NoteKind::Footnote => package.find_footnote_with_id(reference.id),
+            NoteKind::Endnote => package.find_endnote_with_id(reference.id),
+        };
+
+        if let Some(note) = note {
+            self.notes.push(String::new());
+            let note_number = self.notes.len();
+            self.notes[note_number - 1] = self.export_note(note);
+            inlines.push(Inline::Markup(format!("[^{}]", note_number)));
+        }
+    }
+
+    fn export_note(&mut self, note: &FtnEdn) -> String {
+        // Notes have their own field context, they can't continue a field started in the referencing paragraph
+        let saved_field_stack = std::mem::take(&mut self.field_stack);
+        let note_markdown = self.export_block_level_elements(&note.block_level_elements);
+        self.field_stack = saved_field_stack;
+
+        note_markdown.replace('\n', "\n    ")
+    }
+
+    fn image_reference(&mut self, alt_text: &str, package_path: String) -> Option<Inline> {
+        let file_name = Path::new(&package_path).file_name()?.to_str()?;
+
+        let exported_path = if self.options.media_directory.is_empty() {
+            String::from(file_name)
+        } else {
+            format!("{}/{}", self.options.media_directory.trim_end_matches('/'), file_name)
+        };
+
+        self.media_references.push(MediaReference {
+            package_path,
+            exported_path: exported_path.clone(),
+        });
+
+        Some(Inline::Markup(format!(
+            "![{}]({})",
+            escape_markdown(alt_text),
+            encode_link_destination(&exported_path)
+        )))
+    }
+
+    fn collect_table_blocks(&mut self, table: &Tbl, blocks: &mut Vec<Block>) {
+        let rows = table_rows(table);
+        let has_nested_tables = rows.iter().any(|row| {
+            row_cells(row)
+                .iter()
+                .any(|cell| contains_table(&cell.block_level_elements))
+        });
+
+        if has_nested_tables {
+            // GFM tables can't contain block content, fall back to exporting the cells as separate blocks
+            for row in rows {
+                for cell in row_cells(row) {
+                    for element in &cell.block_level_elements {
+                        if let BlockLevelElts::Chunk(content) = element {
+                            self.collect_blocks(content, blocks);
+                        }
+                    }
+                }
+            }
+
+            return;
+        }
+
+        self.table_depth += 1;
+        let mut table_cells: Vec<Vec<String>> = Vec::new();
+        for row in rows {
+            let mut row_texts = Vec::new();
+            for cell in row_cells(row) {
+                let tc_pr_base = cell.properties.as_ref().map(|tc_pr| &tc_pr.base.base);
+                let is_merged_continuation = tc_pr_base
+                    .and_then(|tc_pr_base| tc_pr_base.vertical_merge.as_ref())
+                    .map(|merge| *merge == Merge::Continue)
+                    .unwrap_or(false);
+
+                let cell_text = if is_merged_continuation {
+                    String::new()
+                } else {
+                    self.export_block_level_elements(&cell.block_level_elements)
+                        .replace("\n\n", "<br>")
+                        .replace('\n', " ")
+                        .replace('|', "\\|")
+                };
+
+                row_texts.push(cell_text);
+
+                let grid_span = tc_pr_base.and_then(|tc_pr_base| tc_pr_base.grid_span).unwrap_or(1);
+                for _ in 1..grid_span {
+                    row_texts.push(String::new());
+                }
+            }
+
+            table_cells.push(row_texts);
+        }
+        self.table_depth -= 1;
+
+        let column_count = table_cells.iter().map(Vec::len).max().unwrap_or(0);
+        if column_count == 0 {
+            return;
+        }
+
+        let mut lines = Vec::new();
+        for (row_index, row_texts) in table_cells.iter_mut().enumerate() {
+            row_texts.resize(column_count, String::new());
+            lines.push(format!("| {} |", row_texts.join(" | ")));
+            if row_index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(column_count)));
+            }
+        }
+
+        blocks.push(Block::Paragraph(lines.join("\n")));
+    }
+}
+
+fn join_blocks(blocks: &[Block]) -> String {
+    let mut markdown = String::new();
+    for (index, block) in blocks.iter().enumerate() {
+        if index > 0 {
+            match (&blocks[index - 1], block) {
+                (Block::ListItem(_), Block::ListItem(_)) => markdown.push('\n'),
+                _ => markdown.push_str("\n\n"),
+            }
+        }
+
+        match block {
+            Block::Paragraph(text) | Block::ListItem(text) => markdown.push_str(text),
+        }
+    }
+
+    markdown
+}
+
+fn render_inlines(inlines: &[Inline]) -> String {
+    let mut merged_inlines: Vec<Inline> = Vec::new();
+    for inline in inlines {
+        match (merged_inlines.last_mut(), inline) {
+            (Some(Inline::Text(last_text, last_format)), Inline::Text(text, format)) if last_format == format => {
+                last_text.push_str(text)
+            }
+            _ => merged_inlines.push(inline.clone()),
+        }
+    }
+
+    let mut markdown = String::new();
+    for inline in &merged_inlines {
+        match inline {
+            Inline::Text(text, format) => markdown.push_str(&render_formatted_text(text, *format)),
+            Inline::Markup(markup) => markdown.push_str(markup),
+        }
+    }
+
+    markdown
+}
+
+fn render_formatted_text(text: &str, format: InlineFormat) -> String {
+    // Emphasis markers have to be adjacent to the text, so surrounding whitespace is moved outside of them
+    let trimmed_text = text.trim();
+    if trimmed_text.is_empty() {
+        return String::from(text);
+    }
+
+    let leading_whitespace = &text[..text.len() - text.trim_start().len()];
+    let trailing_whitespace = &text[text.trim_end().len()..];
+
+    let mut formatted_text = if format.code {
+        if trimmed_text.contains('`') {
+            format!("`` {} ``", trimmed_text)
+        } else {
+            format!("`{}`", trimmed_text)
+        }
+    } else {
+        escape_markdown(trimmed_text)
+    };
+
+    if format.strikethrough {
+        formatted_text = format!("~~{}~~", formatted_text);
+    }
+
+    if format.italic {
+        formatted_text = format!("*{}*", formatted_text);
+    }
+
+    if format.bold {
+        formatted_text = format!("**{}**", formatted_text);
+    }
+
+    format!("{}{}{}", leading_whitespace, formatted_text, trailing_whitespace)
+}
+
+// Percent-encodes the characters which can't appear in a link destination. Existing escapes are kept, so URLs
+// which are already encoded don't change.
+fn encode_link_destination(url: &str) -> String {
+    let mut encoded_url = String::with_capacity(url.len());
+    for character in url.chars() {
+        let is_reserved = matches!(character, '(' | ')' | '<' | '>' | '"' | '\\' | '`');
+        if is_reserved || character.is_ascii_whitespace() || character.is_ascii_control() || !character.is_ascii() {
+            let mut buffer = [0; 4];
+            for byte in character.encode_utf8(&mut buffer).bytes() {
+                encoded_url.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded_url.push(character);
+        }
+    }
+
+    encoded_url
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped_text = String::with_capacity(text.len());
+    for character in text.chars() {
+        if let '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' = character {
+            escaped_text.push('\\');
+        }
+
+        escaped_text.push(character);
+    }
+
+    escaped_text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_link_destination, MarkdownExporter, MarkdownOptions};
+    use crate::{
+        package::Package,
+        test_util::{
+            formatted_run, package_with_blocks, package_with_paragraphs, paragraph, paragraph_block, run_content,
+            styled_paragraph, text_run,
+        },
+        wml::{
+            document::{
+                BlockLevelElts, ContentBlockContent, Drawing, DrawingChoice, FtnEdnRef, Hyperlink, NumFmt, NumPr,
+                NumberFormat, PContent, PPr, PPrBase, PPrGeneral, RPrBase, RunInnerContent, P, R,
+            },
+            drawing::{GraphicDataContent, Inline, PictureBlip},
+            footnotes::{Footnotes, FtnEdn},
+            numbering::{AbstractNum, Lvl, Num, Numbering},
+            styles::{Style, StyleType, Styles},
+            table::{ContentCellContent, ContentRowContent, Row, Tbl, TblGrid, TblPr, TblPrBase, Tc},
+        },
+    };
+    use msoffice_shared::relationship::Relationship;
+
+    fn image_run(relationship_id: &str) -> R {
+        let mut inline = Inline {
+            graphic_data_content: Some(GraphicDataContent::Picture(PictureBlip {
+                embed_relationship_id: Some(String::from(relationship_id)),
+                link_relationship_id: None,
+            })),
+            ..Inline::test_instance()
+        };
+        inline.doc_properties.description = Some(format!("Picture {}", relationship_id));
+
+        R {
+            run_inner_contents: vec![RunInnerContent::Drawing(Drawing(vec![DrawingChoice::Inline(inline)]))],
+            ..Default::default()
+        }
+    }
+
+    fn image_relationship(id: &str, target: &str) -> Relationship {
+        Relationship {
+            id: String::from(id),
+            rel_type: String::from("http://schemas.openxmlformats.org/officeDocument/2006/relationships/image"),
+            target: String::from(target),
+        }
+    }
+
+    fn export(package: &Package) -> String {
+        MarkdownExporter::new(package, MarkdownOptions::default()).export_main_document()
+    }
+
+    #[test]
+    pub fn test_export_headings_and_formatting() {
+        let package = package_with_paragraphs(vec![
+            styled_paragraph(vec![formatted_run("Title", vec![RPrBase::Bold(true)])], None, Some(1)),
+            paragraph(vec![
+                formatted_run("bold ", vec![RPrBase::Bold(true)]),
+                formatted_run("italic", vec![RPrBase::Italic(true)]),
+                text_run(" and "),
+                formatted_run("gone", vec![RPrBase::Strikethrough(true)]),
+                text_run(" "),
+                formatted_run(
+                    "let x",
+                    vec![RPrBase::RunFonts(crate::wml::document::Fonts {
+                        ascii: Some(String::from("Courier New")),
+                        ..Default::default()
+                    })],
+                ),
+                text_run(" 2*3"),
+            ]),
+        ]);
+
+        assert_eq!(
+            export(&package),
+            "## Title\n\n**bold** *italic* and ~~gone~~ `let x` 2\\*3"
+        );
+    }
+
+    #[test]
+    pub fn test_export_lists() {
+        let numbered_paragraph = |text: &str, numbering_id: i32, indent_level: i32| P {
+            properties: Some(PPr {
+                base: PPrBase {
+                    numbering_properties: Some(NumPr {
+                        indent_level: Some(indent_level),
+                        numbering_id: Some(numbering_id),
+                        inserted: None,
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..paragraph(vec![text_run(text)])
+        };
+
+        let level = |level: i32, value: NumberFormat| Lvl {
+            start: Some(1),
+            numbering_format: Some(NumFmt { value, format: None }),
+            level_restart: None,
+            paragraph_style: None,
+            display_as_arabic_numerals: None,
+            suffix: None,
+            level_text: None,
+            level_picture_bullet_id: None,
+            level_alignment: None,
+            paragraph_properties: None,
+            run_properties: None,
+            level,
+            template_code: None,
+            tentative: None,
+        };
+
+        // The numbering of the style is inherited, with the level linked to the style
+        let mut package = package_with_paragraphs(vec![
+            numbered_paragraph("first", 1, 0),
+            numbered_paragraph("nested", 1, 1),
+            numbered_paragraph("second", 1, 0),
+            styled_paragraph(vec![text_run("styled")], Some("ListBullet"), None),
+        ]);
+        package.styles = Some(Box::new(Styles {
+            document_defaults: None,
+            latent_styles: None,
+            styles: vec![Style {
+                style_id: Some(String::from("ListBullet")),
+                style_type: Some(StyleType::Paragraph),
+                paragraph_properties: Some(PPrGeneral {
+                    base: PPrBase {
+                        numbering_properties: Some(NumPr {
+                            indent_level: None,
+                            numbering_id: Some(1),
+                            inserted: None,
+                        }),
+                        ..Default::default()
+                    },
+                    change: None,
+                }),
+                ..Default::default()
+            }],
+        }));
+        package.numbering = Some(Numbering {
+            picture_numbering_symbols: Vec::new(),
+            abstract_numberings: vec![AbstractNum {
+                levels: vec![
+                    level(0, NumberFormat::Decimal),
+                    Lvl {
+                        paragraph_style: Some(String::from("ListBullet")),
+                        ..level(1, NumberFormat::Bullet)
+                    },
+                ],
+                ..AbstractNum::new(0)
+            }],
+            numberings: vec![Num {
+                abstract_num_id: 0,
+                level_overrides: Vec::new(),
+                numbering_id: 1,
+            }],
+            numbering_id_mac_at_cleanup: None,
+        });
+
+        assert_eq!(export(&package), "1. first\n    - nested\n1. second\n    - styled");
+    }
+
+    #[test]
+    pub fn test_export_hyperlinks_and_footnotes() {
+        let mut package = package_with_paragraphs(vec![paragraph(vec![
+            PContent::Hyperlink(Hyperlink {
+                paragraph_contents: vec![text_run("site")],
+                rel_id: Some(String::from("rId5")),
+                ..Default::default()
+            }),
+            text_run(" see"),
+            run_content(R {
+                run_inner_contents: vec![RunInnerContent::FootnoteReference(FtnEdnRef {
+                    custom_mark_follows: None,
+                    id: 1,
+                })],
+                ..Default::default()
+            }),
+        ])]);
+        package.main_document_relationships.push(Relationship {
+            id: String::from("rId5"),
+            rel_type: String::from("http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink"),
+            target: String::from("https://example.com"),
+        });
+        package.footnotes = Some(Footnotes(vec![FtnEdn {
+            ftn_edn_type: None,
+            id: 1,
+            block_level_elements: vec![paragraph_block(paragraph(vec![text_run("A note.")]))],
+        }]));
+
+        assert_eq!(
+            export(&package),
+            "[site](https://example.com) see[^1]\n\n[^1]: A note.\n"
+        );
+    }
+
+    #[test]
+    pub fn test_export_table() {
+        let cell = |text: &str| {
+            ContentCellContent::Cell(Box::new(Tc {
+                properties: None,
+                block_level_elements: vec![paragraph_block(paragraph(vec![text_run(text)]))],
+                id: None,
+            }))
+        };
+        let row = |contents: Vec<ContentCellContent>| {
+            ContentRowContent::Table(Box::new(Row {
+                property_exceptions: None,
+                properties: None,
+                contents,
+                run_properties_revision_id: None,
+                run_revision_id: None,
+                deletion_revision_id: None,
+                row_revision_id: None,
+            }))
+        };
+
+        let table = Tbl {
+            range_markup_elements: Vec::new(),
+            properties: TblPr {
+                base: TblPrBase::default(),
+                change: None,
+            },
+            grid: TblGrid::default(),
+            row_contents: vec![
+                row(vec![cell("Name"), cell("Value")]),
+                row(vec![cell("a|b"), cell("1")]),
+            ],
+        };
+        let package = package_with_blocks(vec![BlockLevelElts::Chunk(ContentBlockContent::Table(Box::new(table)))]);
+
+        assert_eq!(export(&package), "| Name | Value |\n| --- | --- |\n| a\\|b | 1 |");
+    }
+
+    #[test]
+    pub fn test_export_images_by_relationship_id() {
+        let mut package = package_with_paragraphs(vec![paragraph(vec![
+            run_content(image_run("rId8")),
+            run_content(image_run("rId7")),
+        ])]);
+        package.main_document_relationships = vec![
+            image_relationship("rId7", "/word/media/image1.png"),
+            image_relationship("rId8", "media/image2.png"),
+        ];
+
+        let mut exporter = MarkdownExporter::new(&package, MarkdownOptions::default());
+        assert_eq!(
+            exporter.export_main_document(),
+            "![Picture rId8](media/image2.png)![Picture rId7](media/image1.png)"
+        );
+
+        let package_paths: Vec<&str> = exporter
+            .media_references()
+            .iter()
+            .map(|media_reference| media_reference.package_path.as_str())
+            .collect();
+        assert_eq!(package_paths, vec!["word/media/image2.png", "word/media/image1.png"]);
+    }
+
+    #[test]
+    pub fn test_encode_link_destination() {
+        assert_eq!(
+            encode_link_destination("https://example.com/a b(1)?q=\"x\"&r=<y>"),
+            "https://example.com/a%20b%281%29?q=%22x%22&r=%3Cy%3E"
+        );
+        assert_eq!(encode_link_destination("media/café.png"), "media/caf%C3%A9.png");
+        assert_eq!(encode_link_destination("a%20b#top"), "a%20b#top");
+    }
+}
@@ -1,20 +1,25 @@
-use super::resolvedstyle::{ResolvedStyle, RunProperties};
+use super::{
+    html::{HtmlExporter, HtmlOptions},
+    markdown::{MarkdownExporter, MarkdownOptions},
+    plaintext::{PlainTextOptions, TextExtractor},
+    resolvedstyle::{ResolvedStyle, RunProperties},
+    styleresolver::StyleResolver,
+};
 use crate::wml::{
     document::{
-        BlockLevelElts, ContentBlockContent, ContentRunContent, Document, PContent, PPr, RPr, RPrBase, SectPrContents,
-        P, R,
+        BlockLevelElts, ContentBlockContent, ContentRunContent, Document, PContent, PPr, RPr, SectPrContents, P, R,
     },
-    footnotes::{Footnotes, FtnEdn, FtnEdnType},
+    footnotes::{Endnotes, Footnotes, FtnEdn, FtnEdnType},
+    headerfooter::HeaderFooter,
     numbering::{Lvl, Numbering},
     settings::Settings,
-    styles::{Style, StyleType, Styles},
+    styles::{StyleType, Styles},
 };
 use log::error;
 use msoffice_shared::{
     docprops::{AppInfo, Core},
     drawingml::sharedstylesheet::OfficeStyleSheet,
     relationship::{Relationship, THEME_RELATION_TYPE},
-    update::Update,
     xml::zip_file_to_xml_node,
 };
 use std::{
@@ -22,10 +27,20 @@ use std::{
     error::Error,
     ffi::OsStr,
     fs::File,
+    io::Read,
     path::{Path, PathBuf},
 };
 use zip::ZipArchive;
 
+pub const IMAGE_RELATION_TYPE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image";
+pub const MAIN_DOCUMENT_PART_PATH: &str = "word/document.xml";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaReference {
+    pub package_path: String,
+    pub exported_path: String,
+}
+
 #[derive(Debug, Default)]
 pub struct Package {
     pub app_info: Option<AppInfo>,
@@ -34,9 +49,13 @@ pub struct Package {
     pub main_document_relationships: Vec<Relationship>,
     pub styles: Option<Box<Styles>>,
     pub footnotes: Option<Footnotes>,
+    pub endnotes: Option<Endnotes>,
+    pub headers: HashMap<String, HeaderFooter>,
+    pub footers: HashMap<String, HeaderFooter>,
     pub numbering: Option<Numbering>,
     pub settings: Option<Box<Settings>>,
     pub medias: Vec<PathBuf>,
+    pub media_files: HashMap<String, Vec<u8>>,
     pub themes: HashMap<String, OfficeStyleSheet>,
 }
 
@@ -75,11 +94,31 @@ impl Package {
                     let xml_node = zip_file_to_xml_node(&mut zip_file)?;
                     instance.footnotes = Some(Footnotes::from_xml_element(&xml_node)?);
                 }
+                "word/endnotes.xml" => {
+                    let xml_node = zip_file_to_xml_node(&mut zip_file)?;
+                    instance.endnotes = Some(Endnotes::from_xml_element(&xml_node)?);
+                }
                 "word/numbering.xml" => {
                     let xml_node = zip_file_to_xml_node(&mut zip_file)?;
                     instance.numbering = Some(Numbering::from_xml_element(&xml_node)?);
                 }
-                path if path.starts_with("word/media/") => instance.medias.push(PathBuf::from(file_path)),
+                path if path.starts_with("word/header") && path.ends_with(".xml") => {
+                    let file_name = String::from(&path["word/".len()..]);
+                    let xml_node = zip_file_to_xml_node(&mut zip_file)?;
+                    instance.headers.insert(file_name, HeaderFooter::from_xml_element(&xml_node)?);
+                }
+                path if path.starts_with("word/footer") && path.ends_with(".xml") => {
+                    let file_name = String::from(&path["word/".len()..]);
+                    let xml_node = zip_file_to_xml_node(&mut zip_file)?;
+                    instance.footers.insert(file_name, HeaderFooter::from_xml_element(&xml_node)?);
+                }
+                path if path.starts_with("word/media/") => {
+                    let path = String::from(path);
+                    let mut data = Vec::new();
+                    zip_file.read_to_end(&mut data)?;
+                    instance.medias.push(PathBuf::from(&path));
+                    instance.media_files.insert(path, data);
+                }
                 path if path.starts_with("word/theme/") => {
                     let file_stem = match Path::new(path).file_stem().and_then(OsStr::to_str).map(String::from) {
                         Some(name) => name,
@@ -98,6 +137,26 @@ impl Package {
         Ok(instance)
     }
 
+    pub fn read_media_files(file_path: &Path) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+        let file = File::open(file_path)?;
+        let mut zipper = ZipArchive::new(&file)?;
+
+        let mut media_files = HashMap::new();
+        for idx in 0..zipper.len() {
+            let mut zip_file = zipper.by_index(idx)?;
+            if !zip_file.name().starts_with("word/media/") {
+                continue;
+            }
+
+            let name = String::from(zip_file.name());
+            let mut data = Vec::new();
+            zip_file.read_to_end(&mut data)?;
+            media_files.insert(name, data);
+        }
+
+        Ok(media_files)
+    }
+
     pub fn resolve_document_default_style(&self) -> Option<ResolvedStyle> {
         self.styles.as_ref()?.document_defaults.as_ref().map(|doc_defaults| {
             let run_properties = Box::new(
@@ -139,100 +198,42 @@ impl Package {
         Some(ResolvedStyle::from_wml_style(default_style))
     }
 
-    pub fn resolve_paragraph_style(&self, paragraph_properties: &PPr) -> Option<ResolvedStyle> {
-        paragraph_properties
-            .base
-            .style
-            .as_ref()
-            .and_then(|style_name| self.resolve_style_with_id(style_name))
+    pub fn style_resolver(&self) -> StyleResolver<'_> {
+        StyleResolver::new(self)
     }
 
-    pub fn resolve_run_style(&self, run_properties: &RPr) -> Option<ResolvedStyle> {
-        run_properties.r_pr_bases.iter().find_map(|r_pr_base| {
-            if let RPrBase::RunStyle(style_name) = r_pr_base {
-                self.resolve_style_with_id(style_name)
-            } else {
-                None
-            }
-        })
+    // The style resolving methods below build a new StyleResolver on every call and cache nothing. Other styles are
+    // only resolved through style_resolver(), which should be kept when resolving more than a few paragraphs.
+    pub fn resolve_paragraph_style(&self, paragraph_properties: &PPr) -> Option<ResolvedStyle> {
+        self.style_resolver().resolve_paragraph_style(paragraph_properties)
     }
 
-    fn resolve_style_with_id<T: AsRef<str>>(&self, style_id: T) -> Option<ResolvedStyle> {
-        // TODO(kalmar.robert) Use caching
-        let styles = &self.styles.as_ref()?.styles;
-
-        let top_most_style = styles.iter().find(|style| {
-            style
-                .style_id
-                .as_ref()
-                .filter(|s_id| (*s_id).as_str() == style_id.as_ref())
-                .is_some()
-        })?;
-
-        let style_hierarchy: Vec<&Style> = std::iter::successors(Some(top_most_style), |child_style| {
-            styles.iter().find(|style| style.style_id == child_style.based_on)
-        })
-        .collect();
-
-        Some(
-            style_hierarchy
-                .iter()
-                .rev()
-                .fold(Default::default(), |mut resolved_style: ResolvedStyle, style| {
-                    if let Some(style_p_pr) = &style.paragraph_properties {
-                        *resolved_style.paragraph_properties =
-                            resolved_style.paragraph_properties.update_with(style_p_pr.base.clone());
-                    }
-
-                    if let Some(style_r_pr) = &style.run_properties {
-                        let folded_style_r_pr = RunProperties::from_vec(&style_r_pr.r_pr_bases);
-                        *resolved_style.run_properties = resolved_style.run_properties.update_with(folded_style_r_pr);
-                    }
-
-                    resolved_style
-                }),
-        )
+    // A paragraph style used as run style is substituted by its linked character style
+    pub fn resolve_run_style(&self, run_properties: &RPr) -> Option<ResolvedStyle> {
+        self.style_resolver().resolve_run_style(run_properties)
     }
 
     pub fn resolve_style_inheritance(&self, paragraph: &P, run: &R) -> Option<ResolvedStyle> {
-        let paragraph_style = paragraph
-            .properties
-            .as_ref()
-            .and_then(|p_pr| self.resolve_paragraph_style(p_pr))
-            .or_else(|| self.resolve_default_style(StyleType::Paragraph));
+        self.style_resolver().resolve_style_inheritance(paragraph, run)
+    }
 
-        let run_style = run
-            .run_properties
-            .as_ref()
-            .and_then(|r_pr| self.resolve_run_style(r_pr))
-            .or_else(|| self.resolve_default_style(StyleType::Character));
+    pub fn main_document_image_relationships(&self) -> Vec<&Relationship> {
+        self.main_document_relationships
+            .iter()
+            .filter(|relationship| relationship.rel_type == IMAGE_RELATION_TYPE)
+            .collect()
+    }
 
-        let calced_style = match (paragraph_style, run_style) {
-            (Some(p_style), Some(r_style)) => Some(p_style.update_with_style_on_another_level(r_style)),
-            (p_style, r_style) => p_style.or(r_style),
-        };
+    pub fn extract_text(&self, options: PlainTextOptions) -> String {
+        TextExtractor::new(self, options).extract_main_document()
+    }
 
-        let default_style = self.resolve_document_default_style();
-        let calced_style = match (default_style, calced_style) {
-            (Some(def_style), Some(calced_style)) => Some(def_style.update_with(calced_style)),
-            (def_style, calced_style) => def_style.or(calced_style),
-        };
+    pub fn export_html(&self, options: HtmlOptions) -> String {
+        HtmlExporter::new(self, options).export_main_document()
+    }
 
-        calced_style.map(|resolved_style| {
-            let run_style = run
-                .run_properties
-                .as_ref()
-                .map(|r_pr| RunProperties::from_vec(&r_pr.r_pr_bases));
-
-            match (paragraph.properties.as_ref(), run_style) {
-                (Some(p_style), Some(r_style)) => resolved_style
-                    .update_paragraph_with(p_style.base.clone())
-                    .update_run_with(r_style),
-                (Some(p_style), None) => resolved_style.update_paragraph_with(p_style.base.clone()),
-                (None, Some(r_style)) => resolved_style.update_run_with(r_style),
-                _ => resolved_style,
-            }
-        })
+    pub fn export_markdown(&self, options: MarkdownOptions) -> String {
+        MarkdownExporter::new(self, options).export_main_document()
     }
 
     pub fn get_main_document_theme(&self) -> Option<&OfficeStyleSheet> {
@@ -263,6 +264,31 @@ impl Package {
         self.footnotes.as_ref()?.0.iter().find(|ftn_edn| ftn_edn.id == id)
     }
 
+    pub fn find_endnote_with_id(&self, id: i32) -> Option<&FtnEdn> {
+        self.endnotes.as_ref()?.0.iter().find(|ftn_edn| ftn_edn.id == id)
+    }
+
+    pub fn find_header_with_relationship_id(&self, relationship_id: &str) -> Option<&HeaderFooter> {
+        self.headers.get(self.main_document_relationship_target(relationship_id)?)
+    }
+
+    pub fn find_footer_with_relationship_id(&self, relationship_id: &str) -> Option<&HeaderFooter> {
+        self.footers.get(self.main_document_relationship_target(relationship_id)?)
+    }
+
+    // The path in the package of the part targeted by a relationship of the main document
+    pub fn main_document_relationship_part_path(&self, relationship_id: &str) -> Option<String> {
+        self.main_document_relationship_target(relationship_id)
+            .map(|target| resolve_relationship_target(MAIN_DOCUMENT_PART_PATH, target))
+    }
+
+    pub fn main_document_relationship_target(&self, relationship_id: &str) -> Option<&str> {
+        self.main_document_relationships
+            .iter()
+            .find(|relationship| relationship.id == relationship_id)
+            .map(|relationship| relationship.target.as_str())
+    }
+
     pub fn resolve_footnote_style(&self, footnote_type: FtnEdnType) -> Option<ResolvedStyle> {
         self.footnotes
             .as_ref()?
@@ -314,17 +340,7 @@ impl Package {
             return None;
         }
 
-        let numbering = self.numbering.as_ref()?;
-        let num = numbering
-            .numberings
-            .iter()
-            .find(|num| num.numbering_id == numbering_id)?;
-        let abstract_num = numbering
-            .abstract_numberings
-            .iter()
-            .find(|abstract_num| abstract_num.abstract_num_id == num.abstract_num_id)?;
-
-        abstract_num.levels.iter().find(|lvl| lvl.level == level)
+        self.numbering.as_ref()?.find_level(numbering_id, level, self.styles.as_deref())
     }
 
     pub fn resolve_numbering_level_style(numbering_level: &Lvl) -> ResolvedStyle {
@@ -351,9 +367,32 @@ impl Package {
     }
 }
 
+// Resolves the target of a relationship of the part at source_part_path to a path in the package. Targets are relative
+// to the folder of the source part, unless they start with a slash.
+pub fn resolve_relationship_target(source_part_path: &str, target: &str) -> String {
+    let mut segments: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        source_part_path.split('/').collect()
+    };
+    segments.pop();
+
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    segments.join("/")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Package, RunProperties};
+    use super::{resolve_relationship_target, Package, RunProperties};
     use crate::{
         resolvedstyle::ParagraphProperties,
         wml::{
@@ -527,6 +566,16 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_resolve_relationship_target() {
+        let source = "word/document.xml";
+        assert_eq!(resolve_relationship_target(source, "media/image1.png"), "word/media/image1.png");
+        assert_eq!(resolve_relationship_target(source, "./media/image1.png"), "word/media/image1.png");
+        assert_eq!(resolve_relationship_target(source, "/word/media/image1.png"), "word/media/image1.png");
+        assert_eq!(resolve_relationship_target(source, "../customXml/item1.xml"), "customXml/item1.xml");
+        assert_eq!(resolve_relationship_target(source, "/media/image1.png"), "media/image1.png");
+    }
+
     #[test]
     pub fn test_resolve_default_style() {
         let package = package_for_test();
@@ -0,0 +1,174 @@
+use crate::{
+    resolvedstyle::ResolvedStyle,
+    styleresolver::StyleResolver,
+    wml::{
+        document::{ParaRPr, ParaRPrTrackChanges, RPr, P, R},
+        table::Tbl,
+    },
+};
+
+// The revision state of a paragraph mark. A paragraph mark inserted and deleted in later revisions is both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParagraphMarkRevision {
+    pub inserted: bool,
+    pub deleted: bool,
+    pub moved_from: bool,
+    pub moved_to: bool,
+}
+
+impl ParagraphMarkRevision {
+    pub fn from_paragraph(paragraph: &P) -> Self {
+        paragraph_mark_track_changes(paragraph)
+            .map(|track_changes| Self {
+                inserted: track_changes.inserted.is_some(),
+                deleted: track_changes.deleted.is_some(),
+                moved_from: track_changes.move_from.is_some(),
+                moved_to: track_changes.move_to.is_some(),
+            })
+            .unwrap_or_default()
+    }
+
+    // Removing a paragraph mark merges the paragraph with the next one
+    pub fn is_removed_when_accepted(&self) -> bool {
+        self.deleted || self.moved_from
+    }
+
+    pub fn is_removed_when_rejected(&self) -> bool {
+        self.inserted || self.moved_to
+    }
+}
+
+pub fn paragraph_mark_properties(paragraph: &P) -> Option<&ParaRPr> {
+    paragraph.properties.as_ref()?.run_properties.as_ref()
+}
+
+// The authors and dates of the revisions of a paragraph mark
+pub fn paragraph_mark_track_changes(paragraph: &P) -> Option<&ParaRPrTrackChanges> {
+    paragraph_mark_properties(paragraph)?.track_changes.as_ref()
+}
+
+// The paragraph mark behaves like a run at the end of the paragraph which is formatted by ParaRPr
+fn paragraph_mark_run(paragraph: &P) -> R {
+    R {
+        run_properties: paragraph_mark_properties(paragraph).map(|para_r_pr| RPr {
+            r_pr_bases: para_r_pr.bases.clone(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+impl<'a> StyleResolver<'a> {
+    // The formatting of the paragraph mark, which applies to the number of a list item and the height of an empty
+    // paragraph
+    pub fn resolve_paragraph_mark_style(&mut self, paragraph: &P) -> Option<ResolvedStyle> {
+        self.resolve_style_inheritance(paragraph, &paragraph_mark_run(paragraph))
+    }
+
+    pub fn resolve_table_cell_paragraph_mark_style(
+        &mut self,
+        table: &Tbl,
+        row_index: usize,
+        cell_index: usize,
+        paragraph: &P,
+    ) -> Option<ResolvedStyle> {
+        let run = paragraph_mark_run(paragraph);
+        self.resolve_table_cell_style_inheritance(table, row_index, cell_index, paragraph, &run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParagraphMarkRevision;
+    use crate::{
+        test_util::{package_with_styles, style},
+        wml::{
+            document::{Markup, PPr, PPrBase, ParaRPr, ParaRPrTrackChanges, RPrBase, TrackChange, P},
+            styles::StyleType,
+        },
+    };
+
+    fn paragraph_with_mark(para_r_pr: ParaRPr) -> P {
+        P {
+            properties: Some(PPr {
+                base: PPrBase {
+                    style: Some(String::from("Heading")),
+                    ..Default::default()
+                },
+                run_properties: Some(para_r_pr),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn track_change() -> Option<TrackChange> {
+        Some(TrackChange {
+            base: Markup { id: 1 },
+            author: String::from("John Doe"),
+            date: None,
+        })
+    }
+
+    #[test]
+    pub fn test_resolve_paragraph_mark_style() {
+        let package = package_with_styles(vec![
+            style(
+                "Heading",
+                StyleType::Paragraph,
+                None,
+                vec![RPrBase::Bold(true), RPrBase::Italic(true)],
+            ),
+            style("Emphasis", StyleType::Character, None, vec![RPrBase::Italic(true)]),
+        ]);
+
+        let paragraph = paragraph_with_mark(ParaRPr {
+            bases: vec![RPrBase::RunStyle(String::from("Emphasis")), RPrBase::Capitals(true)],
+            ..Default::default()
+        });
+        let mut resolver = package.style_resolver();
+        let style = resolver.resolve_paragraph_mark_style(&paragraph).unwrap();
+        assert_eq!(style.run_properties.bold, Some(true));
+        assert_eq!(style.run_properties.italic, Some(false));
+        assert_eq!(style.run_properties.all_capitals, Some(true));
+
+        let style = resolver
+            .resolve_paragraph_mark_style(&paragraph_with_mark(Default::default()))
+            .unwrap();
+        assert_eq!(style.run_properties.italic, Some(true));
+        assert_eq!(style.run_properties.all_capitals, None);
+    }
+
+    #[test]
+    pub fn test_paragraph_mark_revision() {
+        let inserted = paragraph_with_mark(ParaRPr {
+            track_changes: Some(ParaRPrTrackChanges {
+                inserted: track_change(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let revision = ParagraphMarkRevision::from_paragraph(&inserted);
+        assert!(revision.inserted);
+        assert!(!revision.deleted);
+        assert!(!revision.is_removed_when_accepted());
+        assert!(revision.is_removed_when_rejected());
+
+        let deleted = paragraph_with_mark(ParaRPr {
+            track_changes: Some(ParaRPrTrackChanges {
+                inserted: track_change(),
+                deleted: track_change(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let revision = ParagraphMarkRevision::from_paragraph(&deleted);
+        assert!(revision.is_removed_when_accepted());
+        assert!(revision.is_removed_when_rejected());
+
+        assert_eq!(
+            ParagraphMarkRevision::from_paragraph(&Default::default()),
+            Default::default()
+        );
+    }
+}
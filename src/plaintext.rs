@@ -0,0 +1,728 @@
+use crate::{
+    export::{row_cells, table_rows},
+    package::Package,
+    resolvedstyle::RunProperties,
+    styleresolver::StyleResolver,
+    wml::{
+        document::{
+            BlockLevelElts, BrType, ContentBlockContent, ContentRunContent, Drawing, DrawingChoice, FldCharType,
+            FtnEdnRef, PContent, RubyContentChoice, RunInnerContent, RunLevelElts, RunTrackChange,
+            RunTrackChangeChoice, Sym, P, R,
+        },
+        drawing::{
+            GraphicDataContent, TxbxContent, WordprocessingShape, WordprocessingShapeChoice,
+            WordprocessingShapeTextboxInfoChoice,
+        },
+        footnotes::FtnEdn,
+        table::Tbl,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldTextMode {
+    Result,
+    Code,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotePlacement {
+    Omit,
+    Inline,
+    Trailing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlainTextOptions {
+    pub include_deleted_text: bool,
+    pub include_hidden_text: bool,
+    pub field_text_mode: FieldTextMode,
+    pub note_placement: NotePlacement,
+    pub apply_capitalization: bool,
+    pub include_symbols: bool,
+    pub paragraph_separator: String,
+    pub line_break: String,
+    pub page_break: String,
+    pub tab: String,
+    pub non_breaking_hyphen: String,
+    pub optional_hyphen: String,
+    pub table_cell_separator: String,
+    pub table_row_separator: String,
+}
+
+impl Default for PlainTextOptions {
+    fn default() -> Self {
+        Self {
+            include_deleted_text: false,
+            include_hidden_text: false,
+            field_text_mode: FieldTextMode::Result,
+            note_placement: NotePlacement::Trailing,
+            apply_capitalization: true,
+            include_symbols: true,
+            paragraph_separator: String::from("\n"),
+            line_break: String::from("\n"),
+            page_break: String::from("\n"),
+            tab: String::from("\t"),
+            non_breaking_hyphen: String::from("-"),
+            optional_hyphen: String::new(),
+            table_cell_separator: String::from("\t"),
+            table_row_separator: String::from("\n"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldPart {
+    Instruction,
+    Result,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NoteKind {
+    Footnote,
+    Endnote,
+}
+
+pub struct TextExtractor<'a> {
+    package: &'a Package,
+    style_resolver: StyleResolver<'a>,
+    options: PlainTextOptions,
+    field_stack: Vec<FieldPart>,
+    footnote_count: usize,
+    endnote_count: usize,
+    trailing_notes: Vec<String>,
+}
+
+impl<'a> TextExtractor<'a> {
+    pub fn new(package: &'a Package, options: PlainTextOptions) -> Self {
+        Self {
+            package,
+            style_resolver: package.style_resolver(),
+            options,
+            field_stack: Vec::new(),
+            footnote_count: 0,
+            endnote_count: 0,
+            trailing_notes: Vec::new(),
+        }
+    }
+
+    pub fn extract_main_document(mut self) -> String {
+        let package = self.package;
+        let mut text = String::new();
+        if let Some(body) = package
+            .main_document
+            .as_ref()
+            .and_then(|document| document.body.as_ref())
+        {
+            self.write_block_level_elements(&body.block_level_elements, &mut text);
+        }
+
+        if !self.trailing_notes.is_empty() {
+            text.push_str(&self.options.paragraph_separator);
+            for note in &self.trailing_notes {
+                text.push_str(note);
+                text.push_str(&self.options.paragraph_separator);
+            }
+        }
+
+        text
+    }
+
+    pub fn extract_block_level_elements(&mut self, elements: &[BlockLevelElts]) -> String {
+        let mut text = String::new();
+        self.write_block_level_elements(elements, &mut text);
+        text
+    }
+
+    pub fn extract_paragraph(&mut self, paragraph: &P) -> String {
+        let mut text = String::new();
+        self.write_paragraph_contents(paragraph, &mut text);
+        text
+    }
+
+    pub fn extract_table(&mut self, table: &Tbl) -> String {
+        let mut text = String::new();
+        self.write_table(table, &mut text);
+        text
+    }
+
+    pub fn extract_textbox_content(&mut self, textbox_content: &TxbxContent) -> String {
+        self.extract_block_level_elements(&textbox_content.block_level_elements)
+    }
+
+    pub fn extract_wordprocessing_shape(&mut self, shape: &WordprocessingShape) -> String {
+        match &shape.text_box_info {
+            Some(WordprocessingShapeTextboxInfoChoice::Textbox(textbox_info)) => {
+                self.extract_textbox_content(&textbox_info.textbox_content)
+            }
+            _ => String::new(),
+        }
+    }
+
+    pub fn trailing_notes(&self) -> &[String] {
+        &self.trailing_notes
+    }
+
+    fn write_block_level_elements(&mut self, elements: &[BlockLevelElts], out: &mut String) {
+        for element in elements {
+            if let BlockLevelElts::Chunk(content) = element {
+                self.write_block_content(content, out);
+            }
+        }
+    }
+
+    fn write_block_content(&mut self, content: &ContentBlockContent, out: &mut String) {
+        match content {
+            ContentBlockContent::CustomXml(custom_xml) => {
+                for block_content in &custom_xml.block_contents {
+                    self.write_block_content(block_content, out);
+                }
+            }
+            ContentBlockContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.sdt_content {
+                    for block_content in &sdt_content.block_contents {
+                        self.write_block_content(block_content, out);
+                    }
+                }
+            }
+            ContentBlockContent::Paragraph(paragraph) => {
+                self.write_paragraph_contents(paragraph, out);
+                out.push_str(&self.options.paragraph_separator);
+            }
+            ContentBlockContent::Table(table) => self.write_table(table, out),
+            ContentBlockContent::RunLevelElement(_) => (),
+        }
+    }
+
+    fn write_paragraph_contents(&mut self, paragraph: &P, out: &mut String) {
+        for content in &paragraph.contents {
+            self.write_p_content(paragraph, content, out);
+        }
+    }
+
+    fn write_p_content(&mut self, paragraph: &P, content: &PContent, out: &mut String) {
+        match content {
+            PContent::ContentRunContent(run_content) => self.write_content_run_content(paragraph, run_content, out),
+            PContent::SimpleField(simple_field) => match self.options.field_text_mode {
+                FieldTextMode::Result => {
+                    for content in &simple_field.paragraph_contents {
+                        self.write_p_content(paragraph, content, out);
+                    }
+                }
+                FieldTextMode::Code => {
+                    if self.is_text_visible(false) {
+                        out.push('{');
+                        out.push_str(&simple_field.field_codes);
+                        out.push('}');
+                    }
+                }
+            },
+            PContent::Hyperlink(hyperlink) => {
+                for content in &hyperlink.paragraph_contents {
+                    self.write_p_content(paragraph, content, out);
+                }
+            }
+            PContent::SubDocument(_) => (),
+        }
+    }
+
+    fn write_p_contents(&mut self, paragraph: &P, contents: &[PContent], out: &mut String) {
+        for content in contents {
+            self.write_p_content(paragraph, content, out);
+        }
+    }
+
+    fn write_content_run_content(&mut self, paragraph: &P, content: &ContentRunContent, out: &mut String) {
+        match content {
+            ContentRunContent::CustomXml(custom_xml) => {
+                self.write_p_contents(paragraph, &custom_xml.paragraph_contents, out)
+            }
+            ContentRunContent::SmartTag(smart_tag) => {
+                self.write_p_contents(paragraph, &smart_tag.paragraph_contents, out)
+            }
+            ContentRunContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.sdt_content {
+                    self.write_p_contents(paragraph, &sdt_content.p_contents, out);
+                }
+            }
+            ContentRunContent::Bidirectional(dir) => self.write_p_contents(paragraph, &dir.p_contents, out),
+            ContentRunContent::BidirectionalOverride(bdo) => self.write_p_contents(paragraph, &bdo.p_contents, out),
+            ContentRunContent::Run(run) => self.write_run(paragraph, run, out),
+            ContentRunContent::RunLevelElements(run_level_element) => {
+                self.write_run_level_element(paragraph, run_level_element, out)
+            }
+        }
+    }
+
+    fn write_run_level_element(&mut self, paragraph: &P, element: &RunLevelElts, out: &mut String) {
+        match element {
+            RunLevelElts::Insert(track_change) | RunLevelElts::MoveTo(track_change) => {
+                self.write_run_track_change(paragraph, track_change, out)
+            }
+            RunLevelElts::Delete(track_change) | RunLevelElts::MoveFrom(track_change)
+                if self.options.include_deleted_text =>
+            {
+                self.write_run_track_change(paragraph, track_change, out)
+            }
+            _ => (),
+        }
+    }
+
+    fn write_run_track_change(&mut self, paragraph: &P, track_change: &RunTrackChange, out: &mut String) {
+        for choice in &track_change.choices {
+            match choice {
+                RunTrackChangeChoice::ContentRunContent(content) => {
+                    self.write_content_run_content(paragraph, content, out)
+                }
+            }
+        }
+    }
+
+    fn write_run(&mut self, paragraph: &P, run: &R, out: &mut String) {
+        let run_properties = if !self.options.include_hidden_text || self.options.apply_capitalization {
+            self.style_resolver
+                .resolve_style_inheritance(paragraph, run)
+                .map(|resolved_style| *resolved_style.run_properties)
+                .or_else(|| {
+                    run.run_properties
+                        .as_ref()
+                        .map(|r_pr| RunProperties::from_vec(&r_pr.r_pr_bases))
+                })
+        } else {
+            None
+        };
+
+        let is_hidden =
+            !self.options.include_hidden_text && run_properties.as_ref().and_then(|r_pr| r_pr.vanish).unwrap_or(false);
+
+        // Small capitals only change the glyphs of lowercase letters, not the letters themselves
+        let is_capitalized = self.options.apply_capitalization
+            && run_properties
+                .as_ref()
+                .map(|r_pr| r_pr.all_capitals == Some(true))
+                .unwrap_or(false);
+
+        let mut run_text = String::new();
+        for inner_content in &run.run_inner_contents {
+            if let RunInnerContent::FieldCharacter(field_char) = inner_content {
+                self.handle_field_character(field_char.field_char_type, is_hidden, &mut run_text);
+                continue;
+            }
+
+            if is_hidden {
+                continue;
+            }
+
+            match inner_content {
+                RunInnerContent::Text(text) | RunInnerContent::DeletedText(text) => {
+                    if self.is_text_visible(false) {
+                        run_text.push_str(&text.text);
+                    }
+                }
+                RunInnerContent::InstructionText(text) | RunInnerContent::DeletedInstructionText(text) => {
+                    if self.is_text_visible(true) {
+                        run_text.push_str(&text.text);
+                    }
+                }
+                RunInnerContent::Ruby(ruby) => {
+                    for choice in &ruby.ruby_base.ruby_contents {
+                        match choice {
+                            RubyContentChoice::Run(base_run) => self.write_run(paragraph, base_run, &mut run_text),
+                            RubyContentChoice::RunLevelElement(element) => {
+                                self.write_run_level_element(paragraph, element, &mut run_text)
+                            }
+                        }
+                    }
+                }
+                RunInnerContent::FootnoteReference(reference) => {
+                    if self.is_text_visible(false) {
+                        self.write_note_reference(NoteKind::Footnote, reference, &mut run_text);
+                    }
+                }
+                RunInnerContent::EndnoteReference(reference) => {
+                    if self.is_text_visible(false) {
+                        self.write_note_reference(NoteKind::Endnote, reference, &mut run_text);
+                    }
+                }
+                RunInnerContent::Drawing(drawing) => {
+                    if self.is_text_visible(false) {
+                        // Text boxes have their own formatting, so the capitalization of the run doesn't apply to them
+                        write_run_text(&mut run_text, is_capitalized, out);
+                        self.write_drawing(drawing, out);
+                    }
+                }
+                _ => {
+                    if self.is_text_visible(false) {
+                        self.write_special_character(inner_content, &mut run_text);
+                    }
+                }
+            }
+        }
+
+        write_run_text(&mut run_text, is_capitalized, out);
+    }
+
+    // The paragraphs of text boxes are written where the drawing is anchored, separated from the surrounding text
+    fn write_drawing(&mut self, drawing: &Drawing, out: &mut String) {
+        for textbox_content in drawing_textbox_contents(drawing) {
+            out.push_str(&self.options.paragraph_separator);
+            self.write_block_level_elements(&textbox_content.block_level_elements, out);
+        }
+    }
+
+    fn write_special_character(&self, inner_content: &RunInnerContent, out: &mut String) {
+        match inner_content {
+            RunInnerContent::Break(br) => match br.break_type {
+                Some(BrType::Page) => out.push_str(&self.options.page_break),
+                _ => out.push_str(&self.options.line_break),
+            },
+            RunInnerContent::CarriageReturn => out.push_str(&self.options.line_break),
+            RunInnerContent::Tab | RunInnerContent::PositionTab(_) => out.push_str(&self.options.tab),
+            RunInnerContent::NonBreakingHyphen => out.push_str(&self.options.non_breaking_hyphen),
+            RunInnerContent::OptionalHypen => out.push_str(&self.options.optional_hyphen),
+            RunInnerContent::Symbol(symbol) if self.options.include_symbols => {
+                if let Some(character) = symbol_to_char(symbol) {
+                    out.push(character);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn handle_field_character(&mut self, field_char_type: FldCharType, is_hidden: bool, out: &mut String) {
+        let show_braces = self.options.field_text_mode == FieldTextMode::Code && !is_hidden;
+        match field_char_type {
+            FldCharType::Begin => {
+                if show_braces && self.is_text_visible(true) {
+                    out.push('{');
+                }
+
+                self.field_stack.push(FieldPart::Instruction);
+            }
+            FldCharType::Separate => {
+                if let Some(field_part) = self.field_stack.last_mut() {
+                    *field_part = FieldPart::Result;
+                }
+            }
+            FldCharType::End => {
+                self.field_stack.pop();
+                if show_braces && self.is_text_visible(true) {
+                    out.push('}');
+                }
+            }
+        }
+    }
+
+    fn is_text_visible(&self, is_instruction: bool) -> bool {
+        match self.options.field_text_mode {
+            FieldTextMode::Result => !is_instruction && !self.field_stack.contains(&FieldPart::Instruction),
+            FieldTextMode::Code => !self.field_stack.contains(&FieldPart::Result),
+        }
+    }
+
+    fn write_note_reference(&mut self, note_kind: NoteKind, reference: &FtnEdnRef, out: &mut String) {
+        if self.options.note_placement == NotePlacement::Omit {
+            return;
+        }
+
+        let package = self.package;
+        let note = match note_kind {
+            NoteKind::Footnote => package.find_footnote_with_id(reference.id),
+            NoteKind::Endnote => package.find_endnote_with_id(reference.id),
+        };
+
+        let note_text = note.map(|note| self.extract_note(note)).unwrap_or_default();
+        match self.options.note_placement {
+            NotePlacement::Inline => {
+                out.push('[');
+                out.push_str(&note_text);
+                out.push(']');
+            }
+            NotePlacement::Trailing => {
+                let mark = match note_kind {
+                    NoteKind::Footnote => {
+                        self.footnote_count += 1;
+                        format!("[{}]", self.footnote_count)
+                    }
+                    NoteKind::Endnote => {
+                        self.endnote_count += 1;
+                        format!("[{}]", to_lower_roman(self.endnote_count))
+                    }
+                };
+
+                if reference.custom_mark_follows != Some(true) {
+                    out.push_str(&mark);
+                }
+
+                self.trailing_notes.push(format!("{} {}", mark, note_text));
+            }
+            NotePlacement::Omit => (),
+        }
+    }
+
+    fn extract_note(&mut self, note: &FtnEdn) -> String {
+        // Notes have their own field context, they can't continue a field started in the referencing paragraph
+        let saved_field_stack = std::mem::take(&mut self.field_stack);
+        let mut text = String::new();
+        self.write_block_level_elements(&note.block_level_elements, &mut text);
+        self.field_stack = saved_field_stack;
+
+        trim_trailing_separator(&mut text, &self.options.paragraph_separator);
+        text.trim().to_string()
+    }
+
+    fn write_table(&mut self, table: &Tbl, out: &mut String) {
+        for row in table_rows(table) {
+            let mut cell_texts = Vec::new();
+            for cell in row_cells(row) {
+                let mut cell_text = String::new();
+                self.write_block_level_elements(&cell.block_level_elements, &mut cell_text);
+                trim_trailing_separator(&mut cell_text, &self.options.paragraph_separator);
+                cell_texts.push(cell_text);
+            }
+
+            out.push_str(&cell_texts.join(&self.options.table_cell_separator));
+            out.push_str(&self.options.table_row_separator);
+        }
+    }
+}
+
+fn write_run_text(run_text: &mut String, is_capitalized: bool, out: &mut String) {
+    if is_capitalized {
+        out.push_str(&run_text.to_uppercase());
+    } else {
+        out.push_str(run_text);
+    }
+
+    run_text.clear();
+}
+
+// The text boxes of the shapes in a drawing. Text boxes nested in them are reached when their content is written.
+fn drawing_textbox_contents(drawing: &Drawing) -> Vec<&TxbxContent> {
+    fn collect_shapes<'a>(shapes: &'a [WordprocessingShapeChoice], textbox_contents: &mut Vec<&'a TxbxContent>) {
+        for choice in shapes {
+            match choice {
+                WordprocessingShapeChoice::Shape(shape) => collect_shape(shape, textbox_contents),
+                WordprocessingShapeChoice::Group(group) => collect_shapes(&group.shapes, textbox_contents),
+                _ => (),
+            }
+        }
+    }
+
+    fn collect_shape<'a>(shape: &'a WordprocessingShape, textbox_contents: &mut Vec<&'a TxbxContent>) {
+        if let Some(WordprocessingShapeTextboxInfoChoice::Textbox(textbox_info)) = &shape.text_box_info {
+            textbox_contents.push(&textbox_info.textbox_content);
+        }
+    }
+
+    let mut textbox_contents = Vec::new();
+    for choice in &drawing.0 {
+        let graphic_data_content = match choice {
+            DrawingChoice::Anchor(anchor) => &anchor.graphic_data_content,
+            DrawingChoice::Inline(inline) => &inline.graphic_data_content,
+        };
+
+        match graphic_data_content {
+            Some(GraphicDataContent::Shape(shape)) => collect_shape(shape, &mut textbox_contents),
+            Some(GraphicDataContent::Group(group)) => collect_shapes(&group.shapes, &mut textbox_contents),
+            Some(GraphicDataContent::Canvas(canvas)) => collect_shapes(&canvas.shapes, &mut textbox_contents),
+            Some(GraphicDataContent::Picture(_)) | None => (),
+        }
+    }
+
+    textbox_contents
+}
+
+pub(crate) fn symbol_to_char(symbol: &Sym) -> Option<char> {
+    symbol
+        .character
+        .and_then(|character| std::char::from_u32(u32::from(character)))
+}
+
+fn trim_trailing_separator(text: &mut String, separator: &str) {
+    if !separator.is_empty() && text.ends_with(separator) {
+        let new_len = text.len() - separator.len();
+        text.truncate(new_len);
+    }
+}
+
+fn to_lower_roman(mut number: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut roman = String::new();
+    for (value, numeral) in NUMERALS.iter() {
+        while number >= *value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+
+    roman
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldTextMode, NotePlacement, PlainTextOptions, TextExtractor};
+    use crate::{
+        package::Package,
+        test_util::{complex_field, formatted_run, package_with_paragraphs, paragraph, paragraph_block, run, text_run},
+        wml::{
+            document::{
+                ContentRunContent, Drawing, DrawingChoice, FtnEdnRef, Markup, PContent, RPrBase, RunInnerContent,
+                RunLevelElts, RunTrackChange, RunTrackChangeChoice, Text, TrackChange, R,
+            },
+            drawing::{
+                Anchor, GraphicDataContent, TextboxInfo, TxbxContent, WordprocessingShape,
+                WordprocessingShapeTextboxInfoChoice,
+            },
+            footnotes::{Footnotes, FtnEdn, FtnEdnType},
+        },
+    };
+
+    fn field_package() -> Package {
+        let mut contents = vec![text_run("Page ")];
+        contents.extend(complex_field(" PAGE ", "3"));
+        package_with_paragraphs(vec![paragraph(contents)])
+    }
+
+    #[test]
+    pub fn test_extract_field_results() {
+        let package = field_package();
+        let text = TextExtractor::new(&package, Default::default()).extract_main_document();
+        assert_eq!(text, "Page 3\n");
+    }
+
+    #[test]
+    pub fn test_extract_field_codes() {
+        let package = field_package();
+        let options = PlainTextOptions {
+            field_text_mode: FieldTextMode::Code,
+            ..Default::default()
+        };
+        let text = TextExtractor::new(&package, options).extract_main_document();
+        assert_eq!(text, "Page { PAGE }\n");
+    }
+
+    #[test]
+    pub fn test_extract_hidden_and_capitalized_text() {
+        let package = package_with_paragraphs(vec![paragraph(vec![
+            text_run("visible "),
+            formatted_run("hidden", vec![RPrBase::Vanish(true)]),
+            formatted_run("caps", vec![RPrBase::Capitals(true)]),
+            formatted_run(" Small", vec![RPrBase::SmallCapitals(true)]),
+        ])]);
+
+        let text = TextExtractor::new(&package, Default::default()).extract_main_document();
+        assert_eq!(text, "visible CAPS Small\n");
+
+        let options = PlainTextOptions {
+            include_hidden_text: true,
+            apply_capitalization: false,
+            ..Default::default()
+        };
+        let text = TextExtractor::new(&package, options).extract_main_document();
+        assert_eq!(text, "visible hiddencaps Small\n");
+    }
+
+    #[test]
+    pub fn test_extract_deleted_text() {
+        let deletion = RunLevelElts::Delete(RunTrackChange {
+            base: TrackChange {
+                base: Markup { id: 1 },
+                author: String::from("John Smith"),
+                date: None,
+            },
+            choices: vec![RunTrackChangeChoice::ContentRunContent(ContentRunContent::Run(R {
+                run_inner_contents: vec![RunInnerContent::DeletedText(Text {
+                    text: String::from("old "),
+                    xml_space: None,
+                })],
+                ..Default::default()
+            }))],
+        });
+
+        let package = package_with_paragraphs(vec![paragraph(vec![
+            PContent::ContentRunContent(Box::new(ContentRunContent::RunLevelElements(deletion))),
+            text_run("new"),
+        ])]);
+
+        let text = TextExtractor::new(&package, Default::default()).extract_main_document();
+        assert_eq!(text, "new\n");
+
+        let options = PlainTextOptions {
+            include_deleted_text: true,
+            ..Default::default()
+        };
+        let text = TextExtractor::new(&package, options).extract_main_document();
+        assert_eq!(text, "old new\n");
+    }
+
+    #[test]
+    pub fn test_extract_footnotes() {
+        let mut package = package_with_paragraphs(vec![paragraph(vec![
+            text_run("Body"),
+            run(vec![RunInnerContent::FootnoteReference(FtnEdnRef {
+                custom_mark_follows: None,
+                id: 1,
+            })]),
+        ])]);
+        package.footnotes = Some(Footnotes(vec![FtnEdn {
+            ftn_edn_type: Some(FtnEdnType::Normal),
+            id: 1,
+            block_level_elements: vec![paragraph_block(paragraph(vec![text_run("Note")]))],
+        }]));
+
+        let text = TextExtractor::new(&package, Default::default()).extract_main_document();
+        assert_eq!(text, "Body[1]\n\n[1] Note\n");
+
+        let options = PlainTextOptions {
+            note_placement: NotePlacement::Inline,
+            ..Default::default()
+        };
+        let text = TextExtractor::new(&package, options).extract_main_document();
+        assert_eq!(text, "Body[Note]\n");
+    }
+
+    #[test]
+    pub fn test_extract_text_box() {
+        let shape = WordprocessingShape {
+            text_box_info: Some(WordprocessingShapeTextboxInfoChoice::Textbox(TextboxInfo {
+                textbox_content: TxbxContent {
+                    block_level_elements: vec![
+                        paragraph_block(paragraph(vec![text_run("Box 1")])),
+                        paragraph_block(paragraph(vec![text_run("Box 2")])),
+                    ],
+                },
+                id: None,
+            })),
+            ..WordprocessingShape::test_instance()
+        };
+        let anchor = Anchor {
+            graphic_data_content: Some(GraphicDataContent::Shape(Box::new(shape))),
+            ..Anchor::test_instance()
+        };
+        let package = package_with_paragraphs(vec![paragraph(vec![
+            text_run("Before"),
+            run(vec![RunInnerContent::Drawing(Drawing(vec![DrawingChoice::Anchor(
+                anchor,
+            )]))]),
+            text_run("After"),
+        ])]);
+
+        let text = TextExtractor::new(&package, Default::default()).extract_main_document();
+        assert_eq!(text, "Before\nBox 1\nBox 2\nAfter\n");
+    }
+}
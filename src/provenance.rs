@@ -0,0 +1,540 @@
+use crate::{
+    resolvedstyle::{ParagraphProperties, ResolvedStyle, RunProperties},
+    styleresolver::StyleResolver,
+    tablestyle,
+    wml::{
+        document::{P, R},
+        simpletypes::DecimalNumber,
+        styles::{Style, TblStyleOverrideType},
+        table::Tbl,
+    },
+};
+use msoffice_shared::sharedtypes::OnOff;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+// Collects the names of the fields which are set
+macro_rules! set_fields {
+    ($properties:expr, $($field:ident),+ $(,)?) => {{
+        let mut fields = Vec::new();
+        $(
+            if $properties.$field.is_some() {
+                fields.push(stringify!($field));
+            }
+        )+
+        fields
+    }};
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormattingSource {
+    DocumentDefaults,
+    TableStyle {
+        style_id: String,
+        conditional_format: TblStyleOverrideType,
+    },
+    Numbering {
+        numbering_id: DecimalNumber,
+        level: DecimalNumber,
+    },
+    Style(String),
+    DirectFormatting,
+}
+
+impl Display for FormattingSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormattingSource::DocumentDefaults => write!(f, "document defaults"),
+            FormattingSource::TableStyle {
+                style_id,
+                conditional_format,
+            } => write!(f, "table style {} ({:?})", style_id, conditional_format),
+            FormattingSource::Numbering { numbering_id, level } => {
+                write!(f, "numbering {} level {}", numbering_id, level)
+            }
+            FormattingSource::Style(style_id) => write!(f, "style {}", style_id),
+            FormattingSource::DirectFormatting => write!(f, "direct formatting"),
+        }
+    }
+}
+
+pub type PropertySources = BTreeMap<&'static str, FormattingSource>;
+
+// The resolved style of a run together with the source of each property which is set. The sources are keyed by the
+// field names of `ParagraphProperties` and `RunProperties`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FormattingProvenance {
+    pub style: ResolvedStyle,
+    pub paragraph_property_sources: PropertySources,
+    pub run_property_sources: PropertySources,
+}
+
+impl FormattingProvenance {
+    pub fn paragraph_property_source(&self, property: &str) -> Option<&FormattingSource> {
+        self.paragraph_property_sources.get(property)
+    }
+
+    pub fn run_property_source(&self, property: &str) -> Option<&FormattingSource> {
+        self.run_property_sources.get(property)
+    }
+}
+
+// One property per line, so that the provenance of two runs can be diffed
+impl Display for FormattingProvenance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (property, source) in &self.paragraph_property_sources {
+            writeln!(f, "paragraph.{}: {}", property, source)?;
+        }
+
+        for (property, source) in &self.run_property_sources {
+            writeln!(f, "run.{}: {}", property, source)?;
+        }
+
+        Ok(())
+    }
+}
+
+// The properties set by a single style or by the direct formatting
+struct Layer {
+    source: FormattingSource,
+    paragraph_properties: ParagraphProperties,
+    run_properties: RunProperties,
+}
+
+impl Layer {
+    fn from_resolved_style(source: FormattingSource, resolved_style: ResolvedStyle) -> Self {
+        Self {
+            source,
+            paragraph_properties: *resolved_style.paragraph_properties,
+            run_properties: *resolved_style.run_properties,
+        }
+    }
+
+    fn from_style_hierarchy(hierarchy: &[&Style]) -> Vec<Self> {
+        hierarchy
+            .iter()
+            .rev()
+            .map(|style| {
+                let source = FormattingSource::Style(style.style_id.clone().unwrap_or_default());
+                Self::from_resolved_style(source, ResolvedStyle::from_wml_style(style))
+            })
+            .collect()
+    }
+}
+
+// A level of the style hierarchy, within which the layers simply override each other. Between levels toggle
+// properties set to false leave the value of the lower level unchanged.
+struct Level {
+    is_toggling: bool,
+    layers: Vec<Layer>,
+}
+
+impl<'a> StyleResolver<'a> {
+    pub fn explain_formatting(&mut self, paragraph: &P, run: &R) -> FormattingProvenance {
+        let style = self.resolve_style_inheritance(paragraph, run).unwrap_or_default();
+        let levels = self.formatting_levels(None, paragraph, run);
+        explain_levels(style, levels)
+    }
+
+    pub fn explain_table_cell_formatting(
+        &mut self,
+        table: &Tbl,
+        row_index: usize,
+        cell_index: usize,
+        paragraph: &P,
+        run: &R,
+    ) -> FormattingProvenance {
+        let style = self
+            .resolve_table_cell_style_inheritance(table, row_index, cell_index, paragraph, run)
+            .unwrap_or_default();
+        let table_layers = self.table_style_layers(table, row_index, cell_index);
+        let levels = self.formatting_levels(Some(table_layers), paragraph, run);
+        explain_levels(style, levels)
+    }
+
+    fn table_style_layers(&mut self, table: &Tbl, row_index: usize, cell_index: usize) -> Vec<Layer> {
+        let (style_id, conditional_formats) =
+            match self.resolve_table_cell_conditional_formats(table, row_index, cell_index) {
+                Some((Some(style_id), conditional_formats)) => (style_id, conditional_formats),
+                _ => return Vec::new(),
+            };
+
+        let (hierarchy, _) = self.walk_hierarchy(&style_id);
+        tablestyle::table_style_layers(&hierarchy, &conditional_formats)
+            .into_iter()
+            .map(|(style, conditional_format, table_style)| {
+                let source = FormattingSource::TableStyle {
+                    style_id: style.style_id.clone().unwrap_or_default(),
+                    conditional_format,
+                };
+                Layer::from_resolved_style(source, table_style.style)
+            })
+            .collect()
+    }
+
+    // The levels in the order they are applied: document defaults, table style, numbering, paragraph style, character
+    // style and direct formatting
+    fn formatting_levels(&mut self, table_layers: Option<Vec<Layer>>, paragraph: &P, run: &R) -> Vec<Level> {
+        let mut levels = Vec::new();
+
+        if let Some(default_style) = self.resolve_document_default_style() {
+            levels.push(Level {
+                is_toggling: false,
+                layers: vec![Layer::from_resolved_style(
+                    FormattingSource::DocumentDefaults,
+                    default_style.clone(),
+                )],
+            });
+        }
+
+        if let Some(table_layers) = table_layers {
+            levels.push(Level {
+                is_toggling: true,
+                layers: table_layers,
+            });
+        }
+
+        let numbering_layer = self.resolve_numbering(paragraph).and_then(|paragraph_numbering| {
+            let numbering_level = self.resolve_numbering_level(paragraph)?;
+            let source = FormattingSource::Numbering {
+                numbering_id: paragraph_numbering.numbering_id,
+                level: paragraph_numbering.level,
+            };
+            let paragraph_properties = numbering_level.paragraph_properties.as_ref()?.base.clone();
+            Some(Layer::from_resolved_style(
+                source,
+                ResolvedStyle::from_paragraph_properties(Box::new(paragraph_properties)),
+            ))
+        });
+        levels.push(Level {
+            is_toggling: true,
+            layers: numbering_layer.into_iter().collect(),
+        });
+
+        let paragraph_style_id = self
+            .paragraph_style_id(paragraph)
+            .filter(|style_id| self.style(style_id).is_some())
+            .or_else(|| self.default_paragraph_style_id().map(String::from));
+        levels.push(self.style_level(paragraph_style_id));
+
+        let run_style_id = self
+            .run_style_id(run)
+            .filter(|style_id| self.style(style_id).is_some())
+            .or_else(|| self.default_character_style_id().map(String::from));
+        levels.push(self.style_level(run_style_id));
+
+        levels.push(Level {
+            is_toggling: false,
+            layers: vec![Layer {
+                source: FormattingSource::DirectFormatting,
+                paragraph_properties: paragraph
+                    .properties
+                    .as_ref()
+                    .map(|p_pr| p_pr.base.clone())
+                    .unwrap_or_default(),
+                run_properties: run
+                    .run_properties
+                    .as_ref()
+                    .map(|r_pr| RunProperties::from_vec(&r_pr.r_pr_bases))
+                    .unwrap_or_default(),
+            }],
+        });
+
+        levels
+    }
+
+    fn style_level(&self, style_id: Option<String>) -> Level {
+        let hierarchy = style_id
+            .map(|style_id| self.walk_hierarchy(&style_id).0)
+            .unwrap_or_default();
+
+        Level {
+            is_toggling: true,
+            layers: Layer::from_style_hierarchy(&hierarchy),
+        }
+    }
+}
+
+fn explain_levels(style: ResolvedStyle, levels: Vec<Level>) -> FormattingProvenance {
+    let mut provenance = FormattingProvenance {
+        style,
+        ..Default::default()
+    };
+
+    for level in levels {
+        let mut run_properties: RunProperties = Default::default();
+        let mut run_property_sources = PropertySources::new();
+
+        for layer in level.layers {
+            for property in paragraph_property_names(&layer.paragraph_properties) {
+                provenance
+                    .paragraph_property_sources
+                    .insert(property, layer.source.clone());
+            }
+
+            for property in run_property_names(&layer.run_properties) {
+                run_property_sources.insert(property, layer.source.clone());
+            }
+
+            run_properties = run_properties.update_with(layer.run_properties);
+        }
+
+        for (property, source) in run_property_sources {
+            // The style hierarchy replaces the toggle properties of the document defaults
+            let keeps_lower_level = level.is_toggling
+                && toggle_property(&run_properties, property) == Some(false)
+                && provenance
+                    .run_property_sources
+                    .get(property)
+                    .is_some_and(|lower_source| *lower_source != FormattingSource::DocumentDefaults);
+            if !keeps_lower_level {
+                provenance.run_property_sources.insert(property, source);
+            }
+        }
+    }
+
+    provenance
+}
+
+fn toggle_property(run_properties: &RunProperties, property: &str) -> Option<OnOff> {
+    match property {
+        "bold" => run_properties.bold,
+        "complex_script_bold" => run_properties.complex_script_bold,
+        "italic" => run_properties.italic,
+        "complex_script_italic" => run_properties.complex_script_italic,
+        "all_capitals" => run_properties.all_capitals,
+        "all_small_capitals" => run_properties.all_small_capitals,
+        "strikethrough" => run_properties.strikethrough,
+        "double_strikethrough" => run_properties.double_strikethrough,
+        "outline" => run_properties.outline,
+        "shadow" => run_properties.shadow,
+        "emboss" => run_properties.emboss,
+        "imprint" => run_properties.imprint,
+        "vanish" => run_properties.vanish,
+        _ => None,
+    }
+}
+
+fn paragraph_property_names(paragraph_properties: &ParagraphProperties) -> Vec<&'static str> {
+    set_fields!(
+        paragraph_properties,
+        style,
+        keep_with_next,
+        keep_lines_on_one_page,
+        start_on_next_page,
+        frame_properties,
+        widow_control,
+        numbering_properties,
+        suppress_line_numbers,
+        borders,
+        shading,
+        tabs,
+        suppress_auto_hyphens,
+        kinsoku,
+        word_wrapping,
+        overflow_punctuations,
+        top_line_punctuations,
+        auto_space_latin_and_east_asian,
+        auto_space_east_asian_and_numbers,
+        bidirectional,
+        adjust_right_indent,
+        snap_to_grid,
+        spacing,
+        indent,
+        contextual_spacing,
+        mirror_indents,
+        suppress_overlapping,
+        alignment,
+        text_direction,
+        text_alignment,
+        textbox_tight_wrap,
+        outline_level,
+        div_id,
+        conditional_formatting,
+    )
+}
+
+fn run_property_names(run_properties: &RunProperties) -> Vec<&'static str> {
+    set_fields!(
+        run_properties,
+        style,
+        fonts,
+        bold,
+        complex_script_bold,
+        italic,
+        complex_script_italic,
+        all_capitals,
+        all_small_capitals,
+        strikethrough,
+        double_strikethrough,
+        outline,
+        shadow,
+        emboss,
+        imprint,
+        no_proofing,
+        snap_to_grid,
+        vanish,
+        web_hidden,
+        color,
+        spacing,
+        width,
+        kerning,
+        position,
+        font_size,
+        complex_script_font_size,
+        highlight,
+        underline,
+        effect,
+        border,
+        shading,
+        fit_text,
+        vertical_alignment,
+        rtl,
+        complex_script,
+        emphasis_mark,
+        language,
+        east_asian_layout,
+        special_vanish,
+        o_math,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FormattingSource;
+    use crate::{
+        package::Package,
+        styleresolver::StyleResolver,
+        test_util::style,
+        wml::{
+            document::{HpsMeasure, PPr, PPrBase, PPrGeneral, RPr, RPrBase, P, R},
+            styles::{DocDefaults, RPrDefault, StyleType, Styles},
+        },
+    };
+
+    fn package_for_test() -> Package {
+        let mut heading = style("Heading", StyleType::Paragraph, Some("Base"), vec![RPrBase::Bold(true)]);
+        heading.paragraph_properties = Some(PPrGeneral {
+            base: PPrBase {
+                keep_with_next: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        Package {
+            styles: Some(Box::new(Styles {
+                document_defaults: Some(DocDefaults {
+                    run_properties_default: Some(RPrDefault(Some(RPr {
+                        r_pr_bases: vec![RPrBase::FontSize(HpsMeasure::Decimal(22)), RPrBase::Italic(true)],
+                        ..Default::default()
+                    }))),
+                    paragraph_properties_default: None,
+                }),
+                latent_styles: None,
+                styles: vec![
+                    style("Base", StyleType::Paragraph, None, vec![RPrBase::Capitals(true)]),
+                    heading,
+                    style("Emphasis", StyleType::Character, None, vec![RPrBase::Bold(true)]),
+                    style("Plain", StyleType::Character, None, vec![RPrBase::Bold(false)]),
+                    style("Upright", StyleType::Character, None, vec![RPrBase::Italic(false)]),
+                ],
+            })),
+            ..Default::default()
+        }
+    }
+
+    fn paragraph_with_style(style_id: &str) -> P {
+        P {
+            properties: Some(PPr {
+                base: PPrBase {
+                    style: Some(String::from(style_id)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn run_with_properties(r_pr_bases: Vec<RPrBase>) -> R {
+        R {
+            run_properties: Some(RPr {
+                r_pr_bases,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_explain_formatting() {
+        let package = package_for_test();
+        let paragraph = paragraph_with_style("Heading");
+        let run = run_with_properties(vec![RPrBase::FontSize(HpsMeasure::Decimal(28))]);
+        let provenance = package.style_resolver().explain_formatting(&paragraph, &run);
+
+        assert_eq!(
+            provenance.style,
+            package.resolve_style_inheritance(&paragraph, &run).unwrap()
+        );
+        assert_eq!(
+            provenance.run_property_source("font_size"),
+            Some(&FormattingSource::DirectFormatting)
+        );
+        assert_eq!(
+            provenance.run_property_source("italic"),
+            Some(&FormattingSource::DocumentDefaults)
+        );
+        assert_eq!(
+            provenance.run_property_source("all_capitals"),
+            Some(&FormattingSource::Style(String::from("Base")))
+        );
+        assert_eq!(
+            provenance.run_property_source("bold"),
+            Some(&FormattingSource::Style(String::from("Heading")))
+        );
+        assert_eq!(
+            provenance.paragraph_property_source("keep_with_next"),
+            Some(&FormattingSource::Style(String::from("Heading")))
+        );
+        assert_eq!(provenance.run_property_source("vanish"), None);
+        assert!(provenance.to_string().contains("run.font_size: direct formatting\n"));
+    }
+
+    #[test]
+    pub fn test_explain_toggle_properties() {
+        let package = package_for_test();
+        let mut resolver = StyleResolver::new(&package);
+        let paragraph = paragraph_with_style("Heading");
+
+        // The bold of the character style toggles the bold of the paragraph style off
+        let run = run_with_properties(vec![RPrBase::RunStyle(String::from("Emphasis"))]);
+        let provenance = resolver.explain_formatting(&paragraph, &run);
+        assert_eq!(provenance.style.run_properties.bold, Some(false));
+        assert_eq!(
+            provenance.run_property_source("bold"),
+            Some(&FormattingSource::Style(String::from("Emphasis")))
+        );
+
+        // A toggle property set to false leaves the value of the paragraph style unchanged
+        let run = run_with_properties(vec![RPrBase::RunStyle(String::from("Plain"))]);
+        let provenance = resolver.explain_formatting(&paragraph, &run);
+        assert_eq!(provenance.style.run_properties.bold, Some(true));
+        assert_eq!(
+            provenance.run_property_source("bold"),
+            Some(&FormattingSource::Style(String::from("Heading")))
+        );
+
+        // A toggle property set to false replaces the value of the document defaults
+        let run = run_with_properties(vec![RPrBase::RunStyle(String::from("Upright"))]);
+        let provenance = resolver.explain_formatting(&paragraph, &run);
+        assert_eq!(provenance.style.run_properties.italic, Some(false));
+        assert_eq!(
+            provenance.run_property_source("italic"),
+            Some(&FormattingSource::Style(String::from("Upright")))
+        );
+    }
+}
@@ -0,0 +1,820 @@
+use crate::{
+    search::{paragraph_runs, run_inner_content_text, ParagraphMatch, ParagraphText, SearchPattern},
+    visit::{walk_document_mut, walk_paragraph_mut, walk_txbx_content_mut, VisitMut},
+    wml::{
+        document::{
+            BlockLevelElts, ContentRunContent, Document, Markup, PContent, RunInnerContent, RunLevelElts,
+            RunTrackChange, RunTrackChangeChoice, Text, TrackChange, P, R,
+        },
+        drawing::TxbxContent,
+        footnotes::FtnEdn,
+        simpletypes::DateTime,
+    },
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevisionInfo {
+    pub author: String,
+    pub date: Option<DateTime>,
+    pub first_revision_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReplaceOptions {
+    pub track_changes: Option<RevisionInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RunEdit {
+    start: usize,
+    end: usize,
+    insert: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Kept(RunInnerContent),
+    Removed(usize, RunInnerContent),
+}
+
+pub struct Replacer<'p> {
+    pattern: &'p SearchPattern,
+    replacement: String,
+    options: ReplaceOptions,
+    next_revision_id: i32,
+    replacement_count: usize,
+    inside_insertion: bool,
+}
+
+impl<'p> Replacer<'p> {
+    pub fn new(pattern: &'p SearchPattern, replacement: &str, options: ReplaceOptions) -> Self {
+        let next_revision_id = options
+            .track_changes
+            .as_ref()
+            .map(|revision_info| revision_info.first_revision_id)
+            .unwrap_or(0);
+
+        Self {
+            pattern,
+            replacement: String::from(replacement),
+            options,
+            next_revision_id,
+            replacement_count: 0,
+            inside_insertion: false,
+        }
+    }
+
+    pub fn replacement_count(&self) -> usize {
+        self.replacement_count
+    }
+
+    pub fn next_revision_id(&self) -> i32 {
+        self.next_revision_id
+    }
+
+    pub fn replace_in_document(&mut self, document: &mut Document) -> usize {
+        let count_before = self.replacement_count;
+        walk_document_mut(self, document);
+        self.replacement_count - count_before
+    }
+
+    pub fn replace_in_note(&mut self, note: &mut FtnEdn) -> usize {
+        self.replace_in_block_level_elements(&mut note.block_level_elements)
+    }
+
+    pub fn replace_in_txbx_content(&mut self, txbx_content: &mut TxbxContent) -> usize {
+        let count_before = self.replacement_count;
+        walk_txbx_content_mut(self, txbx_content);
+        self.replacement_count - count_before
+    }
+
+    pub fn replace_in_block_level_elements(&mut self, elements: &mut [BlockLevelElts]) -> usize {
+        let count_before = self.replacement_count;
+        for element in elements {
+            self.visit_block_level_element_mut(element);
+        }
+
+        self.replacement_count - count_before
+    }
+
+    pub fn replace_in_paragraph(&mut self, paragraph: &mut P) -> usize {
+        let paragraph_text = ParagraphText::from_paragraph(paragraph);
+        let matches = paragraph_text.search(self.pattern);
+        if matches.is_empty() {
+            return 0;
+        }
+
+        let replacements: Vec<String> = match self.pattern {
+            SearchPattern::Text(_) => vec![self.replacement.clone(); matches.len()],
+            SearchPattern::Regex(regex) => regex
+                .captures_iter(&paragraph_text.text)
+                .filter(|captures| {
+                    captures
+                        .get(0)
+                        .map(|whole| whole.start() < whole.end())
+                        .unwrap_or(false)
+                })
+                .map(|captures| {
+                    let mut replacement = String::new();
+                    captures.expand(&self.replacement, &mut replacement);
+                    replacement
+                })
+                .collect(),
+        };
+
+        self.replace_paragraph_matches(paragraph, matches.into_iter().zip(replacements).collect())
+    }
+
+    pub fn replace_paragraph_matches(
+        &mut self,
+        paragraph: &mut P,
+        replacements: Vec<(ParagraphMatch, String)>,
+    ) -> usize {
+        // Replacing a match which crosses a field character would either drop the field character or move text in or
+        // out of the field, so these matches are left alone
+        let field_character_positions = field_character_positions(paragraph);
+        let replacements: Vec<(ParagraphMatch, String)> = replacements
+            .into_iter()
+            .filter(|(paragraph_match, _)| !crosses_field_character(&field_character_positions, paragraph_match))
+            .collect();
+
+        if replacements.is_empty() {
+            return 0;
+        }
+
+        let run_lengths: Vec<usize> = paragraph_runs(paragraph)
+            .into_iter()
+            .map(|run| run.run_inner_contents.iter().map(content_char_count).sum())
+            .collect();
+
+        let mut edits: HashMap<usize, Vec<RunEdit>> = HashMap::new();
+        for (paragraph_match, replacement) in &replacements {
+            let mut insert = Some(replacement.clone());
+            let matched_run_lengths = run_lengths
+                .iter()
+                .enumerate()
+                .take(paragraph_match.end.run_index + 1)
+                .skip(paragraph_match.start.run_index);
+            for (run_index, run_length) in matched_run_lengths {
+                let start = if run_index == paragraph_match.start.run_index {
+                    paragraph_match.start.char_offset
+                } else {
+                    0
+                };
+                let end = if run_index == paragraph_match.end.run_index {
+                    paragraph_match.end.char_offset
+                } else {
+                    *run_length
+                };
+
+                if start < end {
+                    edits.entry(run_index).or_default().push(RunEdit {
+                        start,
+                        end,
+                        insert: insert.take(),
+                    });
+                }
+            }
+        }
+
+        let mut run_index = 0;
+        self.edit_p_contents(&mut paragraph.contents, &mut run_index, &edits);
+
+        self.replacement_count += replacements.len();
+        replacements.len()
+    }
+
+    // Runs are visited in the same order as search::paragraph_runs lists them
+    fn edit_p_contents(
+        &mut self,
+        contents: &mut Vec<PContent>,
+        run_index: &mut usize,
+        edits: &HashMap<usize, Vec<RunEdit>>,
+    ) {
+        for content in std::mem::take(contents) {
+            match content {
+                PContent::ContentRunContent(run_content) => {
+                    for edited_content in self.edit_content_run_content(*run_content, run_index, edits) {
+                        contents.push(PContent::ContentRunContent(Box::new(edited_content)));
+                    }
+                }
+                PContent::SimpleField(mut simple_field) => {
+                    self.edit_p_contents(&mut simple_field.paragraph_contents, run_index, edits);
+                    contents.push(PContent::SimpleField(simple_field));
+                }
+                PContent::Hyperlink(mut hyperlink) => {
+                    self.edit_p_contents(&mut hyperlink.paragraph_contents, run_index, edits);
+                    contents.push(PContent::Hyperlink(hyperlink));
+                }
+                PContent::SubDocument(_) => contents.push(content),
+            }
+        }
+    }
+
+    fn edit_content_run_content(
+        &mut self,
+        content: ContentRunContent,
+        run_index: &mut usize,
+        edits: &HashMap<usize, Vec<RunEdit>>,
+    ) -> Vec<ContentRunContent> {
+        match content {
+            ContentRunContent::CustomXml(mut custom_xml) => {
+                self.edit_p_contents(&mut custom_xml.paragraph_contents, run_index, edits);
+                vec![ContentRunContent::CustomXml(custom_xml)]
+            }
+            ContentRunContent::SmartTag(mut smart_tag) => {
+                self.edit_p_contents(&mut smart_tag.paragraph_contents, run_index, edits);
+                vec![ContentRunContent::SmartTag(smart_tag)]
+            }
+            ContentRunContent::Sdt(mut sdt) => {
+                if let Some(sdt_content) = &mut sdt.sdt_content {
+                    self.edit_p_contents(&mut sdt_content.p_contents, run_index, edits);
+                }
+                vec![ContentRunContent::Sdt(sdt)]
+            }
+            ContentRunContent::Bidirectional(mut dir) => {
+                self.edit_p_contents(&mut dir.p_contents, run_index, edits);
+                vec![ContentRunContent::Bidirectional(dir)]
+            }
+            ContentRunContent::BidirectionalOverride(mut bdo) => {
+                self.edit_p_contents(&mut bdo.p_contents, run_index, edits);
+                vec![ContentRunContent::BidirectionalOverride(bdo)]
+            }
+            ContentRunContent::Run(run) => {
+                let current_run_index = *run_index;
+                *run_index += 1;
+                match edits.get(&current_run_index) {
+                    Some(run_edits) => self.edit_run(run, run_edits),
+                    None => vec![ContentRunContent::Run(run)],
+                }
+            }
+            ContentRunContent::RunLevelElements(mut element) => {
+                match &mut element {
+                    RunLevelElts::Insert(track_change) | RunLevelElts::MoveTo(track_change) => {
+                        let was_inside_insertion = std::mem::replace(&mut self.inside_insertion, true);
+                        self.edit_track_change(track_change, run_index, edits);
+                        self.inside_insertion = was_inside_insertion;
+                    }
+                    RunLevelElts::Delete(track_change) | RunLevelElts::MoveFrom(track_change) => {
+                        self.edit_track_change(track_change, run_index, edits)
+                    }
+                    _ => (),
+                }
+                vec![ContentRunContent::RunLevelElements(element)]
+            }
+        }
+    }
+
+    fn edit_track_change(
+        &mut self,
+        track_change: &mut RunTrackChange,
+        run_index: &mut usize,
+        edits: &HashMap<usize, Vec<RunEdit>>,
+    ) {
+        for choice in std::mem::take(&mut track_change.choices) {
+            let RunTrackChangeChoice::ContentRunContent(choice_content) = choice;
+            for edited_content in self.edit_content_run_content(choice_content, run_index, edits) {
+                track_change
+                    .choices
+                    .push(RunTrackChangeChoice::ContentRunContent(edited_content));
+            }
+        }
+    }
+
+    fn edit_run(&mut self, run: R, run_edits: &[RunEdit]) -> Vec<ContentRunContent> {
+        let R {
+            run_properties,
+            run_inner_contents,
+            ..
+        } = run.clone();
+        let pieces = split_run_contents(run_inner_contents, run_edits);
+
+        // Runs of a tracked insertion are edited in place rather than nesting revisions in the insertion
+        if self.options.track_changes.is_none() || self.inside_insertion {
+            let mut edited_contents = Vec::new();
+            let mut inserted_edits = Vec::new();
+            for piece in pieces {
+                match piece {
+                    Piece::Kept(content) => edited_contents.push(content),
+                    Piece::Removed(edit_index, _) => {
+                        if !inserted_edits.contains(&edit_index) {
+                            inserted_edits.push(edit_index);
+                            if let Some(insert) = &run_edits[edit_index].insert {
+                                edited_contents.push(RunInnerContent::Text(text_content(insert)));
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Runs left without any content are removed, the replacement lives in the run where the match started
+            if edited_contents.is_empty() {
+                return Vec::new();
+            }
+
+            return vec![ContentRunContent::Run(R {
+                run_inner_contents: merge_adjacent_texts(edited_contents),
+                ..run
+            })];
+        }
+
+        let mut edited_contents = Vec::new();
+        let mut kept_contents = Vec::new();
+        let mut removed_contents = Vec::new();
+        let mut removed_edit_index = None;
+        for piece in pieces {
+            let (edit_index, content) = match piece {
+                Piece::Kept(content) => (None, content),
+                Piece::Removed(edit_index, content) => (Some(edit_index), content),
+            };
+
+            if edit_index != removed_edit_index {
+                if !kept_contents.is_empty() {
+                    edited_contents.push(ContentRunContent::Run(R {
+                        run_inner_contents: std::mem::take(&mut kept_contents),
+                        ..run.clone()
+                    }));
+                }
+
+                if let Some(removed_index) = removed_edit_index {
+                    self.push_tracked_edit(
+                        &run,
+                        std::mem::take(&mut removed_contents),
+                        run_edits[removed_index].insert.as_ref(),
+                        &mut edited_contents,
+                    );
+                }
+
+                removed_edit_index = edit_index;
+            }
+
+            match edit_index {
+                Some(_) => removed_contents.push(to_deleted_content(content)),
+                None => kept_contents.push(content),
+            }
+        }
+
+        if let Some(removed_index) = removed_edit_index {
+            self.push_tracked_edit(
+                &run,
+                removed_contents,
+                run_edits[removed_index].insert.as_ref(),
+                &mut edited_contents,
+            );
+        }
+
+        if !kept_contents.is_empty() {
+            edited_contents.push(ContentRunContent::Run(R {
+                run_inner_contents: kept_contents,
+                run_properties,
+                ..run
+            }));
+        }
+
+        edited_contents
+    }
+
+    fn push_tracked_edit(
+        &mut self,
+        run: &R,
+        removed_contents: Vec<RunInnerContent>,
+        insert: Option<&String>,
+        edited_contents: &mut Vec<ContentRunContent>,
+    ) {
+        let deleted_run = R {
+            run_inner_contents: removed_contents,
+            ..run.clone()
+        };
+        let deletion = RunLevelElts::Delete(self.new_run_track_change(deleted_run));
+        edited_contents.push(ContentRunContent::RunLevelElements(deletion));
+
+        if let Some(insert) = insert {
+            let inserted_run = R {
+                run_inner_contents: vec![RunInnerContent::Text(text_content(insert))],
+                ..run.clone()
+            };
+            let insertion = RunLevelElts::Insert(self.new_run_track_change(inserted_run));
+            edited_contents.push(ContentRunContent::RunLevelElements(insertion));
+        }
+    }
+
+    fn new_run_track_change(&mut self, run: R) -> RunTrackChange {
+        let revision_info = self.options.track_changes.clone().unwrap_or_else(|| RevisionInfo {
+            author: String::new(),
+            date: None,
+            first_revision_id: 0,
+        });
+
+        let id = self.next_revision_id;
+        self.next_revision_id += 1;
+
+        RunTrackChange {
+            base: TrackChange {
+                base: Markup { id },
+                author: revision_info.author,
+                date: revision_info.date,
+            },
+            choices: vec![RunTrackChangeChoice::ContentRunContent(ContentRunContent::Run(run))],
+        }
+    }
+}
+
+impl<'p> VisitMut for Replacer<'p> {
+    fn visit_paragraph_mut(&mut self, paragraph: &mut P) {
+        self.replace_in_paragraph(paragraph);
+
+        // Reaches the paragraphs of the text boxes in the drawings of the paragraph
+        walk_paragraph_mut(self, paragraph);
+    }
+}
+
+fn content_char_count(content: &RunInnerContent) -> usize {
+    run_inner_content_text(content)
+        .map(|text| text.chars().count())
+        .unwrap_or(0)
+}
+
+// The run index and character offset of every field character and field instruction of the paragraph
+pub(crate) fn field_character_positions(paragraph: &P) -> Vec<(usize, usize)> {
+    let mut positions = Vec::new();
+    for (run_index, run) in paragraph_runs(paragraph).into_iter().enumerate() {
+        let mut char_offset = 0;
+        for content in &run.run_inner_contents {
+            match content {
+                RunInnerContent::FieldCharacter(_)
+                | RunInnerContent::InstructionText(_)
+                | RunInnerContent::DeletedInstructionText(_) => positions.push((run_index, char_offset)),
+                _ => char_offset += content_char_count(content),
+            }
+        }
+    }
+
+    positions
+}
+
+pub(crate) fn crosses_field_character(
+    field_character_positions: &[(usize, usize)],
+    paragraph_match: &ParagraphMatch,
+) -> bool {
+    let start = (paragraph_match.start.run_index, paragraph_match.start.char_offset);
+    let end = (paragraph_match.end.run_index, paragraph_match.end.char_offset);
+    field_character_positions
+        .iter()
+        .any(|position| start < *position && *position < end)
+}
+
+fn split_run_contents(contents: Vec<RunInnerContent>, run_edits: &[RunEdit]) -> Vec<Piece> {
+    let edit_index_at = |char_index: usize| {
+        run_edits
+            .iter()
+            .position(|run_edit| run_edit.start <= char_index && char_index < run_edit.end)
+    };
+
+    let mut pieces = Vec::new();
+    let mut char_index = 0;
+    for content in contents {
+        let char_count = content_char_count(&content);
+        if char_count == 0 {
+            // Zero width contents like field characters only belong to an edit if they are strictly inside of it
+            let is_inside_edit = run_edits
+                .iter()
+                .position(|run_edit| run_edit.start < char_index && char_index < run_edit.end);
+            match is_inside_edit {
+                Some(edit_index) => pieces.push(Piece::Removed(edit_index, content)),
+                None => pieces.push(Piece::Kept(content)),
+            }
+            continue;
+        }
+
+        let text = match &content {
+            RunInnerContent::Text(text) => text,
+            _ => {
+                match edit_index_at(char_index) {
+                    Some(edit_index) => pieces.push(Piece::Removed(edit_index, content)),
+                    None => pieces.push(Piece::Kept(content)),
+                }
+                char_index += char_count;
+                continue;
+            }
+        };
+
+        let mut segment = String::new();
+        let mut segment_edit_index = edit_index_at(char_index);
+        for character in text.text.chars() {
+            let current_edit_index = edit_index_at(char_index);
+            if current_edit_index != segment_edit_index {
+                pieces.push(text_piece(segment_edit_index, std::mem::take(&mut segment), text));
+                segment_edit_index = current_edit_index;
+            }
+
+            segment.push(character);
+            char_index += 1;
+        }
+
+        if !segment.is_empty() {
+            pieces.push(text_piece(segment_edit_index, segment, text));
+        }
+    }
+
+    pieces
+}
+
+fn text_piece(edit_index: Option<usize>, segment: String, original: &Text) -> Piece {
+    let content = RunInnerContent::Text(Text {
+        text: segment,
+        xml_space: original.xml_space.clone(),
+    });
+
+    match edit_index {
+        Some(edit_index) => Piece::Removed(edit_index, content),
+        None => Piece::Kept(content),
+    }
+}
+
+fn text_content(text: &str) -> Text {
+    let xml_space = if text.starts_with(' ') || text.ends_with(' ') {
+        Some(String::from("preserve"))
+    } else {
+        None
+    };
+
+    Text {
+        text: String::from(text),
+        xml_space,
+    }
+}
+
+fn merge_adjacent_texts(contents: Vec<RunInnerContent>) -> Vec<RunInnerContent> {
+    let mut merged_contents: Vec<RunInnerContent> = Vec::new();
+    for content in contents {
+        match (merged_contents.last_mut(), content) {
+            (Some(RunInnerContent::Text(last_text)), RunInnerContent::Text(text)) => {
+                let merged_text = text_content(&format!("{}{}", last_text.text, text.text));
+                *last_text = merged_text;
+            }
+            (_, content) => merged_contents.push(content),
+        }
+    }
+
+    merged_contents
+}
+
+fn to_deleted_content(content: RunInnerContent) -> RunInnerContent {
+    match content {
+        RunInnerContent::Text(text) => RunInnerContent::DeletedText(text),
+        RunInnerContent::InstructionText(text) => RunInnerContent::DeletedInstructionText(text),
+        content => content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{text_content, ReplaceOptions, Replacer, RevisionInfo};
+    use crate::{
+        arena::DocumentArena,
+        search::{search, SearchPattern},
+        test_util::{
+            bookmark_start, field_character, formatted_run, instruction, paragraph, paragraph_block, run, text_box_run,
+            text_run,
+        },
+        wml::document::{
+            Body, ContentRunContent, Document, FldCharType, Markup, PContent, RPrBase, RunInnerContent, RunLevelElts,
+            RunTrackChange, RunTrackChangeChoice, Text, TrackChange, P, R,
+        },
+    };
+    use regex::Regex;
+
+    fn paragraph_for_test() -> P {
+        paragraph(vec![
+            formatted_run("Pay within ", Vec::new()),
+            bookmark_start(0, "clause"),
+            formatted_run("thir", vec![RPrBase::Bold(true)]),
+            formatted_run("ty days", Vec::new()),
+            formatted_run(" of receipt.", Vec::new()),
+        ])
+    }
+
+    fn texts(paragraph: &P) -> Vec<String> {
+        paragraph
+            .contents
+            .iter()
+            .map(|content| match content {
+                PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                    ContentRunContent::Run(run) => run
+                        .run_inner_contents
+                        .iter()
+                        .map(|inner_content| match inner_content {
+                            RunInnerContent::Text(text) => text.text.clone(),
+                            _ => String::new(),
+                        })
+                        .collect(),
+                    ContentRunContent::RunLevelElements(RunLevelElts::RangeMarkupElements(_)) => {
+                        String::from("<bookmark>")
+                    }
+                    ContentRunContent::RunLevelElements(RunLevelElts::Delete(_)) => String::from("<del>"),
+                    ContentRunContent::RunLevelElements(RunLevelElts::Insert(_)) => String::from("<ins>"),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_replace_across_runs() {
+        let mut paragraph = paragraph_for_test();
+        let pattern = SearchPattern::Text(String::from("thirty days"));
+        let mut replacer = Replacer::new(&pattern, "60 days", Default::default());
+
+        assert_eq!(replacer.replace_in_paragraph(&mut paragraph), 1);
+        assert_eq!(
+            texts(&paragraph),
+            vec!["Pay within ", "<bookmark>", "60 days", " of receipt."]
+        );
+
+        // The replacement inherits the formatting of the run where the match started
+        match &paragraph.contents[2] {
+            PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                ContentRunContent::Run(run) => assert_eq!(
+                    run.run_properties.as_ref().unwrap().r_pr_bases,
+                    vec![RPrBase::Bold(true)]
+                ),
+                _ => panic!("expected run"),
+            },
+            _ => panic!("expected run"),
+        }
+    }
+
+    #[test]
+    pub fn test_replace_regex_with_captures() {
+        let mut paragraph = paragraph_for_test();
+        let pattern = SearchPattern::Regex(Regex::new(r"(\w+) days").unwrap());
+        let mut replacer = Replacer::new(&pattern, "${1} business days", Default::default());
+
+        assert_eq!(replacer.replace_in_paragraph(&mut paragraph), 1);
+        assert_eq!(
+            texts(&paragraph),
+            vec!["Pay within ", "<bookmark>", "thirty business days", " of receipt."]
+        );
+    }
+
+    #[test]
+    pub fn test_replace_with_tracked_changes() {
+        let mut paragraph = paragraph_for_test();
+        let pattern = SearchPattern::Text(String::from("ty days of"));
+        let options = ReplaceOptions {
+            track_changes: Some(RevisionInfo {
+                author: String::from("Reviewer"),
+                date: None,
+                first_revision_id: 10,
+            }),
+        };
+        let mut replacer = Replacer::new(&pattern, "ty business days from", options);
+
+        assert_eq!(replacer.replace_in_paragraph(&mut paragraph), 1);
+        assert_eq!(
+            texts(&paragraph),
+            vec![
+                "Pay within ",
+                "<bookmark>",
+                "thir",
+                "<del>",
+                "<ins>",
+                "<del>",
+                " receipt."
+            ]
+        );
+        assert_eq!(replacer.next_revision_id(), 13);
+
+        match &paragraph.contents[4] {
+            PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                ContentRunContent::RunLevelElements(RunLevelElts::Insert(insertion)) => {
+                    assert_eq!(insertion.base.author, "Reviewer");
+                    let RunTrackChangeChoice::ContentRunContent(inserted_content) = &insertion.choices[0];
+                    match inserted_content {
+                        ContentRunContent::Run(inserted_run) => assert_eq!(
+                            inserted_run.run_inner_contents,
+                            vec![RunInnerContent::Text(Text {
+                                text: String::from("ty business days from"),
+                                xml_space: None,
+                            })]
+                        ),
+                        _ => panic!("expected run"),
+                    }
+                }
+                _ => panic!("expected insertion"),
+            },
+            _ => panic!("expected insertion"),
+        }
+    }
+
+    #[test]
+    pub fn test_replace_skips_matches_across_fields() {
+        let text = |text: &str| RunInnerContent::Text(text_content(text));
+
+        let run_inner_contents = vec![
+            text("Page "),
+            field_character(FldCharType::Begin),
+            instruction(" PAGE "),
+            field_character(FldCharType::Separate),
+            text("3"),
+            field_character(FldCharType::End),
+            text(" of 5"),
+        ];
+        let mut paragraph = paragraph(vec![run(run_inner_contents.clone())]);
+
+        let pattern = SearchPattern::Text(String::from("Page 3"));
+        let mut replacer = Replacer::new(&pattern, "Sheet 4", Default::default());
+        assert_eq!(replacer.replace_in_paragraph(&mut paragraph), 0);
+
+        let pattern = SearchPattern::Text(String::from("Page"));
+        let mut replacer = Replacer::new(&pattern, "Sheet", Default::default());
+        assert_eq!(replacer.replace_in_paragraph(&mut paragraph), 1);
+
+        let mut expected_contents = run_inner_contents;
+        expected_contents[0] = text("Sheet ");
+        match &paragraph.contents[0] {
+            PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                ContentRunContent::Run(run) => assert_eq!(run.run_inner_contents, expected_contents),
+                _ => panic!("expected run"),
+            },
+            _ => panic!("expected run"),
+        }
+    }
+
+    #[test]
+    pub fn test_replace_agrees_with_search_in_text_boxes() {
+        let mut document = Document {
+            body: Some(Body {
+                block_level_elements: vec![paragraph_block(paragraph(vec![
+                    text_run("Pay the lessor"),
+                    text_box_run(vec![paragraph(vec![text_run("Ask the lessor")])]),
+                ]))],
+                section_properties: None,
+            }),
+            ..Default::default()
+        };
+        let pattern = SearchPattern::Text(String::from("lessor"));
+        let hit_count = search(&DocumentArena::new(document.clone()), &pattern).len();
+        assert_eq!(hit_count, 2);
+
+        let mut replacer = Replacer::new(&pattern, "landlord", Default::default());
+        assert_eq!(replacer.replace_in_document(&mut document), hit_count);
+
+        let arena = DocumentArena::new(document);
+        assert!(search(&arena, &pattern).is_empty());
+        assert_eq!(search(&arena, &SearchPattern::Text(String::from("landlord"))).len(), 2);
+    }
+
+    #[test]
+    pub fn test_replace_in_tracked_insertion() {
+        let insertion = RunTrackChange {
+            base: TrackChange {
+                base: Markup { id: 1 },
+                author: String::from("Author"),
+                date: None,
+            },
+            choices: vec![RunTrackChangeChoice::ContentRunContent(ContentRunContent::Run(R {
+                run_inner_contents: vec![RunInnerContent::Text(text_content("thirty days"))],
+                ..Default::default()
+            }))],
+        };
+        let mut paragraph = paragraph(vec![
+            text_run("Pay within "),
+            PContent::ContentRunContent(Box::new(ContentRunContent::RunLevelElements(RunLevelElts::Insert(
+                insertion,
+            )))),
+        ]);
+        let options = ReplaceOptions {
+            track_changes: Some(RevisionInfo {
+                author: String::from("Reviewer"),
+                date: None,
+                first_revision_id: 10,
+            }),
+        };
+        let pattern = SearchPattern::Text(String::from("thirty"));
+        let mut replacer = Replacer::new(&pattern, "sixty", options);
+
+        // The inserted text is changed in the existing insertion without a revision of its own
+        assert_eq!(replacer.replace_in_paragraph(&mut paragraph), 1);
+        assert_eq!(replacer.next_revision_id(), 10);
+        assert_eq!(paragraph.contents.len(), 2);
+        match &paragraph.contents[1] {
+            PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                ContentRunContent::RunLevelElements(RunLevelElts::Insert(insertion)) => {
+                    assert_eq!(insertion.base.base.id, 1);
+                    assert_eq!(
+                        insertion.choices,
+                        vec![RunTrackChangeChoice::ContentRunContent(ContentRunContent::Run(R {
+                            run_inner_contents: vec![RunInnerContent::Text(text_content("sixty days"))],
+                            ..Default::default()
+                        }))]
+                    );
+                }
+                _ => panic!("expected insertion"),
+            },
+            _ => panic!("expected insertion"),
+        }
+    }
+}
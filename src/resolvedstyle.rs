@@ -157,6 +157,9 @@ impl RunProperties {
         }
     }
 
+    // Combines the styles of two levels of the style hierarchy, e.g. a paragraph style with a character style. Toggle
+    // properties set in the other style toggle the value of this one (ECMA-376 Part 1, 17.7.3), every other property
+    // is simply overridden.
     pub fn update_with_style_on_another_level(self, other: Self) -> Self {
         Self {
             bold: update_or_toggle_on_off(self.bold, other.bold),
@@ -171,14 +174,7 @@ impl RunProperties {
             shadow: update_or_toggle_on_off(self.shadow, other.shadow),
             emboss: update_or_toggle_on_off(self.emboss, other.emboss),
             imprint: update_or_toggle_on_off(self.imprint, other.imprint),
-            no_proofing: update_or_toggle_on_off(self.no_proofing, other.no_proofing),
-            snap_to_grid: update_or_toggle_on_off(self.snap_to_grid, other.snap_to_grid),
             vanish: update_or_toggle_on_off(self.vanish, other.vanish),
-            web_hidden: update_or_toggle_on_off(self.web_hidden, other.web_hidden),
-            rtl: update_or_toggle_on_off(self.rtl, other.rtl),
-            complex_script: update_or_toggle_on_off(self.complex_script, self.complex_script),
-            special_vanish: update_or_toggle_on_off(self.special_vanish, other.special_vanish),
-            o_math: update_or_toggle_on_off(self.o_math, other.o_math),
             ..self.update_with(other)
         }
     }
@@ -252,6 +248,7 @@ impl ResolvedStyle {
     }
 }
 
+// A toggle property set to true flips the inherited value, while false leaves it unchanged
 fn update_or_toggle_on_off(lhs: Option<OnOff>, rhs: Option<OnOff>) -> Option<OnOff> {
     match (lhs, rhs) {
         (Some(lhs), Some(rhs)) => Some(lhs ^ rhs),
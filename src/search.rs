@@ -0,0 +1,256 @@
+use crate::{
+    arena::{DocumentArena, NodeId, NodeRef},
+    visit::Visit,
+    wml::document::{RunInnerContent, P, R},
+};
+use regex::Regex;
+
+#[derive(Debug, Clone)]
+pub enum SearchPattern {
+    Text(String),
+    Regex(Regex),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RunPosition {
+    pub run_index: usize,
+    pub char_offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParagraphMatch {
+    pub start: RunPosition,
+    pub end: RunPosition,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub paragraph_id: NodeId,
+    pub paragraph_path: Vec<usize>,
+    pub paragraph_match: ParagraphMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParagraphText {
+    pub text: String,
+    // Byte offset in the text and run position of every character
+    char_positions: Vec<(usize, RunPosition)>,
+}
+
+impl ParagraphText {
+    pub fn from_paragraph(paragraph: &P) -> Self {
+        let mut instance: Self = Default::default();
+        for (run_index, run) in paragraph_runs(paragraph).into_iter().enumerate() {
+            let mut char_offset = 0;
+            for inner_content in &run.run_inner_contents {
+                if let Some(content_text) = run_inner_content_text(inner_content) {
+                    for character in content_text.chars() {
+                        instance
+                            .char_positions
+                            .push((instance.text.len(), RunPosition { run_index, char_offset }));
+                        instance.text.push(character);
+                        char_offset += 1;
+                    }
+                }
+            }
+        }
+
+        instance
+    }
+
+    pub fn search(&self, pattern: &SearchPattern) -> Vec<ParagraphMatch> {
+        let byte_ranges: Vec<(usize, usize)> = match pattern {
+            SearchPattern::Text(text) if text.is_empty() => Vec::new(),
+            SearchPattern::Text(text) => self
+                .text
+                .match_indices(text.as_str())
+                .map(|(start, matched_text)| (start, start + matched_text.len()))
+                .collect(),
+            SearchPattern::Regex(regex) => regex
+                .find_iter(&self.text)
+                .map(|regex_match| (regex_match.start(), regex_match.end()))
+                .collect(),
+        };
+
+        byte_ranges
+            .into_iter()
+            .filter(|(start, end)| start < end)
+            .filter_map(|(start, end)| self.paragraph_match(start, end))
+            .collect()
+    }
+
+    pub fn position_of_char(&self, char_index: usize) -> Option<RunPosition> {
+        self.char_positions.get(char_index).map(|(_, position)| *position)
+    }
+
+    pub fn paragraph_match(&self, start_byte: usize, end_byte: usize) -> Option<ParagraphMatch> {
+        let start_index = self
+            .char_positions
+            .binary_search_by_key(&start_byte, |(byte_offset, _)| *byte_offset)
+            .ok()?;
+        let last_index = self
+            .char_positions
+            .binary_search_by_key(&end_byte, |(byte_offset, _)| *byte_offset)
+            .unwrap_or_else(|insert_index| insert_index)
+            .checked_sub(1)?;
+
+        let start = self.char_positions[start_index].1;
+        let last = self.char_positions[last_index].1;
+
+        // The end position is exclusive and stays in the run of the last matched character
+        Some(ParagraphMatch {
+            start,
+            end: RunPosition {
+                run_index: last.run_index,
+                char_offset: last.char_offset + 1,
+            },
+            text: String::from(&self.text[start_byte..end_byte]),
+        })
+    }
+}
+
+pub fn run_inner_content_text(inner_content: &RunInnerContent) -> Option<&str> {
+    match inner_content {
+        RunInnerContent::Text(text) => Some(&text.text),
+        RunInnerContent::Tab | RunInnerContent::PositionTab(_) => Some("\t"),
+        RunInnerContent::Break(_) | RunInnerContent::CarriageReturn => Some("\n"),
+        RunInnerContent::NonBreakingHyphen => Some("-"),
+        _ => None,
+    }
+}
+
+pub fn paragraph_runs(paragraph: &P) -> Vec<&R> {
+    struct RunCollector<'a> {
+        runs: Vec<&'a R>,
+    }
+
+    impl<'a> Visit<'a> for RunCollector<'a> {
+        fn visit_run(&mut self, run: &'a R) {
+            // Ruby runs are annotations of the run containing them, not part of the paragraph text
+            self.runs.push(run);
+        }
+    }
+
+    let mut collector = RunCollector { runs: Vec::new() };
+    collector.visit_paragraph(paragraph);
+    collector.runs
+}
+
+pub fn search_paragraph(paragraph: &P, pattern: &SearchPattern) -> Vec<ParagraphMatch> {
+    ParagraphText::from_paragraph(paragraph).search(pattern)
+}
+
+pub fn search(arena: &DocumentArena, pattern: &SearchPattern) -> Vec<SearchHit> {
+    arena
+        .nodes()
+        .into_iter()
+        .filter_map(|(node_id, node)| match node {
+            NodeRef::Paragraph(paragraph) => Some((node_id, paragraph)),
+            _ => None,
+        })
+        .flat_map(|(paragraph_id, paragraph)| {
+            search_paragraph(paragraph, pattern)
+                .into_iter()
+                .map(move |paragraph_match| (paragraph_id, paragraph_match))
+        })
+        .map(|(paragraph_id, paragraph_match)| SearchHit {
+            paragraph_id,
+            paragraph_path: arena.path(paragraph_id),
+            paragraph_match,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search, search_paragraph, RunPosition, SearchPattern};
+    use crate::{
+        arena::DocumentArena,
+        test_util::{package_with_paragraphs, paragraph, paragraph_block, text_run},
+        wml::{
+            document::{ContentRunContent, Hyperlink, PContent, ProofErr, ProofErrType, RunLevelElts, P},
+            drawing::TxbxContent,
+        },
+    };
+    use regex::Regex;
+
+    fn split_paragraph() -> P {
+        paragraph(vec![
+            text_run("The lessee shall "),
+            PContent::ContentRunContent(Box::new(ContentRunContent::RunLevelElements(RunLevelElts::ProofError(
+                ProofErr {
+                    error_type: ProofErrType::SpellingStart,
+                },
+            )))),
+            text_run("indem"),
+            text_run("nify"),
+            PContent::Hyperlink(Hyperlink {
+                paragraph_contents: vec![text_run(" the lessor")],
+                ..Default::default()
+            }),
+            text_run("."),
+        ])
+    }
+
+    #[test]
+    pub fn test_search_across_runs() {
+        let matches = search_paragraph(&split_paragraph(), &SearchPattern::Text(String::from("indemnify the")));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].start,
+            RunPosition {
+                run_index: 1,
+                char_offset: 0
+            }
+        );
+        assert_eq!(
+            matches[0].end,
+            RunPosition {
+                run_index: 3,
+                char_offset: 4
+            }
+        );
+        assert_eq!(matches[0].text, "indemnify the");
+    }
+
+    #[test]
+    pub fn test_search_regex() {
+        let pattern = SearchPattern::Regex(Regex::new(r"(?i)LESS(EE|OR)").unwrap());
+        let matches = search_paragraph(&split_paragraph(), &pattern);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            matches[1].start,
+            RunPosition {
+                run_index: 3,
+                char_offset: 5
+            }
+        );
+        assert_eq!(
+            matches[1].end,
+            RunPosition {
+                run_index: 3,
+                char_offset: 11
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_search_document_and_textbox() {
+        let package = package_with_paragraphs(vec![paragraph(vec![text_run("Nothing here")]), split_paragraph()]);
+        let document = *package.main_document.unwrap();
+
+        let pattern = SearchPattern::Text(String::from("lessor"));
+        let arena = DocumentArena::new(document);
+        let hits = search(&arena, &pattern);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].paragraph_path, vec![0, 1]);
+        assert_eq!(arena.node_at_path(&hits[0].paragraph_path), Some(hits[0].paragraph_id));
+
+        let textbox_content = TxbxContent {
+            block_level_elements: vec![paragraph_block(split_paragraph())],
+        };
+        let textbox_arena = DocumentArena::from_txbx_content(textbox_content);
+        assert_eq!(search(&textbox_arena, &pattern).len(), 1);
+    }
+}
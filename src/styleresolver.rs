@@ -0,0 +1,1007 @@
+use crate::{
+    builtinstyle,
+    export::{row_cells, table_rows},
+    package::Package,
+    resolvedstyle::{ParagraphProperties, ResolvedStyle, RunProperties},
+    tablestyle::{self, ResolvedTableStyle, TableCellPosition},
+    wml::{
+        document::{PPr, RPr, RPrBase, P, R},
+        numbering::{Lvl, Numbering},
+        simpletypes::DecimalNumber,
+        styles::{Style, StyleType, Styles, TblStyleOverrideType},
+        table::Tbl,
+    },
+};
+use log::error;
+use msoffice_shared::update::{update_options, Update};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasedOnCycleError {
+    // The chain of style ids, starting and ending with the same style
+    pub style_ids: Vec<String>,
+}
+
+impl Display for BasedOnCycleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Styles are based on each other: {}", self.style_ids.join(" -> "))
+    }
+}
+
+impl Error for BasedOnCycleError {}
+
+// The numbering instance (numId) and level (ilvl) a paragraph is numbered with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParagraphNumbering {
+    pub numbering_id: DecimalNumber,
+    pub level: DecimalNumber,
+}
+
+// Resolves styles of a package with the styles indexed by id. Resolved styles are cached, so the resolver should be
+// kept around while a document is processed.
+pub struct StyleResolver<'a> {
+    styles: HashMap<&'a str, &'a Style>,
+    numbering: Option<&'a Numbering>,
+    package_styles: Option<&'a Styles>,
+    document_default_style: Option<ResolvedStyle>,
+    default_paragraph_style: Option<ResolvedStyle>,
+    default_character_style: Option<ResolvedStyle>,
+    default_paragraph_style_id: Option<String>,
+    default_character_style_id: Option<String>,
+    default_table_style_id: Option<String>,
+    resolved_styles: HashMap<String, Option<ResolvedStyle>>,
+    combined_styles: HashMap<(Option<String>, Option<String>), Option<ResolvedStyle>>,
+    table_styles: HashMap<(String, Vec<TblStyleOverrideType>), Option<ResolvedTableStyle>>,
+}
+
+impl<'a> StyleResolver<'a> {
+    pub fn new(package: &'a Package) -> Self {
+        let mut styles = HashMap::new();
+        let mut default_paragraph_style_id = None;
+        let mut default_character_style_id = None;
+        let mut default_table_style_id = None;
+        for style in package.styles.iter().flat_map(|styles| styles.styles.iter()) {
+            if let Some(style_id) = &style.style_id {
+                styles.entry(style_id.as_str()).or_insert(style);
+
+                let default_style_id = match (style.style_type, style.is_default) {
+                    (Some(StyleType::Paragraph), Some(true)) => &mut default_paragraph_style_id,
+                    (Some(StyleType::Character), Some(true)) => &mut default_character_style_id,
+                    (Some(StyleType::Table), Some(true)) => &mut default_table_style_id,
+                    _ => continue,
+                };
+                default_style_id.get_or_insert_with(|| style_id.clone());
+            }
+        }
+
+        Self {
+            styles,
+            numbering: package.numbering.as_ref(),
+            package_styles: package.styles.as_deref(),
+            document_default_style: package.resolve_document_default_style(),
+            default_paragraph_style: package.resolve_default_style(StyleType::Paragraph),
+            default_character_style: package.resolve_default_style(StyleType::Character),
+            default_paragraph_style_id,
+            default_character_style_id,
+            default_table_style_id,
+            resolved_styles: HashMap::new(),
+            combined_styles: HashMap::new(),
+            table_styles: HashMap::new(),
+        }
+    }
+
+    // Styles missing from the document fall back to the standard definitions of Word's built-in styles
+    pub fn style<T: AsRef<str>>(&self, style_id: T) -> Option<&'a Style> {
+        let style_id = style_id.as_ref();
+        self.styles
+            .get(style_id)
+            .copied()
+            .or_else(|| builtinstyle::standard_builtin_style(style_id))
+    }
+
+    // Returns the style and its ancestors, starting with the style itself. Missing styles end the chain.
+    pub fn style_hierarchy<T: AsRef<str>>(&self, style_id: T) -> Result<Vec<&'a Style>, BasedOnCycleError> {
+        let (hierarchy, repeated_style_id) = self.walk_hierarchy(style_id.as_ref());
+        match repeated_style_id {
+            Some(repeated_style_id) => {
+                let mut style_ids: Vec<String> = hierarchy
+                    .iter()
+                    .filter_map(|style| style.style_id.clone())
+                    .skip_while(|style_id| *style_id != repeated_style_id)
+                    .collect();
+                style_ids.push(repeated_style_id);
+                Err(BasedOnCycleError { style_ids })
+            }
+            None => Ok(hierarchy),
+        }
+    }
+
+    pub fn resolve_document_default_style(&self) -> Option<&ResolvedStyle> {
+        self.document_default_style.as_ref()
+    }
+
+    pub fn default_paragraph_style_id(&self) -> Option<&str> {
+        self.default_paragraph_style_id.as_deref()
+    }
+
+    pub fn default_character_style_id(&self) -> Option<&str> {
+        self.default_character_style_id.as_deref()
+    }
+
+    pub fn resolve_default_style(&self, style_type: StyleType) -> Option<&ResolvedStyle> {
+        match style_type {
+            StyleType::Paragraph => self.default_paragraph_style.as_ref(),
+            StyleType::Character => self.default_character_style.as_ref(),
+            _ => None,
+        }
+    }
+
+    // Styles in a based_on cycle are resolved up to the first repeated style
+    pub fn resolve_style_with_id<T: AsRef<str>>(&mut self, style_id: T) -> Option<ResolvedStyle> {
+        let style_id = style_id.as_ref();
+        if let Some(resolved_style) = self.resolved_styles.get(style_id) {
+            return resolved_style.clone();
+        }
+
+        let (hierarchy, repeated_style_id) = self.walk_hierarchy(style_id);
+        if hierarchy.is_empty() {
+            self.resolved_styles.insert(String::from(style_id), None);
+            return None;
+        }
+
+        // Ancestors resolved within a cycle depend on where the chain was entered, so they can't be cached
+        let is_cyclic = repeated_style_id.is_some();
+        if let Some(repeated_style_id) = repeated_style_id {
+            error!("Style {} is based on itself through {}", style_id, repeated_style_id);
+        }
+
+        let mut resolved_style: ResolvedStyle = Default::default();
+        let mut unresolved_count = hierarchy.len();
+        if !is_cyclic {
+            for (index, ancestor) in hierarchy.iter().enumerate().skip(1) {
+                let cached_style = ancestor
+                    .style_id
+                    .as_ref()
+                    .and_then(|ancestor_id| self.resolved_styles.get(ancestor_id.as_str()))
+                    .cloned()
+                    .flatten();
+                if let Some(cached_style) = cached_style {
+                    resolved_style = cached_style;
+                    unresolved_count = index;
+                    break;
+                }
+            }
+        }
+
+        for style in hierarchy[..unresolved_count].iter().rev() {
+            resolved_style = apply_style(resolved_style, style);
+            if let (Some(resolved_id), false) = (&style.style_id, is_cyclic) {
+                self.resolved_styles
+                    .insert(resolved_id.clone(), Some(resolved_style.clone()));
+            }
+        }
+
+        if is_cyclic {
+            self.resolved_styles
+                .insert(String::from(style_id), Some(resolved_style.clone()));
+        }
+
+        Some(resolved_style)
+    }
+
+    // The counterpart of a linked paragraph or character style. Links between styles of other types or to missing
+    // styles are ignored.
+    pub fn linked_style<T: AsRef<str>>(&self, style_id: T) -> Option<&'a Style> {
+        let style = self.style(style_id)?;
+        let linked_style = self.style(style.link.as_ref()?)?;
+        match (style.style_type, linked_style.style_type) {
+            (Some(StyleType::Paragraph), Some(StyleType::Character))
+            | (Some(StyleType::Character), Some(StyleType::Paragraph)) => Some(linked_style),
+            _ => None,
+        }
+    }
+
+    // Word substitutes a linked style when a style is used as a different type, e.g. a paragraph style on a run
+    pub fn style_id_for_type<T: AsRef<str>>(&self, style_id: T, style_type: StyleType) -> String {
+        let style_id = style_id.as_ref();
+        let is_other_type = self
+            .style(style_id)
+            .and_then(|style| style.style_type)
+            .is_some_and(|used_type| used_type != style_type);
+
+        self.linked_style(style_id)
+            .filter(|linked_style| is_other_type && linked_style.style_type == Some(style_type))
+            .and_then(|linked_style| linked_style.style_id.clone())
+            .unwrap_or_else(|| String::from(style_id))
+    }
+
+    pub fn paragraph_style_id(&self, paragraph: &P) -> Option<String> {
+        paragraph
+            .properties
+            .as_ref()
+            .and_then(|p_pr| p_pr.base.style.as_ref())
+            .map(|style_id| self.style_id_for_type(style_id, StyleType::Paragraph))
+    }
+
+    pub fn run_style_id(&self, run: &R) -> Option<String> {
+        run.run_properties
+            .as_ref()
+            .and_then(run_style)
+            .map(|style_id| self.style_id_for_type(style_id, StyleType::Character))
+    }
+
+    pub fn resolve_paragraph_style(&mut self, paragraph_properties: &PPr) -> Option<ResolvedStyle> {
+        let style_id = paragraph_properties.base.style.as_ref()?;
+        let style_id = self.style_id_for_type(style_id, StyleType::Paragraph);
+        self.resolve_style_with_id(style_id)
+    }
+
+    pub fn resolve_run_style(&mut self, run_properties: &RPr) -> Option<ResolvedStyle> {
+        let style_id = self.style_id_for_type(run_style(run_properties)?, StyleType::Character);
+        self.resolve_style_with_id(style_id)
+    }
+
+    // The document defaults, the numbering, the paragraph and run styles and the direct formatting
+    pub fn resolve_style_inheritance(&mut self, paragraph: &P, run: &R) -> Option<ResolvedStyle> {
+        let numbering_style = self.resolve_numbering_style(paragraph);
+        let combined_style = self.resolve_combined_style(self.paragraph_style_id(paragraph), self.run_style_id(run));
+
+        apply_document_defaults(
+            self.document_default_style.clone(),
+            merge_styles(numbering_style, combined_style),
+        )
+        .map(|resolved_style| apply_direct_formatting(resolved_style, paragraph, run))
+    }
+
+    // Direct numbering properties override the ones of the paragraph style and a numbering id of 0 removes the
+    // numbering. Without a level the one linked to the paragraph style is used, or else the first one.
+    pub fn resolve_numbering(&mut self, paragraph: &P) -> Option<ParagraphNumbering> {
+        let style_id = self
+            .paragraph_style_id(paragraph)
+            .or_else(|| self.default_paragraph_style_id.clone());
+        let style_numbering_properties = style_id
+            .as_ref()
+            .and_then(|style_id| self.resolve_style_with_id(style_id))
+            .and_then(|resolved_style| resolved_style.paragraph_properties.numbering_properties.clone());
+        let direct_numbering_properties = paragraph
+            .properties
+            .as_ref()
+            .and_then(|p_pr| p_pr.base.numbering_properties.clone());
+
+        let numbering_properties = update_options(style_numbering_properties, direct_numbering_properties)?;
+        let numbering_id = numbering_properties
+            .numbering_id
+            .filter(|numbering_id| *numbering_id != 0)?;
+        let level = numbering_properties
+            .indent_level
+            .or_else(|| {
+                let style_id = style_id?;
+                self.numbering?
+                    .linked_abstract_numbering(numbering_id, self.package_styles)?
+                    .levels
+                    .iter()
+                    .find(|lvl| lvl.paragraph_style.as_ref() == Some(&style_id))
+                    .map(|lvl| lvl.level)
+            })
+            .unwrap_or(0);
+
+        Some(ParagraphNumbering { numbering_id, level })
+    }
+
+    pub fn resolve_numbering_level(&mut self, paragraph: &P) -> Option<&'a Lvl> {
+        let paragraph_numbering = self.resolve_numbering(paragraph)?;
+        self.numbering?.find_level(
+            paragraph_numbering.numbering_id,
+            paragraph_numbering.level,
+            self.package_styles,
+        )
+    }
+
+    // The table style properties applying to a table, with the conditional formats layered in order of precedence
+    pub fn resolve_table_style<T: AsRef<str>>(
+        &mut self,
+        style_id: T,
+        conditional_formats: &[TblStyleOverrideType],
+    ) -> Option<ResolvedTableStyle> {
+        let key = (String::from(style_id.as_ref()), conditional_formats.to_vec());
+        if let Some(table_style) = self.table_styles.get(&key) {
+            return table_style.clone();
+        }
+
+        let (hierarchy, repeated_style_id) = self.walk_hierarchy(&key.0);
+        if let Some(repeated_style_id) = repeated_style_id {
+            error!("Style {} is based on itself through {}", key.0, repeated_style_id);
+        }
+
+        let table_style = if hierarchy.is_empty() {
+            None
+        } else {
+            Some(ResolvedTableStyle::from_style_hierarchy(
+                &hierarchy,
+                conditional_formats,
+            ))
+        };
+
+        self.table_styles.insert(key, table_style.clone());
+        table_style
+    }
+
+    // The table style of a cell, including the direct formatting of the table, row and cell. Returns None if there's no
+    // such cell.
+    pub fn resolve_table_cell_style(
+        &mut self,
+        table: &Tbl,
+        row_index: usize,
+        cell_index: usize,
+    ) -> Option<ResolvedTableStyle> {
+        let rows = table_rows(table);
+        let row = *rows.get(row_index)?;
+        let cell = *row_cells(row).get(cell_index)?;
+
+        let (style_id, conditional_formats) =
+            self.resolve_table_cell_conditional_formats(table, row_index, cell_index)?;
+        let table_style = style_id
+            .and_then(|style_id| self.resolve_table_style(style_id, &conditional_formats))
+            .unwrap_or_default();
+
+        Some(ResolvedTableStyle {
+            table_properties: table_style.table_properties.update_with(table.properties.base.clone()),
+            row_properties: match &row.properties {
+                Some(tr_pr) => table_style.row_properties.update_with(tr_pr.base.clone()),
+                None => table_style.row_properties,
+            },
+            cell_properties: match &cell.properties {
+                Some(tc_pr) => table_style.cell_properties.update_with(tc_pr.base.base.clone()),
+                None => table_style.cell_properties,
+            },
+            style: table_style.style,
+        })
+    }
+
+    // The id of the table style and the conditional formats applying to a cell. The conditional formats are taken
+    // from the cnfStyle of the row or cell if present, otherwise from the table look and the cell position. Returns
+    // None if there's no such cell.
+    pub fn resolve_table_cell_conditional_formats(
+        &mut self,
+        table: &Tbl,
+        row_index: usize,
+        cell_index: usize,
+    ) -> Option<(Option<String>, Vec<TblStyleOverrideType>)> {
+        let rows = table_rows(table);
+        let row = *rows.get(row_index)?;
+        let cell = *row_cells(row).get(cell_index)?;
+
+        let style_id = table
+            .properties
+            .base
+            .style
+            .clone()
+            .or_else(|| self.default_table_style_id.clone());
+
+        let conditional_formats = match tablestyle::cell_cnf(row, cell) {
+            Some(cnf) => tablestyle::cnf_conditional_formats(&cnf),
+            None => {
+                let whole_table_style = style_id
+                    .as_ref()
+                    .and_then(|style_id| self.resolve_table_style(style_id, &[TblStyleOverrideType::WholeTable]))
+                    .unwrap_or_default();
+                let table_properties = whole_table_style
+                    .table_properties
+                    .update_with(table.properties.base.clone());
+
+                let position = TableCellPosition::from_table(table, row_index, cell_index)?;
+                let band_size = |band_size: Option<i32>| band_size.unwrap_or(1).max(1) as usize;
+                tablestyle::conditional_formats(
+                    &table_properties.look.clone().unwrap_or_default(),
+                    &position,
+                    band_size(table_properties.style_row_band_size),
+                    band_size(table_properties.style_column_band_size),
+                )
+            }
+        };
+
+        Some((style_id, conditional_formats))
+    }
+
+    // Like resolve_style_inheritance, with the table style of the containing cell applied between the document
+    // defaults and the paragraph and run styles
+    pub fn resolve_table_cell_style_inheritance(
+        &mut self,
+        table: &Tbl,
+        row_index: usize,
+        cell_index: usize,
+        paragraph: &P,
+        run: &R,
+    ) -> Option<ResolvedStyle> {
+        let table_style = self
+            .resolve_table_cell_style(table, row_index, cell_index)
+            .map(|table_style| table_style.style);
+        let numbering_style = self.resolve_numbering_style(paragraph);
+        let combined_style = self.resolve_combined_style(self.paragraph_style_id(paragraph), self.run_style_id(run));
+
+        let style_hierarchy = merge_styles(merge_styles(table_style, numbering_style), combined_style);
+        apply_document_defaults(self.document_default_style.clone(), style_hierarchy)
+            .map(|resolved_style| apply_direct_formatting(resolved_style, paragraph, run))
+    }
+
+    pub fn resolve_paragraph_properties(&mut self, paragraph: &P) -> ParagraphProperties {
+        self.resolve_layered_paragraph_properties(paragraph, None)
+    }
+
+    pub fn resolve_table_cell_paragraph_properties(
+        &mut self,
+        table: &Tbl,
+        row_index: usize,
+        cell_index: usize,
+        paragraph: &P,
+    ) -> ParagraphProperties {
+        let table_style = self
+            .resolve_table_cell_style(table, row_index, cell_index)
+            .map(|table_style| table_style.style);
+        self.resolve_layered_paragraph_properties(paragraph, table_style)
+    }
+
+    // The document defaults, the table style, the numbering, the paragraph style and the direct formatting
+    fn resolve_layered_paragraph_properties(
+        &mut self,
+        paragraph: &P,
+        table_style: Option<ResolvedStyle>,
+    ) -> ParagraphProperties {
+        let numbering_style = self.resolve_numbering_style(paragraph);
+        let paragraph_style = paragraph
+            .properties
+            .as_ref()
+            .and_then(|p_pr| self.resolve_paragraph_style(p_pr))
+            .or_else(|| self.default_paragraph_style.clone());
+
+        let style_properties = vec![
+            self.document_default_style.clone(),
+            table_style,
+            numbering_style,
+            paragraph_style,
+        ]
+        .into_iter()
+        .flatten()
+        .fold(ParagraphProperties::default(), |properties, resolved_style| {
+            properties.update_with(*resolved_style.paragraph_properties)
+        });
+
+        match &paragraph.properties {
+            Some(p_pr) => style_properties.update_with(p_pr.base.clone()),
+            None => style_properties,
+        }
+    }
+
+    // The paragraph properties of the paragraph's numbering level
+    fn resolve_numbering_style(&mut self, paragraph: &P) -> Option<ResolvedStyle> {
+        let paragraph_properties = self.resolve_numbering_level(paragraph)?.paragraph_properties.as_ref()?;
+        Some(ResolvedStyle::from_paragraph_properties(Box::new(
+            paragraph_properties.base.clone(),
+        )))
+    }
+
+    // The paragraph style and the run style without the document defaults and the direct formatting
+    fn resolve_combined_style(
+        &mut self,
+        paragraph_style_id: Option<String>,
+        run_style_id: Option<String>,
+    ) -> Option<ResolvedStyle> {
+        let key = (paragraph_style_id, run_style_id);
+        if let Some(combined_style) = self.combined_styles.get(&key) {
+            return combined_style.clone();
+        }
+
+        let paragraph_style = key
+            .0
+            .as_ref()
+            .and_then(|style_id| self.resolve_style_with_id(style_id))
+            .or_else(|| self.default_paragraph_style.clone());
+        let run_style = key
+            .1
+            .as_ref()
+            .and_then(|style_id| self.resolve_style_with_id(style_id))
+            .or_else(|| self.default_character_style.clone());
+
+        let calced_style = match (paragraph_style, run_style) {
+            (Some(p_style), Some(r_style)) => Some(p_style.update_with_style_on_another_level(r_style)),
+            (p_style, r_style) => p_style.or(r_style),
+        };
+
+        self.combined_styles.insert(key, calced_style.clone());
+        calced_style
+    }
+
+    // The returned style id is set when the chain runs into a style that is already part of it
+    pub(crate) fn walk_hierarchy(&self, style_id: &str) -> (Vec<&'a Style>, Option<String>) {
+        let mut hierarchy: Vec<&'a Style> = Vec::new();
+        let mut next_style_id = Some(style_id);
+
+        while let Some(current_style_id) = next_style_id {
+            if hierarchy
+                .iter()
+                .any(|style| style.style_id.as_deref() == Some(current_style_id))
+            {
+                return (hierarchy, Some(String::from(current_style_id)));
+            }
+
+            match self.style(current_style_id) {
+                Some(style) => {
+                    hierarchy.push(style);
+                    next_style_id = style.based_on.as_deref();
+                }
+                None => break,
+            }
+        }
+
+        (hierarchy, None)
+    }
+}
+
+fn run_style(run_properties: &RPr) -> Option<&String> {
+    run_properties.r_pr_bases.iter().find_map(|r_pr_base| match r_pr_base {
+        RPrBase::RunStyle(style_id) => Some(style_id),
+        _ => None,
+    })
+}
+
+// Layers two levels of the style hierarchy, so toggle properties of the upper level flip the ones of the lower level
+fn merge_styles(lower_style: Option<ResolvedStyle>, upper_style: Option<ResolvedStyle>) -> Option<ResolvedStyle> {
+    match (lower_style, upper_style) {
+        (Some(lower_style), Some(upper_style)) => Some(lower_style.update_with_style_on_another_level(upper_style)),
+        (lower_style, upper_style) => lower_style.or(upper_style),
+    }
+}
+
+// Toggle properties of the style hierarchy replace the ones of the document defaults instead of flipping them
+fn apply_document_defaults(
+    document_default_style: Option<ResolvedStyle>,
+    style: Option<ResolvedStyle>,
+) -> Option<ResolvedStyle> {
+    match (document_default_style, style) {
+        (Some(document_default_style), Some(style)) => Some(document_default_style.update_with(style)),
+        (document_default_style, style) => document_default_style.or(style),
+    }
+}
+
+fn apply_direct_formatting(resolved_style: ResolvedStyle, paragraph: &P, run: &R) -> ResolvedStyle {
+    let run_style = run
+        .run_properties
+        .as_ref()
+        .map(|r_pr| RunProperties::from_vec(&r_pr.r_pr_bases));
+
+    match (paragraph.properties.as_ref(), run_style) {
+        (Some(p_style), Some(r_style)) => resolved_style
+            .update_paragraph_with(p_style.base.clone())
+            .update_run_with(r_style),
+        (Some(p_style), None) => resolved_style.update_paragraph_with(p_style.base.clone()),
+        (None, Some(r_style)) => resolved_style.update_run_with(r_style),
+        _ => resolved_style,
+    }
+}
+
+fn apply_style(mut resolved_style: ResolvedStyle, style: &Style) -> ResolvedStyle {
+    if let Some(style_p_pr) = &style.paragraph_properties {
+        *resolved_style.paragraph_properties = resolved_style.paragraph_properties.update_with(style_p_pr.base.clone());
+    }
+
+    if let Some(style_r_pr) = &style.run_properties {
+        let folded_style_r_pr = RunProperties::from_vec(&style_r_pr.r_pr_bases);
+        *resolved_style.run_properties = resolved_style.run_properties.update_with(folded_style_r_pr);
+    }
+
+    resolved_style
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BasedOnCycleError, ParagraphNumbering, StyleResolver};
+    use crate::{
+        test_util::{package_with_styles, style},
+        wml::{
+            document::{
+                ContentRunContent, Ind, NumPr, PContent, PPr, PPrBase, PPrGeneral, RPr, RPrBase, SignedTwipsMeasure, P,
+                R,
+            },
+            numbering::{AbstractNum, Lvl, Num, NumLvl, Numbering},
+            styles::{DocDefaults, RPrDefault, StyleType},
+        },
+    };
+
+    fn paragraph_with_style(style_id: &str) -> P {
+        P {
+            properties: Some(PPr {
+                base: PPrBase {
+                    style: Some(String::from(style_id)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            contents: vec![PContent::ContentRunContent(Box::new(ContentRunContent::Run(
+                Default::default(),
+            )))],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_resolve_style_hierarchy() {
+        let package = package_with_styles(vec![
+            style(
+                "Base",
+                StyleType::Paragraph,
+                None,
+                vec![RPrBase::Bold(true), RPrBase::Italic(true)],
+            ),
+            style(
+                "Heading",
+                StyleType::Paragraph,
+                Some("Base"),
+                vec![RPrBase::Italic(false)],
+            ),
+            style(
+                "Title",
+                StyleType::Paragraph,
+                Some("Heading"),
+                vec![RPrBase::Capitals(true)],
+            ),
+        ]);
+        let mut resolver = StyleResolver::new(&package);
+
+        let heading = resolver.resolve_style_with_id("Heading").unwrap();
+        assert_eq!(heading.run_properties.bold, Some(true));
+        assert_eq!(heading.run_properties.italic, Some(false));
+
+        let title = resolver.resolve_style_with_id("Title").unwrap();
+        assert_eq!(title.run_properties.bold, Some(true));
+        assert_eq!(title.run_properties.italic, Some(false));
+        assert_eq!(title.run_properties.all_capitals, Some(true));
+
+        assert_eq!(resolver.resolve_style_with_id("Missing"), None);
+        assert_eq!(resolver.style_hierarchy("Title").unwrap().len(), 3);
+
+        let paragraph = paragraph_with_style("Title");
+        let run: R = Default::default();
+        assert_eq!(
+            resolver.resolve_style_inheritance(&paragraph, &run),
+            package.resolve_style_inheritance(&paragraph, &run)
+        );
+    }
+
+    #[test]
+    pub fn test_resolve_toggle_properties() {
+        let mut emphasis = style(
+            "Emphasis",
+            StyleType::Paragraph,
+            None,
+            vec![RPrBase::Bold(true), RPrBase::NoProofing(true)],
+        );
+        emphasis.style_type = Some(StyleType::Character);
+        let mut strong_emphasis = style(
+            "StrongEmphasis",
+            StyleType::Paragraph,
+            Some("Emphasis"),
+            vec![RPrBase::Italic(true)],
+        );
+        strong_emphasis.style_type = Some(StyleType::Character);
+        let package = package_with_styles(vec![
+            style(
+                "Heading",
+                StyleType::Paragraph,
+                None,
+                vec![RPrBase::Bold(true), RPrBase::NoProofing(true)],
+            ),
+            emphasis,
+            strong_emphasis,
+        ]);
+        let mut resolver = StyleResolver::new(&package);
+        let paragraph = paragraph_with_style("Heading");
+        let run_with_style = |style_id: &str, mut r_pr_bases: Vec<RPrBase>| {
+            r_pr_bases.insert(0, RPrBase::RunStyle(String::from(style_id)));
+            R {
+                run_properties: Some(RPr {
+                    r_pr_bases,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        };
+
+        // Bold of the character style toggles the bold of the paragraph style off, other properties don't toggle
+        let style = resolver
+            .resolve_style_inheritance(&paragraph, &run_with_style("Emphasis", Vec::new()))
+            .unwrap();
+        assert_eq!(style.run_properties.bold, Some(false));
+        assert_eq!(style.run_properties.no_proofing, Some(true));
+
+        // Within a based_on chain the values are inherited
+        let style = resolver
+            .resolve_style_inheritance(&paragraph, &run_with_style("StrongEmphasis", Vec::new()))
+            .unwrap();
+        assert_eq!(style.run_properties.bold, Some(false));
+        assert_eq!(style.run_properties.italic, Some(true));
+
+        // Direct formatting is absolute
+        let style = resolver
+            .resolve_style_inheritance(&paragraph, &run_with_style("Emphasis", vec![RPrBase::Bold(true)]))
+            .unwrap();
+        assert_eq!(style.run_properties.bold, Some(true));
+    }
+
+    #[test]
+    pub fn test_resolve_toggle_properties_with_document_defaults() {
+        let mut package = package_with_styles(vec![
+            style(
+                "Heading",
+                StyleType::Paragraph,
+                None,
+                vec![RPrBase::Bold(true), RPrBase::Italic(true)],
+            ),
+            style(
+                "Quote",
+                StyleType::Paragraph,
+                Some("Heading"),
+                vec![RPrBase::Italic(true)],
+            ),
+        ]);
+        package.styles.as_mut().unwrap().document_defaults = Some(DocDefaults {
+            run_properties_default: Some(RPrDefault(Some(RPr {
+                r_pr_bases: vec![RPrBase::Bold(true), RPrBase::Capitals(true)],
+                ..Default::default()
+            }))),
+            paragraph_properties_default: None,
+        });
+        let mut resolver = StyleResolver::new(&package);
+        let run: R = Default::default();
+
+        // The document defaults are replaced by the style, not flipped
+        let style = resolver
+            .resolve_style_inheritance(&paragraph_with_style("Heading"), &run)
+            .unwrap();
+        assert_eq!(style.run_properties.bold, Some(true));
+        assert_eq!(style.run_properties.italic, Some(true));
+        assert_eq!(style.run_properties.all_capitals, Some(true));
+
+        let style = resolver
+            .resolve_style_inheritance(&paragraph_with_style("Quote"), &run)
+            .unwrap();
+        assert_eq!(style.run_properties.bold, Some(true));
+        assert_eq!(style.run_properties.italic, Some(true));
+    }
+
+    fn indent(start: i32) -> Option<Ind> {
+        Some(Ind {
+            start: Some(SignedTwipsMeasure::Decimal(start)),
+            ..Default::default()
+        })
+    }
+
+    fn numbering_properties(numbering_id: i32, indent_level: Option<i32>) -> Option<NumPr> {
+        Some(NumPr {
+            numbering_id: Some(numbering_id),
+            indent_level,
+            inserted: None,
+        })
+    }
+
+    fn numbering_level(level: i32, paragraph_style: Option<&str>, start: i32) -> Lvl {
+        Lvl {
+            start: Some(1),
+            numbering_format: None,
+            level_restart: None,
+            paragraph_style: paragraph_style.map(String::from),
+            display_as_arabic_numerals: None,
+            suffix: None,
+            level_text: None,
+            level_picture_bullet_id: None,
+            level_alignment: None,
+            paragraph_properties: Some(PPrGeneral {
+                base: PPrBase {
+                    indent: indent(start),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            run_properties: None,
+            level,
+            template_code: None,
+            tentative: None,
+        }
+    }
+
+    fn paragraph_with_properties(base: PPrBase) -> P {
+        P {
+            properties: Some(PPr {
+                base,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_resolve_numbering() {
+        let mut heading = style("Heading2", StyleType::Paragraph, None, Vec::new());
+        heading.paragraph_properties = Some(PPrGeneral {
+            base: PPrBase {
+                keep_with_next: Some(true),
+                numbering_properties: numbering_properties(5, None),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let mut quote = style("Quote", StyleType::Paragraph, None, Vec::new());
+        quote.paragraph_properties = Some(PPrGeneral {
+            base: PPrBase {
+                indent: indent(100),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let mut package = package_with_styles(vec![heading, quote]);
+        package.numbering = Some(Numbering {
+            abstract_numberings: vec![AbstractNum {
+                levels: vec![
+                    numbering_level(0, None, 720),
+                    numbering_level(1, Some("Heading2"), 1440),
+                ],
+                ..AbstractNum::new(1)
+            }],
+            numberings: vec![
+                Num {
+                    abstract_num_id: 1,
+                    level_overrides: Vec::new(),
+                    numbering_id: 5,
+                },
+                Num {
+                    abstract_num_id: 1,
+                    level_overrides: vec![NumLvl {
+                        level: Some(numbering_level(0, None, 360)),
+                        ..NumLvl::new(0)
+                    }],
+                    numbering_id: 6,
+                },
+            ],
+            ..Default::default()
+        });
+        let mut resolver = StyleResolver::new(&package);
+
+        // The level is linked to the style, the numbering comes from the style
+        let paragraph = paragraph_with_style("Heading2");
+        assert_eq!(
+            resolver.resolve_numbering(&paragraph),
+            Some(ParagraphNumbering {
+                numbering_id: 5,
+                level: 1,
+            })
+        );
+        let properties = resolver.resolve_paragraph_properties(&paragraph);
+        assert_eq!(properties.indent, indent(1440));
+        assert_eq!(properties.keep_with_next, Some(true));
+
+        // The paragraph style takes precedence over the numbering
+        let paragraph = paragraph_with_properties(PPrBase {
+            style: Some(String::from("Quote")),
+            numbering_properties: numbering_properties(5, Some(0)),
+            ..Default::default()
+        });
+        assert_eq!(
+            resolver.resolve_numbering(&paragraph),
+            Some(ParagraphNumbering {
+                numbering_id: 5,
+                level: 0,
+            })
+        );
+        assert_eq!(resolver.resolve_paragraph_properties(&paragraph).indent, indent(100));
+
+        // Level overrides of the numbering instance
+        let paragraph = paragraph_with_properties(PPrBase {
+            numbering_properties: numbering_properties(6, Some(0)),
+            ..Default::default()
+        });
+        assert_eq!(resolver.resolve_paragraph_properties(&paragraph).indent, indent(360));
+
+        // A numbering id of 0 removes the numbering of the style
+        let paragraph = paragraph_with_properties(PPrBase {
+            style: Some(String::from("Heading2")),
+            numbering_properties: numbering_properties(0, None),
+            ..Default::default()
+        });
+        assert_eq!(resolver.resolve_numbering(&paragraph), None);
+        assert_eq!(resolver.resolve_paragraph_properties(&paragraph).indent, None);
+    }
+
+    #[test]
+    pub fn test_resolve_numbering_style_link() {
+        let mut list_style = style("ListStyle", StyleType::Paragraph, None, Vec::new());
+        list_style.style_type = Some(StyleType::Numbering);
+        list_style.paragraph_properties = Some(PPrGeneral {
+            base: PPrBase {
+                numbering_properties: numbering_properties(7, None),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let mut package = package_with_styles(vec![list_style]);
+        package.numbering = Some(Numbering {
+            abstract_numberings: vec![
+                AbstractNum {
+                    style_link: Some(String::from("ListStyle")),
+                    levels: vec![numbering_level(0, None, 720)],
+                    ..AbstractNum::new(2)
+                },
+                AbstractNum {
+                    numbering_style_link: Some(String::from("ListStyle")),
+                    ..AbstractNum::new(3)
+                },
+            ],
+            numberings: vec![
+                Num {
+                    abstract_num_id: 2,
+                    level_overrides: Vec::new(),
+                    numbering_id: 7,
+                },
+                Num {
+                    abstract_num_id: 3,
+                    level_overrides: Vec::new(),
+                    numbering_id: 8,
+                },
+            ],
+            ..Default::default()
+        });
+
+        // The levels come from the abstract numbering of the linked numbering style
+        let paragraph = paragraph_with_properties(PPrBase {
+            numbering_properties: numbering_properties(8, Some(0)),
+            ..Default::default()
+        });
+        let mut resolver = StyleResolver::new(&package);
+        assert_eq!(resolver.resolve_paragraph_properties(&paragraph).indent, indent(720));
+        assert_eq!(
+            package.find_numbering_level(8, 0),
+            package.numbering.as_ref().unwrap().abstract_numberings[0]
+                .levels
+                .first()
+        );
+    }
+
+    #[test]
+    pub fn test_resolve_based_on_cycle() {
+        let package = package_with_styles(vec![
+            style("First", StyleType::Paragraph, Some("Second"), vec![RPrBase::Bold(true)]),
+            style(
+                "Second",
+                StyleType::Paragraph,
+                Some("Third"),
+                vec![RPrBase::Italic(true)],
+            ),
+            style(
+                "Third",
+                StyleType::Paragraph,
+                Some("Second"),
+                vec![RPrBase::Bold(false)],
+            ),
+        ]);
+        let mut resolver = StyleResolver::new(&package);
+
+        assert_eq!(
+            resolver.style_hierarchy("First"),
+            Err(BasedOnCycleError {
+                style_ids: vec![String::from("Second"), String::from("Third"), String::from("Second")],
+            })
+        );
+
+        let first = resolver.resolve_style_with_id("First").unwrap();
+        assert_eq!(first.run_properties.bold, Some(true));
+        assert_eq!(first.run_properties.italic, Some(true));
+
+        let third = resolver.resolve_style_with_id("Third").unwrap();
+        assert_eq!(third.run_properties.bold, Some(false));
+        assert_eq!(third.run_properties.italic, Some(true));
+    }
+}
@@ -0,0 +1,492 @@
+use crate::{
+    export::{cell_grid_span, row_cells, table_rows},
+    resolvedstyle::{ResolvedStyle, RunProperties},
+    wml::{
+        document::Cnf,
+        styles::{Style, TblStyleOverrideType, TblStylePr},
+        table::{Row, Tbl, TblLook, TblPrBase, Tc, TcPrBase, TrPrBase},
+    },
+};
+use msoffice_shared::update::Update;
+
+// The order in which the conditional formats of a table style are applied, from ECMA-376 Part 1, 17.7.6
+const CONDITIONAL_FORMAT_PRECEDENCE: [TblStyleOverrideType; 13] = [
+    TblStyleOverrideType::WholeTable,
+    TblStyleOverrideType::Band1Vertical,
+    TblStyleOverrideType::Band2Vertical,
+    TblStyleOverrideType::Band1Horizontal,
+    TblStyleOverrideType::Band2Horizontal,
+    TblStyleOverrideType::FirstRow,
+    TblStyleOverrideType::LastRow,
+    TblStyleOverrideType::FirstColumn,
+    TblStyleOverrideType::LastColumn,
+    TblStyleOverrideType::NorthWestCell,
+    TblStyleOverrideType::NorthEastCell,
+    TblStyleOverrideType::SouthWestCell,
+    TblStyleOverrideType::SouthEastCell,
+];
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvedTableStyle {
+    pub style: ResolvedStyle,
+    pub table_properties: TblPrBase,
+    pub row_properties: TrPrBase,
+    pub cell_properties: TcPrBase,
+}
+
+impl ResolvedTableStyle {
+    pub fn from_wml_style(style: &Style) -> Self {
+        Self {
+            style: ResolvedStyle::from_wml_style(style),
+            table_properties: style.table_properties.clone().unwrap_or_default(),
+            row_properties: style
+                .table_row_properties
+                .as_ref()
+                .map(|tr_pr| tr_pr.base.clone())
+                .unwrap_or_default(),
+            cell_properties: style
+                .table_cell_properties
+                .as_ref()
+                .map(|tc_pr| tc_pr.base.base.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn from_table_style_properties(table_style_properties: &TblStylePr) -> Self {
+        let mut style: ResolvedStyle = Default::default();
+        if let Some(p_pr) = &table_style_properties.paragraph_properties {
+            *style.paragraph_properties = p_pr.base.clone();
+        }
+
+        if let Some(r_pr) = &table_style_properties.run_properties {
+            *style.run_properties = RunProperties::from_vec(&r_pr.r_pr_bases);
+        }
+
+        Self {
+            style,
+            table_properties: table_style_properties.table_properties.clone().unwrap_or_default(),
+            row_properties: table_style_properties
+                .table_row_properties
+                .as_ref()
+                .map(|tr_pr| tr_pr.base.clone())
+                .unwrap_or_default(),
+            cell_properties: table_style_properties
+                .table_cell_properties
+                .as_ref()
+                .map(|tc_pr| tc_pr.base.base.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    // Each conditional format is inherited through the based_on chain on its own, then the formats that apply are
+    // layered on top of each other in the order of precedence. The hierarchy starts with the style itself.
+    pub fn from_style_hierarchy(hierarchy: &[&Style], conditional_formats: &[TblStyleOverrideType]) -> Self {
+        table_style_layers(hierarchy, conditional_formats)
+            .into_iter()
+            .fold(Default::default(), |resolved_style: Self, (_, _, layer)| {
+                resolved_style.update_with(layer)
+            })
+    }
+
+    pub fn update_with(self, other: Self) -> Self {
+        Self {
+            style: self.style.update_with(other.style),
+            table_properties: self.table_properties.update_with(other.table_properties),
+            row_properties: self.row_properties.update_with(other.row_properties),
+            cell_properties: self.cell_properties.update_with(other.cell_properties),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableCellPosition {
+    pub row_index: usize,
+    pub row_count: usize,
+    pub column_index: usize,
+    pub column_span: usize,
+    pub column_count: usize,
+}
+
+impl TableCellPosition {
+    // Rows and cells are counted through custom XML and structured document tag wrappers. The column index is the
+    // index of the first grid column the cell spans.
+    pub fn from_table(table: &Tbl, row_index: usize, cell_index: usize) -> Option<Self> {
+        let rows = table_rows(table);
+        let row = rows.get(row_index)?;
+        let cells = row_cells(row);
+        cells.get(cell_index)?;
+
+        let grid_column_before = row
+            .properties
+            .as_ref()
+            .and_then(|tr_pr| tr_pr.base.grid_column_before_first_cell)
+            .unwrap_or(0)
+            .max(0) as usize;
+        let column_index = grid_column_before
+            + cells[..cell_index]
+                .iter()
+                .map(|cell| cell_grid_span(cell))
+                .sum::<usize>();
+        let column_span = cell_grid_span(cells[cell_index]);
+        let column_count = match table.grid.base.columns.len() {
+            0 => rows
+                .iter()
+                .map(|row| row_cells(row).iter().map(|cell| cell_grid_span(cell)).sum())
+                .max()
+                .unwrap_or(0),
+            column_count => column_count,
+        };
+
+        Some(Self {
+            row_index,
+            row_count: rows.len(),
+            column_index,
+            column_span,
+            column_count: column_count.max(column_index + column_span),
+        })
+    }
+
+    pub fn is_first_row(&self) -> bool {
+        self.row_index == 0
+    }
+
+    pub fn is_last_row(&self) -> bool {
+        self.row_index + 1 == self.row_count
+    }
+
+    pub fn is_first_column(&self) -> bool {
+        self.column_index == 0
+    }
+
+    pub fn is_last_column(&self) -> bool {
+        self.column_index + self.column_span == self.column_count
+    }
+}
+
+// The properties of a table style hierarchy in the order they are applied, with the style and the conditional format
+// they come from. The whole table properties include the properties of the style itself.
+pub fn table_style_layers<'a>(
+    hierarchy: &[&'a Style],
+    conditional_formats: &[TblStyleOverrideType],
+) -> Vec<(&'a Style, TblStyleOverrideType, ResolvedTableStyle)> {
+    let mut layers = Vec::new();
+    for override_type in CONDITIONAL_FORMAT_PRECEDENCE
+        .iter()
+        .filter(|override_type| conditional_formats.contains(override_type))
+    {
+        for style in hierarchy.iter().rev() {
+            if *override_type == TblStyleOverrideType::WholeTable {
+                layers.push((*style, *override_type, ResolvedTableStyle::from_wml_style(style)));
+            }
+
+            layers.extend(
+                style
+                    .table_style_properties_vec
+                    .iter()
+                    .filter(|tbl_style_pr| tbl_style_pr.override_type == *override_type)
+                    .map(|tbl_style_pr| {
+                        (
+                            *style,
+                            *override_type,
+                            ResolvedTableStyle::from_table_style_properties(tbl_style_pr),
+                        )
+                    }),
+            );
+        }
+    }
+
+    layers
+}
+
+// Selects the conditional formats of a cell from the table look and the cell position. Band sizes are taken from
+// the table style.
+pub fn conditional_formats(
+    look: &TblLook,
+    position: &TableCellPosition,
+    row_band_size: usize,
+    column_band_size: usize,
+) -> Vec<TblStyleOverrideType> {
+    let first_row = look.first_row.unwrap_or(false) && position.is_first_row();
+    let last_row = look.last_row.unwrap_or(false) && position.is_last_row();
+    let first_column = look.first_column.unwrap_or(false) && position.is_first_column();
+    let last_column = look.last_column.unwrap_or(false) && position.is_last_column();
+
+    let mut conditional_formats = vec![TblStyleOverrideType::WholeTable];
+
+    if !look.no_vertical_band.unwrap_or(false) && !first_column && !last_column {
+        let band_index = position.column_index - look.first_column.unwrap_or(false) as usize;
+        conditional_formats.push(match (band_index / column_band_size.max(1)) % 2 {
+            0 => TblStyleOverrideType::Band1Vertical,
+            _ => TblStyleOverrideType::Band2Vertical,
+        });
+    }
+
+    if !look.no_horizontal_band.unwrap_or(false) && !first_row && !last_row {
+        let band_index = position.row_index - look.first_row.unwrap_or(false) as usize;
+        conditional_formats.push(match (band_index / row_band_size.max(1)) % 2 {
+            0 => TblStyleOverrideType::Band1Horizontal,
+            _ => TblStyleOverrideType::Band2Horizontal,
+        });
+    }
+
+    let flags = [
+        (first_row, TblStyleOverrideType::FirstRow),
+        (last_row, TblStyleOverrideType::LastRow),
+        (first_column, TblStyleOverrideType::FirstColumn),
+        (last_column, TblStyleOverrideType::LastColumn),
+        (first_row && first_column, TblStyleOverrideType::NorthWestCell),
+        (first_row && last_column, TblStyleOverrideType::NorthEastCell),
+        (last_row && first_column, TblStyleOverrideType::SouthWestCell),
+        (last_row && last_column, TblStyleOverrideType::SouthEastCell),
+    ];
+    conditional_formats.extend(flags.iter().filter(|(is_set, _)| *is_set).map(|(_, format)| *format));
+    conditional_formats
+}
+
+// The conditional formats recorded by the producer of the document in a row's or cell's cnfStyle
+pub fn cnf_conditional_formats(cnf: &Cnf) -> Vec<TblStyleOverrideType> {
+    let flags = [
+        (cnf.odd_vertical_band, TblStyleOverrideType::Band1Vertical),
+        (cnf.even_vertical_band, TblStyleOverrideType::Band2Vertical),
+        (cnf.odd_horizontal_band, TblStyleOverrideType::Band1Horizontal),
+        (cnf.even_horizontal_band, TblStyleOverrideType::Band2Horizontal),
+        (cnf.first_row, TblStyleOverrideType::FirstRow),
+        (cnf.last_row, TblStyleOverrideType::LastRow),
+        (cnf.first_column, TblStyleOverrideType::FirstColumn),
+        (cnf.last_column, TblStyleOverrideType::LastColumn),
+        (cnf.first_row_first_column, TblStyleOverrideType::NorthWestCell),
+        (cnf.first_row_last_column, TblStyleOverrideType::NorthEastCell),
+        (cnf.last_row_first_column, TblStyleOverrideType::SouthWestCell),
+        (cnf.last_row_last_column, TblStyleOverrideType::SouthEastCell),
+    ];
+
+    std::iter::once(TblStyleOverrideType::WholeTable)
+        .chain(
+            flags
+                .iter()
+                .filter(|(is_set, _)| is_set.unwrap_or(false))
+                .map(|(_, format)| *format),
+        )
+        .collect()
+}
+
+// The row's cnfStyle updated with the cell's, if either of them has one
+pub fn cell_cnf(row: &Row, cell: &Tc) -> Option<Cnf> {
+    let row_cnf = row
+        .properties
+        .as_ref()
+        .and_then(|tr_pr| tr_pr.base.conditional_formatting);
+    let cell_cnf = cell
+        .properties
+        .as_ref()
+        .and_then(|tc_pr| tc_pr.base.base.conditional_formatting);
+
+    match (row_cnf, cell_cnf) {
+        (Some(row_cnf), Some(cell_cnf)) => Some(row_cnf.update_with(cell_cnf)),
+        (row_cnf, cell_cnf) => cell_cnf.or(row_cnf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{conditional_formats, TableCellPosition};
+    use crate::{
+        package::Package,
+        wml::{
+            document::{Cnf, RPr, RPrBase},
+            styles::{Style, StyleType, Styles, TblStyleOverrideType, TblStylePr},
+            table::{
+                ContentCellContent, ContentRowContent, Row, Tbl, TblGrid, TblGridBase, TblGridCol, TblLook, TblPr,
+                TblPrBase, Tc, TcPr, TcPrBase, TcPrInner,
+            },
+        },
+    };
+
+    fn run_properties(r_pr_bases: Vec<RPrBase>) -> Option<RPr> {
+        Some(RPr {
+            r_pr_bases,
+            ..Default::default()
+        })
+    }
+
+    fn table_style_properties(override_type: TblStyleOverrideType, r_pr_bases: Vec<RPrBase>) -> TblStylePr {
+        TblStylePr {
+            paragraph_properties: None,
+            run_properties: run_properties(r_pr_bases),
+            table_properties: None,
+            table_row_properties: None,
+            table_cell_properties: None,
+            override_type,
+        }
+    }
+
+    fn cell(cnf: Option<Cnf>) -> ContentCellContent {
+        ContentCellContent::Cell(Box::new(Tc {
+            properties: cnf.map(|cnf| TcPr {
+                base: TcPrInner {
+                    base: TcPrBase {
+                        conditional_formatting: Some(cnf),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+    }
+
+    fn table(row_count: usize, column_count: usize, cnf: Option<Cnf>) -> Tbl {
+        Tbl {
+            range_markup_elements: Vec::new(),
+            properties: TblPr {
+                base: TblPrBase {
+                    style: Some(String::from("Grid")),
+                    look: Some(TblLook {
+                        first_row: Some(true),
+                        first_column: Some(true),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            grid: TblGrid {
+                base: TblGridBase {
+                    columns: vec![TblGridCol { width: None }; column_count],
+                },
+                ..Default::default()
+            },
+            row_contents: (0..row_count)
+                .map(|row_index| {
+                    ContentRowContent::Table(Box::new(Row {
+                        contents: (0..column_count)
+                            .map(|column_index| match (row_index, column_index) {
+                                (1, 1) => cell(cnf),
+                                _ => cell(None),
+                            })
+                            .collect(),
+                        ..Default::default()
+                    }))
+                })
+                .collect(),
+        }
+    }
+
+    fn package_with_table_styles() -> Package {
+        let base_style = Style {
+            style_id: Some(String::from("Base")),
+            style_type: Some(StyleType::Table),
+            run_properties: run_properties(vec![RPrBase::Bold(false)]),
+            table_style_properties_vec: vec![table_style_properties(
+                TblStyleOverrideType::FirstRow,
+                vec![RPrBase::Italic(true)],
+            )],
+            ..Default::default()
+        };
+        let grid_style = Style {
+            style_id: Some(String::from("Grid")),
+            based_on: Some(String::from("Base")),
+            style_type: Some(StyleType::Table),
+            table_style_properties_vec: vec![
+                table_style_properties(TblStyleOverrideType::FirstRow, vec![RPrBase::Bold(true)]),
+                table_style_properties(TblStyleOverrideType::FirstColumn, vec![RPrBase::Capitals(true)]),
+                table_style_properties(
+                    TblStyleOverrideType::NorthWestCell,
+                    vec![RPrBase::Bold(false), RPrBase::Capitals(false)],
+                ),
+                table_style_properties(
+                    TblStyleOverrideType::Band1Horizontal,
+                    vec![RPrBase::Strikethrough(true)],
+                ),
+            ],
+            ..Default::default()
+        };
+
+        Package {
+            styles: Some(Box::new(Styles {
+                document_defaults: None,
+                latent_styles: None,
+                styles: vec![base_style, grid_style],
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_conditional_formats() {
+        let look = TblLook {
+            first_row: Some(true),
+            last_column: Some(true),
+            no_vertical_band: Some(true),
+            ..Default::default()
+        };
+        let table = table(5, 3, None);
+        let position = |row_index, cell_index| TableCellPosition::from_table(&table, row_index, cell_index).unwrap();
+
+        assert_eq!(
+            conditional_formats(&look, &position(0, 2), 1, 1),
+            vec![
+                TblStyleOverrideType::WholeTable,
+                TblStyleOverrideType::FirstRow,
+                TblStyleOverrideType::LastColumn,
+                TblStyleOverrideType::NorthEastCell,
+            ]
+        );
+        assert_eq!(
+            conditional_formats(&look, &position(1, 0), 1, 1),
+            vec![TblStyleOverrideType::WholeTable, TblStyleOverrideType::Band1Horizontal]
+        );
+        assert_eq!(
+            conditional_formats(&look, &position(2, 0), 1, 1),
+            vec![TblStyleOverrideType::WholeTable, TblStyleOverrideType::Band2Horizontal]
+        );
+        assert_eq!(
+            conditional_formats(&look, &position(3, 0), 2, 1),
+            vec![TblStyleOverrideType::WholeTable, TblStyleOverrideType::Band2Horizontal]
+        );
+        assert_eq!(TableCellPosition::from_table(&table, 5, 0), None);
+    }
+
+    #[test]
+    pub fn test_resolve_table_cell_style_precedence() {
+        let package = package_with_table_styles();
+        let mut resolver = package.style_resolver();
+        let table = table(3, 3, None);
+
+        let first_row = resolver.resolve_table_cell_style(&table, 0, 1).unwrap();
+        assert_eq!(first_row.style.run_properties.bold, Some(true));
+        assert_eq!(first_row.style.run_properties.italic, Some(true));
+
+        let north_west = resolver.resolve_table_cell_style(&table, 0, 0).unwrap();
+        assert_eq!(north_west.style.run_properties.bold, Some(false));
+        assert_eq!(north_west.style.run_properties.italic, Some(true));
+        assert_eq!(north_west.style.run_properties.all_capitals, Some(false));
+
+        let first_column = resolver.resolve_table_cell_style(&table, 1, 0).unwrap();
+        assert_eq!(first_column.style.run_properties.bold, Some(false));
+        assert_eq!(first_column.style.run_properties.all_capitals, Some(true));
+        assert_eq!(first_column.style.run_properties.strikethrough, Some(true));
+
+        let second_band = resolver.resolve_table_cell_style(&table, 2, 1).unwrap();
+        assert_eq!(second_band.style.run_properties.strikethrough, None);
+    }
+
+    #[test]
+    pub fn test_resolve_table_cell_style_with_cnf() {
+        let package = package_with_table_styles();
+        let cnf = Cnf {
+            first_row: Some(true),
+            ..Default::default()
+        };
+        let table = table(3, 3, Some(cnf));
+
+        let cell_style = package.style_resolver().resolve_table_cell_style(&table, 1, 1).unwrap();
+        assert_eq!(cell_style.style.run_properties.bold, Some(true));
+        assert_eq!(cell_style.style.run_properties.strikethrough, None);
+        assert_eq!(
+            cell_style.cell_properties.conditional_formatting.unwrap().first_row,
+            Some(true)
+        );
+    }
+}
@@ -0,0 +1,834 @@
+use crate::{
+    package::Package,
+    replace::{crosses_field_character, field_character_positions, Replacer},
+    search::{paragraph_runs, ParagraphText, SearchPattern},
+    visit::{walk_paragraph_mut, VisitMut},
+    wml::{
+        document::{BlockLevelElts, ContentBlockContent, Document, RunInnerContent, P},
+        drawing::TxbxContent,
+        table::{ContentCellContent, ContentRowContent},
+    },
+};
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+pub type TemplateData = HashMap<String, TemplateValue>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateValue {
+    Text(String),
+    Bool(bool),
+    List(Vec<TemplateValue>),
+    Map(TemplateData),
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        TemplateValue::Text(String::from(value))
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        TemplateValue::Text(value)
+    }
+}
+
+impl From<bool> for TemplateValue {
+    fn from(value: bool) -> Self {
+        TemplateValue::Bool(value)
+    }
+}
+
+impl From<Vec<TemplateValue>> for TemplateValue {
+    fn from(value: Vec<TemplateValue>) -> Self {
+        TemplateValue::List(value)
+    }
+}
+
+impl From<TemplateData> for TemplateValue {
+    fn from(value: TemplateData) -> Self {
+        TemplateValue::Map(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    UnclosedSection(String),
+    UnexpectedSectionEnd(String),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::UnclosedSection(name) => write!(f, "Section '{}' is never closed", name),
+            TemplateError::UnexpectedSectionEnd(name) => write!(f, "Section end '{}' has no matching start", name),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+type Result<T> = std::result::Result<T, TemplateError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TagKind {
+    Value,
+    Section,
+    InvertedSection,
+    SectionEnd,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Tag {
+    kind: TagKind,
+    name: String,
+    paragraph_index: usize,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Scopes<'d> {
+    data: &'d TemplateData,
+    items: Vec<&'d TemplateValue>,
+}
+
+impl<'d> Scopes<'d> {
+    fn with_item(&self, item: &'d TemplateValue) -> Self {
+        let mut scopes = self.clone();
+        scopes.items.push(item);
+        scopes
+    }
+
+    fn lookup(&self, name: &str) -> Option<&'d TemplateValue> {
+        if name == "." {
+            return self.items.last().copied();
+        }
+
+        let mut segments = name.split('.');
+        let first_segment = segments.next()?;
+        let value = self
+            .items
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                TemplateValue::Map(map) => map.get(first_segment),
+                _ => None,
+            })
+            .or_else(|| self.data.get(first_segment))?;
+
+        segments.try_fold(value, |value, segment| match value {
+            TemplateValue::Map(map) => map.get(segment),
+            _ => None,
+        })
+    }
+}
+
+trait TemplateElement: Clone {
+    fn paragraphs(&self) -> Vec<&P>;
+
+    fn paragraphs_mut(&mut self) -> Vec<&mut P>;
+
+    fn render<'d>(&mut self, renderer: &mut TemplateRenderer<'d>, scopes: &Scopes<'d>) -> Result<()>;
+
+    fn is_blank(&self) -> bool {
+        let paragraphs = self.paragraphs();
+        !paragraphs.is_empty() && paragraphs.into_iter().all(is_blank_paragraph)
+    }
+}
+
+impl TemplateElement for BlockLevelElts {
+    fn paragraphs(&self) -> Vec<&P> {
+        match self {
+            BlockLevelElts::Chunk(content) => content.paragraphs(),
+            BlockLevelElts::AltChunk(_) => Vec::new(),
+        }
+    }
+
+    fn paragraphs_mut(&mut self) -> Vec<&mut P> {
+        match self {
+            BlockLevelElts::Chunk(content) => content.paragraphs_mut(),
+            BlockLevelElts::AltChunk(_) => Vec::new(),
+        }
+    }
+
+    fn render<'d>(&mut self, renderer: &mut TemplateRenderer<'d>, scopes: &Scopes<'d>) -> Result<()> {
+        match self {
+            BlockLevelElts::Chunk(content) => content.render(renderer, scopes),
+            BlockLevelElts::AltChunk(_) => Ok(()),
+        }
+    }
+}
+
+impl TemplateElement for ContentBlockContent {
+    fn paragraphs(&self) -> Vec<&P> {
+        match self {
+            ContentBlockContent::Paragraph(paragraph) => vec![paragraph],
+            _ => Vec::new(),
+        }
+    }
+
+    fn paragraphs_mut(&mut self) -> Vec<&mut P> {
+        match self {
+            ContentBlockContent::Paragraph(paragraph) => vec![paragraph],
+            _ => Vec::new(),
+        }
+    }
+
+    fn render<'d>(&mut self, renderer: &mut TemplateRenderer<'d>, scopes: &Scopes<'d>) -> Result<()> {
+        match self {
+            ContentBlockContent::Paragraph(paragraph) => renderer.render_paragraph_with_scopes(paragraph, scopes),
+            ContentBlockContent::Table(table) => renderer.render_elements(&mut table.row_contents, scopes),
+            ContentBlockContent::CustomXml(custom_xml) => {
+                renderer.render_elements(&mut custom_xml.block_contents, scopes)
+            }
+            ContentBlockContent::Sdt(sdt) => match &mut sdt.sdt_content {
+                Some(sdt_content) => renderer.render_elements(&mut sdt_content.block_contents, scopes),
+                None => Ok(()),
+            },
+            ContentBlockContent::RunLevelElement(_) => Ok(()),
+        }
+    }
+}
+
+// A row takes part in a section if one of the paragraphs directly in its cells contains the section tag
+impl TemplateElement for ContentRowContent {
+    fn paragraphs(&self) -> Vec<&P> {
+        match self {
+            ContentRowContent::Table(row) => row
+                .contents
+                .iter()
+                .filter_map(|cell_content| match cell_content {
+                    ContentCellContent::Cell(cell) => Some(cell),
+                    _ => None,
+                })
+                .flat_map(|cell| cell.block_level_elements.iter())
+                .flat_map(|element| element.paragraphs())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn paragraphs_mut(&mut self) -> Vec<&mut P> {
+        match self {
+            ContentRowContent::Table(row) => row
+                .contents
+                .iter_mut()
+                .filter_map(|cell_content| match cell_content {
+                    ContentCellContent::Cell(cell) => Some(cell),
+                    _ => None,
+                })
+                .flat_map(|cell| cell.block_level_elements.iter_mut())
+                .flat_map(|element| element.paragraphs_mut())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn render<'d>(&mut self, renderer: &mut TemplateRenderer<'d>, scopes: &Scopes<'d>) -> Result<()> {
+        match self {
+            ContentRowContent::Table(row) => row
+                .contents
+                .iter_mut()
+                .try_for_each(|cell_content| renderer.render_cell_content(cell_content, scopes)),
+            ContentRowContent::CustomXml(custom_xml) => renderer.render_elements(&mut custom_xml.contents, scopes),
+            ContentRowContent::Sdt(sdt) => match &mut sdt.content {
+                Some(sdt_content) => renderer.render_elements(&mut sdt_content.contents, scopes),
+                None => Ok(()),
+            },
+            ContentRowContent::RunLevelElements(_) => Ok(()),
+        }
+    }
+}
+
+pub struct TemplateRenderer<'d> {
+    data: &'d TemplateData,
+    tag_regex: Regex,
+    missing_values: Vec<String>,
+    unresolved_placeholders: Vec<String>,
+}
+
+impl<'d> TemplateRenderer<'d> {
+    pub fn new(data: &'d TemplateData) -> Self {
+        Self {
+            data,
+            tag_regex: Regex::new(r"\{\{\s*([#^/]?)\s*([\w.]+)\s*\}\}").unwrap(),
+            missing_values: Vec::new(),
+            unresolved_placeholders: Vec::new(),
+        }
+    }
+
+    pub fn missing_values(&self) -> &[String] {
+        &self.missing_values
+    }
+
+    // Placeholders which cross a field character are left in the document as they couldn't be replaced without
+    // breaking the field
+    pub fn unresolved_placeholders(&self) -> &[String] {
+        &self.unresolved_placeholders
+    }
+
+    pub fn render_package(&mut self, package: &mut Package) -> Result<()> {
+        if let Some(document) = &mut package.main_document {
+            self.render_document(document)?;
+        }
+
+        for header_footer in package.headers.values_mut().chain(package.footers.values_mut()) {
+            self.render_block_level_elements(&mut header_footer.block_level_elements)?;
+        }
+
+        let footnotes = package
+            .footnotes
+            .iter_mut()
+            .flat_map(|footnotes| footnotes.0.iter_mut());
+        let endnotes = package.endnotes.iter_mut().flat_map(|endnotes| endnotes.0.iter_mut());
+        for note in footnotes.chain(endnotes) {
+            self.render_block_level_elements(&mut note.block_level_elements)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn render_document(&mut self, document: &mut Document) -> Result<()> {
+        match &mut document.body {
+            Some(body) => self.render_block_level_elements(&mut body.block_level_elements),
+            None => Ok(()),
+        }
+    }
+
+    pub fn render_txbx_content(&mut self, txbx_content: &mut TxbxContent) -> Result<()> {
+        self.render_block_level_elements(&mut txbx_content.block_level_elements)
+    }
+
+    pub fn render_block_level_elements(&mut self, elements: &mut Vec<BlockLevelElts>) -> Result<()> {
+        let scopes = self.root_scopes();
+        self.render_elements(elements, &scopes)
+    }
+
+    pub fn render_paragraph(&mut self, paragraph: &mut P) -> Result<()> {
+        let scopes = self.root_scopes();
+        self.render_paragraph_with_scopes(paragraph, &scopes)
+    }
+
+    fn root_scopes(&self) -> Scopes<'d> {
+        Scopes {
+            data: self.data,
+            items: Vec::new(),
+        }
+    }
+
+    fn render_elements<T: TemplateElement>(&mut self, elements: &mut Vec<T>, scopes: &Scopes<'d>) -> Result<()> {
+        let mut index = 0;
+        while index < elements.len() {
+            let section_start = match self.element_section_start(&elements[index])? {
+                Some(section_start) => section_start,
+                None => {
+                    elements[index].render(self, scopes)?;
+                    index += 1;
+                    continue;
+                }
+            };
+
+            // Sections not confined to a single paragraph repeat every element from their start to their end
+            let mut depth = 0;
+            let mut section_end = None;
+            'search: for (end_index, element) in elements.iter().enumerate().skip(index) {
+                for tag in self.element_tags(element) {
+                    let is_after_start = end_index > index
+                        || (tag.paragraph_index, tag.start) > (section_start.paragraph_index, section_start.start);
+                    if !is_after_start || tag.name != section_start.name {
+                        continue;
+                    }
+
+                    match tag.kind {
+                        TagKind::Section | TagKind::InvertedSection => depth += 1,
+                        TagKind::SectionEnd if depth == 0 => {
+                            section_end = Some((end_index, tag));
+                            break 'search;
+                        }
+                        TagKind::SectionEnd => depth -= 1,
+                        TagKind::Value => (),
+                    }
+                }
+            }
+
+            let (end_index, section_end) =
+                section_end.ok_or_else(|| TemplateError::UnclosedSection(section_start.name.clone()))?;
+
+            let mut section_elements: Vec<T> = elements.drain(index..=end_index).collect();
+            let last_index = section_elements.len() - 1;
+            remove_tag(&mut section_elements[0], &section_start);
+            remove_tag(&mut section_elements[last_index], &section_end);
+
+            // Elements holding nothing but the section tags are dropped
+            if section_elements[last_index].is_blank() {
+                section_elements.pop();
+            }
+
+            if !section_elements.is_empty() && section_elements[0].is_blank() {
+                section_elements.remove(0);
+            }
+
+            let value = scopes.lookup(&section_start.name);
+            let mut rendered_elements = Vec::new();
+            for item in section_items(value, section_start.kind == TagKind::InvertedSection) {
+                let item_scopes = match item {
+                    Some(item) => scopes.with_item(item),
+                    None => scopes.clone(),
+                };
+
+                let mut item_elements = section_elements.clone();
+                self.render_elements(&mut item_elements, &item_scopes)?;
+                rendered_elements.extend(item_elements);
+            }
+
+            let rendered_count = rendered_elements.len();
+            elements.splice(index..index, rendered_elements);
+            index += rendered_count;
+        }
+
+        Ok(())
+    }
+
+    fn element_section_start<T: TemplateElement>(&self, element: &T) -> Result<Option<Tag>> {
+        let tags = self.element_tags(element);
+        let mut index = 0;
+        while index < tags.len() {
+            match tags[index].kind {
+                TagKind::Value => index += 1,
+                TagKind::SectionEnd => return Err(TemplateError::UnexpectedSectionEnd(tags[index].name.clone())),
+                TagKind::Section | TagKind::InvertedSection => match matching_section_end(&tags, index) {
+                    // Sections inside of a single paragraph are rendered with the paragraph
+                    Some(end_index) if tags[end_index].paragraph_index == tags[index].paragraph_index => {
+                        index = end_index + 1
+                    }
+                    _ => return Ok(Some(tags[index].clone())),
+                },
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn render_cell_content(&mut self, cell_content: &mut ContentCellContent, scopes: &Scopes<'d>) -> Result<()> {
+        match cell_content {
+            ContentCellContent::Cell(cell) => self.render_elements(&mut cell.block_level_elements, scopes),
+            ContentCellContent::CustomXml(custom_xml) => custom_xml
+                .contents
+                .iter_mut()
+                .try_for_each(|cell_content| self.render_cell_content(cell_content, scopes)),
+            ContentCellContent::Sdt(sdt) => match &mut sdt.content {
+                Some(sdt_content) => sdt_content
+                    .contents
+                    .iter_mut()
+                    .try_for_each(|cell_content| self.render_cell_content(cell_content, scopes)),
+                None => Ok(()),
+            },
+            ContentCellContent::RunLevelElement(_) => Ok(()),
+        }
+    }
+
+    fn render_paragraph_with_scopes(&mut self, paragraph: &mut P, scopes: &Scopes<'d>) -> Result<()> {
+        let paragraph_text = ParagraphText::from_paragraph(paragraph);
+        let tags = parse_tags(&self.tag_regex, &paragraph_text.text, 0);
+        if !tags.is_empty() {
+            let field_character_positions = field_character_positions(paragraph);
+            let mut replacements = Vec::new();
+            for (start, end, replacement) in self.render_tags(&paragraph_text.text, &tags, scopes)? {
+                let paragraph_match = match paragraph_text.paragraph_match(start, end) {
+                    Some(paragraph_match) => paragraph_match,
+                    None => continue,
+                };
+
+                if crosses_field_character(&field_character_positions, &paragraph_match) {
+                    self.unresolved_placeholders.push(paragraph_match.text);
+                } else {
+                    replacements.push((paragraph_match, replacement));
+                }
+            }
+
+            let pattern = SearchPattern::Regex(self.tag_regex.clone());
+            Replacer::new(&pattern, "", Default::default()).replace_paragraph_matches(paragraph, replacements);
+        }
+
+        // Text boxes in the drawings of the paragraph are rendered with the scopes of the paragraph
+        let mut text_box_renderer = TextBoxRenderer {
+            renderer: self,
+            scopes,
+            result: Ok(()),
+        };
+        walk_paragraph_mut(&mut text_box_renderer, paragraph);
+        text_box_renderer.result
+    }
+
+    fn render_text(&mut self, text: &str, scopes: &Scopes<'d>) -> Result<String> {
+        let tags = parse_tags(&self.tag_regex, text, 0);
+        let mut rendered_text = String::new();
+        let mut last_end = 0;
+        for (start, end, replacement) in self.render_tags(text, &tags, scopes)? {
+            rendered_text.push_str(&text[last_end..start]);
+            rendered_text.push_str(&replacement);
+            last_end = end;
+        }
+
+        rendered_text.push_str(&text[last_end..]);
+        Ok(rendered_text)
+    }
+
+    // Returns the byte ranges of top level values and sections in text with their rendered replacement
+    fn render_tags(&mut self, text: &str, tags: &[Tag], scopes: &Scopes<'d>) -> Result<Vec<(usize, usize, String)>> {
+        let mut rendered_tags = Vec::new();
+        let mut index = 0;
+        while index < tags.len() {
+            let tag = &tags[index];
+            match tag.kind {
+                TagKind::Value => {
+                    rendered_tags.push((tag.start, tag.end, self.value_text(&tag.name, scopes)));
+                    index += 1;
+                }
+                TagKind::SectionEnd => return Err(TemplateError::UnexpectedSectionEnd(tag.name.clone())),
+                TagKind::Section | TagKind::InvertedSection => {
+                    let end_index = matching_section_end(tags, index)
+                        .ok_or_else(|| TemplateError::UnclosedSection(tag.name.clone()))?;
+                    let section_text = &text[tag.end..tags[end_index].start];
+
+                    let mut rendered_section = String::new();
+                    for item in section_items(scopes.lookup(&tag.name), tag.kind == TagKind::InvertedSection) {
+                        let item_scopes = match item {
+                            Some(item) => scopes.with_item(item),
+                            None => scopes.clone(),
+                        };
+                        rendered_section.push_str(&self.render_text(section_text, &item_scopes)?);
+                    }
+
+                    rendered_tags.push((tag.start, tags[end_index].end, rendered_section));
+                    index = end_index + 1;
+                }
+            }
+        }
+
+        Ok(rendered_tags)
+    }
+
+    fn value_text(&mut self, name: &str, scopes: &Scopes<'d>) -> String {
+        match scopes.lookup(name) {
+            Some(TemplateValue::Text(text)) => text.clone(),
+            Some(TemplateValue::Bool(value)) => value.to_string(),
+            Some(_) => String::new(),
+            None => {
+                if !self.missing_values.iter().any(|missing_value| missing_value == name) {
+                    self.missing_values.push(String::from(name));
+                }
+
+                String::new()
+            }
+        }
+    }
+
+    fn element_tags<T: TemplateElement>(&self, element: &T) -> Vec<Tag> {
+        element
+            .paragraphs()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(paragraph_index, paragraph)| {
+                parse_tags(
+                    &self.tag_regex,
+                    &ParagraphText::from_paragraph(paragraph).text,
+                    paragraph_index,
+                )
+            })
+            .collect()
+    }
+}
+
+struct TextBoxRenderer<'r, 'd> {
+    renderer: &'r mut TemplateRenderer<'d>,
+    scopes: &'r Scopes<'d>,
+    result: Result<()>,
+}
+
+impl<'r, 'd> VisitMut for TextBoxRenderer<'r, 'd> {
+    fn visit_txbx_content_mut(&mut self, txbx_content: &mut TxbxContent) {
+        if self.result.is_ok() {
+            self.result = self
+                .renderer
+                .render_elements(&mut txbx_content.block_level_elements, self.scopes);
+        }
+    }
+}
+
+impl Package {
+    pub fn render_template(&mut self, data: &TemplateData) -> Result<()> {
+        TemplateRenderer::new(data).render_package(self)
+    }
+}
+
+fn parse_tags(tag_regex: &Regex, text: &str, paragraph_index: usize) -> Vec<Tag> {
+    tag_regex
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            let kind = match captures.get(1).map(|kind| kind.as_str()) {
+                Some("#") => TagKind::Section,
+                Some("^") => TagKind::InvertedSection,
+                Some("/") => TagKind::SectionEnd,
+                _ => TagKind::Value,
+            };
+
+            Some(Tag {
+                kind,
+                name: String::from(captures.get(2)?.as_str()),
+                paragraph_index,
+                start: whole.start(),
+                end: whole.end(),
+            })
+        })
+        .collect()
+}
+
+fn matching_section_end(tags: &[Tag], start_index: usize) -> Option<usize> {
+    let name = &tags[start_index].name;
+    let mut depth = 0;
+    for (index, tag) in tags.iter().enumerate().skip(start_index + 1) {
+        if &tag.name != name {
+            continue;
+        }
+
+        match tag.kind {
+            TagKind::Section | TagKind::InvertedSection => depth += 1,
+            TagKind::SectionEnd if depth == 0 => return Some(index),
+            TagKind::SectionEnd => depth -= 1,
+            TagKind::Value => (),
+        }
+    }
+
+    None
+}
+
+fn section_items(value: Option<&TemplateValue>, is_inverted: bool) -> Vec<Option<&TemplateValue>> {
+    let items = match value {
+        Some(TemplateValue::List(items)) => items.iter().map(Some).collect(),
+        Some(TemplateValue::Bool(false)) | None => Vec::new(),
+        Some(TemplateValue::Text(text)) if text.is_empty() => Vec::new(),
+        Some(value) => vec![Some(value)],
+    };
+
+    match (is_inverted, items.is_empty()) {
+        (true, true) => vec![None],
+        (true, false) => Vec::new(),
+        (false, _) => items,
+    }
+}
+
+fn remove_tag<T: TemplateElement>(element: &mut T, tag: &Tag) {
+    if let Some(paragraph) = element.paragraphs_mut().into_iter().nth(tag.paragraph_index) {
+        let paragraph_match = match ParagraphText::from_paragraph(paragraph).paragraph_match(tag.start, tag.end) {
+            Some(paragraph_match) => paragraph_match,
+            None => return,
+        };
+
+        let pattern = SearchPattern::Text(String::new());
+        Replacer::new(&pattern, "", Default::default())
+            .replace_paragraph_matches(paragraph, vec![(paragraph_match, String::new())]);
+    }
+}
+
+fn is_blank_paragraph(paragraph: &P) -> bool {
+    paragraph_runs(paragraph).into_iter().all(|run| {
+        run.run_inner_contents.iter().all(|content| match content {
+            RunInnerContent::Text(text) => text.text.is_empty(),
+            _ => false,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TemplateData, TemplateError, TemplateRenderer, TemplateValue};
+    use crate::{
+        plaintext::PlainTextOptions,
+        test_util::{
+            complex_field, package_with_blocks, package_with_paragraphs, paragraph, paragraph_block, text_box_run,
+            text_run,
+        },
+        wml::{
+            document::{BlockLevelElts, ContentBlockContent},
+            headerfooter::HeaderFooter,
+            table::{ContentCellContent, ContentRowContent, Row, Tbl, Tc},
+        },
+    };
+
+    fn row(cell_texts: &[&str]) -> ContentRowContent {
+        ContentRowContent::Table(Box::new(Row {
+            property_exceptions: None,
+            properties: None,
+            contents: cell_texts
+                .iter()
+                .map(|text| {
+                    ContentCellContent::Cell(Box::new(Tc {
+                        properties: None,
+                        block_level_elements: vec![paragraph_block(paragraph(vec![text_run(text)]))],
+                        id: None,
+                    }))
+                })
+                .collect(),
+            run_properties_revision_id: None,
+            run_revision_id: None,
+            deletion_revision_id: None,
+            row_revision_id: None,
+        }))
+    }
+
+    fn data_for_test() -> TemplateData {
+        let item = |name: &str, price: &str| {
+            let mut item = TemplateData::new();
+            item.insert(String::from("name"), TemplateValue::from(name));
+            item.insert(String::from("price"), TemplateValue::from(price));
+            TemplateValue::from(item)
+        };
+
+        let mut customer = TemplateData::new();
+        customer.insert(String::from("name"), TemplateValue::from("Jane Doe"));
+
+        let mut data = TemplateData::new();
+        data.insert(String::from("customer"), TemplateValue::from(customer));
+        data.insert(
+            String::from("items"),
+            TemplateValue::from(vec![item("Lamp", "20"), item("Desk", "150")]),
+        );
+        data.insert(String::from("vip"), TemplateValue::from(false));
+        data
+    }
+
+    #[test]
+    pub fn test_render_values_and_paragraph_sections() {
+        let mut package = package_with_paragraphs(vec![
+            paragraph(vec![text_run("Dear {{cust"), text_run("omer.na"), text_run("me}},")]),
+            paragraph(vec![text_run("{{#vip}}")]),
+            paragraph(vec![text_run("Thank you for being a VIP customer.")]),
+            paragraph(vec![text_run("{{/vip}}")]),
+            paragraph(vec![text_run("{{#items}}")]),
+            paragraph(vec![text_run("{{name}}: {{price}} {{currency}}")]),
+            paragraph(vec![text_run("{{/items}}")]),
+            paragraph(vec![text_run(
+                "{{^vip}}Join our club!{{/vip}} Items: {{#items}}{{name}} {{/items}}",
+            )]),
+        ]);
+
+        let data = data_for_test();
+        let mut renderer = TemplateRenderer::new(&data);
+        renderer.render_package(&mut package).unwrap();
+
+        assert_eq!(
+            package.extract_text(PlainTextOptions::default()),
+            "Dear Jane Doe,\nLamp: 20 \nDesk: 150 \nJoin our club! Items: Lamp Desk \n"
+        );
+        assert_eq!(renderer.missing_values(), &[String::from("currency")]);
+    }
+
+    #[test]
+    pub fn test_render_table_rows_and_header() {
+        let table = Tbl {
+            range_markup_elements: Vec::new(),
+            properties: Default::default(),
+            grid: Default::default(),
+            row_contents: vec![
+                row(&["Item", "Price"]),
+                row(&["{{#items}}{{name}}", "{{price}}{{/items}}"]),
+                row(&["Total", "170"]),
+            ],
+        };
+
+        let mut package = package_with_blocks(vec![BlockLevelElts::Chunk(ContentBlockContent::Table(Box::new(table)))]);
+        package.headers.insert(
+            String::from("header1.xml"),
+            HeaderFooter {
+                block_level_elements: vec![paragraph_block(paragraph(vec![text_run(
+                    "Invoice for {{customer.name}}",
+                )]))],
+            },
+        );
+
+        package.render_template(&data_for_test()).unwrap();
+
+        assert_eq!(
+            package.extract_text(PlainTextOptions::default()),
+            "Item\tPrice\nLamp\t20\nDesk\t150\nTotal\t170\n"
+        );
+        assert_eq!(
+            package.headers["header1.xml"].block_level_elements,
+            vec![paragraph_block(paragraph(vec![text_run("Invoice for Jane Doe")]))]
+        );
+    }
+
+    #[test]
+    pub fn test_render_unbalanced_sections() {
+        let data = data_for_test();
+        let mut unclosed = package_with_paragraphs(vec![
+            paragraph(vec![text_run("{{#items}}")]),
+            paragraph(vec![text_run("{{name}}")]),
+        ]);
+        assert_eq!(
+            unclosed.render_template(&data),
+            Err(TemplateError::UnclosedSection(String::from("items")))
+        );
+
+        let mut unexpected_end = package_with_paragraphs(vec![paragraph(vec![text_run("{{name}}{{/items}}")])]);
+        assert_eq!(
+            TemplateRenderer::new(&data).render_package(&mut unexpected_end),
+            Err(TemplateError::UnexpectedSectionEnd(String::from("items")))
+        );
+    }
+
+    #[test]
+    pub fn test_render_text_boxes_in_every_story() {
+        let text_box_paragraph = |text: &str| paragraph(vec![text_box_run(vec![paragraph(vec![text_run(text)])])]);
+        let mut package = package_with_paragraphs(vec![
+            paragraph(vec![text_run("{{#items}}")]),
+            text_box_paragraph("{{name}}"),
+            paragraph(vec![text_run("{{/items}}")]),
+        ]);
+        package.headers.insert(
+            String::from("header1.xml"),
+            HeaderFooter {
+                block_level_elements: vec![paragraph_block(text_box_paragraph("Invoice for {{customer.name}}"))],
+            },
+        );
+
+        package.render_template(&data_for_test()).unwrap();
+
+        assert_eq!(
+            package.main_document.unwrap().body.unwrap().block_level_elements,
+            vec![
+                paragraph_block(text_box_paragraph("Lamp")),
+                paragraph_block(text_box_paragraph("Desk"))
+            ]
+        );
+        assert_eq!(
+            package.headers["header1.xml"].block_level_elements,
+            vec![paragraph_block(text_box_paragraph("Invoice for Jane Doe"))]
+        );
+    }
+
+    #[test]
+    pub fn test_render_placeholders_across_fields_unresolved() {
+        let mut contents = vec![text_run("{{customer.name}}, page {{cust")];
+        contents.extend(complex_field(" PAGE ", "omer.name}}"));
+        let mut package = package_with_paragraphs(vec![paragraph(contents)]);
+
+        let data = data_for_test();
+        let mut renderer = TemplateRenderer::new(&data);
+        renderer.render_package(&mut package).unwrap();
+
+        assert_eq!(renderer.unresolved_placeholders(), &[String::from("{{customer.name}}")]);
+        assert_eq!(
+            package.extract_text(PlainTextOptions::default()),
+            "Jane Doe, page {{customer.name}}\n"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+// Builders for the documents used by the tests of several modules
+
+use crate::{
+    package::Package,
+    wml::{
+        document::{
+            BlockLevelElts, Body, Bookmark, BookmarkRange, ContentBlockContent, ContentRunContent, Document, Drawing,
+            DrawingChoice, FldChar, FldCharType, Markup, MarkupRange, PContent, PPr, PPrBase, RPr, RPrBase,
+            RangeMarkupElements, RunInnerContent, RunLevelElts, Text, P, R,
+        },
+        drawing::{
+            GraphicDataContent, Inline, TextboxInfo, TxbxContent, WordprocessingShape,
+            WordprocessingShapeTextboxInfoChoice,
+        },
+        styles::{Style, StyleType, Styles},
+    },
+};
+
+pub(crate) fn text(text: &str) -> RunInnerContent {
+    RunInnerContent::Text(Text {
+        text: String::from(text),
+        xml_space: None,
+    })
+}
+
+pub(crate) fn instruction(instruction: &str) -> RunInnerContent {
+    RunInnerContent::InstructionText(Text {
+        text: String::from(instruction),
+        xml_space: None,
+    })
+}
+
+pub(crate) fn field_character(field_char_type: FldCharType) -> RunInnerContent {
+    RunInnerContent::FieldCharacter(FldChar {
+        form_field_properties: Vec::new(),
+        field_char_type,
+        field_lock: None,
+        dirty: None,
+    })
+}
+
+pub(crate) fn run(inner_contents: Vec<RunInnerContent>) -> PContent {
+    run_content(R {
+        run_inner_contents: inner_contents,
+        ..Default::default()
+    })
+}
+
+pub(crate) fn text_run(text_content: &str) -> PContent {
+    run(vec![text(text_content)])
+}
+
+pub(crate) fn formatted_run(text_content: &str, r_pr_bases: Vec<RPrBase>) -> PContent {
+    run_content(R {
+        run_properties: Some(RPr {
+            r_pr_bases,
+            ..Default::default()
+        }),
+        run_inner_contents: vec![text(text_content)],
+        ..Default::default()
+    })
+}
+
+pub(crate) fn run_content(run: R) -> PContent {
+    PContent::ContentRunContent(Box::new(ContentRunContent::Run(run)))
+}
+
+// A complex field with every field character, the instruction and the result in a run of its own
+pub(crate) fn complex_field(instruction_text: &str, result: &str) -> Vec<PContent> {
+    vec![
+        run(vec![field_character(FldCharType::Begin)]),
+        run(vec![instruction(instruction_text)]),
+        run(vec![field_character(FldCharType::Separate)]),
+        text_run(result),
+        run(vec![field_character(FldCharType::End)]),
+    ]
+}
+
+// A run with an inline drawing of a shape holding a text box with the given paragraphs
+pub(crate) fn text_box_run(paragraphs: Vec<P>) -> PContent {
+    let shape = WordprocessingShape {
+        text_box_info: Some(WordprocessingShapeTextboxInfoChoice::Textbox(TextboxInfo {
+            textbox_content: TxbxContent {
+                block_level_elements: paragraphs.into_iter().map(paragraph_block).collect(),
+            },
+            id: None,
+        })),
+        ..WordprocessingShape::test_instance()
+    };
+    let inline = Inline {
+        graphic_data_content: Some(GraphicDataContent::Shape(Box::new(shape))),
+        ..Inline::test_instance()
+    };
+
+    run(vec![RunInnerContent::Drawing(Drawing(vec![DrawingChoice::Inline(
+        inline,
+    )]))])
+}
+
+fn bookmark_marker(range_markup: RangeMarkupElements) -> PContent {
+    PContent::ContentRunContent(Box::new(ContentRunContent::RunLevelElements(
+        RunLevelElts::RangeMarkupElements(range_markup),
+    )))
+}
+
+pub(crate) fn bookmark_start(id: i32, name: &str) -> PContent {
+    bookmark_marker(RangeMarkupElements::BookmarkStart(Bookmark {
+        base: BookmarkRange {
+            base: MarkupRange {
+                base: Markup { id },
+                displaced_by_custom_xml: None,
+            },
+            first_column: None,
+            last_column: None,
+        },
+        name: String::from(name),
+    }))
+}
+
+pub(crate) fn bookmark_end(id: i32) -> PContent {
+    bookmark_marker(RangeMarkupElements::BookmarkEnd(MarkupRange {
+        base: Markup { id },
+        displaced_by_custom_xml: None,
+    }))
+}
+
+pub(crate) fn paragraph(contents: Vec<PContent>) -> P {
+    P {
+        contents,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn styled_paragraph(contents: Vec<PContent>, style: Option<&str>, outline_level: Option<i32>) -> P {
+    P {
+        properties: Some(PPr {
+            base: PPrBase {
+                style: style.map(String::from),
+                outline_level,
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        contents,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn paragraph_block(paragraph: P) -> BlockLevelElts {
+    BlockLevelElts::Chunk(ContentBlockContent::Paragraph(Box::new(paragraph)))
+}
+
+pub(crate) fn package_with_blocks(block_level_elements: Vec<BlockLevelElts>) -> Package {
+    Package {
+        main_document: Some(Box::new(Document {
+            body: Some(Body {
+                block_level_elements,
+                section_properties: None,
+            }),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn package_with_paragraphs(paragraphs: Vec<P>) -> Package {
+    package_with_blocks(paragraphs.into_iter().map(paragraph_block).collect())
+}
+
+pub(crate) fn style(style_id: &str, style_type: StyleType, based_on: Option<&str>, r_pr_bases: Vec<RPrBase>) -> Style {
+    Style {
+        style_id: Some(String::from(style_id)),
+        based_on: based_on.map(String::from),
+        style_type: Some(style_type),
+        run_properties: Some(RPr {
+            r_pr_bases,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn package_with_styles(styles: Vec<Style>) -> Package {
+    Package {
+        styles: Some(Box::new(Styles {
+            document_defaults: None,
+            latent_styles: None,
+            styles,
+        })),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn body_paragraphs(package: &Package) -> Vec<&P> {
+    let body = package.main_document.as_ref().unwrap().body.as_ref().unwrap();
+    body.block_level_elements
+        .iter()
+        .filter_map(|element| match element {
+            BlockLevelElts::Chunk(ContentBlockContent::Paragraph(paragraph)) => Some(paragraph.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
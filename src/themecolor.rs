@@ -0,0 +1,552 @@
+use crate::{
+    package::Package,
+    resolvedstyle::RunProperties,
+    styleresolver::StyleResolver,
+    wml::{
+        document::{Background, Border, Color, HexColor, Shd, ShdType, ThemeColor, Underline, P, R},
+        settings::{ColorSchemeMapping, WmlColorSchemeIndex},
+    },
+};
+use msoffice_shared::drawingml::{
+    colors::Color as DrawingColor, sharedstylesheet::ColorScheme, simpletypes::HexColorRGB,
+};
+
+const BLACK: HexColorRGB = [0, 0, 0];
+const WHITE: HexColorRGB = [0xff, 0xff, 0xff];
+
+// The final colors of a run. The text color is always known, since auto resolves to black or white.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunColors {
+    pub text: HexColorRGB,
+    pub underline: Option<HexColorRGB>,
+    pub highlight: Option<HexColorRGB>,
+    pub shading: Option<HexColorRGB>,
+    pub border: Option<HexColorRGB>,
+}
+
+// Maps theme colors to sRGB using the color scheme of the main document's theme. The settings' color scheme mapping
+// decides which scheme colors the background and text theme colors refer to.
+pub struct ColorResolver<'a> {
+    color_scheme: Option<&'a ColorScheme>,
+    color_scheme_mapping: Option<&'a ColorSchemeMapping>,
+}
+
+impl<'a> ColorResolver<'a> {
+    pub fn new(package: &'a Package) -> Self {
+        Self {
+            color_scheme: package
+                .get_main_document_theme()
+                .map(|theme| &*theme.theme_elements.color_scheme),
+            color_scheme_mapping: package
+                .settings
+                .as_ref()
+                .and_then(|settings| settings.color_scheme_mapping.as_ref()),
+        }
+    }
+
+    pub fn theme_color(&self, theme_color: ThemeColor) -> Option<HexColorRGB> {
+        let mapping = self.color_scheme_mapping;
+        let scheme_index = match theme_color {
+            ThemeColor::Dark1 => WmlColorSchemeIndex::Dark1,
+            ThemeColor::Light1 => WmlColorSchemeIndex::Light1,
+            ThemeColor::Dark2 => WmlColorSchemeIndex::Dark2,
+            ThemeColor::Light2 => WmlColorSchemeIndex::Light2,
+            ThemeColor::Background1 => mapping.map_or(WmlColorSchemeIndex::Light1, |mapping| mapping.background1),
+            ThemeColor::Text1 => mapping.map_or(WmlColorSchemeIndex::Dark1, |mapping| mapping.text1),
+            ThemeColor::Background2 => mapping.map_or(WmlColorSchemeIndex::Light2, |mapping| mapping.background2),
+            ThemeColor::Text2 => mapping.map_or(WmlColorSchemeIndex::Dark2, |mapping| mapping.text2),
+            ThemeColor::Accent1 => mapping.map_or(WmlColorSchemeIndex::Accent1, |mapping| mapping.accent1),
+            ThemeColor::Accent2 => mapping.map_or(WmlColorSchemeIndex::Accent2, |mapping| mapping.accent2),
+            ThemeColor::Accent3 => mapping.map_or(WmlColorSchemeIndex::Accent3, |mapping| mapping.accent3),
+            ThemeColor::Accent4 => mapping.map_or(WmlColorSchemeIndex::Accent4, |mapping| mapping.accent4),
+            ThemeColor::Accent5 => mapping.map_or(WmlColorSchemeIndex::Accent5, |mapping| mapping.accent5),
+            ThemeColor::Accent6 => mapping.map_or(WmlColorSchemeIndex::Accent6, |mapping| mapping.accent6),
+            ThemeColor::Hyperlink => mapping.map_or(WmlColorSchemeIndex::Hyperlink, |mapping| mapping.hyperlink),
+            ThemeColor::FollowedHyperlink => mapping.map_or(WmlColorSchemeIndex::FollowedHyperlink, |mapping| {
+                mapping.followed_hyperlink
+            }),
+            ThemeColor::None => return None,
+        };
+
+        let color_scheme = self.color_scheme?;
+        let scheme_color = match scheme_index {
+            WmlColorSchemeIndex::Dark1 => &color_scheme.dark1,
+            WmlColorSchemeIndex::Light1 => &color_scheme.light1,
+            WmlColorSchemeIndex::Dark2 => &color_scheme.dark2,
+            WmlColorSchemeIndex::Light2 => &color_scheme.light2,
+            WmlColorSchemeIndex::Accent1 => &color_scheme.accent1,
+            WmlColorSchemeIndex::Accent2 => &color_scheme.accent2,
+            WmlColorSchemeIndex::Accent3 => &color_scheme.accent3,
+            WmlColorSchemeIndex::Accent4 => &color_scheme.accent4,
+            WmlColorSchemeIndex::Accent5 => &color_scheme.accent5,
+            WmlColorSchemeIndex::Accent6 => &color_scheme.accent6,
+            WmlColorSchemeIndex::Hyperlink => &color_scheme.hyperlink,
+            WmlColorSchemeIndex::FollowedHyperlink => &color_scheme.followed_hyperlink,
+        };
+
+        drawing_color_to_rgb(scheme_color)
+    }
+
+    // The theme color takes precedence over the value if it can be resolved. Auto is kept, since its meaning depends
+    // on the colored property.
+    pub fn resolve_color_value(
+        &self,
+        value: Option<HexColor>,
+        theme_color: Option<ThemeColor>,
+        theme_tint: Option<u8>,
+        theme_shade: Option<u8>,
+    ) -> Option<HexColor> {
+        theme_color
+            .and_then(|theme_color| self.theme_color(theme_color))
+            .map(|rgb| HexColor::RGB(apply_tint_and_shade(rgb, theme_tint, theme_shade)))
+            .or(value)
+    }
+
+    // Auto text is black, or white on a dark background
+    pub fn resolve_text_color(&self, color: Option<&Color>, background: Option<HexColorRGB>) -> HexColorRGB {
+        let value = color.and_then(|color| {
+            self.resolve_color_value(
+                Some(color.value),
+                color.theme_color,
+                color.theme_tint,
+                color.theme_shade,
+            )
+        });
+
+        match value {
+            Some(HexColor::RGB(rgb)) => rgb,
+            _ => auto_color(background),
+        }
+    }
+
+    // Auto underlines have the color of the text
+    pub fn resolve_underline_color(&self, underline: &Underline, text_color: HexColorRGB) -> HexColorRGB {
+        let value = self.resolve_color_value(
+            underline.color,
+            underline.theme_color,
+            underline.theme_tint,
+            underline.theme_shade,
+        );
+
+        match value {
+            Some(HexColor::RGB(rgb)) => rgb,
+            _ => text_color,
+        }
+    }
+
+    // Auto borders are black
+    pub fn resolve_border_color(&self, border: &Border) -> HexColorRGB {
+        let value = self.resolve_color_value(border.color, border.theme_color, border.theme_tint, border.theme_shade);
+
+        match value {
+            Some(HexColor::RGB(rgb)) => rgb,
+            _ => BLACK,
+        }
+    }
+
+    // The color the shading appears in, with the pattern color mixed into the fill by the percentage of the pattern.
+    // An auto fill is transparent, an auto pattern color is black. Returns None for no shading.
+    pub fn resolve_shading_color(&self, shading: &Shd) -> Option<HexColorRGB> {
+        let fill = match self.resolve_color_value(
+            shading.fill,
+            shading.theme_fill,
+            shading.theme_fill_tint,
+            shading.theme_fill_shade,
+        ) {
+            Some(HexColor::RGB(rgb)) => Some(rgb),
+            _ => None,
+        };
+        let pattern_color = match self.resolve_color_value(
+            shading.color,
+            shading.theme_color,
+            shading.theme_tint,
+            shading.theme_shade,
+        ) {
+            Some(HexColor::RGB(rgb)) => rgb,
+            _ => BLACK,
+        };
+
+        match shading_pattern_percentage(shading.value) {
+            None => None,
+            Some(0) => fill,
+            Some(100) => Some(pattern_color),
+            Some(percentage) => Some(mix_colors(fill.unwrap_or(WHITE), pattern_color, percentage)),
+        }
+    }
+
+    // An auto page background is white
+    pub fn resolve_background_color(&self, background: &Background) -> HexColorRGB {
+        let value = self.resolve_color_value(
+            background.color,
+            background.theme_color,
+            background.theme_tint,
+            background.theme_shade,
+        );
+
+        match value {
+            Some(HexColor::RGB(rgb)) => rgb,
+            _ => WHITE,
+        }
+    }
+
+    // Auto text is chosen against the highlight, or else the shading of the run
+    pub fn resolve_run_colors(&self, run_properties: &RunProperties) -> RunColors {
+        let highlight = run_properties.highlight.and_then(|highlight| highlight.to_rgb());
+        let shading = run_properties
+            .shading
+            .as_ref()
+            .and_then(|shading| self.resolve_shading_color(shading));
+        let text = self.resolve_text_color(run_properties.color.as_ref(), highlight.or(shading));
+
+        RunColors {
+            text,
+            underline: run_properties
+                .underline
+                .as_ref()
+                .map(|underline| self.resolve_underline_color(underline, text)),
+            highlight,
+            shading,
+            border: run_properties
+                .border
+                .as_ref()
+                .map(|border| self.resolve_border_color(border)),
+        }
+    }
+
+    // The colors of a run after resolving its style hierarchy
+    pub fn resolve_styled_run_colors(&self, style_resolver: &mut StyleResolver, paragraph: &P, run: &R) -> RunColors {
+        let run_properties = style_resolver
+            .resolve_style_inheritance(paragraph, run)
+            .map(|resolved_style| *resolved_style.run_properties)
+            .unwrap_or_default();
+
+        self.resolve_run_colors(&run_properties)
+    }
+}
+
+// Tint and shade are applied to the luminance of the color in HSL space, the way Word does it
+pub fn apply_tint_and_shade(rgb: HexColorRGB, theme_tint: Option<u8>, theme_shade: Option<u8>) -> HexColorRGB {
+    if theme_tint.is_none() && theme_shade.is_none() {
+        return rgb;
+    }
+
+    let (hue, saturation, mut luminance) = rgb_to_hsl(rgb);
+    if let Some(theme_shade) = theme_shade {
+        luminance *= f64::from(theme_shade) / 255.0;
+    }
+
+    if let Some(theme_tint) = theme_tint {
+        let tint = f64::from(theme_tint) / 255.0;
+        luminance = luminance * tint + (1.0 - tint);
+    }
+
+    hsl_to_rgb(hue, saturation, luminance.clamp(0.0, 1.0))
+}
+
+fn auto_color(background: Option<HexColorRGB>) -> HexColorRGB {
+    match background {
+        Some([r, g, b]) if 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b) < 128.0 => WHITE,
+        _ => BLACK,
+    }
+}
+
+fn shading_pattern_percentage(pattern: ShdType) -> Option<u8> {
+    let percentage = match pattern {
+        ShdType::Nil => return None,
+        ShdType::Clear => 0,
+        ShdType::Solid => 100,
+        ShdType::Percent5 => 5,
+        ShdType::Percent10 => 10,
+        ShdType::Percent12 => 12,
+        ShdType::Percent15 => 15,
+        ShdType::Percent20 => 20,
+        ShdType::Percent25 => 25,
+        ShdType::Percent30 => 30,
+        ShdType::Percent35 => 35,
+        ShdType::Percent37 => 37,
+        ShdType::Percent40 => 40,
+        ShdType::Percent45 => 45,
+        ShdType::Percent50 => 50,
+        ShdType::Percent55 => 55,
+        ShdType::Percent60 => 60,
+        ShdType::Percent62 => 62,
+        ShdType::Percent65 => 65,
+        ShdType::Percent70 => 70,
+        ShdType::Percent75 => 75,
+        ShdType::Percent80 => 80,
+        ShdType::Percent85 => 85,
+        ShdType::Percent87 => 87,
+        ShdType::Percent90 => 90,
+        ShdType::Percent95 => 95,
+        // Stripes and crosses cover about a quarter of the area with thin lines and half with thick ones
+        ShdType::ThinHorizontalStripe
+        | ShdType::ThinVerticalStripe
+        | ShdType::ThinReverseDiagonalStripe
+        | ShdType::ThinDiagonalStripe
+        | ShdType::ThinHorizontalCross
+        | ShdType::ThinDiagonalCross => 25,
+        ShdType::HorizontalStripe
+        | ShdType::VerticalStripe
+        | ShdType::ReverseDiagonalStripe
+        | ShdType::DiagonalStripe
+        | ShdType::HorizontalCross
+        | ShdType::DiagonalCross => 50,
+    };
+
+    Some(percentage)
+}
+
+fn mix_colors(background: HexColorRGB, foreground: HexColorRGB, percentage: u8) -> HexColorRGB {
+    let ratio = f64::from(percentage) / 100.0;
+    let mix = |background: u8, foreground: u8| {
+        (f64::from(background) * (1.0 - ratio) + f64::from(foreground) * ratio).round() as u8
+    };
+
+    [
+        mix(background[0], foreground[0]),
+        mix(background[1], foreground[1]),
+        mix(background[2], foreground[2]),
+    ]
+}
+
+// Color transforms of the scheme colors are ignored, preset colors aren't supported
+fn drawing_color_to_rgb(color: &DrawingColor) -> Option<HexColorRGB> {
+    match color {
+        DrawingColor::SRgbColor(srgb_color) => {
+            let value = srgb_color.value;
+            Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+        }
+        DrawingColor::SystemColor(system_color) => system_color.last_color,
+        DrawingColor::ScRgbColor(scrgb_color) => {
+            // Components are linear and given in 1000th of a percent
+            let to_srgb = |component: f32| {
+                let linear = (f64::from(component) / 100_000.0).clamp(0.0, 1.0);
+                let srgb = if linear <= 0.003_130_8 {
+                    linear * 12.92
+                } else {
+                    1.055 * linear.powf(1.0 / 2.4) - 0.055
+                };
+                (srgb * 255.0).round() as u8
+            };
+            Some([to_srgb(scrgb_color.r), to_srgb(scrgb_color.g), to_srgb(scrgb_color.b)])
+        }
+        DrawingColor::HslColor(hsl_color) => Some(hsl_to_rgb(
+            f64::from(hsl_color.hue) / 60_000.0,
+            f64::from(hsl_color.saturation) / 100_000.0,
+            f64::from(hsl_color.luminance) / 100_000.0,
+        )),
+        DrawingColor::SchemeColor(_) | DrawingColor::PresetColor(_) => None,
+    }
+}
+
+// Hue is in degrees, saturation and luminance are in the 0..=1 range
+fn rgb_to_hsl(rgb: HexColorRGB) -> (f64, f64, f64) {
+    let [r, g, b] = [
+        f64::from(rgb[0]) / 255.0,
+        f64::from(rgb[1]) / 255.0,
+        f64::from(rgb[2]) / 255.0,
+    ];
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let luminance = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta <= f64::EPSILON {
+        return (0.0, 0.0, luminance);
+    }
+
+    let saturation = if luminance < 0.5 {
+        delta / (max + min)
+    } else {
+        delta / (2.0 - max - min)
+    };
+
+    let hue = if (max - r).abs() <= f64::EPSILON {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if (max - g).abs() <= f64::EPSILON {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+
+    (hue * 60.0, saturation, luminance)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, luminance: f64) -> HexColorRGB {
+    let chroma = (1.0 - (2.0 * luminance - 1.0).abs()) * saturation;
+    let hue_sector = hue.rem_euclid(360.0) / 60.0;
+    let second = chroma * (1.0 - (hue_sector.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match hue_sector as u8 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let lightness_match = luminance - chroma / 2.0;
+    let to_component = |value: f64| ((value + lightness_match) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    [to_component(r), to_component(g), to_component(b)]
+}
+
+impl Package {
+    pub fn color_resolver(&self) -> ColorResolver<'_> {
+        ColorResolver::new(self)
+    }
+
+    pub fn resolve_theme_color(&self, theme_color: ThemeColor) -> Option<HexColorRGB> {
+        self.color_resolver().theme_color(theme_color)
+    }
+
+    pub fn resolve_page_background_color(&self) -> Option<HexColorRGB> {
+        let background = self.main_document.as_ref()?.base.background.as_ref()?;
+        Some(self.color_resolver().resolve_background_color(background))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_tint_and_shade, ColorResolver, RunColors};
+    use crate::{
+        resolvedstyle::RunProperties,
+        wml::{
+            document::{Color, HexColor, HighlightColor, Shd, ShdType, ThemeColor, Underline, UnderlineType},
+            settings::{ColorSchemeMapping, WmlColorSchemeIndex},
+        },
+    };
+    use msoffice_shared::{drawingml::sharedstylesheet::ColorScheme, xml::XmlNode};
+
+    fn color_scheme_for_test() -> ColorScheme {
+        let xml = r#"<a:clrScheme name="Office">
+            <a:dk1><a:sysClr val="windowText" lastClr="000000"/></a:dk1>
+            <a:lt1><a:sysClr val="window" lastClr="FFFFFF"/></a:lt1>
+            <a:dk2><a:srgbClr val="44546A"/></a:dk2>
+            <a:lt2><a:srgbClr val="E7E6E6"/></a:lt2>
+            <a:accent1><a:srgbClr val="4472C4"/></a:accent1>
+            <a:accent2><a:srgbClr val="ED7D31"/></a:accent2>
+            <a:accent3><a:srgbClr val="A5A5A5"/></a:accent3>
+            <a:accent4><a:srgbClr val="FFC000"/></a:accent4>
+            <a:accent5><a:srgbClr val="5B9BD5"/></a:accent5>
+            <a:accent6><a:srgbClr val="70AD47"/></a:accent6>
+            <a:hlink><a:srgbClr val="0563C1"/></a:hlink>
+            <a:folHlink><a:srgbClr val="954F72"/></a:folHlink>
+        </a:clrScheme>"#;
+
+        ColorScheme::from_xml_element(&XmlNode::from_str(xml).unwrap()).unwrap()
+    }
+
+    fn inverted_mapping() -> ColorSchemeMapping {
+        ColorSchemeMapping {
+            background1: WmlColorSchemeIndex::Dark1,
+            text1: WmlColorSchemeIndex::Light1,
+            background2: WmlColorSchemeIndex::Dark2,
+            text2: WmlColorSchemeIndex::Light2,
+            accent1: WmlColorSchemeIndex::Accent1,
+            accent2: WmlColorSchemeIndex::Accent2,
+            accent3: WmlColorSchemeIndex::Accent3,
+            accent4: WmlColorSchemeIndex::Accent4,
+            accent5: WmlColorSchemeIndex::Accent5,
+            accent6: WmlColorSchemeIndex::Accent6,
+            hyperlink: WmlColorSchemeIndex::Hyperlink,
+            followed_hyperlink: WmlColorSchemeIndex::FollowedHyperlink,
+        }
+    }
+
+    #[test]
+    pub fn test_theme_color() {
+        let color_scheme = color_scheme_for_test();
+        let resolver = ColorResolver {
+            color_scheme: Some(&color_scheme),
+            color_scheme_mapping: None,
+        };
+        assert_eq!(resolver.theme_color(ThemeColor::Text1), Some([0, 0, 0]));
+        assert_eq!(resolver.theme_color(ThemeColor::Background2), Some([0xe7, 0xe6, 0xe6]));
+        assert_eq!(resolver.theme_color(ThemeColor::Accent1), Some([0x44, 0x72, 0xc4]));
+        assert_eq!(resolver.theme_color(ThemeColor::None), None);
+
+        let mapping = inverted_mapping();
+        let resolver = ColorResolver {
+            color_scheme: Some(&color_scheme),
+            color_scheme_mapping: Some(&mapping),
+        };
+        assert_eq!(resolver.theme_color(ThemeColor::Text1), Some([0xff, 0xff, 0xff]));
+        assert_eq!(resolver.theme_color(ThemeColor::Background2), Some([0x44, 0x54, 0x6a]));
+        assert_eq!(resolver.theme_color(ThemeColor::Dark1), Some([0, 0, 0]));
+    }
+
+    #[test]
+    pub fn test_apply_tint_and_shade() {
+        let accent1 = [0x44, 0x72, 0xc4];
+        assert_eq!(apply_tint_and_shade(accent1, None, None), accent1);
+        // Accent 1, lighter 80%
+        assert_eq!(apply_tint_and_shade(accent1, Some(0x33), None), [0xda, 0xe3, 0xf3]);
+        // Accent 1, darker 25%
+        assert_eq!(apply_tint_and_shade(accent1, None, Some(0xbf)), [0x2f, 0x54, 0x96]);
+    }
+
+    #[test]
+    pub fn test_resolve_run_colors() {
+        let color_scheme = color_scheme_for_test();
+        let resolver = ColorResolver {
+            color_scheme: Some(&color_scheme),
+            color_scheme_mapping: None,
+        };
+
+        let run_properties = RunProperties {
+            color: Some(Color {
+                value: HexColor::Auto,
+                theme_color: None,
+                theme_tint: None,
+                theme_shade: None,
+            }),
+            shading: Some(Shd {
+                value: ShdType::Clear,
+                color: Some(HexColor::Auto),
+                theme_color: None,
+                theme_tint: None,
+                theme_shade: None,
+                fill: Some(HexColor::RGB([0xff, 0xff, 0xff])),
+                theme_fill: Some(ThemeColor::Text2),
+                theme_fill_tint: None,
+                theme_fill_shade: None,
+            }),
+            underline: Some(Underline {
+                value: Some(UnderlineType::Single),
+                color: Some(HexColor::Auto),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolver.resolve_run_colors(&run_properties),
+            RunColors {
+                text: [0xff, 0xff, 0xff],
+                underline: Some([0xff, 0xff, 0xff]),
+                highlight: None,
+                shading: Some([0x44, 0x54, 0x6a]),
+                border: None,
+            }
+        );
+
+        let run_properties = RunProperties {
+            highlight: Some(HighlightColor::Yellow),
+            shading: Some(Shd {
+                value: ShdType::Percent50,
+                color: Some(HexColor::RGB([0, 0, 0])),
+                theme_color: None,
+                theme_tint: None,
+                theme_shade: None,
+                fill: Some(HexColor::Auto),
+                theme_fill: None,
+                theme_fill_tint: None,
+                theme_fill_shade: None,
+            }),
+            ..Default::default()
+        };
+        let run_colors = resolver.resolve_run_colors(&run_properties);
+        assert_eq!(run_colors.text, [0, 0, 0]);
+        assert_eq!(run_colors.highlight, Some([0xff, 0xff, 0]));
+        assert_eq!(run_colors.shading, Some([0x80, 0x80, 0x80]));
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    package::Package,
+    styleresolver::StyleResolver,
+    wml::document::{Fonts, Language, Theme, P, R},
+};
+use msoffice_shared::drawingml::styles::{FontCollection, FontScheme};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontSlot {
+    Ascii,
+    HighAnsi,
+    EastAsia,
+    ComplexScript,
+}
+
+// The concrete typefaces of a run per script slot
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvedFonts {
+    pub ascii: Option<String>,
+    pub high_ansi: Option<String>,
+    pub east_asia: Option<String>,
+    pub complex_script: Option<String>,
+}
+
+impl ResolvedFonts {
+    pub fn font(&self, slot: FontSlot) -> Option<&str> {
+        match slot {
+            FontSlot::Ascii => self.ascii.as_deref(),
+            FontSlot::HighAnsi => self.high_ansi.as_deref(),
+            FontSlot::EastAsia => self.east_asia.as_deref(),
+            FontSlot::ComplexScript => self.complex_script.as_deref(),
+        }
+    }
+}
+
+// Maps theme fonts to the typefaces of the main document's theme. The supplemental fonts of the theme are chosen by
+// the script of the language set for the slot in the themeFontLang setting.
+pub struct ThemeFontResolver<'a> {
+    font_scheme: Option<&'a FontScheme>,
+    theme_font_language: Option<&'a Language>,
+}
+
+impl<'a> ThemeFontResolver<'a> {
+    pub fn new(package: &'a Package) -> Self {
+        Self {
+            font_scheme: package
+                .get_main_document_theme()
+                .map(|theme| &theme.theme_elements.font_scheme),
+            theme_font_language: package
+                .settings
+                .as_ref()
+                .and_then(|settings| settings.theme_font_lang.as_ref()),
+        }
+    }
+
+    pub fn theme_font(&self, theme: Theme) -> Option<String> {
+        let font_scheme = self.font_scheme?;
+        let (font_collection, slot): (&FontCollection, _) = match theme {
+            Theme::MajorAscii => (&font_scheme.major_font, FontSlot::Ascii),
+            Theme::MajorHighAnsi => (&font_scheme.major_font, FontSlot::HighAnsi),
+            Theme::MajorEastAsia => (&font_scheme.major_font, FontSlot::EastAsia),
+            Theme::MajorBidirectional => (&font_scheme.major_font, FontSlot::ComplexScript),
+            Theme::MinorAscii => (&font_scheme.minor_font, FontSlot::Ascii),
+            Theme::MinorHighAnsi => (&font_scheme.minor_font, FontSlot::HighAnsi),
+            Theme::MinorEastAsia => (&font_scheme.minor_font, FontSlot::EastAsia),
+            Theme::MinorBidirectional => (&font_scheme.minor_font, FontSlot::ComplexScript),
+        };
+
+        let supplemental_font = self
+            .theme_font_language
+            .and_then(|language| match slot {
+                FontSlot::Ascii | FontSlot::HighAnsi => language.value.as_ref(),
+                FontSlot::EastAsia => language.east_asia.as_ref(),
+                FontSlot::ComplexScript => language.bidirectional.as_ref(),
+            })
+            .and_then(|language| language_script(language))
+            .and_then(|script| {
+                font_collection
+                    .supplemental_font_list
+                    .iter()
+                    .find(|supplemental_font| supplemental_font.script == script)
+            })
+            .map(|supplemental_font| &supplemental_font.typeface);
+
+        let typeface = supplemental_font.unwrap_or(match slot {
+            FontSlot::Ascii | FontSlot::HighAnsi => &font_collection.latin.typeface,
+            FontSlot::EastAsia => &font_collection.east_asian.typeface,
+            FontSlot::ComplexScript => &font_collection.complex_script.typeface,
+        });
+
+        // An empty typeface means the theme doesn't define a font for the slot
+        Some(typeface.clone()).filter(|typeface| !typeface.is_empty())
+    }
+
+    // Theme fonts take precedence over font names of the same slot
+    pub fn resolve_fonts(&self, fonts: &Fonts) -> ResolvedFonts {
+        let resolve_slot = |name: &Option<String>, theme: Option<Theme>| {
+            theme.and_then(|theme| self.theme_font(theme)).or_else(|| name.clone())
+        };
+
+        ResolvedFonts {
+            ascii: resolve_slot(&fonts.ascii, fonts.ascii_theme),
+            high_ansi: resolve_slot(&fonts.high_ansi, fonts.high_ansi_theme),
+            east_asia: resolve_slot(&fonts.east_asia, fonts.east_asia_theme),
+            complex_script: resolve_slot(&fonts.complex_script, fonts.complex_script_theme),
+        }
+    }
+
+    // The typefaces of a run after resolving its style hierarchy
+    pub fn resolve_run_fonts(&self, style_resolver: &mut StyleResolver, paragraph: &P, run: &R) -> ResolvedFonts {
+        style_resolver
+            .resolve_style_inheritance(paragraph, run)
+            .and_then(|resolved_style| resolved_style.run_properties.fonts)
+            .map(|fonts| self.resolve_fonts(&fonts))
+            .unwrap_or_default()
+    }
+}
+
+// The ISO 15924 script code of a language tag as used by the supplemental fonts of a theme
+fn language_script(language: &str) -> Option<&'static str> {
+    let mut subtags = language.split(['-', '_']);
+    let primary_language = subtags.next()?.to_lowercase();
+    let region = subtags.next().map(str::to_uppercase);
+
+    let script = match (primary_language.as_str(), region.as_deref()) {
+        ("ja", _) => "Jpan",
+        ("zh", Some("TW")) | ("zh", Some("HK")) | ("zh", Some("MO")) | ("zh", Some("HANT")) => "Hant",
+        ("zh", _) => "Hans",
+        ("ko", _) => "Hang",
+        ("ar", _) | ("fa", _) | ("ur", _) | ("ps", _) | ("sd", _) => "Arab",
+        ("he", _) | ("yi", _) => "Hebr",
+        ("th", _) => "Thai",
+        ("hi", _) | ("mr", _) | ("ne", _) | ("sa", _) | ("kok", _) => "Deva",
+        ("bn", _) | ("as", _) => "Beng",
+        ("gu", _) => "Gujr",
+        ("pa", _) => "Guru",
+        ("or", _) => "Orya",
+        ("ta", _) => "Taml",
+        ("te", _) => "Telu",
+        ("kn", _) => "Knda",
+        ("ml", _) => "Mlym",
+        ("si", _) => "Sinh",
+        ("km", _) => "Khmr",
+        ("lo", _) => "Laoo",
+        ("my", _) => "Mymr",
+        ("bo", _) => "Tibt",
+        ("ka", _) => "Geor",
+        ("hy", _) => "Armn",
+        ("am", _) | ("ti", _) => "Ethi",
+        ("dv", _) => "Thaa",
+        ("syr", _) => "Syrc",
+        ("ug", _) => "Uigh",
+        ("chr", _) => "Cher",
+        ("iu", _) => "Cans",
+        ("ii", _) => "Yiii",
+        ("mn", Some("CN")) => "Mong",
+        ("vi", _) => "Viet",
+        _ => return None,
+    };
+
+    Some(script)
+}
+
+impl Package {
+    pub fn theme_font_resolver(&self) -> ThemeFontResolver<'_> {
+        ThemeFontResolver::new(self)
+    }
+
+    pub fn resolve_theme_font(&self, theme: Theme) -> Option<String> {
+        self.theme_font_resolver().theme_font(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResolvedFonts, ThemeFontResolver};
+    use crate::wml::document::{Fonts, Language, Theme};
+    use msoffice_shared::{drawingml::styles::FontScheme, xml::XmlNode};
+
+    fn font_scheme_for_test() -> FontScheme {
+        let xml = r#"<a:fontScheme name="Office">
+            <a:majorFont>
+                <a:latin typeface="Calibri Light"/>
+                <a:ea typeface=""/>
+                <a:cs typeface=""/>
+                <a:font script="Jpan" typeface="Yu Gothic Light"/>
+                <a:font script="Arab" typeface="Times New Roman"/>
+            </a:majorFont>
+            <a:minorFont>
+                <a:latin typeface="Calibri"/>
+                <a:ea typeface=""/>
+                <a:cs typeface="Arial"/>
+                <a:font script="Jpan" typeface="Yu Mincho"/>
+                <a:font script="Hebr" typeface="David"/>
+            </a:minorFont>
+        </a:fontScheme>"#;
+
+        FontScheme::from_xml_element(&XmlNode::from_str(xml).unwrap()).unwrap()
+    }
+
+    #[test]
+    pub fn test_theme_font() {
+        let font_scheme = font_scheme_for_test();
+        let language = Language {
+            value: Some(String::from("en-US")),
+            east_asia: Some(String::from("ja-JP")),
+            bidirectional: Some(String::from("ar-SA")),
+        };
+        let resolver = ThemeFontResolver {
+            font_scheme: Some(&font_scheme),
+            theme_font_language: Some(&language),
+        };
+
+        assert_eq!(
+            resolver.theme_font(Theme::MajorAscii),
+            Some(String::from("Calibri Light"))
+        );
+        assert_eq!(resolver.theme_font(Theme::MinorHighAnsi), Some(String::from("Calibri")));
+        assert_eq!(
+            resolver.theme_font(Theme::MajorEastAsia),
+            Some(String::from("Yu Gothic Light"))
+        );
+        assert_eq!(
+            resolver.theme_font(Theme::MinorEastAsia),
+            Some(String::from("Yu Mincho"))
+        );
+        assert_eq!(
+            resolver.theme_font(Theme::MajorBidirectional),
+            Some(String::from("Times New Roman"))
+        );
+        // There's no supplemental Arabic font, so the complex script font is used
+        assert_eq!(
+            resolver.theme_font(Theme::MinorBidirectional),
+            Some(String::from("Arial"))
+        );
+
+        let resolver = ThemeFontResolver {
+            font_scheme: Some(&font_scheme),
+            theme_font_language: None,
+        };
+        assert_eq!(resolver.theme_font(Theme::MajorEastAsia), None);
+    }
+
+    #[test]
+    pub fn test_resolve_fonts() {
+        let font_scheme = font_scheme_for_test();
+        let language = Language {
+            value: Some(String::from("en-US")),
+            east_asia: Some(String::from("ja-JP")),
+            bidirectional: Some(String::from("he-IL")),
+        };
+        let resolver = ThemeFontResolver {
+            font_scheme: Some(&font_scheme),
+            theme_font_language: Some(&language),
+        };
+
+        let fonts = Fonts {
+            ascii: Some(String::from("Times New Roman")),
+            ascii_theme: Some(Theme::MinorAscii),
+            high_ansi: Some(String::from("Times New Roman")),
+            east_asia_theme: Some(Theme::MinorEastAsia),
+            complex_script_theme: Some(Theme::MinorBidirectional),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolver.resolve_fonts(&fonts),
+            ResolvedFonts {
+                ascii: Some(String::from("Calibri")),
+                high_ansi: Some(String::from("Times New Roman")),
+                east_asia: Some(String::from("Yu Mincho")),
+                complex_script: Some(String::from("David")),
+            }
+        );
+    }
+}
@@ -0,0 +1,769 @@
+use crate::{
+    field::{as_run, field_character_run, ContentPosition, Field, FieldCollector},
+    fieldcode::{FieldType, TocField},
+    package::Package,
+    search::ParagraphText,
+    styleresolver::StyleResolver,
+    visit::{walk_p_content, walk_paragraph, Visit, VisitMut},
+    wml::{
+        document::{
+            BlockLevelElts, Bookmark, BookmarkRange, ContentBlockContent, ContentRunContent, Document, Drawing,
+            FldCharType, Hyperlink, Markup, MarkupRange, PContent, PPr, PPrBase, RangeMarkupElements, RunInnerContent,
+            RunLevelElts, SignedTwipsMeasure, TabJc, TabStop, TabTlc, Tabs, Text, P, R,
+        },
+        styles::StyleType,
+    },
+};
+use msoffice_shared::sharedtypes::TwipsMeasure;
+use std::collections::{HashMap, HashSet};
+
+pub const NO_TOC_ENTRIES: &str = "No table of contents entries found.";
+
+// Right edge of the text area of a Letter page with 1 inch margins, used when the section doesn't tell otherwise
+const DEFAULT_TAB_POSITION: i32 = 9350;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TocOptions {
+    // Page numbers of the headings keyed by the index of the heading among the body paragraphs, counted in visiting
+    // order before the update
+    pub page_numbers: HashMap<usize, u32>,
+    // Shown for the headings missing from page_numbers
+    pub page_placeholder: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocEntry {
+    pub level: u8,
+    pub text: String,
+    pub paragraph_index: usize,
+    pub bookmark: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct HeadingParagraph {
+    paragraph_index: usize,
+    style_id: Option<String>,
+    style_name: Option<String>,
+    outline_level: Option<i32>,
+    direct_outline_level: Option<i32>,
+    text: String,
+    toc_bookmark: Option<String>,
+}
+
+struct HeadingScanner<'a> {
+    package: &'a Package,
+    style_resolver: StyleResolver<'a>,
+    paragraphs: Vec<HeadingParagraph>,
+    bookmark_names: HashSet<String>,
+    max_bookmark_id: i32,
+}
+
+impl<'a, 'd> Visit<'d> for HeadingScanner<'a> {
+    fn visit_paragraph(&mut self, paragraph: &'d P) {
+        let style_id = paragraph
+            .properties
+            .as_ref()
+            .and_then(|p_pr| p_pr.base.style.clone())
+            .or_else(|| self.default_paragraph_style_id());
+        let style_name = style_id
+            .as_ref()
+            .and_then(|style_id| self.style_resolver.style(style_id)?.name.clone());
+
+        self.paragraphs.push(HeadingParagraph {
+            paragraph_index: self.paragraphs.len(),
+            style_id,
+            style_name,
+            outline_level: self
+                .style_resolver
+                .resolve_paragraph_properties(paragraph)
+                .outline_level,
+            direct_outline_level: paragraph.properties.as_ref().and_then(|p_pr| p_pr.base.outline_level),
+            text: ParagraphText::from_paragraph(paragraph).text,
+            toc_bookmark: None,
+        });
+
+        walk_paragraph(self, paragraph);
+    }
+
+    // The update doesn't enter text boxes, their paragraphs aren't counted
+    fn visit_drawing(&mut self, _drawing: &'d Drawing) {}
+
+    fn visit_range_markup_element(&mut self, range_markup: &'d RangeMarkupElements) {
+        if let RangeMarkupElements::BookmarkStart(bookmark) = range_markup {
+            self.max_bookmark_id = std::cmp::max(self.max_bookmark_id, bookmark.base.base.base.id);
+            self.bookmark_names.insert(bookmark.name.clone());
+
+            if let Some(paragraph) = self.paragraphs.last_mut() {
+                if bookmark.name.starts_with("_Toc") && paragraph.toc_bookmark.is_none() {
+                    paragraph.toc_bookmark = Some(bookmark.name.clone());
+                }
+            }
+        }
+    }
+}
+
+impl<'a> HeadingScanner<'a> {
+    fn default_paragraph_style_id(&self) -> Option<String> {
+        self.package
+            .styles
+            .as_ref()?
+            .styles
+            .iter()
+            .find(|style| style.style_type == Some(StyleType::Paragraph) && style.is_default == Some(true))?
+            .style_id
+            .clone()
+    }
+}
+
+struct BookmarkInserter<'b> {
+    bookmarks: &'b HashMap<usize, (i32, String)>,
+    paragraph_index: usize,
+}
+
+impl<'b> VisitMut for BookmarkInserter<'b> {
+    fn visit_paragraph_mut(&mut self, paragraph: &mut P) {
+        if let Some((id, name)) = self.bookmarks.get(&self.paragraph_index) {
+            let markup_range = MarkupRange {
+                base: Markup { id: *id },
+                displaced_by_custom_xml: None,
+            };
+            let bookmark_start = RangeMarkupElements::BookmarkStart(Bookmark {
+                base: BookmarkRange {
+                    base: markup_range,
+                    first_column: None,
+                    last_column: None,
+                },
+                name: name.clone(),
+            });
+
+            paragraph.contents.insert(0, range_markup_content(bookmark_start));
+            paragraph
+                .contents
+                .push(range_markup_content(RangeMarkupElements::BookmarkEnd(markup_range)));
+        }
+
+        self.paragraph_index += 1;
+    }
+
+    fn visit_drawing_mut(&mut self, _drawing: &mut Drawing) {}
+}
+
+// Lets the TOC replacement work on the body and on the contents of block level content controls alike
+trait TocBlock: Sized {
+    fn paragraph(&self) -> Option<&P>;
+    fn block_contents_mut(&mut self) -> Option<&mut Vec<ContentBlockContent>>;
+    fn from_paragraph(paragraph: P) -> Self;
+}
+
+impl TocBlock for ContentBlockContent {
+    fn paragraph(&self) -> Option<&P> {
+        match self {
+            ContentBlockContent::Paragraph(paragraph) => Some(paragraph),
+            _ => None,
+        }
+    }
+
+    fn block_contents_mut(&mut self) -> Option<&mut Vec<ContentBlockContent>> {
+        match self {
+            ContentBlockContent::CustomXml(custom_xml) => Some(&mut custom_xml.block_contents),
+            ContentBlockContent::Sdt(sdt) => sdt
+                .sdt_content
+                .as_mut()
+                .map(|sdt_content| &mut sdt_content.block_contents),
+            _ => None,
+        }
+    }
+
+    fn from_paragraph(paragraph: P) -> Self {
+        ContentBlockContent::Paragraph(Box::new(paragraph))
+    }
+}
+
+impl TocBlock for BlockLevelElts {
+    fn paragraph(&self) -> Option<&P> {
+        match self {
+            BlockLevelElts::Chunk(content) => content.paragraph(),
+            BlockLevelElts::AltChunk(_) => None,
+        }
+    }
+
+    fn block_contents_mut(&mut self) -> Option<&mut Vec<ContentBlockContent>> {
+        match self {
+            BlockLevelElts::Chunk(content) => content.block_contents_mut(),
+            BlockLevelElts::AltChunk(_) => None,
+        }
+    }
+
+    fn from_paragraph(paragraph: P) -> Self {
+        BlockLevelElts::Chunk(ContentBlockContent::from_paragraph(paragraph))
+    }
+}
+
+pub struct TocGenerator<'a> {
+    options: &'a TocOptions,
+    headings: Vec<HeadingParagraph>,
+    bookmark_names: HashSet<String>,
+    next_bookmark_id: i32,
+    tab_position: i32,
+    generated_count: usize,
+}
+
+impl<'a> TocGenerator<'a> {
+    pub fn new(package: &Package, document: &Document, options: &'a TocOptions) -> Self {
+        let mut scanner = HeadingScanner {
+            package,
+            style_resolver: package.style_resolver(),
+            paragraphs: Vec::new(),
+            bookmark_names: HashSet::new(),
+            max_bookmark_id: -1,
+        };
+        scanner.visit_document(document);
+
+        Self {
+            options,
+            headings: scanner.paragraphs,
+            bookmark_names: scanner.bookmark_names,
+            next_bookmark_id: scanner.max_bookmark_id + 1,
+            tab_position: text_area_width(document).unwrap_or(DEFAULT_TAB_POSITION),
+            generated_count: 0,
+        }
+    }
+
+    pub fn entries(&self, toc_field: &TocField) -> Vec<TocEntry> {
+        self.headings
+            .iter()
+            .filter(|heading| !heading.text.trim().is_empty())
+            .filter_map(|heading| {
+                Some(TocEntry {
+                    level: heading_level(heading, toc_field)?,
+                    text: entry_text(&heading.text, toc_field),
+                    paragraph_index: heading.paragraph_index,
+                    bookmark: heading.toc_bookmark.clone(),
+                })
+            })
+            .collect()
+    }
+
+    // Replaces the results of the TOC fields found in the body or in its block level content controls, returns the
+    // number of updated tables of contents
+    pub fn update_document(&mut self, document: &mut Document) -> usize {
+        let toc_fields: Vec<TocField> = crate::field::document_fields(document)
+            .iter()
+            .filter_map(|field| match field.field_code().ok()?.field_type {
+                FieldType::Toc(toc_field) => Some(toc_field),
+                _ => None,
+            })
+            .collect();
+
+        if toc_fields.iter().any(|toc_field| toc_field.hyperlinks) {
+            self.insert_bookmarks(document, &toc_fields);
+        }
+
+        if let Some(body) = &mut document.body {
+            self.update_block_contents(&mut body.block_level_elements);
+        }
+
+        self.generated_count
+    }
+
+    // Headings get a _Toc bookmark unless they already have one from an earlier generation
+    fn insert_bookmarks(&mut self, document: &mut Document, toc_fields: &[TocField]) {
+        let paragraph_indices: HashSet<usize> = toc_fields
+            .iter()
+            .filter(|toc_field| toc_field.hyperlinks)
+            .flat_map(|toc_field| self.entries(toc_field))
+            .filter(|entry| entry.bookmark.is_none())
+            .map(|entry| entry.paragraph_index)
+            .collect();
+        let mut paragraph_indices: Vec<usize> = paragraph_indices.into_iter().collect();
+        paragraph_indices.sort_unstable();
+
+        let mut bookmarks = HashMap::new();
+        for paragraph_index in paragraph_indices {
+            let mut name = format!("_Toc{:09}", self.next_bookmark_id);
+            while self.bookmark_names.contains(&name) {
+                self.next_bookmark_id += 1;
+                name = format!("_Toc{:09}", self.next_bookmark_id);
+            }
+
+            self.headings[paragraph_index].toc_bookmark = Some(name.clone());
+            self.bookmark_names.insert(name.clone());
+            bookmarks.insert(paragraph_index, (self.next_bookmark_id, name));
+            self.next_bookmark_id += 1;
+        }
+
+        let mut inserter = BookmarkInserter {
+            bookmarks: &bookmarks,
+            paragraph_index: 0,
+        };
+        inserter.visit_document_mut(document);
+    }
+
+    fn update_block_contents<T: TocBlock>(&mut self, elements: &mut Vec<T>) {
+        let mut collector = FieldCollector::new();
+        let mut element_indices = Vec::new();
+        for (element_index, element) in elements.iter().enumerate() {
+            if let Some(paragraph) = element.paragraph() {
+                collector.collect_paragraph(paragraph);
+                element_indices.push(element_index);
+            }
+        }
+
+        // Later tables of contents are replaced first so the element indices of the earlier ones stay valid
+        let mut replacements = Vec::new();
+        for field in collector.finish() {
+            if let Ok(FieldType::Toc(toc_field)) = field.field_code().map(|field_code| field_code.field_type) {
+                let start = element_indices[field.separate.unwrap_or(field.end).paragraph_index];
+                let end = element_indices[field.end.paragraph_index];
+                let paragraphs = match (elements[start].paragraph(), elements[end].paragraph()) {
+                    (Some(start_paragraph), Some(end_paragraph)) => {
+                        self.result_paragraphs(start_paragraph, end_paragraph, &field, &toc_field)
+                    }
+                    _ => None,
+                };
+
+                if let Some(paragraphs) = paragraphs {
+                    replacements.push((start, end, paragraphs));
+                }
+            }
+        }
+
+        for (start, end, paragraphs) in replacements.into_iter().rev() {
+            elements.splice(start..=end, paragraphs.into_iter().map(T::from_paragraph));
+            self.generated_count += 1;
+        }
+
+        for element in elements.iter_mut() {
+            if let Some(block_contents) = element.block_contents_mut() {
+                self.update_block_contents(block_contents);
+            }
+        }
+    }
+
+    // Replaces the runs between the separate and the end field characters, the runs before and after them are kept.
+    // Paragraph properties belong to the paragraph mark at the end of the paragraph, so only the paragraph holding
+    // the end of the field keeps its properties, the marks of the other paragraphs are part of the result.
+    fn result_paragraphs(
+        &self,
+        start_paragraph: &P,
+        end_paragraph: &P,
+        field: &Field,
+        toc_field: &TocField,
+    ) -> Option<Vec<P>> {
+        let (_, tail) = split_paragraph_contents(end_paragraph, field.end, false)?;
+        let head = match field.separate {
+            Some(separate) => split_paragraph_contents(start_paragraph, separate, true)?.0,
+            None => {
+                let (mut head, _) = split_paragraph_contents(end_paragraph, field.end, false)?;
+                head.push(field_character_run(FldCharType::Separate, None));
+                head
+            }
+        };
+
+        let mut paragraphs = self.entry_paragraphs(toc_field);
+        if let Some(first_paragraph) = paragraphs.first_mut() {
+            first_paragraph.contents.splice(0..0, head);
+        }
+
+        let is_multi_paragraph = field
+            .separate
+            .is_some_and(|separate| separate.paragraph_index != field.end.paragraph_index);
+        let end_contents = if is_multi_paragraph {
+            tail
+        } else {
+            let mut contents = paragraphs.pop()?.contents;
+            contents.extend(tail);
+            contents
+        };
+        paragraphs.push(P {
+            contents: end_contents,
+            ..end_paragraph.clone()
+        });
+
+        Some(paragraphs)
+    }
+
+    pub fn toc_paragraphs(&self, instruction: &str, toc_field: &TocField) -> Vec<P> {
+        let mut paragraphs = self.entry_paragraphs(toc_field);
+        let field_start = vec![
+            field_character_run(FldCharType::Begin, None),
+            PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+                run_inner_contents: vec![RunInnerContent::InstructionText(preserved_text(instruction))],
+                ..Default::default()
+            }))),
+            field_character_run(FldCharType::Separate, None),
+        ];
+        if let Some(first_paragraph) = paragraphs.first_mut() {
+            first_paragraph.contents.splice(0..0, field_start);
+        }
+        if let Some(last_paragraph) = paragraphs.last_mut() {
+            last_paragraph
+                .contents
+                .push(field_character_run(FldCharType::End, None));
+        }
+
+        paragraphs
+    }
+
+    fn entry_paragraphs(&self, toc_field: &TocField) -> Vec<P> {
+        let entries = self.entries(toc_field);
+        if entries.is_empty() {
+            vec![P {
+                contents: vec![text_run(NO_TOC_ENTRIES)],
+                ..Default::default()
+            }]
+        } else {
+            entries
+                .iter()
+                .map(|entry| self.entry_paragraph(entry, toc_field))
+                .collect()
+        }
+    }
+
+    fn entry_paragraph(&self, entry: &TocEntry, toc_field: &TocField) -> P {
+        let has_page_number = !toc_field
+            .omit_page_numbers
+            .map(|levels| levels.contains(entry.level))
+            .unwrap_or(false);
+
+        let mut contents = vec![text_run(&entry.text)];
+        if has_page_number {
+            contents.push(match &toc_field.separator {
+                Some(separator) => text_run(separator),
+                None => PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+                    run_inner_contents: vec![RunInnerContent::Tab],
+                    ..Default::default()
+                }))),
+            });
+
+            let page_number = self
+                .options
+                .page_numbers
+                .get(&entry.paragraph_index)
+                .map(|page_number| page_number.to_string())
+                .unwrap_or_else(|| self.options.page_placeholder.clone());
+            match &entry.bookmark {
+                Some(bookmark) => contents.extend(vec![
+                    field_character_run(FldCharType::Begin, None),
+                    PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+                        run_inner_contents: vec![RunInnerContent::InstructionText(preserved_text(&format!(
+                            " PAGEREF {} \\h ",
+                            bookmark
+                        )))],
+                        ..Default::default()
+                    }))),
+                    field_character_run(FldCharType::Separate, None),
+                    text_run(&page_number),
+                    field_character_run(FldCharType::End, None),
+                ]),
+                None => contents.push(text_run(&page_number)),
+            }
+        }
+
+        let contents = match (&entry.bookmark, toc_field.hyperlinks) {
+            (Some(bookmark), true) => vec![PContent::Hyperlink(Hyperlink {
+                paragraph_contents: contents,
+                anchor: Some(bookmark.clone()),
+                history: Some(true),
+                ..Default::default()
+            })],
+            _ => contents,
+        };
+
+        let tabs = if has_page_number && toc_field.separator.is_none() {
+            Some(Tabs(vec![TabStop {
+                value: TabJc::Right,
+                leader: Some(TabTlc::Dot),
+                position: SignedTwipsMeasure::Decimal(self.tab_position),
+            }]))
+        } else {
+            None
+        };
+
+        P {
+            properties: Some(PPr {
+                base: PPrBase {
+                    style: Some(format!("TOC{}", entry.level)),
+                    tabs,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            contents,
+            ..Default::default()
+        }
+    }
+}
+
+impl Package {
+    // Regenerates the result of every TOC field of the main document, returns the number of updated tables
+    pub fn update_tables_of_contents(&mut self, options: &TocOptions) -> usize {
+        let mut document = match self.main_document.take() {
+            Some(document) => document,
+            None => return 0,
+        };
+
+        let updated_count = TocGenerator::new(self, &document, options).update_document(&mut document);
+        self.main_document = Some(document);
+        updated_count
+    }
+}
+
+// Splits the paragraph contents at the run inner content of the position. The content at the position goes with the
+// contents before it if include_position is set, with the contents after it otherwise. Runs nested in other contents
+// aren't split, the content holding them goes as a whole.
+fn split_paragraph_contents(
+    paragraph: &P,
+    position: ContentPosition,
+    include_position: bool,
+) -> Option<(Vec<PContent>, Vec<PContent>)> {
+    let mut run_counter = RunCounter { run_count: 0 };
+    let content_index = paragraph.contents.iter().position(|content| {
+        walk_p_content(&mut run_counter, content);
+        run_counter.run_count > position.run_index
+    })?;
+
+    let content = &paragraph.contents[content_index];
+    let mut before = paragraph.contents[..content_index].to_vec();
+    let mut after = paragraph.contents[content_index + 1..].to_vec();
+    match as_run(content) {
+        Some(run) => {
+            let split_index =
+                (position.content_index + usize::from(include_position)).min(run.run_inner_contents.len());
+            let (first_contents, second_contents) = run.run_inner_contents.split_at(split_index);
+            let split_run = |run_inner_contents: &[RunInnerContent]| {
+                PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+                    run_inner_contents: run_inner_contents.to_vec(),
+                    ..run.clone()
+                })))
+            };
+
+            if !first_contents.is_empty() {
+                before.push(split_run(first_contents));
+            }
+            if !second_contents.is_empty() {
+                after.insert(0, split_run(second_contents));
+            }
+        }
+        None if include_position => before.push(content.clone()),
+        None => after.insert(0, content.clone()),
+    }
+
+    Some((before, after))
+}
+
+// Counts the runs like search::paragraph_runs does
+struct RunCounter {
+    run_count: usize,
+}
+
+impl<'a> Visit<'a> for RunCounter {
+    fn visit_run(&mut self, _run: &'a R) {
+        self.run_count += 1;
+    }
+}
+
+// \t styles take precedence over the outline levels of \o and \u
+fn heading_level(heading: &HeadingParagraph, toc_field: &TocField) -> Option<u8> {
+    let style_level = toc_field.styles.iter().find_map(|(style, level)| {
+        let is_matching = heading
+            .style_name
+            .iter()
+            .chain(heading.style_id.iter())
+            .any(|name| name.eq_ignore_ascii_case(style.trim()));
+        if is_matching {
+            Some(*level)
+        } else {
+            None
+        }
+    });
+
+    let outline_level = |outline_level: Option<i32>| {
+        outline_level
+            .filter(|outline_level| (0..9).contains(outline_level))
+            .map(|outline_level| outline_level as u8 + 1)
+    };
+
+    // \u adds the paragraphs with an outline level set directly, within the \o range when there's one
+    let levels = toc_field.outline_levels;
+    let is_in_range = |level: &u8| levels.map(|levels| levels.contains(*level)).unwrap_or(true);
+    style_level
+        .or_else(|| {
+            levels.and_then(|levels| outline_level(heading.outline_level).filter(|level| levels.contains(*level)))
+        })
+        .or_else(|| {
+            if toc_field.use_outline_levels {
+                outline_level(heading.direct_outline_level).filter(is_in_range)
+            } else {
+                None
+            }
+        })
+}
+
+fn entry_text(text: &str, toc_field: &TocField) -> String {
+    text.trim()
+        .chars()
+        .map(|character| match character {
+            '\t' if !toc_field.preserve_tabs => ' ',
+            '\n' if !toc_field.preserve_line_breaks => ' ',
+            character => character,
+        })
+        .collect()
+}
+
+fn text_area_width(document: &Document) -> Option<i32> {
+    let section_properties = document.body.as_ref()?.section_properties.as_ref()?.contents.as_ref()?;
+    let twips = |measure: &TwipsMeasure| match measure {
+        TwipsMeasure::Decimal(value) => Some(*value as i32),
+        TwipsMeasure::UniversalMeasure(_) => None,
+    };
+
+    let page_width = twips(section_properties.page_size.as_ref()?.width.as_ref()?)?;
+    let page_margin = section_properties.page_margin.as_ref()?;
+    Some(page_width - twips(&page_margin.left)? - twips(&page_margin.right)?)
+}
+
+fn preserved_text(text: &str) -> Text {
+    Text {
+        text: String::from(text),
+        xml_space: Some(String::from("preserve")),
+    }
+}
+
+fn text_run(text: &str) -> PContent {
+    PContent::ContentRunContent(Box::new(ContentRunContent::Run(R {
+        run_inner_contents: vec![RunInnerContent::Text(preserved_text(text))],
+        ..Default::default()
+    })))
+}
+
+fn range_markup_content(range_markup: RangeMarkupElements) -> PContent {
+    PContent::ContentRunContent(Box::new(ContentRunContent::RunLevelElements(
+        RunLevelElts::RangeMarkupElements(range_markup),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TocOptions, NO_TOC_ENTRIES};
+    use crate::{
+        field::paragraph_fields,
+        search::ParagraphText,
+        test_util::{body_paragraphs, complex_field, package_with_paragraphs, paragraph, styled_paragraph, text_run},
+        wml::document::{ContentRunContent, PContent, RangeMarkupElements, RunLevelElts, P},
+    };
+
+    fn toc_paragraph(instruction: &str) -> P {
+        paragraph(complex_field(instruction, "Stale entry"))
+    }
+
+    // Word ends the field in a paragraph of its own after the last entry
+    fn multi_paragraph_toc(instruction: &str) -> Vec<P> {
+        let mut contents = complex_field(instruction, "Stale entry");
+        let end = contents.pop().unwrap();
+        vec![styled_paragraph(contents, Some("TOC1"), None), paragraph(vec![end])]
+    }
+
+    fn bookmark_name(paragraph: &P) -> Option<&str> {
+        paragraph.contents.iter().find_map(|content| match content {
+            PContent::ContentRunContent(run_content) => match run_content.as_ref() {
+                ContentRunContent::RunLevelElements(RunLevelElts::RangeMarkupElements(
+                    RangeMarkupElements::BookmarkStart(bookmark),
+                )) => Some(bookmark.name.as_str()),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    #[test]
+    pub fn test_update_toc_with_outline_levels_and_hyperlinks() {
+        let mut paragraphs = multi_paragraph_toc(" TOC \\o \"1-2\" \\h \\z \\u ");
+        paragraphs.extend(vec![
+            styled_paragraph(vec![text_run("Introduction")], Some("Heading1"), Some(0)),
+            styled_paragraph(vec![text_run("Details")], Some("Heading2"), Some(1)),
+            styled_paragraph(vec![text_run("Too deep")], Some("Heading3"), Some(2)),
+            styled_paragraph(vec![text_run("Body text")], None, None),
+        ]);
+        let mut package = package_with_paragraphs(paragraphs);
+
+        let mut options = TocOptions {
+            page_placeholder: String::from("?"),
+            ..Default::default()
+        };
+        options.page_numbers.insert(2, 3);
+
+        assert_eq!(package.update_tables_of_contents(&options), 1);
+        let paragraphs = body_paragraphs(&package);
+        assert_eq!(paragraphs.len(), 7);
+
+        let texts: Vec<String> = paragraphs
+            .iter()
+            .map(|paragraph| ParagraphText::from_paragraph(paragraph).text)
+            .collect();
+        assert_eq!(texts[0], "Introduction\t3");
+        assert_eq!(texts[1], "Details\t?");
+        assert_eq!(texts[2], "");
+
+        let styles: Vec<Option<&str>> = paragraphs[..2]
+            .iter()
+            .map(|paragraph| paragraph.properties.as_ref().unwrap().base.style.as_deref())
+            .collect();
+        assert_eq!(styles, vec![Some("TOC1"), Some("TOC2")]);
+        assert!(paragraphs[2].properties.is_none());
+
+        assert_eq!(bookmark_name(paragraphs[3]), Some("_Toc000000000"));
+        assert_eq!(bookmark_name(paragraphs[4]), Some("_Toc000000001"));
+        assert_eq!(bookmark_name(paragraphs[5]), None);
+
+        // Generating again reuses the bookmarks of the headings
+        assert_eq!(package.update_tables_of_contents(&options), 1);
+        let paragraphs = body_paragraphs(&package);
+        assert_eq!(paragraphs.len(), 7);
+        assert_eq!(bookmark_name(paragraphs[3]), Some("_Toc000000000"));
+    }
+
+    #[test]
+    pub fn test_update_toc_keeps_surrounding_runs() {
+        let mut contents = vec![text_run("See: ")];
+        contents.extend(complex_field(" TOC \\o \\n ", "Stale entry"));
+        contents.push(text_run(" (generated)"));
+        let mut package = package_with_paragraphs(vec![
+            styled_paragraph(contents, Some("Quote"), None),
+            styled_paragraph(vec![text_run("Introduction")], Some("Heading1"), Some(0)),
+        ]);
+
+        assert_eq!(package.update_tables_of_contents(&TocOptions::default()), 1);
+        let paragraphs = body_paragraphs(&package);
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(
+            ParagraphText::from_paragraph(paragraphs[0]).text,
+            "See: Introduction (generated)"
+        );
+        assert_eq!(
+            paragraphs[0].properties.as_ref().unwrap().base.style.as_deref(),
+            Some("Quote")
+        );
+
+        let fields = paragraph_fields(paragraphs[0]);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].instruction, " TOC \\o \\n ");
+        assert_eq!(fields[0].result_text, "Introduction");
+    }
+
+    #[test]
+    pub fn test_update_toc_with_styles_and_omitted_page_numbers() {
+        let mut package = package_with_paragraphs(vec![
+            styled_paragraph(vec![text_run("Annual report")], Some("Title"), None),
+            toc_paragraph(" TOC \\t \"Title,1\" \\n "),
+            toc_paragraph(" TOC \\o \"1-3\" "),
+        ]);
+
+        assert_eq!(package.update_tables_of_contents(&TocOptions::default()), 2);
+        let texts: Vec<String> = body_paragraphs(&package)
+            .iter()
+            .map(|paragraph| ParagraphText::from_paragraph(paragraph).text)
+            .collect();
+        assert_eq!(texts, vec!["Annual report", "Annual report", NO_TOC_ENTRIES]);
+    }
+}
@@ -0,0 +1,1098 @@
+use crate::{
+    package::Package,
+    wml::{
+        document::{
+            BdoContentRun, BlockLevelElts, Body, ContentBlockContent, ContentRunContent, CustomXmlBlock, CustomXmlRun,
+            DirContentRun, Document, Drawing, Hyperlink, PContent, RangeMarkupElements, Ruby, RubyContent,
+            RubyContentChoice, RunInnerContent, RunLevelElts, RunTrackChange, RunTrackChangeChoice, SdtBlock, SdtRun,
+            DrawingChoice, SimpleField, SmartTagRun, P, R,
+        },
+        drawing::{
+            GraphicDataContent, TxbxContent, WordprocessingShape, WordprocessingShapeChoice,
+            WordprocessingShapeTextboxInfoChoice,
+        },
+        footnotes::FtnEdn,
+        headerfooter::HeaderFooter,
+        table::{ContentCellContent, ContentRowContent, CustomXmlCell, CustomXmlRow, Row, SdtCell, SdtRow, Tbl, Tc},
+    },
+};
+
+pub trait Visit<'a> {
+    fn visit_package(&mut self, package: &'a Package) {
+        walk_package(self, package);
+    }
+
+    fn visit_document(&mut self, document: &'a Document) {
+        walk_document(self, document);
+    }
+
+    fn visit_body(&mut self, body: &'a Body) {
+        walk_body(self, body);
+    }
+
+    fn visit_footnote(&mut self, footnote: &'a FtnEdn) {
+        walk_footnote(self, footnote);
+    }
+
+    fn visit_endnote(&mut self, endnote: &'a FtnEdn) {
+        walk_endnote(self, endnote);
+    }
+
+    fn visit_header_footer(&mut self, header_footer: &'a HeaderFooter) {
+        walk_header_footer(self, header_footer);
+    }
+
+    fn visit_block_level_element(&mut self, element: &'a BlockLevelElts) {
+        walk_block_level_element(self, element);
+    }
+
+    fn visit_content_block_content(&mut self, content: &'a ContentBlockContent) {
+        walk_content_block_content(self, content);
+    }
+
+    fn visit_custom_xml_block(&mut self, custom_xml: &'a CustomXmlBlock) {
+        walk_custom_xml_block(self, custom_xml);
+    }
+
+    fn visit_sdt_block(&mut self, sdt: &'a SdtBlock) {
+        walk_sdt_block(self, sdt);
+    }
+
+    fn visit_paragraph(&mut self, paragraph: &'a P) {
+        walk_paragraph(self, paragraph);
+    }
+
+    fn visit_p_content(&mut self, content: &'a PContent) {
+        walk_p_content(self, content);
+    }
+
+    fn visit_simple_field(&mut self, simple_field: &'a SimpleField) {
+        walk_simple_field(self, simple_field);
+    }
+
+    fn visit_hyperlink(&mut self, hyperlink: &'a Hyperlink) {
+        walk_hyperlink(self, hyperlink);
+    }
+
+    fn visit_content_run_content(&mut self, content: &'a ContentRunContent) {
+        walk_content_run_content(self, content);
+    }
+
+    fn visit_custom_xml_run(&mut self, custom_xml: &'a CustomXmlRun) {
+        walk_custom_xml_run(self, custom_xml);
+    }
+
+    fn visit_smart_tag_run(&mut self, smart_tag: &'a SmartTagRun) {
+        walk_smart_tag_run(self, smart_tag);
+    }
+
+    fn visit_sdt_run(&mut self, sdt: &'a SdtRun) {
+        walk_sdt_run(self, sdt);
+    }
+
+    fn visit_dir_content_run(&mut self, dir: &'a DirContentRun) {
+        walk_dir_content_run(self, dir);
+    }
+
+    fn visit_bdo_content_run(&mut self, bdo: &'a BdoContentRun) {
+        walk_bdo_content_run(self, bdo);
+    }
+
+    fn visit_run(&mut self, run: &'a R) {
+        walk_run(self, run);
+    }
+
+    fn visit_run_inner_content(&mut self, content: &'a RunInnerContent) {
+        walk_run_inner_content(self, content);
+    }
+
+    fn visit_ruby(&mut self, ruby: &'a Ruby) {
+        walk_ruby(self, ruby);
+    }
+
+    fn visit_ruby_content(&mut self, ruby_content: &'a RubyContent) {
+        walk_ruby_content(self, ruby_content);
+    }
+
+    fn visit_drawing(&mut self, drawing: &'a Drawing) {
+        walk_drawing(self, drawing);
+    }
+
+    fn visit_wordprocessing_shape(&mut self, shape: &'a WordprocessingShape) {
+        walk_wordprocessing_shape(self, shape);
+    }
+
+    fn visit_txbx_content(&mut self, txbx_content: &'a TxbxContent) {
+        walk_txbx_content(self, txbx_content);
+    }
+
+    fn visit_run_level_element(&mut self, element: &'a RunLevelElts) {
+        walk_run_level_element(self, element);
+    }
+
+    fn visit_range_markup_element(&mut self, range_markup: &'a RangeMarkupElements) {
+        walk_range_markup_element(self, range_markup);
+    }
+
+    fn visit_run_track_change(&mut self, track_change: &'a RunTrackChange) {
+        walk_run_track_change(self, track_change);
+    }
+
+    fn visit_table(&mut self, table: &'a Tbl) {
+        walk_table(self, table);
+    }
+
+    fn visit_content_row_content(&mut self, content: &'a ContentRowContent) {
+        walk_content_row_content(self, content);
+    }
+
+    fn visit_custom_xml_row(&mut self, custom_xml: &'a CustomXmlRow) {
+        walk_custom_xml_row(self, custom_xml);
+    }
+
+    fn visit_sdt_row(&mut self, sdt: &'a SdtRow) {
+        walk_sdt_row(self, sdt);
+    }
+
+    fn visit_row(&mut self, row: &'a Row) {
+        walk_row(self, row);
+    }
+
+    fn visit_content_cell_content(&mut self, content: &'a ContentCellContent) {
+        walk_content_cell_content(self, content);
+    }
+
+    fn visit_custom_xml_cell(&mut self, custom_xml: &'a CustomXmlCell) {
+        walk_custom_xml_cell(self, custom_xml);
+    }
+
+    fn visit_sdt_cell(&mut self, sdt: &'a SdtCell) {
+        walk_sdt_cell(self, sdt);
+    }
+
+    fn visit_cell(&mut self, cell: &'a Tc) {
+        walk_cell(self, cell);
+    }
+}
+
+pub trait VisitMut {
+    fn visit_package_mut(&mut self, package: &mut Package) {
+        walk_package_mut(self, package);
+    }
+
+    fn visit_document_mut(&mut self, document: &mut Document) {
+        walk_document_mut(self, document);
+    }
+
+    fn visit_body_mut(&mut self, body: &mut Body) {
+        walk_body_mut(self, body);
+    }
+
+    fn visit_footnote_mut(&mut self, footnote: &mut FtnEdn) {
+        walk_footnote_mut(self, footnote);
+    }
+
+    fn visit_endnote_mut(&mut self, endnote: &mut FtnEdn) {
+        walk_endnote_mut(self, endnote);
+    }
+
+    fn visit_header_footer_mut(&mut self, header_footer: &mut HeaderFooter) {
+        walk_header_footer_mut(self, header_footer);
+    }
+
+    fn visit_block_level_element_mut(&mut self, element: &mut BlockLevelElts) {
+        walk_block_level_element_mut(self, element);
+    }
+
+    fn visit_content_block_content_mut(&mut self, content: &mut ContentBlockContent) {
+        walk_content_block_content_mut(self, content);
+    }
+
+    fn visit_custom_xml_block_mut(&mut self, custom_xml: &mut CustomXmlBlock) {
+        walk_custom_xml_block_mut(self, custom_xml);
+    }
+
+    fn visit_sdt_block_mut(&mut self, sdt: &mut SdtBlock) {
+        walk_sdt_block_mut(self, sdt);
+    }
+
+    fn visit_paragraph_mut(&mut self, paragraph: &mut P) {
+        walk_paragraph_mut(self, paragraph);
+    }
+
+    fn visit_p_content_mut(&mut self, content: &mut PContent) {
+        walk_p_content_mut(self, content);
+    }
+
+    fn visit_simple_field_mut(&mut self, simple_field: &mut SimpleField) {
+        walk_simple_field_mut(self, simple_field);
+    }
+
+    fn visit_hyperlink_mut(&mut self, hyperlink: &mut Hyperlink) {
+        walk_hyperlink_mut(self, hyperlink);
+    }
+
+    fn visit_content_run_content_mut(&mut self, content: &mut ContentRunContent) {
+        walk_content_run_content_mut(self, content);
+    }
+
+    fn visit_custom_xml_run_mut(&mut self, custom_xml: &mut CustomXmlRun) {
+        walk_custom_xml_run_mut(self, custom_xml);
+    }
+
+    fn visit_smart_tag_run_mut(&mut self, smart_tag: &mut SmartTagRun) {
+        walk_smart_tag_run_mut(self, smart_tag);
+    }
+
+    fn visit_sdt_run_mut(&mut self, sdt: &mut SdtRun) {
+        walk_sdt_run_mut(self, sdt);
+    }
+
+    fn visit_dir_content_run_mut(&mut self, dir: &mut DirContentRun) {
+        walk_dir_content_run_mut(self, dir);
+    }
+
+    fn visit_bdo_content_run_mut(&mut self, bdo: &mut BdoContentRun) {
+        walk_bdo_content_run_mut(self, bdo);
+    }
+
+    fn visit_run_mut(&mut self, run: &mut R) {
+        walk_run_mut(self, run);
+    }
+
+    fn visit_run_inner_content_mut(&mut self, content: &mut RunInnerContent) {
+        walk_run_inner_content_mut(self, content);
+    }
+
+    fn visit_ruby_mut(&mut self, ruby: &mut Ruby) {
+        walk_ruby_mut(self, ruby);
+    }
+
+    fn visit_ruby_content_mut(&mut self, ruby_content: &mut RubyContent) {
+        walk_ruby_content_mut(self, ruby_content);
+    }
+
+    fn visit_drawing_mut(&mut self, drawing: &mut Drawing) {
+        walk_drawing_mut(self, drawing);
+    }
+
+    fn visit_wordprocessing_shape_mut(&mut self, shape: &mut WordprocessingShape) {
+        walk_wordprocessing_shape_mut(self, shape);
+    }
+
+    fn visit_txbx_content_mut(&mut self, txbx_content: &mut TxbxContent) {
+        walk_txbx_content_mut(self, txbx_content);
+    }
+
+    fn visit_run_level_element_mut(&mut self, element: &mut RunLevelElts) {
+        walk_run_level_element_mut(self, element);
+    }
+
+    fn visit_range_markup_element_mut(&mut self, range_markup: &mut RangeMarkupElements) {
+        walk_range_markup_element_mut(self, range_markup);
+    }
+
+    fn visit_run_track_change_mut(&mut self, track_change: &mut RunTrackChange) {
+        walk_run_track_change_mut(self, track_change);
+    }
+
+    fn visit_table_mut(&mut self, table: &mut Tbl) {
+        walk_table_mut(self, table);
+    }
+
+    fn visit_content_row_content_mut(&mut self, content: &mut ContentRowContent) {
+        walk_content_row_content_mut(self, content);
+    }
+
+    fn visit_custom_xml_row_mut(&mut self, custom_xml: &mut CustomXmlRow) {
+        walk_custom_xml_row_mut(self, custom_xml);
+    }
+
+    fn visit_sdt_row_mut(&mut self, sdt: &mut SdtRow) {
+        walk_sdt_row_mut(self, sdt);
+    }
+
+    fn visit_row_mut(&mut self, row: &mut Row) {
+        walk_row_mut(self, row);
+    }
+
+    fn visit_content_cell_content_mut(&mut self, content: &mut ContentCellContent) {
+        walk_content_cell_content_mut(self, content);
+    }
+
+    fn visit_custom_xml_cell_mut(&mut self, custom_xml: &mut CustomXmlCell) {
+        walk_custom_xml_cell_mut(self, custom_xml);
+    }
+
+    fn visit_sdt_cell_mut(&mut self, sdt: &mut SdtCell) {
+        walk_sdt_cell_mut(self, sdt);
+    }
+
+    fn visit_cell_mut(&mut self, cell: &mut Tc) {
+        walk_cell_mut(self, cell);
+    }
+}
+
+pub fn walk_package<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, package: &'a Package) {
+    if let Some(document) = &package.main_document {
+        visitor.visit_document(document);
+    }
+
+    if let Some(footnotes) = &package.footnotes {
+        for footnote in &footnotes.0 {
+            visitor.visit_footnote(footnote);
+        }
+    }
+
+    if let Some(endnotes) = &package.endnotes {
+        for endnote in &endnotes.0 {
+            visitor.visit_endnote(endnote);
+        }
+    }
+
+    // Headers and footers are visited in the order of their part names
+    let mut headers_footers: Vec<_> = package.headers.iter().chain(package.footers.iter()).collect();
+    headers_footers.sort_by_key(|(part_name, _)| *part_name);
+    for (_, header_footer) in headers_footers {
+        visitor.visit_header_footer(header_footer);
+    }
+}
+
+pub fn walk_document<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, document: &'a Document) {
+    if let Some(body) = &document.body {
+        visitor.visit_body(body);
+    }
+}
+
+pub fn walk_body<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, body: &'a Body) {
+    for element in &body.block_level_elements {
+        visitor.visit_block_level_element(element);
+    }
+}
+
+pub fn walk_footnote<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, footnote: &'a FtnEdn) {
+    for element in &footnote.block_level_elements {
+        visitor.visit_block_level_element(element);
+    }
+}
+
+pub fn walk_endnote<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, endnote: &'a FtnEdn) {
+    for element in &endnote.block_level_elements {
+        visitor.visit_block_level_element(element);
+    }
+}
+
+pub fn walk_header_footer<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, header_footer: &'a HeaderFooter) {
+    for element in &header_footer.block_level_elements {
+        visitor.visit_block_level_element(element);
+    }
+}
+
+pub fn walk_block_level_element<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, element: &'a BlockLevelElts) {
+    match element {
+        BlockLevelElts::Chunk(content) => visitor.visit_content_block_content(content),
+        BlockLevelElts::AltChunk(_) => (),
+    }
+}
+
+pub fn walk_content_block_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, content: &'a ContentBlockContent) {
+    match content {
+        ContentBlockContent::CustomXml(custom_xml) => visitor.visit_custom_xml_block(custom_xml),
+        ContentBlockContent::Sdt(sdt) => visitor.visit_sdt_block(sdt),
+        ContentBlockContent::Paragraph(paragraph) => visitor.visit_paragraph(paragraph),
+        ContentBlockContent::Table(table) => visitor.visit_table(table),
+        ContentBlockContent::RunLevelElement(element) => visitor.visit_run_level_element(element),
+    }
+}
+
+pub fn walk_custom_xml_block<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, custom_xml: &'a CustomXmlBlock) {
+    for content in &custom_xml.block_contents {
+        visitor.visit_content_block_content(content);
+    }
+}
+
+pub fn walk_sdt_block<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, sdt: &'a SdtBlock) {
+    if let Some(sdt_content) = &sdt.sdt_content {
+        for content in &sdt_content.block_contents {
+            visitor.visit_content_block_content(content);
+        }
+    }
+}
+
+pub fn walk_paragraph<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, paragraph: &'a P) {
+    for content in &paragraph.contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_p_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, content: &'a PContent) {
+    match content {
+        PContent::ContentRunContent(run_content) => visitor.visit_content_run_content(run_content),
+        PContent::SimpleField(simple_field) => visitor.visit_simple_field(simple_field),
+        PContent::Hyperlink(hyperlink) => visitor.visit_hyperlink(hyperlink),
+        PContent::SubDocument(_) => (),
+    }
+}
+
+pub fn walk_simple_field<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, simple_field: &'a SimpleField) {
+    for content in &simple_field.paragraph_contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_hyperlink<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, hyperlink: &'a Hyperlink) {
+    for content in &hyperlink.paragraph_contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_content_run_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, content: &'a ContentRunContent) {
+    match content {
+        ContentRunContent::CustomXml(custom_xml) => visitor.visit_custom_xml_run(custom_xml),
+        ContentRunContent::SmartTag(smart_tag) => visitor.visit_smart_tag_run(smart_tag),
+        ContentRunContent::Sdt(sdt) => visitor.visit_sdt_run(sdt),
+        ContentRunContent::Bidirectional(dir) => visitor.visit_dir_content_run(dir),
+        ContentRunContent::BidirectionalOverride(bdo) => visitor.visit_bdo_content_run(bdo),
+        ContentRunContent::Run(run) => visitor.visit_run(run),
+        ContentRunContent::RunLevelElements(element) => visitor.visit_run_level_element(element),
+    }
+}
+
+pub fn walk_custom_xml_run<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, custom_xml: &'a CustomXmlRun) {
+    for content in &custom_xml.paragraph_contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_smart_tag_run<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, smart_tag: &'a SmartTagRun) {
+    for content in &smart_tag.paragraph_contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_sdt_run<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, sdt: &'a SdtRun) {
+    if let Some(sdt_content) = &sdt.sdt_content {
+        for content in &sdt_content.p_contents {
+            visitor.visit_p_content(content);
+        }
+    }
+}
+
+pub fn walk_dir_content_run<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, dir: &'a DirContentRun) {
+    for content in &dir.p_contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_bdo_content_run<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, bdo: &'a BdoContentRun) {
+    for content in &bdo.p_contents {
+        visitor.visit_p_content(content);
+    }
+}
+
+pub fn walk_run<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, run: &'a R) {
+    for content in &run.run_inner_contents {
+        visitor.visit_run_inner_content(content);
+    }
+}
+
+pub fn walk_run_inner_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, content: &'a RunInnerContent) {
+    match content {
+        RunInnerContent::Ruby(ruby) => visitor.visit_ruby(ruby),
+        RunInnerContent::Drawing(drawing) => visitor.visit_drawing(drawing),
+        _ => (),
+    }
+}
+
+pub fn walk_ruby<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, ruby: &'a Ruby) {
+    visitor.visit_ruby_content(&ruby.ruby_base);
+    visitor.visit_ruby_content(&ruby.ruby_content);
+}
+
+pub fn walk_ruby_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, ruby_content: &'a RubyContent) {
+    for choice in &ruby_content.ruby_contents {
+        match choice {
+            RubyContentChoice::Run(run) => visitor.visit_run(run),
+            RubyContentChoice::RunLevelElement(element) => visitor.visit_run_level_element(element),
+        }
+    }
+}
+
+pub fn walk_drawing<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, drawing: &'a Drawing) {
+    for choice in &drawing.0 {
+        let graphic_data_content = match choice {
+            DrawingChoice::Anchor(anchor) => &anchor.graphic_data_content,
+            DrawingChoice::Inline(inline) => &inline.graphic_data_content,
+        };
+
+        match graphic_data_content {
+            Some(GraphicDataContent::Shape(shape)) => visitor.visit_wordprocessing_shape(shape),
+            Some(GraphicDataContent::Group(group)) => walk_shape_choices(visitor, &group.shapes),
+            Some(GraphicDataContent::Canvas(canvas)) => walk_shape_choices(visitor, &canvas.shapes),
+            Some(GraphicDataContent::Picture(_)) | None => (),
+        }
+    }
+}
+
+fn walk_shape_choices<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, shapes: &'a [WordprocessingShapeChoice]) {
+    for choice in shapes {
+        match choice {
+            WordprocessingShapeChoice::Shape(shape) => visitor.visit_wordprocessing_shape(shape),
+            WordprocessingShapeChoice::Group(group) => walk_shape_choices(visitor, &group.shapes),
+            _ => (),
+        }
+    }
+}
+
+pub fn walk_wordprocessing_shape<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, shape: &'a WordprocessingShape) {
+    if let Some(WordprocessingShapeTextboxInfoChoice::Textbox(textbox_info)) = &shape.text_box_info {
+        visitor.visit_txbx_content(&textbox_info.textbox_content);
+    }
+}
+
+pub fn walk_txbx_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, txbx_content: &'a TxbxContent) {
+    for element in &txbx_content.block_level_elements {
+        visitor.visit_block_level_element(element);
+    }
+}
+
+pub fn walk_run_level_element<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, element: &'a RunLevelElts) {
+    match element {
+        RunLevelElts::RangeMarkupElements(range_markup) => visitor.visit_range_markup_element(range_markup),
+        RunLevelElts::Insert(track_change)
+        | RunLevelElts::Delete(track_change)
+        | RunLevelElts::MoveFrom(track_change)
+        | RunLevelElts::MoveTo(track_change) => visitor.visit_run_track_change(track_change),
+        _ => (),
+    }
+}
+
+pub fn walk_range_markup_element<'a, V: Visit<'a> + ?Sized>(_visitor: &mut V, _range_markup: &'a RangeMarkupElements) {}
+
+pub fn walk_run_track_change<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, track_change: &'a RunTrackChange) {
+    for choice in &track_change.choices {
+        match choice {
+            RunTrackChangeChoice::ContentRunContent(content) => visitor.visit_content_run_content(content),
+        }
+    }
+}
+
+pub fn walk_table<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, table: &'a Tbl) {
+    for range_markup in &table.range_markup_elements {
+        visitor.visit_range_markup_element(range_markup);
+    }
+
+    for content in &table.row_contents {
+        visitor.visit_content_row_content(content);
+    }
+}
+
+pub fn walk_content_row_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, content: &'a ContentRowContent) {
+    match content {
+        ContentRowContent::Table(row) => visitor.visit_row(row),
+        ContentRowContent::CustomXml(custom_xml) => visitor.visit_custom_xml_row(custom_xml),
+        ContentRowContent::Sdt(sdt) => visitor.visit_sdt_row(sdt),
+        ContentRowContent::RunLevelElements(element) => visitor.visit_run_level_element(element),
+    }
+}
+
+pub fn walk_custom_xml_row<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, custom_xml: &'a CustomXmlRow) {
+    for content in &custom_xml.contents {
+        visitor.visit_content_row_content(content);
+    }
+}
+
+pub fn walk_sdt_row<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, sdt: &'a SdtRow) {
+    if let Some(sdt_content) = &sdt.content {
+        for content in &sdt_content.contents {
+            visitor.visit_content_row_content(content);
+        }
+    }
+}
+
+pub fn walk_row<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, row: &'a Row) {
+    for content in &row.contents {
+        visitor.visit_content_cell_content(content);
+    }
+}
+
+pub fn walk_content_cell_content<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, content: &'a ContentCellContent) {
+    match content {
+        ContentCellContent::Cell(cell) => visitor.visit_cell(cell),
+        ContentCellContent::CustomXml(custom_xml) => visitor.visit_custom_xml_cell(custom_xml),
+        ContentCellContent::Sdt(sdt) => visitor.visit_sdt_cell(sdt),
+        ContentCellContent::RunLevelElement(element) => visitor.visit_run_level_element(element),
+    }
+}
+
+pub fn walk_custom_xml_cell<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, custom_xml: &'a CustomXmlCell) {
+    for content in &custom_xml.contents {
+        visitor.visit_content_cell_content(content);
+    }
+}
+
+pub fn walk_sdt_cell<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, sdt: &'a SdtCell) {
+    if let Some(sdt_content) = &sdt.content {
+        for content in &sdt_content.contents {
+            visitor.visit_content_cell_content(content);
+        }
+    }
+}
+
+pub fn walk_cell<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, cell: &'a Tc) {
+    for element in &cell.block_level_elements {
+        visitor.visit_block_level_element(element);
+    }
+}
+
+pub fn walk_package_mut<V: VisitMut + ?Sized>(visitor: &mut V, package: &mut Package) {
+    if let Some(document) = &mut package.main_document {
+        visitor.visit_document_mut(document);
+    }
+
+    if let Some(footnotes) = &mut package.footnotes {
+        for footnote in &mut footnotes.0 {
+            visitor.visit_footnote_mut(footnote);
+        }
+    }
+
+    if let Some(endnotes) = &mut package.endnotes {
+        for endnote in &mut endnotes.0 {
+            visitor.visit_endnote_mut(endnote);
+        }
+    }
+
+    let mut headers_footers: Vec<_> = package.headers.iter_mut().chain(package.footers.iter_mut()).collect();
+    headers_footers.sort_by_key(|(part_name, _)| *part_name);
+    for (_, header_footer) in headers_footers {
+        visitor.visit_header_footer_mut(header_footer);
+    }
+}
+
+pub fn walk_document_mut<V: VisitMut + ?Sized>(visitor: &mut V, document: &mut Document) {
+    if let Some(body) = &mut document.body {
+        visitor.visit_body_mut(body);
+    }
+}
+
+pub fn walk_body_mut<V: VisitMut + ?Sized>(visitor: &mut V, body: &mut Body) {
+    for element in &mut body.block_level_elements {
+        visitor.visit_block_level_element_mut(element);
+    }
+}
+
+pub fn walk_footnote_mut<V: VisitMut + ?Sized>(visitor: &mut V, footnote: &mut FtnEdn) {
+    for element in &mut footnote.block_level_elements {
+        visitor.visit_block_level_element_mut(element);
+    }
+}
+
+pub fn walk_endnote_mut<V: VisitMut + ?Sized>(visitor: &mut V, endnote: &mut FtnEdn) {
+    for element in &mut endnote.block_level_elements {
+        visitor.visit_block_level_element_mut(element);
+    }
+}
+
+pub fn walk_header_footer_mut<V: VisitMut + ?Sized>(visitor: &mut V, header_footer: &mut HeaderFooter) {
+    for element in &mut header_footer.block_level_elements {
+        visitor.visit_block_level_element_mut(element);
+    }
+}
+
+pub fn walk_block_level_element_mut<V: VisitMut + ?Sized>(visitor: &mut V, element: &mut BlockLevelElts) {
+    match element {
+        BlockLevelElts::Chunk(content) => visitor.visit_content_block_content_mut(content),
+        BlockLevelElts::AltChunk(_) => (),
+    }
+}
+
+pub fn walk_content_block_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, content: &mut ContentBlockContent) {
+    match content {
+        ContentBlockContent::CustomXml(custom_xml) => visitor.visit_custom_xml_block_mut(custom_xml),
+        ContentBlockContent::Sdt(sdt) => visitor.visit_sdt_block_mut(sdt),
+        ContentBlockContent::Paragraph(paragraph) => visitor.visit_paragraph_mut(paragraph),
+        ContentBlockContent::Table(table) => visitor.visit_table_mut(table),
+        ContentBlockContent::RunLevelElement(element) => visitor.visit_run_level_element_mut(element),
+    }
+}
+
+pub fn walk_custom_xml_block_mut<V: VisitMut + ?Sized>(visitor: &mut V, custom_xml: &mut CustomXmlBlock) {
+    for content in &mut custom_xml.block_contents {
+        visitor.visit_content_block_content_mut(content);
+    }
+}
+
+pub fn walk_sdt_block_mut<V: VisitMut + ?Sized>(visitor: &mut V, sdt: &mut SdtBlock) {
+    if let Some(sdt_content) = &mut sdt.sdt_content {
+        for content in &mut sdt_content.block_contents {
+            visitor.visit_content_block_content_mut(content);
+        }
+    }
+}
+
+pub fn walk_paragraph_mut<V: VisitMut + ?Sized>(visitor: &mut V, paragraph: &mut P) {
+    for content in &mut paragraph.contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_p_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, content: &mut PContent) {
+    match content {
+        PContent::ContentRunContent(run_content) => visitor.visit_content_run_content_mut(run_content),
+        PContent::SimpleField(simple_field) => visitor.visit_simple_field_mut(simple_field),
+        PContent::Hyperlink(hyperlink) => visitor.visit_hyperlink_mut(hyperlink),
+        PContent::SubDocument(_) => (),
+    }
+}
+
+pub fn walk_simple_field_mut<V: VisitMut + ?Sized>(visitor: &mut V, simple_field: &mut SimpleField) {
+    for content in &mut simple_field.paragraph_contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_hyperlink_mut<V: VisitMut + ?Sized>(visitor: &mut V, hyperlink: &mut Hyperlink) {
+    for content in &mut hyperlink.paragraph_contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_content_run_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, content: &mut ContentRunContent) {
+    match content {
+        ContentRunContent::CustomXml(custom_xml) => visitor.visit_custom_xml_run_mut(custom_xml),
+        ContentRunContent::SmartTag(smart_tag) => visitor.visit_smart_tag_run_mut(smart_tag),
+        ContentRunContent::Sdt(sdt) => visitor.visit_sdt_run_mut(sdt),
+        ContentRunContent::Bidirectional(dir) => visitor.visit_dir_content_run_mut(dir),
+        ContentRunContent::BidirectionalOverride(bdo) => visitor.visit_bdo_content_run_mut(bdo),
+        ContentRunContent::Run(run) => visitor.visit_run_mut(run),
+        ContentRunContent::RunLevelElements(element) => visitor.visit_run_level_element_mut(element),
+    }
+}
+
+pub fn walk_custom_xml_run_mut<V: VisitMut + ?Sized>(visitor: &mut V, custom_xml: &mut CustomXmlRun) {
+    for content in &mut custom_xml.paragraph_contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_smart_tag_run_mut<V: VisitMut + ?Sized>(visitor: &mut V, smart_tag: &mut SmartTagRun) {
+    for content in &mut smart_tag.paragraph_contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_sdt_run_mut<V: VisitMut + ?Sized>(visitor: &mut V, sdt: &mut SdtRun) {
+    if let Some(sdt_content) = &mut sdt.sdt_content {
+        for content in &mut sdt_content.p_contents {
+            visitor.visit_p_content_mut(content);
+        }
+    }
+}
+
+pub fn walk_dir_content_run_mut<V: VisitMut + ?Sized>(visitor: &mut V, dir: &mut DirContentRun) {
+    for content in &mut dir.p_contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_bdo_content_run_mut<V: VisitMut + ?Sized>(visitor: &mut V, bdo: &mut BdoContentRun) {
+    for content in &mut bdo.p_contents {
+        visitor.visit_p_content_mut(content);
+    }
+}
+
+pub fn walk_run_mut<V: VisitMut + ?Sized>(visitor: &mut V, run: &mut R) {
+    for content in &mut run.run_inner_contents {
+        visitor.visit_run_inner_content_mut(content);
+    }
+}
+
+pub fn walk_run_inner_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, content: &mut RunInnerContent) {
+    match content {
+        RunInnerContent::Ruby(ruby) => visitor.visit_ruby_mut(ruby),
+        RunInnerContent::Drawing(drawing) => visitor.visit_drawing_mut(drawing),
+        _ => (),
+    }
+}
+
+pub fn walk_ruby_mut<V: VisitMut + ?Sized>(visitor: &mut V, ruby: &mut Ruby) {
+    visitor.visit_ruby_content_mut(&mut ruby.ruby_base);
+    visitor.visit_ruby_content_mut(&mut ruby.ruby_content);
+}
+
+pub fn walk_ruby_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, ruby_content: &mut RubyContent) {
+    for choice in &mut ruby_content.ruby_contents {
+        match choice {
+            RubyContentChoice::Run(run) => visitor.visit_run_mut(run),
+            RubyContentChoice::RunLevelElement(element) => visitor.visit_run_level_element_mut(element),
+        }
+    }
+}
+
+pub fn walk_drawing_mut<V: VisitMut + ?Sized>(visitor: &mut V, drawing: &mut Drawing) {
+    for choice in &mut drawing.0 {
+        let graphic_data_content = match choice {
+            DrawingChoice::Anchor(anchor) => &mut anchor.graphic_data_content,
+            DrawingChoice::Inline(inline) => &mut inline.graphic_data_content,
+        };
+
+        match graphic_data_content {
+            Some(GraphicDataContent::Shape(shape)) => visitor.visit_wordprocessing_shape_mut(shape),
+            Some(GraphicDataContent::Group(group)) => walk_shape_choices_mut(visitor, &mut group.shapes),
+            Some(GraphicDataContent::Canvas(canvas)) => walk_shape_choices_mut(visitor, &mut canvas.shapes),
+            Some(GraphicDataContent::Picture(_)) | None => (),
+        }
+    }
+}
+
+fn walk_shape_choices_mut<V: VisitMut + ?Sized>(visitor: &mut V, shapes: &mut [WordprocessingShapeChoice]) {
+    for choice in shapes {
+        match choice {
+            WordprocessingShapeChoice::Shape(shape) => visitor.visit_wordprocessing_shape_mut(shape),
+            WordprocessingShapeChoice::Group(group) => walk_shape_choices_mut(visitor, &mut group.shapes),
+            _ => (),
+        }
+    }
+}
+
+pub fn walk_wordprocessing_shape_mut<V: VisitMut + ?Sized>(visitor: &mut V, shape: &mut WordprocessingShape) {
+    if let Some(WordprocessingShapeTextboxInfoChoice::Textbox(textbox_info)) = &mut shape.text_box_info {
+        visitor.visit_txbx_content_mut(&mut textbox_info.textbox_content);
+    }
+}
+
+pub fn walk_txbx_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, txbx_content: &mut TxbxContent) {
+    for element in &mut txbx_content.block_level_elements {
+        visitor.visit_block_level_element_mut(element);
+    }
+}
+
+pub fn walk_run_level_element_mut<V: VisitMut + ?Sized>(visitor: &mut V, element: &mut RunLevelElts) {
+    match element {
+        RunLevelElts::RangeMarkupElements(range_markup) => visitor.visit_range_markup_element_mut(range_markup),
+        RunLevelElts::Insert(track_change)
+        | RunLevelElts::Delete(track_change)
+        | RunLevelElts::MoveFrom(track_change)
+        | RunLevelElts::MoveTo(track_change) => visitor.visit_run_track_change_mut(track_change),
+        _ => (),
+    }
+}
+
+pub fn walk_range_markup_element_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _range_markup: &mut RangeMarkupElements) {}
+
+pub fn walk_run_track_change_mut<V: VisitMut + ?Sized>(visitor: &mut V, track_change: &mut RunTrackChange) {
+    for choice in &mut track_change.choices {
+        match choice {
+            RunTrackChangeChoice::ContentRunContent(content) => visitor.visit_content_run_content_mut(content),
+        }
+    }
+}
+
+pub fn walk_table_mut<V: VisitMut + ?Sized>(visitor: &mut V, table: &mut Tbl) {
+    for range_markup in &mut table.range_markup_elements {
+        visitor.visit_range_markup_element_mut(range_markup);
+    }
+
+    for content in &mut table.row_contents {
+        visitor.visit_content_row_content_mut(content);
+    }
+}
+
+pub fn walk_content_row_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, content: &mut ContentRowContent) {
+    match content {
+        ContentRowContent::Table(row) => visitor.visit_row_mut(row),
+        ContentRowContent::CustomXml(custom_xml) => visitor.visit_custom_xml_row_mut(custom_xml),
+        ContentRowContent::Sdt(sdt) => visitor.visit_sdt_row_mut(sdt),
+        ContentRowContent::RunLevelElements(element) => visitor.visit_run_level_element_mut(element),
+    }
+}
+
+pub fn walk_custom_xml_row_mut<V: VisitMut + ?Sized>(visitor: &mut V, custom_xml: &mut CustomXmlRow) {
+    for content in &mut custom_xml.contents {
+        visitor.visit_content_row_content_mut(content);
+    }
+}
+
+pub fn walk_sdt_row_mut<V: VisitMut + ?Sized>(visitor: &mut V, sdt: &mut SdtRow) {
+    if let Some(sdt_content) = &mut sdt.content {
+        for content in &mut sdt_content.contents {
+            visitor.visit_content_row_content_mut(content);
+        }
+    }
+}
+
+pub fn walk_row_mut<V: VisitMut + ?Sized>(visitor: &mut V, row: &mut Row) {
+    for content in &mut row.contents {
+        visitor.visit_content_cell_content_mut(content);
+    }
+}
+
+pub fn walk_content_cell_content_mut<V: VisitMut + ?Sized>(visitor: &mut V, content: &mut ContentCellContent) {
+    match content {
+        ContentCellContent::Cell(cell) => visitor.visit_cell_mut(cell),
+        ContentCellContent::CustomXml(custom_xml) => visitor.visit_custom_xml_cell_mut(custom_xml),
+        ContentCellContent::Sdt(sdt) => visitor.visit_sdt_cell_mut(sdt),
+        ContentCellContent::RunLevelElement(element) => visitor.visit_run_level_element_mut(element),
+    }
+}
+
+pub fn walk_custom_xml_cell_mut<V: VisitMut + ?Sized>(visitor: &mut V, custom_xml: &mut CustomXmlCell) {
+    for content in &mut custom_xml.contents {
+        visitor.visit_content_cell_content_mut(content);
+    }
+}
+
+pub fn walk_sdt_cell_mut<V: VisitMut + ?Sized>(visitor: &mut V, sdt: &mut SdtCell) {
+    if let Some(sdt_content) = &mut sdt.content {
+        for content in &mut sdt_content.contents {
+            visitor.visit_content_cell_content_mut(content);
+        }
+    }
+}
+
+pub fn walk_cell_mut<V: VisitMut + ?Sized>(visitor: &mut V, cell: &mut Tc) {
+    for element in &mut cell.block_level_elements {
+        visitor.visit_block_level_element_mut(element);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_run_inner_content, walk_run_inner_content_mut, Visit, VisitMut};
+    use crate::{
+        package::Package,
+        test_util::{package_with_blocks, paragraph, paragraph_block, text_box_run, text_run},
+        wml::{
+            document::{
+                BlockLevelElts, ContentBlockContent, ContentRunContent, Hyperlink, PContent, RunInnerContent, SdtBlock,
+                SdtContentBlock, SmartTagRun,
+            },
+            footnotes::{Footnotes, FtnEdn},
+            headerfooter::HeaderFooter,
+            table::{ContentCellContent, ContentRowContent, Row, Tbl, TblGrid, TblPr, TblPrBase, Tc},
+        },
+    };
+
+    #[derive(Default)]
+    struct TextCollector<'a> {
+        texts: Vec<&'a str>,
+    }
+
+    impl<'a> Visit<'a> for TextCollector<'a> {
+        fn visit_run_inner_content(&mut self, content: &'a RunInnerContent) {
+            if let RunInnerContent::Text(text) = content {
+                self.texts.push(&text.text);
+            }
+
+            walk_run_inner_content(self, content);
+        }
+    }
+
+    struct Capitalizer;
+
+    impl VisitMut for Capitalizer {
+        fn visit_run_inner_content_mut(&mut self, content: &mut RunInnerContent) {
+            if let RunInnerContent::Text(text) = content {
+                text.text = text.text.to_uppercase();
+            }
+
+            walk_run_inner_content_mut(self, content);
+        }
+    }
+
+    fn nested_package() -> Package {
+        let hyperlink = PContent::Hyperlink(Hyperlink {
+            paragraph_contents: vec![PContent::ContentRunContent(Box::new(ContentRunContent::SmartTag(
+                SmartTagRun {
+                    smart_tag_properties: None,
+                    paragraph_contents: vec![text_run("link")],
+                    uri: String::new(),
+                    element: String::from("place"),
+                },
+            )))],
+            ..Default::default()
+        });
+
+        let sdt = ContentBlockContent::Sdt(Box::new(SdtBlock {
+            sdt_properties: None,
+            sdt_end_properties: None,
+            sdt_content: Some(SdtContentBlock {
+                block_contents: vec![ContentBlockContent::Paragraph(Box::new(paragraph(vec![
+                    text_run("in sdt "),
+                    hyperlink,
+                ])))],
+            }),
+        }));
+
+        let table = ContentBlockContent::Table(Box::new(Tbl {
+            range_markup_elements: Vec::new(),
+            properties: TblPr {
+                base: TblPrBase::default(),
+                change: None,
+            },
+            grid: TblGrid::default(),
+            row_contents: vec![ContentRowContent::Table(Box::new(Row {
+                property_exceptions: None,
+                properties: None,
+                contents: vec![ContentCellContent::Cell(Box::new(Tc {
+                    properties: None,
+                    block_level_elements: vec![BlockLevelElts::Chunk(sdt)],
+                    id: None,
+                }))],
+                run_properties_revision_id: None,
+                run_revision_id: None,
+                deletion_revision_id: None,
+                row_revision_id: None,
+            }))],
+        }));
+
+        Package {
+            footnotes: Some(Footnotes(vec![FtnEdn {
+                ftn_edn_type: None,
+                id: 1,
+                block_level_elements: vec![paragraph_block(paragraph(vec![text_run("note")]))],
+            }])),
+            headers: vec![(
+                String::from("header1.xml"),
+                HeaderFooter {
+                    block_level_elements: vec![paragraph_block(paragraph(vec![text_run("header")]))],
+                },
+            )]
+            .into_iter()
+            .collect(),
+            footers: vec![(
+                String::from("footer1.xml"),
+                HeaderFooter {
+                    block_level_elements: vec![paragraph_block(paragraph(vec![text_run("footer")]))],
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..package_with_blocks(vec![
+                paragraph_block(paragraph(vec![
+                    text_run("before"),
+                    text_box_run(vec![paragraph(vec![text_run("box")])]),
+                ])),
+                BlockLevelElts::Chunk(table),
+            ])
+        }
+    }
+
+    #[test]
+    pub fn test_visit_nested_content() {
+        let package = nested_package();
+        let mut collector = TextCollector::default();
+        collector.visit_package(&package);
+        assert_eq!(collector.texts, vec!["before", "box", "in sdt ", "link", "note", "footer", "header"]);
+    }
+
+    #[test]
+    pub fn test_visit_mut_nested_content() {
+        let mut package = nested_package();
+        Capitalizer.visit_package_mut(&mut package);
+
+        let mut collector = TextCollector::default();
+        collector.visit_package(&package);
+        assert_eq!(collector.texts, vec!["BEFORE", "BOX", "IN SDT ", "LINK", "NOTE", "FOOTER", "HEADER"]);
+    }
+}
@@ -307,13 +307,10 @@ impl Color {
 }
 
 impl Update for Color {
+    // The color value is mandatory, so the other color replaces this one entirely. Inheriting the theme attributes
+    // would override the value of the other color.
     fn update_with(self, other: Self) -> Self {
-        Self {
-            value: other.value,
-            theme_color: other.theme_color.or(self.theme_color),
-            theme_tint: other.theme_tint.or(self.theme_tint),
-            theme_shade: other.theme_shade.or(self.theme_shade),
-        }
+        other
     }
 }
 
@@ -965,20 +962,46 @@ impl Fonts {
 
 impl Update for Fonts {
     fn update_with(self, other: Self) -> Self {
+        let (ascii, ascii_theme) = update_font_slot((self.ascii, self.ascii_theme), (other.ascii, other.ascii_theme));
+        let (high_ansi, high_ansi_theme) = update_font_slot(
+            (self.high_ansi, self.high_ansi_theme),
+            (other.high_ansi, other.high_ansi_theme),
+        );
+        let (east_asia, east_asia_theme) = update_font_slot(
+            (self.east_asia, self.east_asia_theme),
+            (other.east_asia, other.east_asia_theme),
+        );
+        let (complex_script, complex_script_theme) = update_font_slot(
+            (self.complex_script, self.complex_script_theme),
+            (other.complex_script, other.complex_script_theme),
+        );
+
         Self {
             hint: other.hint.or(self.hint),
-            ascii: other.ascii.or(self.ascii),
-            high_ansi: other.high_ansi.or(self.high_ansi),
-            east_asia: other.east_asia.or(self.east_asia),
-            complex_script: other.complex_script.or(self.complex_script),
-            ascii_theme: other.ascii_theme.or(self.ascii_theme),
-            high_ansi_theme: other.high_ansi_theme.or(self.high_ansi_theme),
-            east_asia_theme: other.east_asia_theme.or(self.east_asia_theme),
-            complex_script_theme: other.complex_script_theme.or(self.complex_script_theme),
+            ascii,
+            high_ansi,
+            east_asia,
+            complex_script,
+            ascii_theme,
+            high_ansi_theme,
+            east_asia_theme,
+            complex_script_theme,
         }
     }
 }
 
+// The font name and the theme font of a slot are overridden together, otherwise an inherited theme font would take
+// precedence over a font name set on a higher level
+fn update_font_slot(
+    slot: (Option<String>, Option<Theme>),
+    other_slot: (Option<String>, Option<Theme>),
+) -> (Option<String>, Option<Theme>) {
+    match other_slot {
+        (None, None) => slot,
+        other_slot => other_slot,
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, EnumString)]
 pub enum UnderlineType {
@@ -3031,7 +3054,7 @@ pub enum FldCharType {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FldChar {
-    pub form_field_properties: Option<FFData>,
+    pub form_field_properties: Vec<FFData>,
     pub field_char_type: FldCharType,
     pub field_lock: Option<OnOff>,
     pub dirty: Option<OnOff>,
@@ -3054,11 +3077,19 @@ impl FldChar {
             }
         }
 
-        let form_field_properties = xml_node
+        // The form field properties are the children of the ffData element
+        let ff_data_node = xml_node
             .child_nodes
             .iter()
-            .find_map(|child_node| FFData::try_from_xml_element(child_node))
-            .transpose()?;
+            .find(|child_node| child_node.local_name() == "ffData");
+        let form_field_properties = match ff_data_node {
+            Some(ff_data_node) => ff_data_node
+                .child_nodes
+                .iter()
+                .filter_map(FFData::try_from_xml_element)
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
 
         let field_char_type =
             field_char_type.ok_or_else(|| MissingAttributeError::new(xml_node.name.clone(), "fldCharType"))?;
@@ -4395,7 +4426,7 @@ impl Cnf {
 impl Update for Cnf {
     fn update_with(self, other: Self) -> Self {
         Self {
-            first_row: other.first_column.or(self.first_row),
+            first_row: other.first_row.or(self.first_row),
             last_row: other.last_row.or(self.last_row),
             first_column: other.first_column.or(self.first_column),
             last_column: other.last_column.or(self.last_column),
@@ -7849,7 +7880,10 @@ mod tests {
         pub fn test_xml(node_name: &'static str) -> String {
             format!(
                 r#"<{node_name} w:fldCharType="begin" w:fldLock="false" w:dirty="false">
-                <name w:val="Some name" />
+                <ffData>
+                    <name w:val="Some name" />
+                    <enabled />
+                </ffData>
             </{node_name}>"#,
                 node_name = node_name,
             )
@@ -7857,7 +7891,7 @@ mod tests {
 
         pub fn test_instance() -> Self {
             Self {
-                form_field_properties: Some(FFData::Name(FFName::from("Some name"))),
+                form_field_properties: vec![FFData::Name(FFName::from("Some name")), FFData::Enabled(true)],
                 field_char_type: FldCharType::Begin,
                 field_lock: Some(false),
                 dirty: Some(false),
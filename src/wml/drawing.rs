@@ -72,6 +72,7 @@ pub struct Inline {
     pub doc_properties: NonVisualDrawingProps,
     pub graphic_frame_properties: Option<NonVisualGraphicFrameProperties>,
     pub graphic: GraphicalObject,
+    pub graphic_data_content: Option<GraphicDataContent>,
 
     pub distance_top: Option<WrapDistance>,
     pub distance_bottom: Option<WrapDistance>,
@@ -101,6 +102,7 @@ impl Inline {
         let mut doc_properties = None;
         let mut graphic_frame_properties = None;
         let mut graphic = None;
+        let mut graphic_data_content = None;
 
         for child_node in &xml_node.child_nodes {
             match child_node.local_name() {
@@ -110,7 +112,10 @@ impl Inline {
                 "cNvGraphicFramePr" => {
                     graphic_frame_properties = Some(NonVisualGraphicFrameProperties::from_xml_element(child_node)?)
                 }
-                "graphic" => graphic = Some(GraphicalObject::from_xml_element(child_node)?),
+                "graphic" => {
+                    graphic = Some(GraphicalObject::from_xml_element(child_node)?);
+                    graphic_data_content = GraphicDataContent::from_graphic_element(child_node)?;
+                }
                 _ => (),
             }
         }
@@ -121,6 +126,7 @@ impl Inline {
             doc_properties: doc_properties.ok_or_else(|| MissingChildNodeError::new(xml_node.name.clone(), "docPr"))?,
             graphic_frame_properties,
             graphic: graphic.ok_or_else(|| MissingChildNodeError::new(xml_node.name.clone(), "graphic"))?,
+            graphic_data_content,
             distance_top,
             distance_bottom,
             distance_left,
@@ -586,6 +592,7 @@ pub struct Anchor {
     pub document_properties: NonVisualDrawingProps,
     pub graphic_frame_properties: Option<NonVisualGraphicFrameProperties>,
     pub graphic: GraphicalObject,
+    pub graphic_data_content: Option<GraphicDataContent>,
 
     pub distance_top: Option<WrapDistance>,
     pub distance_bottom: Option<WrapDistance>,
@@ -640,6 +647,7 @@ impl Anchor {
         let mut document_properties = None;
         let mut graphic_frame_properties = None;
         let mut graphic = None;
+        let mut graphic_data_content = None;
 
         for child_node in &xml_node.child_nodes {
             match child_node.local_name() {
@@ -655,7 +663,10 @@ impl Anchor {
                 "cNvGraphicFramePr" => {
                     graphic_frame_properties = Some(NonVisualGraphicFrameProperties::from_xml_element(child_node)?)
                 }
-                "graphic" => graphic = Some(GraphicalObject::from_xml_element(child_node)?),
+                "graphic" => {
+                    graphic = Some(GraphicalObject::from_xml_element(child_node)?);
+                    graphic_data_content = GraphicDataContent::from_graphic_element(child_node)?;
+                }
                 _ => (),
             }
         }
@@ -691,6 +702,7 @@ impl Anchor {
             document_properties,
             graphic_frame_properties,
             graphic,
+            graphic_data_content,
             distance_top,
             distance_bottom,
             distance_left,
@@ -1094,6 +1106,78 @@ impl WordprocessingCanvas {
     }
 }
 
+// The blip of a picture, which refers to the image part by relationship id
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PictureBlip {
+    pub embed_relationship_id: Option<RelationshipId>,
+    pub link_relationship_id: Option<RelationshipId>,
+}
+
+impl PictureBlip {
+    pub fn from_xml_element(xml_node: &XmlNode) -> Result<Self> {
+        let blip_node = xml_node
+            .child_nodes
+            .iter()
+            .find(|child_node| child_node.local_name() == "blipFill")
+            .and_then(|blip_fill_node| {
+                blip_fill_node
+                    .child_nodes
+                    .iter()
+                    .find(|child_node| child_node.local_name() == "blip")
+            });
+
+        let mut instance: Self = Default::default();
+        if let Some(blip_node) = blip_node {
+            for (attr, value) in &blip_node.attributes {
+                match attr.as_ref() {
+                    "r:embed" => instance.embed_relationship_id = Some(value.clone()),
+                    "r:link" => instance.link_relationship_id = Some(value.clone()),
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(instance)
+    }
+}
+
+// The content of a:graphicData which is relevant to WordprocessingML. The GraphicalObject of msoffice_shared only
+// parses the uri of the graphic data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphicDataContent {
+    Picture(PictureBlip),
+    Shape(Box<WordprocessingShape>),
+    Group(Box<WordprocessingGroup>),
+    Canvas(Box<WordprocessingCanvas>),
+}
+
+impl GraphicDataContent {
+    pub fn from_graphic_element(xml_node: &XmlNode) -> Result<Option<Self>> {
+        let graphic_data_node = match xml_node
+            .child_nodes
+            .iter()
+            .find(|child_node| child_node.local_name() == "graphicData")
+        {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+
+        for child_node in &graphic_data_node.child_nodes {
+            let content = match child_node.local_name() {
+                "pic" => GraphicDataContent::Picture(PictureBlip::from_xml_element(child_node)?),
+                "wsp" => GraphicDataContent::Shape(Box::new(WordprocessingShape::from_xml_element(child_node)?)),
+                "wgp" => GraphicDataContent::Group(Box::new(WordprocessingGroup::from_xml_element(child_node)?)),
+                "wpc" => GraphicDataContent::Canvas(Box::new(WordprocessingCanvas::from_xml_element(child_node)?)),
+                _ => continue,
+            };
+
+            return Ok(Some(content));
+        }
+
+        Ok(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1243,6 +1327,7 @@ mod tests {
                 doc_properties: test_non_visual_drawing_props_instance(),
                 graphic_frame_properties: None,
                 graphic: test_graphical_object_instance(),
+                graphic_data_content: None,
                 distance_top: Some(0),
                 distance_bottom: Some(100),
                 distance_left: Some(0),
@@ -1567,6 +1652,7 @@ mod tests {
                 document_properties: test_non_visual_drawing_props_instance(),
                 graphic_frame_properties: None,
                 graphic: test_graphical_object_instance(),
+                graphic_data_content: None,
                 distance_top: Some(0),
                 distance_bottom: Some(100),
                 distance_left: Some(0),
@@ -1708,6 +1794,37 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_graphic_data_content_from_xml() {
+        let xml = r#"<graphic>
+            <graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+                <pic><blipFill><blip r:embed="rId5" /></blipFill></pic>
+            </graphicData>
+        </graphic>"#;
+        assert_eq!(
+            GraphicDataContent::from_graphic_element(&XmlNode::from_str(xml).unwrap()).unwrap(),
+            Some(GraphicDataContent::Picture(PictureBlip {
+                embed_relationship_id: Some(String::from("rId5")),
+                link_relationship_id: None,
+            })),
+        );
+
+        let xml = format!(
+            r#"<graphic><graphicData uri="http://some/url">{}</graphicData></graphic>"#,
+            WordprocessingShape::test_xml("wsp"),
+        );
+        assert_eq!(
+            GraphicDataContent::from_graphic_element(&XmlNode::from_str(xml.as_str()).unwrap()).unwrap(),
+            Some(GraphicDataContent::Shape(Box::new(WordprocessingShape::test_instance()))),
+        );
+
+        let xml = test_graphical_object_xml("graphic");
+        assert_eq!(
+            GraphicDataContent::from_graphic_element(&XmlNode::from_str(xml.as_str()).unwrap()).unwrap(),
+            None,
+        );
+    }
+
     impl GraphicFrame {
         pub fn test_xml(node_name: &'static str) -> String {
             format!(
@@ -82,6 +82,22 @@ impl Footnotes {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Endnotes(pub Vec<FtnEdn>);
+
+impl Endnotes {
+    pub fn from_xml_element(xml_node: &XmlNode) -> Result<Self> {
+        let endnotes = xml_node
+            .child_nodes
+            .iter()
+            .filter(|child_node| child_node.local_name() == "endnote")
+            .map(FtnEdn::from_xml_element)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self(endnotes))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +129,31 @@ mod tests {
         );
     }
 
+    impl Endnotes {
+        pub fn test_xml(node_name: &'static str) -> String {
+            format!(
+                r#"<{node_name}>
+                {}
+            </{node_name}>"#,
+                FtnEdn::test_xml("w:endnote"),
+                node_name = node_name,
+            )
+        }
+
+        pub fn test_instance() -> Self {
+            Self(vec![FtnEdn::test_instance()])
+        }
+    }
+
+    #[test]
+    pub fn test_endnotes_from_xml() {
+        let xml = Endnotes::test_xml("w:endnotes");
+        assert_eq!(
+            Endnotes::from_xml_element(&XmlNode::from_str(xml.as_str()).unwrap()).unwrap(),
+            Endnotes::test_instance(),
+        );
+    }
+
     impl FtnEdn {
         pub fn test_xml(node_name: &'static str) -> String {
             format!(
@@ -0,0 +1,57 @@
+use super::document::BlockLevelElts;
+use msoffice_shared::{xml::XmlNode, xsdtypes::XsdChoice};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HeaderFooter {
+    pub block_level_elements: Vec<BlockLevelElts>,
+}
+
+impl HeaderFooter {
+    pub fn from_xml_element(xml_node: &XmlNode) -> Result<Self> {
+        let block_level_elements = xml_node
+            .child_nodes
+            .iter()
+            .filter_map(BlockLevelElts::try_from_xml_element)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { block_level_elements })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wml::document::{ContentBlockContent, P};
+    use std::str::FromStr;
+
+    impl HeaderFooter {
+        pub fn test_xml(node_name: &'static str) -> String {
+            format!(
+                r#"<{node_name}>
+                {}
+            </{node_name}>"#,
+                P::test_xml("w:p"),
+                node_name = node_name,
+            )
+        }
+
+        pub fn test_instance() -> Self {
+            Self {
+                block_level_elements: vec![BlockLevelElts::Chunk(ContentBlockContent::Paragraph(Box::new(
+                    P::test_instance(),
+                )))],
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_header_footer_from_xml() {
+        let xml = HeaderFooter::test_xml("w:hdr");
+        assert_eq!(
+            HeaderFooter::from_xml_element(&XmlNode::from_str(xml.as_str()).unwrap()).unwrap(),
+            HeaderFooter::test_instance(),
+        );
+    }
+}
@@ -2,6 +2,7 @@ pub mod document;
 pub mod drawing;
 pub mod error;
 pub mod footnotes;
+pub mod headerfooter;
 pub mod numbering;
 pub mod settings;
 pub mod simpletypes;
@@ -1,6 +1,7 @@
 use super::{
     document::{Control, Drawing, Jc, NumFmt, PPrGeneral, RPr, Rel},
     simpletypes::{parse_on_off_xml_element, DecimalNumber, LongHexNumber},
+    styles::Styles,
     util::XmlNodeExt,
 };
 use log::info;
@@ -428,6 +429,61 @@ impl Numbering {
                 Ok(instance)
             })
     }
+
+    pub fn abstract_numbering(&self, numbering_id: DecimalNumber) -> Option<&AbstractNum> {
+        let num = self.numberings.iter().find(|num| num.numbering_id == numbering_id)?;
+        self.abstract_numberings
+            .iter()
+            .find(|abstract_num| abstract_num.abstract_num_id == num.abstract_num_id)
+    }
+
+    // An abstract numbering with a numStyleLink only refers to a numbering style, whose numPr points to the abstract
+    // numbering defining the levels
+    pub fn linked_abstract_numbering(
+        &self,
+        numbering_id: DecimalNumber,
+        styles: Option<&Styles>,
+    ) -> Option<&AbstractNum> {
+        let abstract_num = self.abstract_numbering(numbering_id)?;
+        let linked_abstract_num = abstract_num.numbering_style_link.as_ref().and_then(|style_id| {
+            let numbering_style = styles?
+                .styles
+                .iter()
+                .find(|style| style.style_id.as_ref() == Some(style_id))?;
+            let linked_numbering_id = numbering_style
+                .paragraph_properties
+                .as_ref()?
+                .base
+                .numbering_properties
+                .as_ref()?
+                .numbering_id?;
+            self.abstract_numbering(linked_numbering_id)
+        });
+
+        Some(linked_abstract_num.unwrap_or(abstract_num))
+    }
+
+    // Level definitions overridden by the numbering instance take precedence over the abstract numbering's
+    pub fn find_level(
+        &self,
+        numbering_id: DecimalNumber,
+        level: DecimalNumber,
+        styles: Option<&Styles>,
+    ) -> Option<&Lvl> {
+        let num = self.numberings.iter().find(|num| num.numbering_id == numbering_id)?;
+        let level_override = num
+            .level_overrides
+            .iter()
+            .filter(|level_override| level_override.numbering_level == level)
+            .find_map(|level_override| level_override.level.as_ref());
+
+        level_override.or_else(|| {
+            self.linked_abstract_numbering(numbering_id, styles)?
+                .levels
+                .iter()
+                .find(|lvl| lvl.level == level)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -161,7 +161,7 @@ impl LatentStyles {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString)]
 pub enum TblStyleOverrideType {
     #[strum(serialize = "wholeTable")]
     WholeTable,
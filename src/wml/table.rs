@@ -12,6 +12,7 @@ use msoffice_shared::{
     error::{LimitViolationError, MaxOccurs, MissingAttributeError, MissingChildNodeError, NotGroupMemberError},
     sharedtypes::{OnOff, TwipsMeasure, XAlign, XmlName, YAlign},
     xml::{parse_xml_bool, XmlNode},
+    update::{update_options, Update},
     xsdtypes::{XsdChoice, XsdType},
 };
 
@@ -143,6 +144,19 @@ impl TblBorders {
     }
 }
 
+impl Update for TblBorders {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            top: update_options(self.top, other.top),
+            start: update_options(self.start, other.start),
+            bottom: update_options(self.bottom, other.bottom),
+            end: update_options(self.end, other.end),
+            inside_horizontal: update_options(self.inside_horizontal, other.inside_horizontal),
+            inside_vertical: update_options(self.inside_vertical, other.inside_vertical),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, EnumString)]
 pub enum TblLayoutType {
     #[strum(serialize = "fixed")]
@@ -179,6 +193,17 @@ impl TblCellMar {
     }
 }
 
+impl Update for TblCellMar {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            top: other.top.or(self.top),
+            start: other.start.or(self.start),
+            bottom: other.bottom.or(self.bottom),
+            end: other.end.or(self.end),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct TblLook {
     pub first_row: Option<OnOff>,
@@ -268,6 +293,30 @@ impl TblPrBase {
     }
 }
 
+impl Update for TblPrBase {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            style: other.style.or(self.style),
+            paragraph_properties: other.paragraph_properties.or(self.paragraph_properties),
+            overlap: other.overlap.or(self.overlap),
+            bidirectional_visual: other.bidirectional_visual.or(self.bidirectional_visual),
+            style_row_band_size: other.style_row_band_size.or(self.style_row_band_size),
+            style_column_band_size: other.style_column_band_size.or(self.style_column_band_size),
+            width: other.width.or(self.width),
+            alignment: other.alignment.or(self.alignment),
+            cell_spacing: other.cell_spacing.or(self.cell_spacing),
+            indent: other.indent.or(self.indent),
+            borders: update_options(self.borders, other.borders),
+            shading: update_options(self.shading, other.shading),
+            layout: other.layout.or(self.layout),
+            cell_margin: update_options(self.cell_margin, other.cell_margin),
+            look: other.look.or(self.look),
+            caption: other.caption.or(self.caption),
+            description: other.description.or(self.description),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TblPrChange {
     pub base: TrackChange,
@@ -525,6 +574,25 @@ impl TrPrBase {
     }
 }
 
+impl Update for TrPrBase {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            conditional_formatting: update_options(self.conditional_formatting, other.conditional_formatting),
+            div_id: other.div_id.or(self.div_id),
+            grid_column_before_first_cell: other.grid_column_before_first_cell.or(self.grid_column_before_first_cell),
+            grid_column_after_last_cell: other.grid_column_after_last_cell.or(self.grid_column_after_last_cell),
+            width_before_row: other.width_before_row.or(self.width_before_row),
+            width_after_row: other.width_after_row.or(self.width_after_row),
+            cant_split: other.cant_split.or(self.cant_split),
+            row_height: other.row_height.or(self.row_height),
+            header: other.header.or(self.header),
+            cell_spacing: other.cell_spacing.or(self.cell_spacing),
+            alignment: other.alignment.or(self.alignment),
+            hidden: other.hidden.or(self.hidden),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TrPrChange {
     pub base: TrackChange,
@@ -618,6 +686,21 @@ impl TcBorders {
     }
 }
 
+impl Update for TcBorders {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            top: update_options(self.top, other.top),
+            start: update_options(self.start, other.start),
+            bottom: update_options(self.bottom, other.bottom),
+            end: update_options(self.end, other.end),
+            inside_horizontal: update_options(self.inside_horizontal, other.inside_horizontal),
+            inside_vertical: update_options(self.inside_vertical, other.inside_vertical),
+            top_left_to_bottom_right: update_options(self.top_left_to_bottom_right, other.top_left_to_bottom_right),
+            top_right_to_bottom_left: update_options(self.top_right_to_bottom_left, other.top_right_to_bottom_left),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct TcMar {
     pub top: Option<TblWidth>,
@@ -646,6 +729,17 @@ impl TcMar {
     }
 }
 
+impl Update for TcMar {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            top: other.top.or(self.top),
+            start: other.start.or(self.start),
+            bottom: other.bottom.or(self.bottom),
+            end: other.end.or(self.end),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Headers(pub Vec<String>);
 
@@ -722,6 +816,26 @@ impl TcPrBase {
     }
 }
 
+impl Update for TcPrBase {
+    fn update_with(self, other: Self) -> Self {
+        Self {
+            conditional_formatting: update_options(self.conditional_formatting, other.conditional_formatting),
+            width: other.width.or(self.width),
+            grid_span: other.grid_span.or(self.grid_span),
+            vertical_merge: other.vertical_merge.or(self.vertical_merge),
+            borders: update_options(self.borders, other.borders),
+            shading: update_options(self.shading, other.shading),
+            no_wrapping: other.no_wrapping.or(self.no_wrapping),
+            margin: update_options(self.margin, other.margin),
+            text_direction: other.text_direction.or(self.text_direction),
+            fit_text: other.fit_text.or(self.fit_text),
+            vertical_alignment: other.vertical_alignment.or(self.vertical_alignment),
+            hide_marker: other.hide_marker.or(self.hide_marker),
+            headers: other.headers.or(self.headers),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, EnumString)]
 pub enum AnnotationVMerge {
     #[strum(serialize = "cont")]
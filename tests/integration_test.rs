@@ -21,6 +21,7 @@ fn test_package_load() {
     assert!(package.numbering.is_some());
     assert!(package.settings.is_some());
     assert_eq!(package.medias.len(), 4);
+    assert_eq!(package.media_files.len(), 4);
     assert_eq!(package.themes.len(), 1);
     package.themes.get("theme1").unwrap();
 }